@@ -1,16 +1,59 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use chrono::{Datelike, TimeZone, Utc};
 use directories::ProjectDirs;
-use iced::widget::{button, column, container, row, scrollable, text, text_input, Space};
-use iced::{Element, Length, Subscription, Task, Theme};
+use iced::futures::{SinkExt, StreamExt};
+use iced::widget::{
+    button, checkbox, column, container, mouse_area, opaque, pick_list, row, scrollable, stack,
+    text, text_input, tooltip, Space,
+};
+use iced::{keyboard, mouse, window, Color, ContentFit, Element, Length, Subscription, Task, Theme};
 
+use crate::cache::{self, ArtRequest};
+#[cfg(feature = "steamgriddb")]
+use crate::cache::steamgriddb;
 use crate::constants::{
-    APP_APPLICATION, APP_ORGANIZATION, APP_QUALIFIER, CONFIG_FILE, LIBRARY_FILE,
+    ACTIVITY_FILE, ACTIVITY_HISTORY_LIMIT, APP_APPLICATION, APP_NAME, APP_ORGANIZATION,
+    APP_QUALIFIER, ART_DOWNLOAD_CONCURRENCY, AUTO_SCAN_INTERVAL_CHOICES, AUTO_SCAN_POLL_SECS,
+    CONFIG_FILE, DATA_DIR_ENV, DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH,
+    EXECUTABLE_SEARCH_DEPTH, FONT_SCALE_CHOICES, FONT_SCALE_MAX, FONT_SCALE_MIN,
+    GAME_LOGS_DIR, IMPORT_WARN_THRESHOLD_CHOICES, LAUNCH_COOLDOWN_POLL_MS, LAUNCH_STATUS_FILE,
+    LIBRARY_FILE, MAX_ACTIVITY_LOG_BYTES, MAX_CARD_NAME_CHARS, MAX_RECENT_DATA_DIRS,
+    NARROW_LAYOUT_WIDTH_CHOICES, NEW_BADGE_DAY_CHOICES, NOW_PLAYING_REFRESH_SECS,
+    NOW_PLAYING_WINDOW_HEIGHT, NOW_PLAYING_WINDOW_WIDTH, RECENT_DATA_DIRS_FILE,
+    RELATIVE_TIME_REFRESH_SECS, RESOURCE_POLL_INTERVAL_SECS, RULE_PLAYTIME_HOUR_CHOICES,
+    SAVE_BACKUPS_DIR, SCAN_CACHE_FILE, SCAN_CACHE_MAX_AGE_HOURS, SIDEBAR_MAX_WIDTH,
+    SIDEBAR_MIN_WIDTH, THEMES_DIR,
+};
+use crate::data::{
+    ActivityEntry, ActivityKind, ArtShape, BundleExportReport, BundleImportReport, CardStyle,
+    Category, CategoryCollision, CategoryId, Clock, Config, CsvImportReport, CsvRowOutcome,
+    DataDir, DataDirStatus, Density, DisplayPrefs, ExcludePatterns, Game, GameField, GameId,
+    GameSource, LaunchWindowBehavior, Library, LibraryError, PlayStats, PlayStatus,
+    RecentDataDirs, RuleCondition, SaveBackup, SearchIndex, SmartMembers, SmartRule, SystemClock,
+    WindowModePref, append_activity, backup_saves, choose_data_dir, delete_activity,
+    executable_candidates, find_path_ignoring_case, group_games, guess_save_folders, hash_pin,
+    is_uri, list_save_backups, probe_private, probe_writable, read_activity, restore_saves,
+    same_path, sort_games, temp_data_dir, truncate_display_name, verify_pin, weekday_name,
+};
+use crate::import::{
+    create_manual_game, read_steam_collections, refresh_from_import, steamapps_dir,
+    check_import_size, CachedScan, DetectedGame, GOGImporter, GameImporter, ImportCheck,
+    ImportSummary, ItchImporter, LargeImport, RomImporter, ScanCache, ScanInputs, ScanReport,
+    SteamImporter, UbisoftImporter, XboxImporter, validate_executable,
+};
+#[cfg(feature = "discord")]
+use crate::launcher::DiscordPresence;
+use crate::launcher::{LaunchError, LaunchOverrides, LaunchStatus, ResolvedCommand, StatusWriter};
+use crate::message::{
+    Filter, GameUpdate, GroupBy, ImportPreviewTab, ImportProgress, Message, ScanResult,
+    SettingsSection, SortOrder, View, ViewMode,
 };
-use crate::data::{Category, CategoryId, Config, Game, GameId, GameSource, Library};
-use crate::import::{GameImporter, SteamImporter};
-use crate::message::{Message, SortOrder, View, ViewMode};
-use crate::theme::CustomTheme;
+use crate::platform::{self, ResourceSampler, ResourceUsage};
+use crate::theme::{CategoryIcon, CustomTheme, HexColor};
 
 /// Import status for UI feedback
 #[derive(Debug, Clone, Default)]
@@ -18,15 +61,114 @@ pub enum ImportStatus {
     #[default]
     Idle,
     Importing(GameSource),
-    Complete { count: usize, source: GameSource },
+    Preview {
+        source: GameSource,
+        entries: Vec<PreviewEntry>,
+        tab: ImportPreviewTab,
+        report: ScanReport,
+        /// Set while the large-import warning waits on the user
+        confirm: Option<LargeImport>,
+        /// When the games were found, if they came from the scan cache
+        cached_at: Option<chrono::DateTime<Utc>>,
+    },
+    Complete(ImportSummary),
+    Error(String),
+}
+
+/// A detected game awaiting confirmation in the import preview
+#[derive(Debug, Clone)]
+pub struct PreviewEntry {
+    pub game: DetectedGame,
+    pub selected: bool,
+    /// Whether the game is already in the library
+    pub existing: bool,
+    /// Existing entry from another store this game will be linked to
+    pub link_target: Option<String>,
+}
+
+/// A condition offered in the smart category rule editor
+#[derive(Debug, Clone, PartialEq)]
+struct RuleChoice {
+    condition: RuleCondition,
+    label: String,
+}
+
+impl std::fmt::Display for RuleChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// Passphrase prompt shown while an encrypted library is locked
+#[derive(Debug, Clone, Default)]
+struct UnlockPrompt {
+    input: String,
+    error: Option<String>,
+    checking: bool,
+}
+
+/// Modal asking the user to confirm an action before it runs
+#[derive(Debug, Clone)]
+struct ConfirmDialog {
+    title: String,
+    body: String,
+    /// Dispatched when the user confirms
+    action: Message,
+    /// Game whose launch is being confirmed, so it isn't asked about twice
+    game_id: Option<GameId>,
+    /// Whether the launch PIN has to be entered
+    needs_pin: bool,
+    pin_input: String,
+    error: Option<String>,
+}
+
+/// Artwork download status for UI feedback
+#[derive(Debug, Clone, Default)]
+pub enum ArtStatus {
+    #[default]
+    Idle,
+    Downloading(Option<ImportProgress>),
+    Complete { updated: usize, total: usize },
+}
+
+/// State of the SteamGridDB artwork picker in the detail view
+#[cfg(feature = "steamgriddb")]
+#[derive(Debug, Clone)]
+struct ArtworkPicker {
+    game_id: GameId,
+    status: ArtworkPickerStatus,
+    /// Candidates whose thumbnails haven't been fetched yet
+    pending: Vec<steamgriddb::ArtEntry>,
+    /// Whether a page of thumbnails is on its way
+    loading_more: bool,
+}
+
+#[cfg(feature = "steamgriddb")]
+#[derive(Debug, Clone)]
+enum ArtworkPickerStatus {
+    Searching,
+    Ready(Vec<(steamgriddb::ArtCandidate, iced::widget::image::Handle)>),
+    Downloading,
     Error(String),
 }
 
+/// A game process the launcher is currently tracking
+#[derive(Debug, Clone)]
+struct RunningGame {
+    pid: Option<u32>,
+    usage: Option<ResourceUsage>,
+    started: Instant,
+    /// Wall-clock launch time, for the status file
+    started_at: chrono::DateTime<Utc>,
+}
+
 /// Main application state
 pub struct App {
     // Data
     library: Library,
     config: Config,
+    /// Where "now" comes from when stamping games, syncs and backups
+    clock: Arc<dyn Clock>,
 
     // Theme
     theme: CustomTheme,
@@ -34,20 +176,140 @@ pub struct App {
     // UI State
     current_view: View,
     search_query: String,
-    selected_category: Option<CategoryId>,
+    search_index: SearchIndex,
+    /// Smart category members, refreshed whenever the library is saved
+    smart_members: SmartMembers,
+    filter: Option<Filter>,
     selected_game: Option<GameId>,
     sort_order: SortOrder,
+    /// Sort picked in the header while a category with its own sort is shown,
+    /// until the filter changes
+    sort_override: Option<SortOrder>,
     view_mode: ViewMode,
+    group_by: GroupBy,
+    show_hidden: bool,
+    // Game whose card context menu is open
+    context_menu: Option<GameId>,
+    // Games picked for bulk actions
+    selected_games: HashSet<GameId>,
+    bundle_export: Option<Result<(PathBuf, BundleExportReport), String>>,
 
     // Import state
     import_status: ImportStatus,
+    /// Whether a background scan is in flight, so they never overlap
+    auto_scan_running: bool,
+    /// When the last background scan started
+    last_auto_scan: Option<Instant>,
+
+    // Artwork state
+    art_status: ArtStatus,
+    art_force_redownload: bool,
+    #[cfg(feature = "steamgriddb")]
+    artwork_picker: Option<ArtworkPicker>,
+    /// The SteamGridDB key while it's being edited in settings
+    #[cfg(feature = "steamgriddb")]
+    steamgriddb_key_draft: Option<String>,
+
+    // Launch state
+    running_games: HashMap<GameId, RunningGame>,
+    /// When each game was last launched, while its cooldown lasts
+    launch_cooldowns: HashMap<GameId, Instant>,
+    /// Captured output of games whose last session exited with an error
+    failed_launch_logs: HashMap<GameId, PathBuf>,
+    /// Launch command worked out for the detail view, without running it
+    launch_preview: Option<(GameId, Result<ResolvedCommand, LaunchError>)>,
+    /// Recent entries of the activity log, oldest first
+    activity: Vec<ActivityEntry>,
+    activity_filter: Option<ActivityKind>,
+    /// Executables found in a game's install folder, best guess first
+    exe_candidates: Option<(GameId, Vec<PathBuf>)>,
+    emulator_command_input: String,
+    install_path_input: String,
+    save_path_input: String,
+    /// Folders that look like a game's saves, from "Find save folder"
+    save_folder_guesses: Option<(GameId, Vec<PathBuf>)>,
+    /// A game's save backups, newest first, while the restore picker is open
+    save_backups: Option<(GameId, Vec<SaveBackup>)>,
+    /// How the last backup or restore went
+    save_status: Option<(GameId, Result<String, String>)>,
+    window_hidden_for_game: bool,
+    /// The library window, closing it quits the launcher
+    main_window: Option<window::Id>,
+    /// The always-on-top window listing running games, while it is open
+    now_playing_window: Option<window::Id>,
+    /// Where an unparseable library file was moved at startup
+    quarantined_library: Option<PathBuf>,
+    /// The last scan of each store, so reopening the import is instant
+    scan_cache: ScanCache,
+    /// Whether the sidebar splitter is being dragged
+    sidebar_dragging: bool,
+    /// Width of the main window, to pick the narrow layout
+    window_width: f32,
+    /// Whether the header menu holding the sidebar is open, in narrow windows
+    sidebar_menu_open: bool,
+    /// Card under the mouse, for cover cards' hover buttons
+    hovered_card: Option<GameId>,
+    resource_sampler: ResourceSampler,
+    /// Connection to Discord while it shows a presence, made on the next
+    /// launch when Discord wasn't running
+    #[cfg(feature = "discord")]
+    discord: Arc<tokio::sync::Mutex<Option<DiscordPresence>>>,
+
+    // Settings form state
+    settings_section: SettingsSection,
+    steam_path_input: String,
+    exclude_pattern_input: String,
+    emulator_extension_input: String,
+    emulator_template_input: String,
+    category_name_input: String,
+    /// Category whose smart rule is being edited
+    editing_rule: Option<CategoryId>,
+    csv_path_input: String,
+    csv_import: Option<Result<CsvImportReport, String>>,
+    csv_importing: bool,
+    bundle_path_input: String,
+    bundle_import: Option<Result<BundleImportReport, String>>,
+    bundle_importing: bool,
+    rom_folder_input: String,
+    steam_path_error: Option<String>,
+    passphrase_input: String,
+
+    // Confirmation modal and the launch it just let through
+    confirm_dialog: Option<ConfirmDialog>,
+    launch_confirmed: Option<GameId>,
+    /// Set while the action a PIN dialog let through runs
+    pin_confirmed: bool,
+    launch_pin_input: String,
+
+    // Where the year in review was last exported, or why it failed
+    review_export: Option<Result<PathBuf, String>>,
+
+    // Last library save failure, shown until a save succeeds
+    save_error: Option<String>,
+
+    // Encryption state; the passphrase is only ever kept in memory
+    library_passphrase: Option<String>,
+    unlock: Option<UnlockPrompt>,
+
+    // Why the library file couldn't be loaded; nothing is saved over it
+    load_error: Option<String>,
 
     // Form state for adding games
     new_game_name: String,
     new_game_path: String,
 
-    // Paths
-    data_dir: PathBuf,
+    // Paths, and whether the data directory had to fall back
+    data_dir: DataDir,
+    /// Data dirs opened before, offered under "Switch library"
+    recent_data_dirs: RecentDataDirs,
+    recent_data_dirs_path: PathBuf,
+    switch_library_input: String,
+    switch_library_error: Option<String>,
+    /// Orders the writes of the launch status file
+    status_writer: StatusWriter,
+    /// Bumped on every library switch, so results of work started for the
+    /// previous library are dropped
+    library_generation: u64,
 }
 
 impl Default for App {
@@ -56,45 +318,214 @@ impl Default for App {
         let project_dirs = ProjectDirs::from(APP_QUALIFIER, APP_ORGANIZATION, APP_APPLICATION)
             .expect("Could not determine project directories");
 
-        let data_dir = project_dirs.data_dir().to_path_buf();
+        // Reopen the library that was open last, if the user switched away
+        // from the usual one
+        let recent_data_dirs_path = project_dirs.config_dir().join(RECENT_DATA_DIRS_FILE);
+        let recent_data_dirs = RecentDataDirs::load(&recent_data_dirs_path);
+        let default_dir = project_dirs.data_dir().to_path_buf();
+        let preferred = recent_data_dirs.last().map(Path::to_path_buf);
+
+        // A read-only data dir would make every save fail, so look for a
+        // writable one up front: the usual dir if another was preferred, the
+        // user's override, then a private dir under the temp dir
+        let temp_dir = temp_data_dir();
+        let fallbacks = preferred
+            .as_ref()
+            .map(|_| default_dir.clone())
+            .into_iter()
+            .chain(std::env::var_os(DATA_DIR_ENV).map(PathBuf::from))
+            .chain([temp_dir.clone()]);
+        let data_dir = choose_data_dir(preferred.unwrap_or(default_dir), fallbacks, |dir| {
+            if dir == temp_dir {
+                probe_private(dir)
+            } else {
+                probe_writable(dir)
+            }
+        });
+
+        tracing::info!("Data directory: {:?}", data_dir.path);
+        if let Some(warning) = data_dir.warning() {
+            tracing::warn!("{}", warning);
+        }
 
-        tracing::info!("Data directory: {:?}", data_dir);
+        Self::with_data_dir(data_dir, recent_data_dirs, recent_data_dirs_path)
+    }
+}
 
+impl App {
+    /// A fresh state using `data_dir`, with nothing loaded yet
+    fn with_data_dir(
+        data_dir: DataDir,
+        recent_data_dirs: RecentDataDirs,
+        recent_data_dirs_path: PathBuf,
+    ) -> Self {
         Self {
-            library: Library::new(),
+            library: Library::empty(),
             config: Config::default(),
+            clock: Arc::new(SystemClock),
             theme: CustomTheme::dark(),
             current_view: View::Library,
             search_query: String::new(),
-            selected_category: None,
+            search_index: SearchIndex::default(),
+            smart_members: SmartMembers::default(),
+            filter: None,
             selected_game: None,
             sort_order: SortOrder::NameAsc,
+            sort_override: None,
             view_mode: ViewMode::Grid,
+            group_by: GroupBy::None,
+            show_hidden: false,
+            context_menu: None,
+            selected_games: HashSet::new(),
+            bundle_export: None,
             import_status: ImportStatus::Idle,
+            auto_scan_running: false,
+            last_auto_scan: None,
+            art_status: ArtStatus::Idle,
+            art_force_redownload: false,
+            #[cfg(feature = "steamgriddb")]
+            artwork_picker: None,
+            #[cfg(feature = "steamgriddb")]
+            steamgriddb_key_draft: None,
+            running_games: HashMap::new(),
+            launch_cooldowns: HashMap::new(),
+            failed_launch_logs: HashMap::new(),
+            launch_preview: None,
+            activity: Vec::new(),
+            activity_filter: None,
+            exe_candidates: None,
+            emulator_command_input: String::new(),
+            install_path_input: String::new(),
+            save_path_input: String::new(),
+            save_folder_guesses: None,
+            save_backups: None,
+            save_status: None,
+            window_hidden_for_game: false,
+            main_window: None,
+            now_playing_window: None,
+            quarantined_library: None,
+            scan_cache: ScanCache::default(),
+            sidebar_dragging: false,
+            window_width: DEFAULT_WINDOW_WIDTH,
+            sidebar_menu_open: false,
+            hovered_card: None,
+            resource_sampler: ResourceSampler::new(),
+            #[cfg(feature = "discord")]
+            discord: Arc::default(),
+            settings_section: SettingsSection::default(),
+            steam_path_input: String::new(),
+            exclude_pattern_input: String::new(),
+            emulator_extension_input: String::new(),
+            emulator_template_input: String::new(),
+            category_name_input: String::new(),
+            editing_rule: None,
+            csv_path_input: String::new(),
+            csv_import: None,
+            csv_importing: false,
+            bundle_path_input: String::new(),
+            bundle_import: None,
+            bundle_importing: false,
+            rom_folder_input: String::new(),
+            steam_path_error: None,
+            passphrase_input: String::new(),
+            confirm_dialog: None,
+            launch_confirmed: None,
+            pin_confirmed: false,
+            launch_pin_input: String::new(),
+            review_export: None,
+            save_error: None,
+            library_passphrase: None,
+            unlock: None,
+            load_error: None,
             new_game_name: String::new(),
             new_game_path: String::new(),
             data_dir,
+            recent_data_dirs,
+            recent_data_dirs_path,
+            switch_library_input: String::new(),
+            switch_library_error: None,
+            status_writer: StatusWriter::default(),
+            library_generation: 0,
         }
     }
-}
 
-impl App {
     /// Create a new application instance with initial task
     pub fn new() -> (Self, Task<Message>) {
-        let app = Self::default();
+        let (main_window, open_window) = window::open(window::Settings {
+            size: iced::Size::new(DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT),
+            ..Default::default()
+        });
+        let app = Self {
+            main_window: Some(main_window),
+            ..Self::default()
+        };
         // Load library and config asynchronously
         let load_task = Task::perform(async {}, |_| Message::LoadLibrary);
-        (app, load_task)
+        (app, Task::batch([open_window.discard(), load_task]))
     }
 
     /// Get the library file path
     fn library_path(&self) -> PathBuf {
-        self.data_dir.join(LIBRARY_FILE)
+        self.data_dir.path.join(LIBRARY_FILE)
     }
 
     /// Get the config file path
     fn config_path(&self) -> PathBuf {
-        self.data_dir.join(CONFIG_FILE)
+        self.data_dir.path.join(CONFIG_FILE)
+    }
+
+    /// Extra arguments, environment and wrapping a game is launched with
+    fn launch_overrides(&self, game: &Game) -> LaunchOverrides {
+        let mut overrides =
+            crate::launcher::display_overrides(&game.display_prefs, &game.executable_path);
+        overrides.in_terminal = game.launch_in_terminal.unwrap_or(self.config.launch_in_terminal);
+        overrides.run_as_admin = game.run_as_admin;
+        overrides.resolve_folders = self.config.resolve_executable_folders;
+        overrides.exclude = ExcludePatterns::new(&self.config.import_exclude_patterns);
+        overrides.emulator_command = game.emulator_command.clone();
+        if self.config.capture_game_output {
+            overrides.log_path = Some(self.game_log_path(&game.id));
+        }
+        overrides
+    }
+
+    /// Folder relative executable paths are resolved against
+    fn launch_base_dir(&self) -> PathBuf {
+        self.config.portable_base_dir.clone().unwrap_or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(PathBuf::from))
+                .unwrap_or_else(|| self.data_dir.path.clone())
+        })
+    }
+
+    /// Get the user themes directory
+    fn themes_dir(&self) -> PathBuf {
+        self.data_dir.path.join(THEMES_DIR)
+    }
+
+    /// Get the save backups directory
+    fn save_backups_dir(&self) -> PathBuf {
+        self.data_dir.path.join(SAVE_BACKUPS_DIR)
+    }
+
+    /// Handle a message from iced
+    ///
+    /// Results of work started before a library switch would land in the
+    /// new library, so every task is tagged with the library it was started
+    /// for and stale results are dropped.
+    pub fn dispatch(&mut self, message: Message) -> Task<Message> {
+        let message = match message {
+            Message::ForLibrary(generation, message) if generation != self.library_generation => {
+                tracing::debug!("Dropping {:?}, its library was switched away from", message);
+                return Task::none();
+            }
+            Message::ForLibrary(_, message) => *message,
+            message => message,
+        };
+        let task = self.update(message);
+        let generation = self.library_generation;
+        task.map(move |message| Message::ForLibrary(generation, Box::new(message)))
     }
 
     /// Handle messages and update state
@@ -103,6 +534,8 @@ impl App {
             // Navigation
             Message::NavigateTo(view) => {
                 self.current_view = view;
+                self.context_menu = None;
+                self.sidebar_menu_open = false;
                 Task::none()
             }
 
@@ -113,552 +546,5677 @@ impl App {
             }
 
             Message::LaunchGame(id) => {
-                if let Some(game) = self.library.get_game_mut(&id) {
-                    game.mark_played();
+                if !self.can_launch(&id) {
+                    tracing::info!("Ignoring launch of {:?}, already starting or running", id);
+                    return Task::none();
+                }
+                if let Some(task) = self.launch_not_installed(id) {
+                    return task;
+                }
+                if self.needs_launch_confirmation(id, Message::LaunchGame(id)) {
+                    return Task::none();
+                }
+                let overrides = self.library.get_game(&id).map(|game| self.launch_overrides(game));
+                let base_dir = self.launch_base_dir();
+                if let (Some(game), Some(overrides)) = (self.library.get_game_mut(&id), overrides) {
+                    if game.track_playtime {
+                        game.mark_played(self.clock.as_ref());
+                    }
+                    self.launch_cooldowns.insert(id, Instant::now());
+                    if let Some(uri) = game.uri_target() {
+                        return open_uri(id, uri.to_string(), self.config.prefer_big_picture);
+                    }
                     let exe_path = game.executable_path.clone();
                     let launch_args = game.launch_args.clone();
 
-                    return Task::perform(
-                        async move {
-                            crate::launcher::launch_game(&exe_path, launch_args.as_deref())
-                                .map_err(|e| e.to_string())
-                        },
-                        Message::GameLaunched,
-                    );
+                    // Spawn the game, report the launch, then keep waiting for it to exit
+                    return Task::stream(iced::stream::channel(1, async move |mut output| {
+                        let started = Instant::now();
+                        let launched = crate::launcher::launch_game(
+                            &exe_path,
+                            launch_args.as_deref(),
+                            &overrides,
+                            &base_dir,
+                        );
+                        match launched.await {
+                            // The game runs on in a terminal window we can't follow,
+                            // so it's reported like a store launch
+                            Ok(process) if !process.is_tracked() => {
+                                let _ = output.send(Message::GameLaunched(id, Ok(None))).await;
+                            }
+                            Ok(process) => {
+                                let pid = process.id();
+                                let _ = output.send(Message::GameLaunched(id, Ok(pid))).await;
+                                let (minutes, status) = match process.wait().await {
+                                    Ok(status) => (started.elapsed().as_secs() / 60, status),
+                                    Err(LaunchError::ElevationDeclined) => {
+                                        tracing::info!("Administrator prompt declined");
+                                        (0, None)
+                                    }
+                                    // The game never started
+                                    Err(e @ LaunchError::ElevationFailed) => {
+                                        tracing::warn!("{}", e);
+                                        (0, None)
+                                    }
+                                    Err(e) => {
+                                        tracing::warn!("{}", e);
+                                        (started.elapsed().as_secs() / 60, None)
+                                    }
+                                };
+                                let _ =
+                                    output.send(Message::GameExited(id, minutes, status)).await;
+                            }
+                            Err(e) => {
+                                let _ = output.send(Message::GameLaunched(id, Err(e))).await;
+                            }
+                        }
+                    }));
                 }
                 Task::none()
             }
 
-            Message::GameLaunched(result) => {
-                match &result {
-                    Ok(()) => tracing::info!("Game launched successfully"),
-                    Err(e) => tracing::error!("Failed to launch game: {}", e),
+            Message::PreviewLaunchCommand(id) => {
+                if let Some(game) = self.library.get_game(&id) {
+                    let resolved = crate::launcher::build_command(
+                        &game.executable_path,
+                        game.launch_args.as_deref(),
+                        &self.launch_overrides(game),
+                        &self.launch_base_dir(),
+                    );
+                    self.launch_preview = Some((id, resolved));
                 }
-                // Save library to persist the last_played update
-                self.save_library()
+                Task::none()
             }
 
-            // Search and filtering
-            Message::SearchChanged(query) => {
-                self.search_query = query;
+            Message::CloseLaunchPreview => {
+                self.launch_preview = None;
                 Task::none()
             }
 
-            Message::CategorySelected(category) => {
-                self.selected_category = category;
-                Task::none()
+            Message::RedetectExecutable(id) => {
+                let install_path = self.library.get_game(&id).and_then(|g| g.install_path.clone());
+                let Some(install_path) = install_path else {
+                    return Task::none();
+                };
+                let exclude = ExcludePatterns::new(&self.config.import_exclude_patterns);
+                Task::perform(
+                    async move {
+                        // Walking the install folder can take a while on a slow drive
+                        tokio::task::spawn_blocking(move || {
+                            executable_candidates(&install_path, &exclude, EXECUTABLE_SEARCH_DEPTH)
+                        })
+                        .await
+                        .unwrap_or_default()
+                    },
+                    move |candidates| Message::ExecutableCandidatesFound(id, candidates),
+                )
             }
 
-            Message::SortChanged(order) => {
-                self.sort_order = order;
+            Message::ExecutableCandidatesFound(id, candidates) => {
+                self.exe_candidates = Some((id, candidates));
                 Task::none()
             }
 
-            Message::ViewModeChanged(mode) => {
-                self.view_mode = mode;
-                Task::none()
+            Message::ExecutableChosen(id, path) => {
+                self.exe_candidates = None;
+                // Marked as the user's choice, so a later sync leaves it alone
+                self.update(Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        executable_path: Some(path),
+                        ..Default::default()
+                    },
+                ))
             }
 
-            // Game management
-            Message::AddGamePressed => {
-                self.current_view = View::AddGame;
-                self.new_game_name.clear();
-                self.new_game_path.clear();
+            Message::CloseExecutableCandidates => {
+                self.exe_candidates = None;
                 Task::none()
             }
 
-            Message::AddGame(game) => {
-                self.library.add_game(game);
-                self.current_view = View::Library;
-                self.save_library()
+            Message::EmulatorCommandInputChanged(command) => {
+                self.emulator_command_input = command;
+                Task::none()
             }
 
-            Message::RemoveGame(id) => {
-                self.library.remove_game(&id);
-                if self.selected_game == Some(id) {
-                    self.selected_game = None;
+            Message::SetEmulatorCommand(id) => {
+                let command = self.emulator_command_input.trim();
+                if command.is_empty() {
+                    return Task::none();
                 }
-                self.save_library()
+                self.update(Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        emulator_command: Some(command.to_string()),
+                        ..Default::default()
+                    },
+                ))
             }
 
-            Message::EditGame(id) => {
-                self.current_view = View::GameDetail(id);
+            Message::InstallPathInputChanged(path) => {
+                self.install_path_input = path;
                 Task::none()
             }
 
-            Message::UpdateGame(id, update) => {
-                if let Some(game) = self.library.get_game_mut(&id) {
-                    if let Some(name) = update.name {
-                        game.name = name;
-                    }
-                    if let Some(path) = update.executable_path {
-                        game.executable_path = path;
-                    }
-                    if let Some(args) = update.launch_args {
-                        game.launch_args = Some(args);
-                    }
-                    if let Some(icon) = update.icon_path {
-                        game.icon_path = Some(icon);
-                    }
+            Message::SetInstallPath(id) => {
+                let path = self.install_path_input.trim();
+                if path.is_empty() {
+                    return Task::none();
                 }
-                self.save_library()
+                self.update(Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        install_path: Some(PathBuf::from(path)),
+                        ..Default::default()
+                    },
+                ))
             }
 
-            Message::ToggleFavorite(id) => {
-                if let Some(game) = self.library.get_game_mut(&id) {
-                    game.toggle_favorite();
+            Message::SavePathInputChanged(path) => {
+                self.save_path_input = path;
+                Task::none()
+            }
+
+            Message::SetSavePath(id) => {
+                let path = self.save_path_input.trim();
+                if path.is_empty() {
+                    return Task::none();
                 }
-                self.save_library()
+                self.save_folder_guesses = None;
+                self.update(Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        save_path: Some(PathBuf::from(path)),
+                        ..Default::default()
+                    },
+                ))
             }
 
-            // Add Game Form
-            Message::NewGameNameChanged(name) => {
-                self.new_game_name = name;
-                Task::none()
+            Message::FindSaveFolders(id) => {
+                let Some(name) = self.library.get_game(&id).map(|g| g.name.clone()) else {
+                    return Task::none();
+                };
+                Task::perform(
+                    async move { guess_save_folders(&name, &platform::save_folder_roots()) },
+                    move |folders| Message::SaveFoldersFound(id, folders),
+                )
             }
 
-            Message::NewGamePathChanged(path) => {
-                self.new_game_path = path;
+            Message::SaveFoldersFound(id, folders) => {
+                self.save_folder_guesses = Some((id, folders));
                 Task::none()
             }
 
-            // Category management
-            Message::AddCategory(name) => {
-                let category = Category::new(name);
-                self.library.add_category(category);
-                self.save_library()
+            Message::BackupSaves(id) => {
+                let save_path = self.library.get_game(&id).and_then(|g| g.save_path.clone());
+                let Some(save_path) = save_path else {
+                    return Task::none();
+                };
+                let backups_dir = self.save_backups_dir();
+                let clock = self.clock.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            backup_saves(&backups_dir, id, &save_path, clock.as_ref())
+                                .map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    move |result| Message::SavesBackedUp(id, result),
+                )
             }
 
-            Message::RemoveCategory(id) => {
-                self.library.remove_category(&id);
-                if self.selected_category == Some(id) {
-                    self.selected_category = None;
-                }
-                self.save_library()
+            Message::SavesBackedUp(id, result) => {
+                let status = match result {
+                    Ok(path) => Ok(format!("Backed up to {}", path.display())),
+                    Err(e) => {
+                        tracing::error!("Failed to back up saves: {}", e);
+                        Err(format!("Backup failed: {}", e))
+                    }
+                };
+                self.save_status = Some((id, status));
+                Task::none()
             }
 
-            Message::AssignCategory(game_id, category_id) => {
-                if let Some(game) = self.library.get_game_mut(&game_id) {
-                    game.add_category(category_id);
-                }
-                self.save_library()
+            Message::ShowSaveBackups(id) => {
+                let backups_dir = self.save_backups_dir();
+                Task::perform(
+                    async move { list_save_backups(&backups_dir, id) },
+                    move |backups| Message::SaveBackupsListed(id, backups),
+                )
             }
 
-            Message::UnassignCategory(game_id, category_id) => {
-                if let Some(game) = self.library.get_game_mut(&game_id) {
-                    game.remove_category(&category_id);
-                }
-                self.save_library()
+            Message::SaveBackupsListed(id, backups) => {
+                self.save_backups = Some((id, backups));
+                Task::none()
             }
 
-            // Settings
-            Message::ThemeChanged(theme_name) => {
-                self.theme = CustomTheme::by_name(&theme_name);
-                self.config.theme = theme_name;
-                self.save_config()
+            Message::AskRestoreSaves(id, backup) => {
+                let Some(game) = self.library.get_game(&id) else {
+                    return Task::none();
+                };
+                self.confirm_dialog = Some(ConfirmDialog {
+                    title: format!("Restore {}'s saves?", game.display_name()),
+                    body: "The current saves are backed up first, then replaced with this backup."
+                        .to_string(),
+                    action: Message::RestoreSaves(id, backup),
+                    game_id: None,
+                    needs_pin: false,
+                    pin_input: String::new(),
+                    error: None,
+                });
+                Task::none()
             }
 
-            Message::SettingChanged(key, value) => {
-                use crate::message::{SettingKey, SettingValue};
-                match (key, value) {
-                    (SettingKey::StartMinimized, SettingValue::Bool(v)) => {
-                        self.config.start_minimized = v;
-                    }
-                    (SettingKey::CloseToTray, SettingValue::Bool(v)) => {
-                        self.config.close_to_tray = v;
-                    }
-                    _ => {}
-                }
-                self.save_config()
+            Message::RestoreSaves(id, backup) => {
+                let save_path = self.library.get_game(&id).and_then(|g| g.save_path.clone());
+                let Some(save_path) = save_path else {
+                    return Task::none();
+                };
+                self.save_backups = None;
+                let backups_dir = self.save_backups_dir();
+                let clock = self.clock.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            restore_saves(&backups_dir, id, &backup, &save_path, clock.as_ref())
+                                .map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    move |result| Message::SavesRestored(id, result),
+                )
             }
 
-            // File dialogs (placeholder - requires native dialog integration)
-            Message::SelectExecutable => {
-                // TODO: Implement native file dialog
-                tracing::info!("File dialog requested");
+            Message::SavesRestored(id, result) => {
+                let status = match result {
+                    Ok(Some(undo)) => Ok(format!(
+                        "Restored. The saves it replaced were backed up to {}",
+                        undo.display()
+                    )),
+                    Ok(None) => Ok("Restored".to_string()),
+                    Err(e) => {
+                        tracing::error!("Failed to restore saves: {}", e);
+                        Err(format!("Restore failed: {}", e))
+                    }
+                };
+                self.save_status = Some((id, status));
                 Task::none()
             }
 
-            Message::ExecutableSelected(path) => {
-                if let Some(p) = path {
-                    self.new_game_path = p.to_string_lossy().to_string();
-                }
+            Message::CloseSaveBackups => {
+                self.save_backups = None;
                 Task::none()
             }
 
-            // Persistence
-            Message::SaveLibrary => self.save_library(),
-
-            Message::LibrarySaved(result) => {
-                match &result {
-                    Ok(()) => tracing::debug!("Library saved"),
-                    Err(e) => tracing::error!("Failed to save library: {}", e),
+            Message::LaunchGameVia(id, source) => {
+                if !self.can_launch(&id) {
+                    tracing::info!("Ignoring launch of {:?}, already starting or running", id);
+                    return Task::none();
                 }
-                Task::none()
+                if self.needs_launch_confirmation(id, Message::LaunchGameVia(id, source)) {
+                    return Task::none();
+                }
+                let Some(game) = self.library.get_game_mut(&id) else {
+                    return Task::none();
+                };
+                let uri = game
+                    .store_links
+                    .iter()
+                    .find(|(s, _)| *s == source)
+                    .and_then(|(s, source_id)| s.launch_uri(source_id));
+                let Some(uri) = uri else {
+                    tracing::warn!("No {} link to launch for {:?}", source.label(), id);
+                    return Task::none();
+                };
+                if game.track_playtime {
+                    game.mark_played(self.clock.as_ref());
+                }
+                self.launch_cooldowns.insert(id, Instant::now());
+                open_uri(id, uri, self.config.prefer_big_picture)
             }
 
-            Message::LoadLibrary => {
-                let library_path = self.library_path();
-                let config_path = self.config_path();
-
-                Task::perform(
-                    async move {
-                        let library = Library::load_or_create(&library_path).await;
-                        let config = Config::load_or_create(&config_path).await;
-                        (library, config)
-                    },
-                    |(library, config)| Message::LibraryLoaded(library, config),
-                )
+            Message::InstallGame(id) => {
+                let Some((source, uri)) = self.library.get_game(&id).and_then(Game::install_uri)
+                else {
+                    return Task::none();
+                };
+                tracing::info!("Installing {:?} through {}", id, source.label());
+                if let Err(e) = crate::launcher::launch_uri(&uri) {
+                    tracing::error!("Failed to open {} to install: {}", source.label(), e);
+                }
+                Task::none()
             }
 
-            Message::LibraryLoaded(library, config) => {
-                let game_count = library.game_count();
-                self.library = library;
-                self.config = config;
-                tracing::info!("Library loaded with {} games", game_count);
+            Message::ConfirmPinChanged(pin) => {
+                if let Some(dialog) = &mut self.confirm_dialog {
+                    dialog.pin_input = pin;
+                }
                 Task::none()
             }
 
-            // Import
-            Message::StartImport(source) => {
-                tracing::info!("Starting import from {:?}", source);
-                self.import_status = ImportStatus::Importing(source);
-
-                Task::perform(
-                    async move {
-                        match source {
-                            GameSource::Steam => {
-                                let importer = SteamImporter::new();
-                                if !importer.is_available() {
-                                    return Err("Steam is not installed".to_string());
-                                }
-                                match importer.scan_games() {
-                                    Ok(detected) => {
-                                        let games: Vec<Game> = detected
-                                            .into_iter()
-                                            .map(|d| d.into_game(GameSource::Steam))
-                                            .collect();
-                                        Ok((games, source))
-                                    }
-                                    Err(e) => Err(e.to_string()),
-                                }
-                            }
-                            _ => Err(format!("{:?} import not yet implemented", source)),
-                        }
-                    },
-                    |result| match result {
-                        Ok((games, source)) => Message::ImportComplete(Ok((games, source))),
-                        Err(e) => Message::ImportComplete(Err(e)),
-                    },
-                )
+            Message::ConfirmAccepted => {
+                let Some(dialog) = &mut self.confirm_dialog else {
+                    return Task::none();
+                };
+                if dialog.needs_pin {
+                    let pin_ok = self
+                        .config
+                        .launch_pin
+                        .as_deref()
+                        .is_some_and(|hash| verify_pin(&dialog.pin_input, hash));
+                    if !pin_ok {
+                        dialog.pin_input.clear();
+                        dialog.error = Some("Wrong PIN".to_string());
+                        return Task::none();
+                    }
+                }
+                let dialog = self.confirm_dialog.take().expect("checked above");
+                self.launch_confirmed = dialog.game_id;
+                self.pin_confirmed = dialog.needs_pin;
+                let task = self.update(dialog.action);
+                self.pin_confirmed = false;
+                task
             }
 
-            Message::ImportProgress(_progress) => Task::none(),
+            Message::ConfirmCancelled => {
+                self.confirm_dialog = None;
+                Task::none()
+            }
 
-            Message::ImportComplete(result) => {
-                match result {
-                    Ok((games, source)) => {
-                        let count = games.len();
-                        for game in games {
-                            self.library.add_game(game);
+            Message::GameLaunched(id, result) => {
+                let window_task = match &result {
+                    Ok(pid) => {
+                        tracing::info!("Game launched successfully");
+                        let mut now_playing = Task::none();
+                        let started_at = self.clock.now();
+                        // Store and URL launches can't be followed, but they did start
+                        let status = self.write_launch_status(id, started_at, true, pid.is_some());
+                        if pid.is_some() {
+                            self.running_games.insert(
+                                id,
+                                RunningGame {
+                                    pid: *pid,
+                                    usage: None,
+                                    started: Instant::now(),
+                                    started_at,
+                                },
+                            );
+                            now_playing = self.open_now_playing_window();
                         }
-                        self.import_status = ImportStatus::Complete { count, source };
-                        self.save_library()
+                        let clock = self.clock.as_ref();
+                        let launched = self.library.get_game(&id).map(|game| {
+                            let name = game.display_name();
+                            ActivityEntry::game(ActivityKind::Launched, id, &name, clock)
+                        });
+                        Task::batch([
+                            now_playing,
+                            status,
+                            self.update_discord_presence(),
+                            self.apply_launch_window_behavior(),
+                            self.record_activity(launched.into_iter().collect()),
+                        ])
+                    }
+                    // Not an error, the user chose not to start the game
+                    Err(LaunchError::ElevationDeclined) => {
+                        tracing::info!("Administrator prompt declined, game not started");
+                        self.launch_cooldowns.remove(&id);
+                        Task::none()
                     }
                     Err(e) => {
-                        tracing::error!("Import failed: {}", e);
-                        self.import_status = ImportStatus::Error(e);
+                        tracing::error!("Failed to launch game: {}", e);
+                        self.launch_cooldowns.remove(&id);
                         Task::none()
                     }
-                }
+                };
+                // Save library to persist the last_played update
+                Task::batch([window_task, self.save_library()])
             }
 
-            Message::ClearLibrary => {
-                tracing::info!("Clearing library");
-                self.library = Library::new();
-                self.import_status = ImportStatus::Idle;
+            Message::GameExited(id, minutes, exit) => {
+                tracing::info!("Game {:?} exited after {} minutes ({:?})", id, minutes, exit);
+                let exited_game = self.running_games.remove(&id);
+                self.launch_cooldowns.remove(&id);
+
+                // Point the status file at the newest game still running, if any
+                let status = match (self.newest_running_game(), exited_game) {
+                    (Some((other, started_at)), _) => {
+                        self.write_launch_status(other, started_at, true, true)
+                    }
+                    (None, Some(exited)) => {
+                        self.write_launch_status(id, exited.started_at, false, true)
+                    }
+                    (None, None) => Task::none(),
+                };
+
+                // Session lengths are only kept for games whose playtime is tracked
+                let exited = self.library.get_game(&id).map(|game| {
+                    let name = game.display_name();
+                    let mut entry =
+                        ActivityEntry::game(ActivityKind::Exited, id, &name, self.clock.as_ref());
+                    entry.minutes = game.track_playtime.then_some(minutes);
+                    entry
+                });
+                let activity = self.record_activity(exited.into_iter().collect());
+
+                let tracked = self.library.get_game_mut(&id).filter(|game| game.track_playtime);
+                let mut save = match tracked {
+                    Some(game) if minutes > 0 => {
+                        game.add_playtime(minutes);
+                        self.save_library()
+                    }
+                    _ => Task::none(),
+                };
+                save = Task::batch([save, activity, status, self.update_discord_presence()]);
+                if self.running_games.is_empty() {
+                    if let Some(now_playing) = self.now_playing_window.take() {
+                        save = Task::batch([save, window::close(now_playing)]);
+                    }
+                }
+
+                // Offer the captured output when the game failed or crashed
+                let log_path = self.game_log_path(&id);
+                if exit.is_some_and(|s| !s.success()) && log_path.exists() {
+                    self.failed_launch_logs.insert(id, log_path);
+                } else {
+                    self.failed_launch_logs.remove(&id);
+                }
+
+                // With several games running, wait for the last one
+                if self.config.restore_window_on_game_exit
+                    && self.window_hidden_for_game
+                    && self.running_games.is_empty()
+                {
+                    self.window_hidden_for_game = false;
+                    return Task::batch([save, restore_window()]);
+                }
+                save
+            }
+
+            Message::WindowFocused(id) => {
+                // The user brought the window back themselves, so don't pop it
+                // up again when the game exits
+                if Some(id) == self.main_window {
+                    self.window_hidden_for_game = false;
+                }
+                Task::none()
+            }
+
+            Message::WindowClosed(id) => {
+                if Some(id) == self.main_window {
+                    return self.quit();
+                }
+                // Games keep being tracked, the window just stays shut until
+                // the next launch
+                if Some(id) == self.now_playing_window {
+                    self.now_playing_window = None;
+                }
+                Task::none()
+            }
+
+            Message::WindowResized(id, size) => {
+                if Some(id) == self.main_window {
+                    self.window_width = size.width;
+                    self.sidebar_menu_open &= self.is_narrow_layout();
+                }
+                Task::none()
+            }
+
+            Message::StopGame(id) => {
+                match self.running_games.get(&id).and_then(|running| running.pid) {
+                    Some(pid) if self.resource_sampler.stop(pid) => {
+                        tracing::info!("Stopped {:?} (pid {})", id, pid);
+                    }
+                    _ => tracing::warn!("Could not stop {:?}, its process is gone", id),
+                }
+                // Its exit arrives as GameExited like any other
+                Task::none()
+            }
+
+            Message::SampleResources => {
+                let pids: Vec<u32> = self.running_games.values().filter_map(|r| r.pid).collect();
+                let usage = self.resource_sampler.sample(&pids);
+                for running in self.running_games.values_mut() {
+                    running.usage = running.pid.and_then(|pid| usage.get(&pid).copied());
+                }
+                Task::none()
+            }
+
+            // Search and filtering
+            Message::SearchChanged(query) => {
+                self.search_query = query;
+                Task::none()
+            }
+
+            Message::FilterSelected(filter) => {
+                self.filter = filter;
+                self.sort_override = None;
+                self.sidebar_menu_open = false;
+                Task::none()
+            }
+
+            Message::SortChanged(order) => {
+                // Under a category with its own sort this is only a temporary
+                // change, the library sort stays as it was
+                if self.library.category_sort(self.filter).is_some() {
+                    self.sort_override = Some(order);
+                    return Task::none();
+                }
+                self.sort_order = order;
+                self.config.default_sort = order;
+                self.save_config()
+            }
+
+            Message::ViewModeChanged(mode) => {
+                self.view_mode = mode;
+                self.config.default_view_mode = mode;
+                self.save_config()
+            }
+
+            Message::ToggleViewMode => {
+                self.update(Message::ViewModeChanged(self.view_mode.toggled()))
+            }
+
+            Message::GroupByChanged(group_by) => {
+                self.group_by = group_by;
+                Task::none()
+            }
+
+            // Sidebar
+            Message::ToggleSidebar => {
+                self.config.sidebar_collapsed = !self.config.sidebar_collapsed;
+                self.save_config()
+            }
+
+            Message::ToggleSidebarMenu => {
+                self.sidebar_menu_open = !self.sidebar_menu_open;
+                Task::none()
+            }
+
+            Message::SidebarDragStarted => {
+                self.sidebar_dragging = true;
+                Task::none()
+            }
+
+            Message::SidebarDragged(x) => {
+                if self.sidebar_dragging {
+                    self.config.sidebar_width = x.clamp(SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH);
+                }
+                Task::none()
+            }
+
+            // Only saved once the drag is over, not on every mouse move
+            Message::SidebarDragEnded => {
+                if !self.sidebar_dragging {
+                    return Task::none();
+                }
+                self.sidebar_dragging = false;
+                self.save_config()
+            }
+
+            // Game management
+            Message::AddGamePressed => {
+                self.current_view = View::AddGame;
+                self.sidebar_menu_open = false;
+                self.new_game_name.clear();
+                self.new_game_path.clear();
+                Task::none()
+            }
+
+            Message::AddGame(game) => {
+                let name = game.display_name();
+                let added =
+                    ActivityEntry::game(ActivityKind::Added, game.id, &name, self.clock.as_ref());
+                self.search_index.insert(&game);
+                self.library.add_game(game);
+                self.current_view = View::Library;
+                Task::batch([self.save_library(), self.record_activity(vec![added])])
+            }
+
+            Message::RemoveGame(id) => {
+                let removed = self.library.get_game(&id).map(|game| {
+                    let name = game.display_name();
+                    ActivityEntry::game(ActivityKind::Removed, id, &name, self.clock.as_ref())
+                });
+                let activity = self.record_activity(removed.into_iter().collect());
+                self.library.remove_game(&id);
+                self.search_index.remove(&id);
+                self.selected_games.remove(&id);
+                if self.selected_game == Some(id) {
+                    self.selected_game = None;
+                }
+                Task::batch([self.save_library(), activity])
+            }
+
+            Message::DuplicateGame(id) => {
+                let Some(copy_id) = self.library.duplicate_game(&id, self.clock.as_ref()) else {
+                    return Task::none();
+                };
+                if let Some(copy) = self.library.get_game(&copy_id) {
+                    self.search_index.insert(copy);
+                }
+                self.selected_game = Some(copy_id);
+                Task::batch([self.update(Message::EditGame(copy_id)), self.save_library()])
+            }
+
+            Message::EditGame(id) => {
+                self.current_view = View::GameDetail(id);
+                let game = self.library.get_game(&id);
+                self.save_path_input = game
+                    .and_then(|game| game.save_path.as_ref())
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                self.emulator_command_input =
+                    game.and_then(|game| game.emulator_command.clone()).unwrap_or_default();
+                self.install_path_input = game
+                    .and_then(|game| game.install_path.as_ref())
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                self.save_folder_guesses = None;
+                self.save_backups = None;
+                self.save_status = None;
+                Task::none()
+            }
+
+            Message::UpdateGame(id, update) => {
+                let stops_confirming = update.confirm_before_launch == Some(false)
+                    && self.library.get_game(&id).is_some_and(|g| g.confirm_before_launch);
+                if stops_confirming {
+                    let action = Message::UpdateGame(id, update.clone());
+                    if self.needs_current_pin("Stop confirming this game's launches?", action) {
+                        return Task::none();
+                    }
+                }
+                if let Some(game) = self.library.get_game_mut(&id) {
+                    if let Some(name) = update.name {
+                        game.name = name;
+                        game.mark_user_modified(GameField::Name);
+                        self.search_index.insert(game);
+                    }
+                    if let Some(path) = update.executable_path {
+                        game.executable_path = path;
+                        game.mark_user_modified(GameField::ExecutablePath);
+                    }
+                    if let Some(path) = update.install_path {
+                        self.install_path_input = path.display().to_string();
+                        game.install_path = Some(path);
+                        game.mark_user_modified(GameField::InstallPath);
+                    }
+                    if let Some(args) = update.launch_args {
+                        game.launch_args = Some(args);
+                        game.mark_user_modified(GameField::LaunchArgs);
+                    }
+                    if let Some(icon) = update.icon_path {
+                        game.icon_path = Some(icon);
+                    }
+                    if let Some(prefs) = update.display_prefs {
+                        game.display_prefs = prefs;
+                    }
+                    if let Some(in_terminal) = update.launch_in_terminal {
+                        game.launch_in_terminal = Some(in_terminal);
+                    }
+                    if let Some(confirm) = update.confirm_before_launch {
+                        game.confirm_before_launch = confirm;
+                    }
+                    if let Some(run_as_admin) = update.run_as_admin {
+                        game.run_as_admin = run_as_admin;
+                    }
+                    if let Some(track) = update.track_playtime {
+                        game.track_playtime = track;
+                    }
+                    if let Some(status) = update.status {
+                        game.status = status;
+                    }
+                    if let Some(command) = update.emulator_command {
+                        self.emulator_command_input = command.clone();
+                        game.emulator_command = Some(command);
+                        game.mark_user_modified(GameField::EmulatorCommand);
+                    }
+                    if let Some(save_path) = update.save_path {
+                        self.save_path_input = save_path.display().to_string();
+                        game.save_path = Some(save_path);
+                    }
+                }
+                self.save_library()
+            }
+
+            Message::ToggleFavorite(id) => {
+                if let Some(game) = self.library.get_game_mut(&id) {
+                    game.toggle_favorite();
+                }
+                self.save_library()
+            }
+
+            Message::ToggleHidden(id) => {
+                if let Some(game) = self.library.get_game_mut(&id) {
+                    game.toggle_hidden();
+                }
+                self.save_library()
+            }
+
+            Message::TogglePinned(id) => {
+                self.library.toggle_pinned(id);
+                self.save_library()
+            }
+
+            Message::ShowHiddenToggled(show) => {
+                self.show_hidden = show;
+                Task::none()
+            }
+
+            Message::ToggleGameSelected(id) => {
+                if !self.selected_games.remove(&id) {
+                    self.selected_games.insert(id);
+                }
+                self.bundle_export = None;
+                Task::none()
+            }
+
+            Message::ClearSelection => {
+                self.selected_games.clear();
+                self.bundle_export = None;
+                Task::none()
+            }
+
+            Message::ExportSelected => {
+                Task::perform(platform::pick_export_folder(), Message::ExportFolderPicked)
+            }
+
+            Message::ExportFolderPicked(folder) => {
+                let Some(folder) = folder else {
+                    return Task::none();
+                };
+                let ids: Vec<GameId> = self.selected_games.iter().copied().collect();
+                let path =
+                    folder.join(format!("games-{}", self.clock.now().format("%Y%m%d-%H%M%S")));
+                let library = self.library.clone();
+                self.bundle_export = None;
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            library
+                                .export_subset(&ids, &path)
+                                .map(|report| (path, report))
+                                .map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    Message::SelectionExported,
+                )
+            }
+
+            Message::SelectionExported(result) => {
+                match &result {
+                    Ok((path, report)) => {
+                        tracing::info!("Exported {} games to {:?}", report.games, path)
+                    }
+                    Err(e) => tracing::error!("Failed to export games: {}", e),
+                }
+                self.bundle_export = Some(result);
+                Task::none()
+            }
+
+            Message::MergeSelected => {
+                let Some((manual, imported)) = self.manual_merge_pair() else {
+                    return Task::none();
+                };
+                let Some(game) = self.library.get_game(&imported) else {
+                    return Task::none();
+                };
+                let moved = format!(
+                    "Its tags, art, settings and playtime move to the {} entry.",
+                    game.source.label()
+                );
+                self.confirm_dialog = Some(ConfirmDialog {
+                    title: format!("Merge into {}?", game.display_name()),
+                    body: format!("The manual entry is removed. {}", moved),
+                    action: Message::MergeIntoImported(manual, imported),
+                    game_id: None,
+                    needs_pin: false,
+                    pin_input: String::new(),
+                    error: None,
+                });
+                Task::none()
+            }
+
+            Message::MergeIntoImported(manual, imported) => {
+                if !self.library.merge_manual_into(&manual, &imported) {
+                    return Task::none();
+                }
+                tracing::info!("Merged manual entry {:?} into {:?}", manual, imported);
+                self.search_index.remove(&manual);
+                if let Some(game) = self.library.get_game(&imported) {
+                    self.search_index.insert(game);
+                }
+                self.selected_games.clear();
+                if self.selected_game == Some(manual) {
+                    self.selected_game = Some(imported);
+                }
+                self.save_library()
+            }
+
+            Message::OpenContextMenu(id) => {
+                self.context_menu = Some(id);
+                Task::none()
+            }
+
+            Message::CloseContextMenu => {
+                self.context_menu = None;
+                Task::none()
+            }
+
+            Message::ContextMenuAction(action) => {
+                self.context_menu = None;
+                self.update(*action)
+            }
+
+            // Add Game Form
+            Message::NewGameNameChanged(name) => {
+                self.new_game_name = name;
+                Task::none()
+            }
+
+            Message::NewGamePathChanged(path) => {
+                self.new_game_path = path;
+                Task::none()
+            }
+
+            // Category management
+            Message::CategoryNameInputChanged(name) => {
+                self.category_name_input = name;
+                Task::none()
+            }
+
+            Message::AddCategory(name) => {
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return Task::none();
+                }
+                self.category_name_input.clear();
+                // Reuse a category of the same name rather than adding a twin
+                self.library
+                    .add_category(Category::new(name), CategoryCollision::Reject);
+                self.save_library()
+            }
+
+            Message::SetCategoryIcon(id, icon) => {
+                if let Some(category) = self.library.get_category_mut(&id) {
+                    category.icon = icon;
+                }
+                self.save_library()
+            }
+
+            Message::SetCategorySort(id, sort) => {
+                if let Some(category) = self.library.get_category_mut(&id) {
+                    category.default_sort = sort;
+                }
+                if self.filter == Some(Filter::Category(id)) {
+                    self.sort_override = None;
+                }
+                self.save_library()
+            }
+
+            Message::EditCategoryRule(id) => {
+                self.editing_rule = id;
+                Task::none()
+            }
+
+            Message::AddRuleCondition(id, condition) => {
+                if let Some(category) = self.library.get_category_mut(&id) {
+                    let rule = category.rule.get_or_insert_with(SmartRule::default);
+                    if !rule.conditions.contains(&condition) {
+                        rule.conditions.push(condition);
+                    }
+                }
+                self.save_library()
+            }
+
+            Message::RemoveRuleCondition(id, index) => {
+                if let Some(rule) = self
+                    .library
+                    .get_category_mut(&id)
+                    .and_then(|category| category.rule.as_mut())
+                {
+                    if index < rule.conditions.len() {
+                        rule.conditions.remove(index);
+                    }
+                }
+                self.save_library()
+            }
+
+            Message::ClearCategoryRule(id) => {
+                if let Some(category) = self.library.get_category_mut(&id) {
+                    category.rule = None;
+                }
+                self.editing_rule = None;
+                self.save_library()
+            }
+
+            Message::RemoveCategory(id) => {
+                self.library.remove_category(&id);
+                if self.editing_rule == Some(id) {
+                    self.editing_rule = None;
+                }
+                if self.filter == Some(Filter::Category(id)) {
+                    self.filter = None;
+                }
+                if self.config.forget_missing_manual_category(&self.library) {
+                    return Task::batch([self.save_library(), self.save_config()]);
+                }
                 self.save_library()
             }
 
-            // Misc
-            Message::Tick => Task::none(),
-            Message::None => Task::none(),
+            Message::DefaultManualCategoryChanged(id) => {
+                self.config.default_manual_category = id;
+                self.save_config()
+            }
+
+            Message::AssignCategory(game_id, category_id) => {
+                if self.is_smart_category(&category_id) {
+                    return Task::none();
+                }
+                if let Some(game) = self.library.get_game_mut(&game_id) {
+                    game.add_category(category_id);
+                }
+                self.save_library()
+            }
+
+            // Games tagged by hand before a category got its rule can still be untagged
+            Message::UnassignCategory(game_id, category_id) => {
+                if let Some(game) = self.library.get_game_mut(&game_id) {
+                    game.remove_category(&category_id);
+                }
+                self.save_library()
+            }
+
+            // Settings
+            Message::ThemeChanged(theme_name) => {
+                self.theme = CustomTheme::by_name(&theme_name);
+                self.config.theme = theme_name;
+                Task::batch([self.save_config(), self.query_system_theme()])
+            }
+
+            Message::SystemThemeChanged(mode) => {
+                if CustomTheme::follows_system(&self.config.theme) {
+                    self.theme = CustomTheme::for_mode(mode);
+                }
+                Task::none()
+            }
+
+            Message::SettingChanged(key, value) => {
+                use crate::message::{SettingKey, SettingValue};
+                match (key, value) {
+                    (SettingKey::EncryptLibrary, SettingValue::Bool(v)) => {
+                        self.config.encrypt_library = v;
+                        if !v && self.library_passphrase.take().is_some() {
+                            // Rewrite the library as plaintext
+                            return Task::batch([self.save_config(), self.save_library()]);
+                        }
+                        if v {
+                            let forget = self.delete_plaintext_history();
+                            return Task::batch([self.save_config(), forget]);
+                        }
+                    }
+                    (SettingKey::StartMinimized, SettingValue::Bool(v)) => {
+                        self.config.start_minimized = v;
+                    }
+                    (SettingKey::CloseToTray, SettingValue::Bool(v)) => {
+                        self.config.close_to_tray = v;
+                    }
+                    (SettingKey::RestoreWindowOnGameExit, SettingValue::Bool(v)) => {
+                        self.config.restore_window_on_game_exit = v;
+                    }
+                    (SettingKey::ShowNowPlayingWindow, SettingValue::Bool(v)) => {
+                        self.config.show_now_playing_window = v;
+                    }
+                    (SettingKey::PreferBigPicture, SettingValue::Bool(v)) => {
+                        self.config.prefer_big_picture = v;
+                    }
+                    (SettingKey::ConfirmInstallOnLaunch, SettingValue::Bool(v)) => {
+                        self.config.confirm_install_on_launch = v;
+                    }
+                    (SettingKey::ResolveExecutableFolders, SettingValue::Bool(v)) => {
+                        self.config.resolve_executable_folders = v;
+                    }
+                    (SettingKey::ShowResourceUsage, SettingValue::Bool(v)) => {
+                        self.config.show_resource_usage = v;
+                    }
+                    (SettingKey::HideEmptyCategories, SettingValue::Bool(v)) => {
+                        self.config.hide_empty_categories = v;
+                    }
+                    (SettingKey::CropCardArt, SettingValue::Bool(v)) => {
+                        self.config.crop_card_art = v;
+                    }
+                    (SettingKey::LaunchInTerminal, SettingValue::Bool(v)) => {
+                        self.config.launch_in_terminal = v;
+                    }
+                    (SettingKey::CaptureGameOutput, SettingValue::Bool(v)) => {
+                        self.config.capture_game_output = v;
+                    }
+                    (SettingKey::WriteStatusFile, SettingValue::Bool(v)) => {
+                        self.config.write_status_file = v;
+                    }
+                    (SettingKey::DiscordRpcEnabled, SettingValue::Bool(v)) => {
+                        self.config.discord_rpc_enabled = v;
+                        return Task::batch([self.save_config(), self.update_discord_presence()]);
+                    }
+                    (SettingKey::DiscordClientId, SettingValue::String(v)) => {
+                        self.config.discord_client_id =
+                            Some(v).filter(|id| !id.trim().is_empty());
+                    }
+                    (SettingKey::ShowSources, SettingValue::Bool(v)) => {
+                        self.config.show_sources = v;
+                    }
+                    (SettingKey::ImportSteamCollections, SettingValue::Bool(v)) => {
+                        self.config.import_steam_collections = v;
+                    }
+                    (SettingKey::MergeCrossStore, SettingValue::Bool(v)) => {
+                        self.config.merge_cross_store = v;
+                    }
+                    (SettingKey::SteamGridDbApiKey, SettingValue::String(v)) => {
+                        self.config.steamgriddb_api_key =
+                            Some(v).filter(|key| !key.trim().is_empty());
+                        #[cfg(feature = "steamgriddb")]
+                        {
+                            self.steamgriddb_key_draft = None;
+                        }
+                    }
+                    _ => {}
+                }
+                self.save_config()
+            }
+
+            Message::LaunchBehaviorChanged(behavior) => {
+                self.config.on_launch = behavior;
+                self.save_config()
+            }
+
+            Message::DensityChanged(density) => {
+                self.config.ui_density = density;
+                self.save_config()
+            }
+
+            Message::SettingsSectionSelected(section) => {
+                self.settings_section = section;
+                Task::none()
+            }
+
+            Message::SettingsSectionStep(forward) => {
+                self.settings_section = if forward {
+                    self.settings_section.next()
+                } else {
+                    self.settings_section.previous()
+                };
+                Task::none()
+            }
+
+            Message::FocusStep(forward) => {
+                if forward {
+                    iced::widget::operation::focus_next()
+                } else {
+                    iced::widget::operation::focus_previous()
+                }
+            }
+
+            Message::FontScaleChanged(scale) => {
+                self.config.font_scale = scale.clamp(FONT_SCALE_MIN, FONT_SCALE_MAX);
+                self.save_config()
+            }
+
+            Message::NarrowLayoutWidthChanged(width) => {
+                self.config.narrow_layout_width = width;
+                self.sidebar_menu_open &= self.is_narrow_layout();
+                self.save_config()
+            }
+
+            Message::NewBadgeDaysChanged(days) => {
+                self.config.new_badge_days = days;
+                self.save_config()
+            }
+
+            Message::ClearNewBadges => {
+                self.config.new_badges_cleared_at = Some(self.clock.now());
+                self.save_config()
+            }
+
+            Message::AutoScanIntervalChanged(minutes) => {
+                self.config.auto_scan_interval_minutes = minutes;
+                self.save_config()
+            }
+
+            Message::IncludeNotInstalledToggled(source, include) => {
+                if let Some(options) = self.config.source_import.get_mut(source) {
+                    options.include_not_installed = include;
+                }
+                self.save_config()
+            }
+
+            Message::ImportWarnThresholdChanged(games) => {
+                self.config.import_warn_threshold = games;
+                self.save_config()
+            }
+
+            Message::SourceBadgeToggled(source, shown) => {
+                self.config.hidden_sources.retain(|s| *s != source);
+                if !shown {
+                    self.config.hidden_sources.push(source);
+                }
+                self.save_config()
+            }
+
+            Message::CardStyleChanged(style) => {
+                self.config.card_style = style;
+                self.save_config()
+            }
+
+            Message::CardHovered(id) => {
+                self.hovered_card = Some(id);
+                Task::none()
+            }
+
+            // Moving straight onto another card may report the enter first
+            Message::CardUnhovered(id) => {
+                if self.hovered_card == Some(id) {
+                    self.hovered_card = None;
+                }
+                Task::none()
+            }
+
+            Message::SteamLibraryPathInputChanged(path) => {
+                self.steam_path_input = path;
+                self.steam_path_error = None;
+                Task::none()
+            }
+
+            Message::AddSteamLibraryPath => {
+                let path = PathBuf::from(self.steam_path_input.trim());
+                if steamapps_dir(&path).is_none() {
+                    self.steam_path_error =
+                        Some(format!("No steamapps folder found in {}", path.display()));
+                    return Task::none();
+                }
+                if !self.config.steam_library_paths.contains(&path) {
+                    self.config.steam_library_paths.push(path);
+                }
+                self.steam_path_input.clear();
+                self.steam_path_error = None;
+                self.save_config()
+            }
+
+            Message::RemoveSteamLibraryPath(path) => {
+                self.config.steam_library_paths.retain(|p| p != &path);
+                self.save_config()
+            }
+
+            Message::ExcludePatternInputChanged(pattern) => {
+                self.exclude_pattern_input = pattern;
+                Task::none()
+            }
+
+            Message::AddExcludePattern => {
+                let pattern = self.exclude_pattern_input.trim().to_string();
+                if pattern.is_empty() {
+                    return Task::none();
+                }
+                if !self.config.import_exclude_patterns.contains(&pattern) {
+                    self.config.import_exclude_patterns.push(pattern);
+                }
+                self.exclude_pattern_input.clear();
+                self.save_config()
+            }
+
+            Message::RemoveExcludePattern(pattern) => {
+                self.config.import_exclude_patterns.retain(|p| p != &pattern);
+                self.save_config()
+            }
+
+            Message::EmulatorExtensionInputChanged(extension) => {
+                self.emulator_extension_input = extension;
+                Task::none()
+            }
+
+            Message::EmulatorTemplateInputChanged(template) => {
+                self.emulator_template_input = template;
+                Task::none()
+            }
+
+            Message::AddEmulator => {
+                let extension = self
+                    .emulator_extension_input
+                    .trim()
+                    .trim_start_matches('.')
+                    .to_lowercase();
+                let template = self.emulator_template_input.trim().to_string();
+                if extension.is_empty() || template.is_empty() {
+                    return Task::none();
+                }
+                self.config.emulators.insert(extension, template);
+                self.emulator_extension_input.clear();
+                self.emulator_template_input.clear();
+                self.save_config()
+            }
+
+            Message::RemoveEmulator(extension) => {
+                self.config.emulators.remove(&extension);
+                self.save_config()
+            }
+
+            Message::LibraryPassphraseInputChanged(input) => {
+                self.passphrase_input = input;
+                Task::none()
+            }
+
+            Message::SetLibraryPassphrase => {
+                if self.passphrase_input.is_empty() {
+                    return Task::none();
+                }
+                self.library_passphrase = Some(std::mem::take(&mut self.passphrase_input));
+                self.config.encrypt_library = true;
+                let forget = self.delete_plaintext_history();
+                Task::batch([self.save_config(), self.save_library(), forget])
+            }
+
+            Message::LaunchPinInputChanged(pin) => {
+                self.launch_pin_input = pin;
+                Task::none()
+            }
+
+            Message::SetLaunchPin => {
+                if self.launch_pin_input.is_empty()
+                    || self.needs_current_pin("Change the launch PIN?", Message::SetLaunchPin)
+                {
+                    return Task::none();
+                }
+                let pin = std::mem::take(&mut self.launch_pin_input);
+                match hash_pin(&pin) {
+                    Ok(hash) => self.config.launch_pin = Some(hash),
+                    Err(e) => tracing::error!("Failed to hash PIN: {}", e),
+                }
+                self.save_config()
+            }
+
+            Message::ClearLaunchPin => {
+                if self.needs_current_pin("Remove the launch PIN?", Message::ClearLaunchPin) {
+                    return Task::none();
+                }
+                self.config.launch_pin = None;
+                self.save_config()
+            }
+
+            Message::RevealPath(path) => {
+                if let Err(e) = platform::reveal_in_file_manager(&path) {
+                    tracing::error!("Failed to reveal {:?}: {}", path, e);
+                }
+                Task::none()
+            }
+
+            Message::CopyToClipboard(contents) => iced::clipboard::write(contents),
+
+            // Artwork
+            Message::DownloadMissingArt => {
+                if matches!(self.art_status, ArtStatus::Downloading(_)) {
+                    return Task::none();
+                }
+
+                let force = self.art_force_redownload;
+                let requests: Vec<ArtRequest> = self
+                    .library
+                    .games
+                    .values()
+                    .filter_map(|g| ArtRequest::from_game(g, force))
+                    .collect();
+                let art_dir = cache::art_dir(&self.data_dir.path);
+                let steam_path = SteamImporter::new().steam_path().map(|p| p.to_path_buf());
+
+                tracing::info!("Fetching artwork for {} games", requests.len());
+                self.art_status = ArtStatus::Downloading(None);
+
+                Task::stream(iced::stream::channel(1, async move |mut output| {
+                    let total = requests.len();
+                    let downloader = cache::Downloader::shared();
+                    let mut fetches = iced::futures::stream::iter(requests.into_iter().map(|r| {
+                        cache::fetch_art(downloader.clone(), r, art_dir.clone(), steam_path.clone())
+                    }))
+                    .buffer_unordered(ART_DOWNLOAD_CONCURRENCY);
+
+                    let mut results = Vec::new();
+                    let mut current = 0;
+                    while let Some((request, paths)) = fetches.next().await {
+                        current += 1;
+                        let progress = ImportProgress {
+                            source: request.source,
+                            current,
+                            total,
+                            current_game: Some(request.name),
+                        };
+                        let _ = output.send(Message::ArtDownloadProgress(progress)).await;
+                        if !paths.is_empty() {
+                            results.push((request.game_id, paths));
+                        }
+                    }
+
+                    let _ = output.send(Message::ArtDownloadComplete(results)).await;
+                }))
+            }
+
+            Message::ArtForceRedownloadToggled(force) => {
+                self.art_force_redownload = force;
+                Task::none()
+            }
+
+            Message::ArtDownloadProgress(progress) => {
+                self.art_status = ArtStatus::Downloading(Some(progress));
+                Task::none()
+            }
+
+            Message::ArtDownloadComplete(results) => {
+                let total = match &self.art_status {
+                    ArtStatus::Downloading(Some(progress)) => progress.total,
+                    _ => results.len(),
+                };
+                let updated = results.len();
+
+                for (id, paths) in results {
+                    if let Some(game) = self.library.get_game_mut(&id) {
+                        if paths.banner_path.is_some() {
+                            game.banner_path = paths.banner_path;
+                            game.banner_shape = paths.banner_shape;
+                        }
+                        if paths.icon_path.is_some() {
+                            game.icon_path = paths.icon_path;
+                        }
+                    }
+                }
+
+                tracing::info!("Artwork updated for {} of {} games", updated, total);
+                self.art_status = ArtStatus::Complete { updated, total };
+                // Persist all updated paths in one go
+                self.save_library()
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::FindArtwork(id) => {
+                let Some(game) = self.library.get_game(&id) else {
+                    return Task::none();
+                };
+
+                let Some(api_key) = self.config.steamgriddb_api_key.clone() else {
+                    self.artwork_picker = Some(ArtworkPicker {
+                        game_id: id,
+                        status: ArtworkPickerStatus::Error(
+                            steamgriddb::SteamGridDbError::MissingKey.to_string(),
+                        ),
+                        pending: Vec::new(),
+                        loading_more: false,
+                    });
+                    return Task::none();
+                };
+
+                self.artwork_picker = Some(ArtworkPicker {
+                    game_id: id,
+                    status: ArtworkPickerStatus::Searching,
+                    pending: Vec::new(),
+                    loading_more: false,
+                });
+
+                let name = game.name.clone();
+                let source = game.source;
+                let source_id = game.source_id.clone();
+                Task::perform(
+                    steamgriddb::find_artwork(api_key, name, source, source_id),
+                    move |result| Message::ArtworkCandidatesFound(id, result),
+                )
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::ArtworkCandidatesFound(id, result) => {
+                // Ignore results for a picker the user already closed or replaced
+                let Some(picker) = self.artwork_picker.as_mut().filter(|p| p.game_id == id) else {
+                    return Task::none();
+                };
+                match result {
+                    Ok(entries) => {
+                        picker.pending = entries;
+                        self.update(Message::MoreArtwork(id))
+                    }
+                    Err(e) => {
+                        tracing::warn!("SteamGridDB lookup failed: {}", e);
+                        picker.status = ArtworkPickerStatus::Error(e.to_string());
+                        Task::none()
+                    }
+                }
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::MoreArtwork(id) => {
+                let Some(picker) = self.artwork_picker.as_mut().filter(|p| p.game_id == id) else {
+                    return Task::none();
+                };
+                if picker.loading_more || picker.pending.is_empty() {
+                    return Task::none();
+                }
+                let count = picker.pending.len().min(steamgriddb::CANDIDATES_PER_PAGE);
+                let page: Vec<_> = picker.pending.drain(..count).collect();
+                picker.loading_more = true;
+                Task::perform(steamgriddb::fetch_candidates(page), move |candidates| {
+                    Message::ArtworkThumbnailsLoaded(id, candidates)
+                })
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::ArtworkThumbnailsLoaded(id, candidates) => {
+                let Some(picker) = self.artwork_picker.as_mut().filter(|p| p.game_id == id) else {
+                    return Task::none();
+                };
+                picker.loading_more = false;
+                let loaded = candidates.into_iter().map(|c| {
+                    let handle = iced::widget::image::Handle::from_bytes(c.thumbnail.clone());
+                    (c, handle)
+                });
+                match &mut picker.status {
+                    ArtworkPickerStatus::Ready(shown) => shown.extend(loaded),
+                    // A candidate is already downloading, or the search failed
+                    ArtworkPickerStatus::Downloading | ArtworkPickerStatus::Error(_) => {}
+                    ArtworkPickerStatus::Searching => {
+                        let shown: Vec<_> = loaded.collect();
+                        picker.status = if shown.is_empty() && picker.pending.is_empty() {
+                            ArtworkPickerStatus::Error(
+                                steamgriddb::SteamGridDbError::NotFound.to_string(),
+                            )
+                        } else {
+                            ArtworkPickerStatus::Ready(shown)
+                        };
+                    }
+                }
+                Task::none()
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::ArtworkCandidateChosen(id, index) => {
+                let Some(picker) = self.artwork_picker.as_mut().filter(|p| p.game_id == id) else {
+                    return Task::none();
+                };
+                let ArtworkPickerStatus::Ready(candidates) = &picker.status else {
+                    return Task::none();
+                };
+                let Some((candidate, _)) = candidates.get(index).cloned() else {
+                    return Task::none();
+                };
+
+                picker.status = ArtworkPickerStatus::Downloading;
+                let art_dir = cache::art_dir(&self.data_dir.path);
+                let kind = candidate.kind;
+                let file_stem = match kind {
+                    steamgriddb::ArtKind::Icon => format!("{}_icon", id.0),
+                    _ => format!("{}_banner", id.0),
+                };
+
+                Task::perform(
+                    async move {
+                        steamgriddb::download_candidate(candidate, &art_dir, &file_stem)
+                            .await
+                            .map(|path| (kind, path))
+                    },
+                    move |result| Message::ArtworkDownloaded(id, result),
+                )
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::ArtworkDownloaded(id, result) => match result {
+                Ok((kind, path)) => {
+                    if let Some(game) = self.library.get_game_mut(&id) {
+                        match kind {
+                            steamgriddb::ArtKind::Icon => game.icon_path = Some(path),
+                            steamgriddb::ArtKind::Hero => game.hero_path = Some(path),
+                            steamgriddb::ArtKind::Grid => {
+                                game.banner_shape = cache::art_shape(&path);
+                                game.banner_path = Some(path);
+                            }
+                        }
+                    }
+                    self.artwork_picker = None;
+                    self.save_library()
+                }
+                Err(e) => {
+                    tracing::error!("Failed to download artwork: {}", e);
+                    if let Some(picker) = self.artwork_picker.as_mut().filter(|p| p.game_id == id) {
+                        picker.status = ArtworkPickerStatus::Error(e.to_string());
+                    }
+                    Task::none()
+                }
+            },
+
+            #[cfg(feature = "steamgriddb")]
+            Message::CloseArtworkPicker => {
+                self.artwork_picker = None;
+                Task::none()
+            }
+
+            #[cfg(feature = "steamgriddb")]
+            Message::SteamGridDbKeyEdited(key) => {
+                self.steamgriddb_key_draft = Some(key);
+                Task::none()
+            }
+
+            // File dialogs
+            Message::SelectExecutable => {
+                Task::perform(platform::pick_executable(), Message::ExecutableSelected)
+            }
+
+            // A cancelled picker keeps whatever path was already typed
+            Message::ExecutableSelected(path) => {
+                if let Some(p) = path {
+                    self.new_game_path = p.to_string_lossy().to_string();
+                }
+                Task::none()
+            }
+
+            // Persistence
+            Message::SaveLibrary => self.save_library(),
+
+            Message::LibrarySaved(result) => {
+                match &result {
+                    Ok(()) => tracing::debug!("Library saved"),
+                    Err(e) => tracing::error!("Failed to save library: {}", e),
+                }
+                self.save_error = result.err();
+                Task::none()
+            }
+
+            Message::LoadLibrary => {
+                let library_path = self.library_path();
+                let config_path = self.config_path();
+                let passphrase = self.library_passphrase.clone();
+                let activity_path = self.activity_path();
+                let load_activity = Task::perform(
+                    async move { read_activity(&activity_path, ACTIVITY_HISTORY_LIMIT).await },
+                    Message::ActivityLoaded,
+                );
+
+                let scan_cache_path = self.scan_cache_path();
+                let load_scan_cache = Task::perform(
+                    async move { ScanCache::load(&scan_cache_path).await },
+                    Message::ScanCacheLoaded,
+                );
+
+                let clock = self.clock.clone();
+                let load_library = Task::perform(
+                    async move {
+                        let config = Config::load_or_create(&config_path).await;
+                        let first_run = !config.first_run_complete;
+                        let loaded = Library::load_or_create_protected(
+                            &library_path,
+                            passphrase.as_deref(),
+                            first_run,
+                            clock.as_ref(),
+                        )
+                        .await;
+                        (loaded, config)
+                    },
+                    |(loaded, config)| match loaded {
+                        Ok((library, quarantined)) => {
+                            Message::LibraryLoaded(Box::new((Ok(library), config)), quarantined)
+                        }
+                        Err(e) => Message::LibraryLoaded(Box::new((Err(e), config)), None),
+                    },
+                );
+                Task::batch([load_library, load_activity, load_scan_cache])
+            }
+
+            Message::ScanCacheLoaded(cache) => {
+                self.scan_cache = cache;
+                Task::none()
+            }
+
+            Message::LibraryLoaded(loaded, quarantined) => {
+                let (library, config) = *loaded;
+                self.quarantined_library = quarantined;
+                self.theme = CustomTheme::by_name(&config.theme);
+                self.sort_order = config.default_sort;
+                self.view_mode = config.default_view_mode;
+                self.config = config;
+                let system_theme = self.query_system_theme();
+                Task::batch([self.update(Message::LibraryUnlocked(library)), system_theme])
+            }
+
+            Message::DismissQuarantineNotice => {
+                self.quarantined_library = None;
+                Task::none()
+            }
+
+            Message::UnlockPassphraseChanged(input) => {
+                if let Some(unlock) = &mut self.unlock {
+                    unlock.input = input;
+                }
+                Task::none()
+            }
+
+            Message::UnlockLibrary => {
+                let Some(unlock) = &mut self.unlock else {
+                    return Task::none();
+                };
+                unlock.checking = true;
+                unlock.error = None;
+                let passphrase = unlock.input.clone();
+                let path = self.library_path();
+                let clock = self.clock.clone();
+
+                Task::perform(
+                    async move {
+                        // Only encrypted files get here, and those are never quarantined
+                        let passphrase = Some(passphrase.as_str());
+                        Library::load_or_create_protected(&path, passphrase, false, clock.as_ref())
+                            .await
+                            .map(|(library, _)| library)
+                    },
+                    Message::LibraryUnlocked,
+                )
+            }
+
+            Message::LibraryUnlocked(result) => {
+                match result {
+                    Ok(library) => {
+                        self.load_error = None;
+                        if let Some(unlock) = self.unlock.take() {
+                            self.library_passphrase = Some(unlock.input);
+                            // Keep it encrypted even if the config was lost
+                            self.config.encrypt_library = true;
+                        }
+                        tracing::info!("Library loaded with {} games", library.game_count());
+                        let mut library = library;
+                        let merged = library.merge_duplicate_categories();
+                        self.search_index = SearchIndex::build(&library);
+                        self.smart_members = library.smart_members();
+                        self.library = library;
+                        let mut saves = Vec::new();
+                        if merged > 0 {
+                            tracing::info!("Merged {} duplicate categories", merged);
+                            saves.push(self.save_library());
+                        }
+                        let mut config_changed = !self.config.first_run_complete;
+                        self.config.first_run_complete = true;
+                        if self.config.forget_missing_manual_category(&self.library) {
+                            tracing::warn!("Default manual category no longer exists, cleared it");
+                            config_changed = true;
+                        }
+                        if config_changed {
+                            saves.push(self.save_config());
+                        }
+                        return Task::batch(saves);
+                    }
+                    // Never fall back to an empty library here, the next save would
+                    // overwrite the encrypted one
+                    Err(e @ (LibraryError::PassphraseRequired | LibraryError::WrongPassphrase)) => {
+                        let unlock = self.unlock.get_or_insert_with(UnlockPrompt::default);
+                        unlock.checking = false;
+                        unlock.error =
+                            matches!(e, LibraryError::WrongPassphrase).then(|| e.to_string());
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load library: {}", e);
+                        match &mut self.unlock {
+                            Some(unlock) => {
+                                unlock.checking = false;
+                                unlock.error = Some(e.to_string());
+                            }
+                            None => self.load_error = Some(e.to_string()),
+                        }
+                    }
+                }
+                Task::none()
+            }
+
+            Message::SwitchLibraryInputChanged(value) => {
+                self.switch_library_input = value;
+                self.switch_library_error = None;
+                Task::none()
+            }
+
+            Message::SwitchLibrary(path) => {
+                // The running games' sessions are recorded in this library
+                if !self.running_games.is_empty() || same_path(&path, &self.data_dir.path) {
+                    return Task::none();
+                }
+                let data_dir = choose_data_dir(path, Vec::new(), probe_writable);
+                if let DataDirStatus::ReadOnly { reason } = &data_dir.status {
+                    self.switch_library_error =
+                        Some(format!("Can't use {}: {}", data_dir.path.display(), reason));
+                    return Task::none();
+                }
+                tracing::info!("Switching library to {:?}", data_dir.path);
+                // Save first, the switch waits for both files to be written
+                let config = self.config.clone();
+                let config_path = self.config_path();
+                self.save_library().chain(Task::perform(
+                    async move {
+                        config.save_to_file(&config_path).await.map_err(|e| e.to_string())
+                    },
+                    move |config_saved| Message::LibrarySwitched(data_dir, config_saved),
+                ))
+            }
+
+            Message::LibrarySwitched(data_dir, config_saved) => {
+                if let Some(e) = &self.save_error {
+                    self.switch_library_error =
+                        Some(format!("Not switching, the library couldn't be saved: {}", e));
+                    return Task::none();
+                }
+                if let Err(e) = config_saved {
+                    self.switch_library_error =
+                        Some(format!("Not switching, the settings couldn't be saved: {}", e));
+                    return Task::none();
+                }
+                let mut recent_data_dirs = std::mem::take(&mut self.recent_data_dirs);
+                recent_data_dirs.touch(&self.data_dir.path, MAX_RECENT_DATA_DIRS);
+                recent_data_dirs.touch(&data_dir.path, MAX_RECENT_DATA_DIRS);
+                let recent_path = self.recent_data_dirs_path.clone();
+                let recent = recent_data_dirs.clone();
+                let save_recent = Task::perform(
+                    async move {
+                        if let Err(e) = recent.save(&recent_path) {
+                            tracing::warn!("Could not save the recent libraries: {}", e);
+                        }
+                    },
+                    |_| Message::None,
+                );
+
+                // Start over from a fresh state, keeping only the windows
+                let recent_data_dirs_path = self.recent_data_dirs_path.clone();
+                *self = Self {
+                    clock: self.clock.clone(),
+                    main_window: self.main_window,
+                    now_playing_window: self.now_playing_window,
+                    window_width: self.window_width,
+                    library_generation: self.library_generation + 1,
+                    ..Self::with_data_dir(data_dir, recent_data_dirs, recent_data_dirs_path)
+                };
+                Task::batch([save_recent, Task::done(Message::LoadLibrary)])
+            }
+
+            // Import
+            Message::StartImport(source) => {
+                // A recent scan with the same settings is shown again rather than redone
+                let max_age = chrono::Duration::hours(SCAN_CACHE_MAX_AGE_HOURS);
+                let inputs = self.scan_inputs(source);
+                let now = self.clock.now();
+                if let Some(scan) = self.scan_cache.get(source, &inputs, max_age, now).cloned() {
+                    tracing::info!("Showing the cached {:?} scan from {}", source, scan.scanned_at);
+                    let entries = self.preview_entries(source, scan.games);
+                    self.import_status = ImportStatus::Preview {
+                        source,
+                        entries,
+                        tab: ImportPreviewTab::New,
+                        report: scan.report,
+                        confirm: None,
+                        cached_at: Some(scan.scanned_at),
+                    };
+                    return Task::none();
+                }
+                self.update(Message::RescanImport(source))
+            }
+
+            Message::RescanImport(source) => {
+                tracing::info!("Starting import from {:?}", source);
+                self.import_status = ImportStatus::Importing(source);
+                let steam_library_paths = self.config.steam_library_paths.clone();
+                let exclude = ExcludePatterns::new(&self.config.import_exclude_patterns);
+                let not_installed = self.config.source_import.get(source).include_not_installed;
+
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            scan_source(source, steam_library_paths, exclude, not_installed)
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    move |result| match result {
+                        Ok((detected, report)) => Message::ImportPreview(source, detected, report),
+                        Err(e) => Message::ImportComplete(Err(e)),
+                    },
+                )
+            }
+
+            Message::RomFolderInputChanged(path) => {
+                self.rom_folder_input = path;
+                Task::none()
+            }
+
+            Message::StartRomImport => {
+                let rom_dir = PathBuf::from(self.rom_folder_input.trim());
+                tracing::info!("Scanning {:?} for ROMs", rom_dir);
+                self.import_status = ImportStatus::Importing(GameSource::Emulator);
+                let exclude = ExcludePatterns::new(&self.config.import_exclude_patterns);
+                let importer = RomImporter::new(rom_dir, self.config.emulators.clone())
+                    .with_exclude_patterns(exclude);
+
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            importer.scan_games().map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    |result| match result {
+                        Ok((detected, report)) => {
+                            Message::ImportPreview(GameSource::Emulator, detected, report)
+                        }
+                        Err(e) => Message::ImportComplete(Err(e)),
+                    },
+                )
+            }
+
+            Message::ImportProgress(_progress) => Task::none(),
+
+            Message::AutoScanDue => {
+                let Some(minutes) = self.config.auto_scan_interval_minutes else {
+                    return Task::none();
+                };
+                let interval = Duration::from_secs(u64::from(minutes) * 60);
+                if self.last_auto_scan.is_some_and(|started| started.elapsed() < interval) {
+                    return Task::none();
+                }
+                if self.auto_scan_running || self.import_in_progress() {
+                    tracing::debug!("Background scan skipped, another import is running");
+                    return Task::none();
+                }
+
+                // Stores imported by hand recently don't need scanning again
+                let max_age = chrono::Duration::minutes(i64::from(minutes));
+                let now = self.clock.now();
+                let due: Vec<GameSource> = SCANNABLE_SOURCES
+                    .iter()
+                    .map(|(source, _)| *source)
+                    .filter(|source| self.config.last_sync.is_stale(*source, max_age, now))
+                    .collect();
+                if due.is_empty() {
+                    return Task::none();
+                }
+
+                tracing::info!("Starting background scan of {:?}", due);
+                self.auto_scan_running = true;
+                self.last_auto_scan = Some(Instant::now());
+                let steam_library_paths = self.config.steam_library_paths.clone();
+                let exclude = ExcludePatterns::new(&self.config.import_exclude_patterns);
+                let source_import = self.config.source_import.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            // Checking for each store's app touches the disk and registry
+                            due.into_iter()
+                                .filter(|source| source_available(*source, &steam_library_paths))
+                                .map(|source| {
+                                    let paths = steam_library_paths.clone();
+                                    let not_installed =
+                                        source_import.get(source).include_not_installed;
+                                    let result =
+                                        scan_source(source, paths, exclude.clone(), not_installed);
+                                    (source, result)
+                                })
+                                .collect()
+                        })
+                        .await
+                        // A scan that panicked found nothing
+                        .unwrap_or_default()
+                    },
+                    Message::AutoScanComplete,
+                )
+            }
+
+            Message::AutoScanComplete(results) => {
+                self.auto_scan_running = false;
+                // The user started an import meanwhile; theirs wins
+                if self.import_in_progress() {
+                    tracing::info!("Background scan results dropped, an import is in progress");
+                    return Task::none();
+                }
+
+                let mut changed = false;
+                let mut tasks = Vec::new();
+                for (source, result) in results {
+                    let (detected, scan) = match result {
+                        Ok(scanned) => scanned,
+                        Err(e) => {
+                            tracing::debug!("Background scan of {:?} skipped: {}", source, e);
+                            continue;
+                        }
+                    };
+                    let detected: Vec<DetectedGame> =
+                        detected.into_iter().map(DetectedGame::normalized).collect();
+                    tasks.push(self.cache_scan(source, &detected, &scan));
+
+                    // A runaway scan waits in the import preview rather than flooding the library
+                    let new_names = detected
+                        .iter()
+                        .filter(|d| !self.is_in_library(source, d))
+                        .map(|d| d.name.as_str());
+                    let threshold = self.config.import_warn_threshold;
+                    let check = check_import_size(new_names, threshold);
+                    if let ImportCheck::ConfirmRequired(large) = check {
+                        tracing::warn!(
+                            "Background scan of {:?} would add {} games, holding it for review",
+                            source,
+                            large.count
+                        );
+                        if !self.import_in_progress() {
+                            let entries = self.preview_entries(source, detected);
+                            self.import_status = ImportStatus::Preview {
+                                source,
+                                entries,
+                                tab: ImportPreviewTab::New,
+                                report: scan,
+                                confirm: Some(large),
+                                cached_at: None,
+                            };
+                        }
+                        continue;
+                    }
+
+                    let found = detected.iter().map(|d| d.source_id.clone()).collect();
+                    let clock = self.clock.as_ref();
+                    let games = detected.into_iter().map(|d| d.into_game(source, clock)).collect();
+
+                    // Missing games are only reported, never removed without the user
+                    let summary = self.merge_imported_games(games, source, &found, scan);
+                    if !summary.added.is_empty() || !summary.updated.is_empty() {
+                        changed = true;
+                        let activity = import_activity(&summary, self.clock.as_ref());
+                        tasks.push(self.record_activity(vec![activity]));
+                    }
+                }
+
+                if changed {
+                    tasks.push(self.save_library());
+                }
+                tasks.push(self.save_config());
+                Task::batch(tasks)
+            }
+
+            Message::SteamCollectionsLoaded(collections) => {
+                let mut assigned = 0;
+                for collection in collections {
+                    let members: Vec<GameId> = collection
+                        .app_ids
+                        .iter()
+                        .filter_map(|app_id| self.library.find_by_source(GameSource::Steam, app_id))
+                        .map(|game| game.id)
+                        .collect();
+                    // Collections of games not installed here would only be clutter
+                    if members.is_empty() {
+                        continue;
+                    }
+                    let category = self
+                        .library
+                        .add_category(Category::new(collection.name), CategoryCollision::Reject);
+                    if self.is_smart_category(&category) {
+                        continue;
+                    }
+                    for id in members {
+                        if let Some(game) = self.library.get_game_mut(&id) {
+                            if !game.has_category(&category) {
+                                game.add_category(category);
+                                assigned += 1;
+                            }
+                        }
+                    }
+                }
+                tracing::info!("Added {} games to categories from Steam collections", assigned);
+                if assigned > 0 {
+                    self.save_library()
+                } else {
+                    Task::none()
+                }
+            }
+
+            Message::ImportPreview(source, detected, report) => {
+                let detected: Vec<DetectedGame> =
+                    detected.into_iter().map(DetectedGame::normalized).collect();
+                // ROM folders are picked each time, so only store scans are kept
+                let save_cache = if source == GameSource::Emulator {
+                    Task::none()
+                } else {
+                    self.cache_scan(source, &detected, &report)
+                };
+                let entries = self.preview_entries(source, detected);
+                self.import_status = ImportStatus::Preview {
+                    source,
+                    entries,
+                    tab: ImportPreviewTab::New,
+                    report,
+                    confirm: None,
+                    cached_at: None,
+                };
+                save_cache
+            }
+
+            Message::ImportPreviewToggled(index, selected) => {
+                if let ImportStatus::Preview { entries, .. } = &mut self.import_status {
+                    if let Some(entry) = entries.get_mut(index) {
+                        entry.selected = selected;
+                    }
+                }
+                Task::none()
+            }
+
+            Message::ImportPreviewSelectAll(selected) => {
+                if let ImportStatus::Preview { entries, tab, .. } = &mut self.import_status {
+                    let show_existing = *tab == ImportPreviewTab::InLibrary;
+                    for entry in entries.iter_mut().filter(|e| e.existing == show_existing) {
+                        entry.selected = selected;
+                    }
+                }
+                Task::none()
+            }
+
+            Message::ImportPreviewTabChanged(new_tab) => {
+                if let ImportStatus::Preview { tab, .. } = &mut self.import_status {
+                    *tab = new_tab;
+                }
+                Task::none()
+            }
+
+            Message::CommitImport(source, detected) => {
+                let threshold = self.config.import_warn_threshold;
+                if let ImportStatus::Preview { entries, confirm, .. } = &mut self.import_status {
+                    let new_names = entries
+                        .iter()
+                        .filter(|e| e.selected && !e.existing)
+                        .map(|e| e.game.name.as_str());
+                    let check = check_import_size(new_names, threshold);
+                    if let ImportCheck::ConfirmRequired(large) = check {
+                        tracing::warn!(
+                            "Import from {:?} would add {} games, asking first",
+                            source,
+                            large.count
+                        );
+                        *confirm = Some(large);
+                        return Task::none();
+                    }
+                }
+                self.commit_import_preview(source, detected)
+            }
+
+            Message::ConfirmLargeImport => {
+                let ImportStatus::Preview { source, entries, .. } = &self.import_status else {
+                    return Task::none();
+                };
+                let source = *source;
+                let selected = entries
+                    .iter()
+                    .filter(|e| e.selected)
+                    .map(|e| e.game.clone())
+                    .collect();
+                self.commit_import_preview(source, selected)
+            }
+
+            Message::ReviewLargeImport => {
+                if let ImportStatus::Preview { confirm, .. } = &mut self.import_status {
+                    *confirm = None;
+                }
+                Task::none()
+            }
+
+            Message::CancelImport | Message::DismissImportSummary => {
+                self.import_status = ImportStatus::Idle;
+                Task::none()
+            }
+
+            Message::ImportComplete(result) => match result {
+                Ok((games, source)) => {
+                    let found = games.iter().filter_map(|g| g.source_id.clone()).collect();
+                    self.commit_imported_games(games, source, &found, ScanReport::default())
+                }
+                Err(e) => {
+                    tracing::error!("Import failed: {}", e);
+                    self.import_status = ImportStatus::Error(e);
+                    Task::none()
+                }
+            },
+
+            Message::CsvPathInputChanged(path) => {
+                self.csv_path_input = path;
+                Task::none()
+            }
+
+            Message::ImportCsv => {
+                if self.csv_importing {
+                    return Task::none();
+                }
+                self.csv_importing = true;
+                self.csv_import = None;
+                let path = PathBuf::from(self.csv_path_input.trim());
+                let clock = self.clock.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            Library::read_csv(&path, clock.as_ref()).map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    Message::CsvRead,
+                )
+            }
+
+            Message::CsvRead(result) => {
+                self.csv_importing = false;
+                match result {
+                    Ok(contents) => {
+                        let category = self.config.default_manual_category;
+                        let report = self.library.add_csv(contents, category);
+                        let added = report.added();
+                        tracing::info!("CSV import: {} of {} rows added", added, report.rows.len());
+                        self.search_index = SearchIndex::build(&self.library);
+                        self.csv_import = Some(Ok(report));
+                        if added > 0 {
+                            let detail = format!("{} added", added);
+                            let imported =
+                                ActivityEntry::import("CSV", detail, self.clock.as_ref());
+                            return Task::batch([
+                                self.save_library(),
+                                self.record_activity(vec![imported]),
+                            ]);
+                        }
+                    }
+                    Err(e) => self.csv_import = Some(Err(e)),
+                }
+                Task::none()
+            }
+
+            Message::BundlePathInputChanged(path) => {
+                self.bundle_path_input = path;
+                Task::none()
+            }
+
+            Message::ImportBundle => {
+                if self.bundle_importing {
+                    return Task::none();
+                }
+                self.bundle_importing = true;
+                self.bundle_import = None;
+                let path = PathBuf::from(self.bundle_path_input.trim());
+                let art_dir = cache::art_dir(&self.data_dir.path);
+                let library = self.library.clone();
+                let clock = self.clock.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            library
+                                .read_bundle(&path, &art_dir, clock.as_ref())
+                                .map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                    },
+                    Message::BundleRead,
+                )
+            }
+
+            Message::BundleRead(result) => {
+                self.bundle_importing = false;
+                match result {
+                    Ok(contents) => {
+                        let report = self.library.add_bundle(contents);
+                        tracing::info!(
+                            "Bundle import: {} added, {} already in library",
+                            report.added.len(),
+                            report.duplicates.len()
+                        );
+                        self.search_index = SearchIndex::build(&self.library);
+                        let added = report.added.len();
+                        self.bundle_import = Some(Ok(report));
+                        if added > 0 {
+                            let detail = format!("{} added", added);
+                            let imported =
+                                ActivityEntry::import("game bundle", detail, self.clock.as_ref());
+                            return Task::batch([
+                                self.save_library(),
+                                self.record_activity(vec![imported]),
+                            ]);
+                        }
+                    }
+                    Err(e) => self.bundle_import = Some(Err(e)),
+                }
+                Task::none()
+            }
+
+            Message::ClearLibrary => {
+                tracing::info!("Clearing library");
+                self.library = Library::new();
+                self.selected_games.clear();
+                self.bundle_export = None;
+                self.search_index = SearchIndex::default();
+                self.import_status = ImportStatus::Idle;
+                self.save_library()
+            }
+
+            Message::ActivityLoaded(mut entries) => {
+                // Keep anything recorded while the log was still loading
+                entries.append(&mut self.activity);
+                let skip = entries.len().saturating_sub(ACTIVITY_HISTORY_LIMIT);
+                entries.drain(..skip);
+                self.activity = entries;
+                Task::none()
+            }
+
+            Message::ActivityFilterChanged(kind) => {
+                self.activity_filter = kind;
+                Task::none()
+            }
+
+            Message::ExportYearInReview => {
+                let (year, stats) = self.year_in_review();
+                let text = stats.to_text(&format!("{} in Review", year));
+                let path = self.data_dir.path.join(format!("year-in-review-{}.txt", year));
+                Task::perform(
+                    async move {
+                        tokio::fs::write(&path, text)
+                            .await
+                            .map(|()| path)
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::YearInReviewExported,
+                )
+            }
+
+            Message::YearInReviewExported(result) => {
+                match &result {
+                    Ok(path) => tracing::info!("Exported year in review to {:?}", path),
+                    Err(e) => tracing::error!("Failed to export year in review: {}", e),
+                }
+                self.review_export = Some(result);
+                Task::none()
+            }
+
+            // Misc
+            // Mostly here to re-render, so relative times and Play buttons stay current
+            Message::Tick => {
+                let cooldown = self.launch_cooldown();
+                self.launch_cooldowns.retain(|_, started| started.elapsed() < cooldown);
+                Task::none()
+            }
+            Message::None => Task::none(),
+            // Already checked against the open library in `dispatch`
+            Message::ForLibrary(_, message) => self.update(*message),
+        }
+    }
+
+    /// Whether a detected game is already in the library, under any store
+    fn is_in_library(&self, source: GameSource, game: &DetectedGame) -> bool {
+        self.library.find_by_source(source, &game.source_id).is_some()
+            || self.library.find_by_store_link(source, &game.source_id).is_some()
+            || self.library.find_by_executable(&game.executable_path).is_some()
+    }
+
+    /// Preview entries for a scan, every game selected to start with
+    fn preview_entries(
+        &self,
+        source: GameSource,
+        detected: Vec<DetectedGame>,
+    ) -> Vec<PreviewEntry> {
+        detected
+            .into_iter()
+            .map(|game| {
+                let existing = self.is_in_library(source, &game);
+                let link_target = if self.config.merge_cross_store && !existing {
+                    self.library
+                        .find_cross_store_match(&game.name, source)
+                        .map(|g| format!("{} ({})", g.name, g.source.label()))
+                } else {
+                    None
+                };
+                PreviewEntry {
+                    game,
+                    selected: true,
+                    existing,
+                    link_target,
+                }
+            })
+            .collect()
+    }
+
+    /// Add the games picked in the import preview to the library
+    fn commit_import_preview(
+        &mut self,
+        source: GameSource,
+        detected: Vec<DetectedGame>,
+    ) -> Task<Message> {
+        // Everything the scan found counts as present, selected or not
+        let (found, scan): (HashSet<String>, _) = match &self.import_status {
+            ImportStatus::Preview {
+                entries, report, ..
+            } => (
+                entries.iter().map(|e| e.game.source_id.clone()).collect(),
+                report.clone(),
+            ),
+            _ => (
+                detected.iter().map(|d| d.source_id.clone()).collect(),
+                ScanReport::default(),
+            ),
+        };
+        let clock = self.clock.as_ref();
+        let games = detected.into_iter().map(|d| d.into_game(source, clock)).collect();
+        self.commit_imported_games(games, source, &found, scan)
+    }
+
+    /// Add imported games to the library and show what changed
+    fn commit_imported_games(
+        &mut self,
+        games: Vec<Game>,
+        source: GameSource,
+        found: &HashSet<String>,
+        scan: ScanReport,
+    ) -> Task<Message> {
+        let summary = self.merge_imported_games(games, source, found, scan);
+        let activity = self.record_activity(vec![import_activity(&summary, self.clock.as_ref())]);
+        self.import_status = ImportStatus::Complete(summary);
+        Task::batch([
+            self.save_library(),
+            self.save_config(),
+            self.load_steam_collections(source),
+            activity,
+        ])
+    }
+
+    /// Read the user's Steam collections after a Steam import, if enabled
+    ///
+    /// Only imports the user starts do this; background scans leave the
+    /// categories alone, so games taken out of one stay out.
+    fn load_steam_collections(&self, source: GameSource) -> Task<Message> {
+        if source != GameSource::Steam || !self.config.import_steam_collections {
+            return Task::none();
+        }
+        let Some(steam_path) = SteamImporter::new().steam_path().map(Path::to_path_buf) else {
+            return Task::none();
+        };
+        Task::perform(
+            async move { read_steam_collections(&steam_path) },
+            Message::SteamCollectionsLoaded,
+        )
+    }
+
+    /// Add imported games to the library, refreshing entries that already exist
+    ///
+    /// `found` holds the source IDs of everything the scan detected, so games
+    /// that have disappeared from the store can be listed in the summary.
+    fn merge_imported_games(
+        &mut self,
+        games: Vec<Game>,
+        source: GameSource,
+        found: &HashSet<String>,
+        scan: ScanReport,
+    ) -> ImportSummary {
+        let mut summary = ImportSummary::new(source);
+        summary.scan = scan;
+
+        for game in games {
+            let source_id = game.source_id.clone().unwrap_or_default();
+
+            // Already linked into another store's entry, nothing to refresh
+            if self.library.find_by_store_link(source, &source_id).is_some() {
+                summary.unchanged += 1;
+                continue;
+            }
+
+            let existing = self
+                .library
+                .find_by_source(source, &source_id)
+                .or_else(|| self.library.find_by_executable(&game.executable_path))
+                .map(|g| g.id);
+            if let Some(existing) = existing.and_then(|id| self.library.get_game_mut(&id)) {
+                let fields = refresh_from_import(existing, game);
+                summary.record_update(&existing.name, fields);
+                self.search_index.insert(existing);
+                continue;
+            }
+
+            let cross_store = if self.config.merge_cross_store {
+                self.library
+                    .find_cross_store_match(&game.name, source)
+                    .map(|g| g.id)
+            } else {
+                None
+            };
+            match cross_store.and_then(|id| self.library.get_game_mut(&id)) {
+                Some(linked) => {
+                    tracing::info!("Linking {} copy of {} to existing entry", source.label(), linked.name);
+                    linked.add_store_link(source, source_id);
+                    summary.record_update(&linked.name, vec!["linked store"]);
+                }
+                None => {
+                    summary.added.push(game.name.clone());
+                    self.search_index.insert(&game);
+                    self.library.add_game(game);
+                }
+            }
+        }
+
+        summary.missing = self
+            .library
+            .all_games()
+            .into_iter()
+            .filter(|g| g.source == source)
+            .filter(|g| g.source_id.as_ref().is_some_and(|id| !found.contains(id)))
+            // ROMs in other folders weren't part of this scan
+            .filter(|g| source != GameSource::Emulator || !g.executable_path.exists())
+            // Nor were games on library folders that couldn't be reached
+            .filter(|g| {
+                !summary.scan.unreachable.iter().any(|(folder, _)| {
+                    g.install_path.as_ref().is_some_and(|path| path.starts_with(folder))
+                })
+            })
+            .map(|g| g.name.clone())
+            .collect();
+        summary.added.sort();
+        summary.updated.sort_by(|a, b| a.name.cmp(&b.name));
+        summary.missing.sort();
+
+        tracing::info!(
+            "Import from {:?}: {} added, {} updated, {} missing",
+            source,
+            summary.added.len(),
+            summary.updated.len(),
+            summary.missing.len()
+        );
+        self.config.last_sync.set(source, self.clock.now());
+        summary
+    }
+
+    /// Whether the user is partway through an import
+    fn import_in_progress(&self) -> bool {
+        matches!(
+            self.import_status,
+            ImportStatus::Importing(_) | ImportStatus::Preview { .. }
+        )
+    }
+
+    /// Whether a category's members come from its rule rather than by hand
+    fn is_smart_category(&self, id: &CategoryId) -> bool {
+        self.library.get_category(id).is_some_and(|category| category.rule.is_some())
+    }
+
+    /// The selected (manual, imported) pair when exactly one of each is selected
+    fn manual_merge_pair(&self) -> Option<(GameId, GameId)> {
+        let [a, b] = self
+            .selected_games
+            .iter()
+            .filter_map(|id| self.library.get_game(id))
+            .collect::<Vec<_>>()[..]
+        else {
+            return None;
+        };
+        match (a.source == GameSource::Manual, b.source == GameSource::Manual) {
+            (true, false) => Some((a.id, b.id)),
+            (false, true) => Some((b.id, a.id)),
+            _ => None,
+        }
+    }
+
+    /// Ask the OS for its light/dark preference when the theme follows it
+    fn query_system_theme(&self) -> Task<Message> {
+        if CustomTheme::follows_system(&self.config.theme) {
+            iced::system::theme().map(Message::SystemThemeChanged)
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Where a game's captured output is written
+    fn game_log_path(&self, id: &GameId) -> PathBuf {
+        self.data_dir.path.join(GAME_LOGS_DIR).join(format!("{}.log", id.0))
+    }
+
+    fn launch_cooldown(&self) -> Duration {
+        Duration::from_millis(self.config.launch_cooldown_ms)
+    }
+
+    /// Whether Play should do anything for a game right now
+    ///
+    /// A game is held back while it's still running, or for the cooldown
+    /// after it was launched unless it has already exited.
+    fn can_launch(&self, id: &GameId) -> bool {
+        let cooling_down = self
+            .launch_cooldowns
+            .get(id)
+            .is_some_and(|started| started.elapsed() < self.launch_cooldown());
+        !cooling_down && !self.running_games.contains_key(id)
+    }
+
+    /// Route a launch of a game that isn't installed to its store's installer
+    ///
+    /// Returns `None` for installed games, which launch as usual. Games no
+    /// store can install fail like any launch of a missing executable.
+    fn launch_not_installed(&mut self, id: GameId) -> Option<Task<Message>> {
+        // An executable whose case differs from the stored path still launches
+        let game = self.library.get_game(&id).filter(|game| {
+            !game.is_installed() && find_path_ignoring_case(&game.executable_path).is_none()
+        })?;
+        let Some((source, _)) = game.install_uri() else {
+            let path = game.executable_path.display().to_string();
+            let missing = LaunchError::ExecutableNotFound(path);
+            return Some(Task::done(Message::GameLaunched(id, Err(missing))));
+        };
+        if !self.config.confirm_install_on_launch {
+            return Some(Task::done(Message::InstallGame(id)));
+        }
+
+        self.confirm_dialog = Some(ConfirmDialog {
+            title: format!("{} is not installed", game.display_name()),
+            body: format!("Install it through {}?", source.label()),
+            action: Message::InstallGame(id),
+            game_id: None,
+            needs_pin: false,
+            pin_input: String::new(),
+            error: None,
+        });
+        Some(Task::none())
+    }
+
+    /// Ask for the current launch PIN before `action`, when one is set
+    ///
+    /// Changing or removing the PIN, or turning a game's confirmation off,
+    /// would otherwise get around it. Returns false when there's no PIN or
+    /// it was just entered for this action.
+    fn needs_current_pin(&mut self, title: &str, action: Message) -> bool {
+        if self.pin_confirmed || self.config.launch_pin.is_none() {
+            return false;
+        }
+        self.confirm_dialog = Some(ConfirmDialog {
+            title: title.to_string(),
+            body: "Enter the current launch PIN to continue.".to_string(),
+            action,
+            game_id: None,
+            needs_pin: true,
+            pin_input: String::new(),
+            error: None,
+        });
+        true
+    }
+
+    /// Hold back launches of games flagged for confirmation
+    ///
+    /// Returns true when a dialog was opened and `action` will be dispatched
+    /// once the user confirms (and enters the PIN, if one is set).
+    fn needs_launch_confirmation(&mut self, id: GameId, action: Message) -> bool {
+        if self.launch_confirmed.take() == Some(id) {
+            return false;
+        }
+        let Some(game) = self.library.get_game(&id).filter(|g| g.confirm_before_launch) else {
+            return false;
+        };
+
+        let needs_pin = self.config.launch_pin.is_some();
+        self.confirm_dialog = Some(ConfirmDialog {
+            title: format!("Launch {}?", game.display_name()),
+            body: if needs_pin {
+                "Enter the launch PIN to start this game.".to_string()
+            } else {
+                "This game asks for confirmation before starting.".to_string()
+            },
+            action,
+            game_id: Some(id),
+            needs_pin,
+            pin_input: String::new(),
+            error: None,
+        });
+        true
+    }
+
+    /// Apply the configured window behavior after a game has been spawned
+    ///
+    /// Hiding only makes sense when the tray integration is enabled, otherwise
+    /// the window would be unreachable, so it degrades to minimizing.
+    fn apply_launch_window_behavior(&mut self) -> Task<Message> {
+        let hide_available = self.config.close_to_tray;
+
+        match self.config.on_launch {
+            LaunchWindowBehavior::DoNothing => Task::none(),
+            LaunchWindowBehavior::Minimize => {
+                self.window_hidden_for_game = true;
+                window::oldest().and_then(|id| window::minimize(id, true))
+            }
+            LaunchWindowBehavior::HideToTray | LaunchWindowBehavior::Close if hide_available => {
+                self.window_hidden_for_game = true;
+                window::oldest().and_then(|id| window::set_mode(id, window::Mode::Hidden))
+            }
+            LaunchWindowBehavior::HideToTray => {
+                self.window_hidden_for_game = true;
+                window::oldest().and_then(|id| window::minimize(id, true))
+            }
+            LaunchWindowBehavior::Close => self.quit(),
+        }
+    }
+
+    /// Save and exit
+    ///
+    /// Games still running are no longer watched once the launcher is gone, so
+    /// their sessions so far are added to their playtime first.
+    fn quit(&mut self) -> Task<Message> {
+        let newest = self.newest_running_game();
+        for (id, running) in self.running_games.drain() {
+            let minutes = running.started.elapsed().as_secs() / 60;
+            tracing::info!("Closing while {:?} runs, adding its {} minutes so far", id, minutes);
+            if let Some(game) = self.library.get_game_mut(&id).filter(|game| game.track_playtime) {
+                game.add_playtime(minutes);
+            }
+        }
+        let status = match newest {
+            Some((id, started_at)) => self.write_launch_status(id, started_at, false, true),
+            None => Task::none(),
+        };
+        Task::batch([self.save_library(), status]).chain(iced::exit())
+    }
+
+    /// Open the now playing window, when enabled and not already open
+    fn open_now_playing_window(&mut self) -> Task<Message> {
+        if !self.config.show_now_playing_window || self.now_playing_window.is_some() {
+            return Task::none();
+        }
+        let (id, open) = window::open(window::Settings {
+            size: iced::Size::new(NOW_PLAYING_WINDOW_WIDTH, NOW_PLAYING_WINDOW_HEIGHT),
+            resizable: false,
+            minimizable: false,
+            level: window::Level::AlwaysOnTop,
+            ..Default::default()
+        });
+        self.now_playing_window = Some(id);
+        open.discard()
+    }
+
+    /// Save library to disk
+    fn save_library(&mut self) -> Task<Message> {
+        self.smart_members = self.library.smart_members();
+        // Nothing real is loaded while locked, saving would clobber the file
+        if self.unlock.is_some() || self.load_error.is_some() {
+            return Task::none();
+        }
+
+        let library = self.library.clone();
+        let path = self.library_path();
+        let passphrase = self
+            .library_passphrase
+            .clone()
+            .filter(|_| self.config.encrypt_library);
+
+        Task::perform(
+            async move {
+                let result = match passphrase {
+                    Some(passphrase) => library.save_to_file_encrypted(&path, &passphrase).await,
+                    None => library.save_to_file(&path).await,
+                };
+                result.map_err(|e| e.to_string())
+            },
+            Message::LibrarySaved,
+        )
+    }
+
+    /// The most recently launched game still running, and when it started
+    fn newest_running_game(&self) -> Option<(GameId, chrono::DateTime<Utc>)> {
+        self.running_games
+            .iter()
+            .max_by_key(|(_, running)| running.started_at)
+            .map(|(id, running)| (*id, running.started_at))
+    }
+
+    /// Show the newest running game on Discord, or clear the presence when
+    /// none is running or it was turned off
+    ///
+    /// Discord not running is logged and otherwise ignored; the connection is
+    /// tried again on the next launch.
+    fn update_discord_presence(&self) -> Task<Message> {
+        #[cfg(feature = "discord")]
+        {
+            let client_id = self
+                .config
+                .discord_client_id
+                .clone()
+                .filter(|_| self.config.discord_rpc_enabled);
+            let playing = client_id.as_ref().and_then(|_| {
+                let (id, started_at) = self.newest_running_game()?;
+                Some((self.library.get_game(&id)?.display_name(), started_at))
+            });
+            let discord = self.discord.clone();
+            Task::perform(
+                async move {
+                    let mut connection = discord.lock().await;
+                    let result = match (playing, client_id) {
+                        (Some((name, started_at)), Some(client_id)) => {
+                            if connection.is_none() {
+                                match DiscordPresence::connect(&client_id).await {
+                                    Ok(presence) => *connection = Some(presence),
+                                    Err(e) => {
+                                        tracing::debug!("Discord not reachable: {}", e);
+                                        return;
+                                    }
+                                }
+                            }
+                            let presence = connection.as_mut().expect("connected above");
+                            presence.set_activity(&name, started_at).await
+                        }
+                        _ => match connection.as_mut() {
+                            Some(presence) => presence.clear_activity().await,
+                            None => Ok(()),
+                        },
+                    };
+                    if let Err(e) = result {
+                        tracing::warn!("Could not update Discord presence: {}", e);
+                        *connection = None;
+                    }
+                },
+                |_| Message::None,
+            )
+        }
+        #[cfg(not(feature = "discord"))]
+        Task::none()
+    }
+
+    /// Write which game is being played for external tools, when enabled
+    ///
+    /// Writes go through one [`StatusWriter`], so the file ends up with the
+    /// newest status even when an earlier write finishes last.
+    fn write_launch_status(
+        &self,
+        id: GameId,
+        started_at: chrono::DateTime<Utc>,
+        running: bool,
+        exit_tracked: bool,
+    ) -> Task<Message> {
+        if !self.config.write_status_file {
+            return Task::none();
+        }
+        let Some(game) = self.library.get_game(&id) else {
+            return Task::none();
+        };
+        let status = LaunchStatus {
+            game: game.display_name(),
+            source: game.source,
+            started_at,
+            running,
+            exit_tracked,
+        };
+        let path = self.data_dir.path.join(LAUNCH_STATUS_FILE);
+        let write = self.status_writer.write(status, path);
+        Task::perform(
+            async move {
+                if let Err(e) = write.await {
+                    tracing::warn!("Could not write the launch status file: {}", e);
+                }
+            },
+            |_| Message::None,
+        )
+    }
+
+    fn activity_path(&self) -> PathBuf {
+        self.data_dir.path.join(ACTIVITY_FILE)
+    }
+
+    fn scan_cache_path(&self) -> PathBuf {
+        self.data_dir.path.join(SCAN_CACHE_FILE)
+    }
+
+    /// The settings a scan of `source` runs with, which its cached scan must match
+    fn scan_inputs(&self, source: GameSource) -> ScanInputs {
+        let roots = match source {
+            GameSource::Steam => self.config.steam_library_paths.clone(),
+            _ => Vec::new(),
+        };
+        ScanInputs {
+            roots,
+            exclude_patterns: self.config.import_exclude_patterns.clone(),
+            include_not_installed: self.config.source_import.get(source).include_not_installed,
+        }
+    }
+
+    /// Keep a store scan's results, replacing its previous scan
+    ///
+    /// Nothing is cached while the library is encrypted, since the cache
+    /// would keep game names in plain text beside it.
+    fn cache_scan(
+        &mut self,
+        source: GameSource,
+        games: &[DetectedGame],
+        report: &ScanReport,
+    ) -> Task<Message> {
+        if self.config.encrypt_library {
+            return Task::none();
+        }
+        let scan = CachedScan {
+            scanned_at: self.clock.now(),
+            inputs: self.scan_inputs(source),
+            games: games.to_vec(),
+            report: report.clone(),
+        };
+        self.scan_cache.insert(source, scan);
+
+        let cache = self.scan_cache.clone();
+        let path = self.scan_cache_path();
+        Task::perform(
+            async move {
+                if let Err(e) = cache.save(&path).await {
+                    tracing::warn!("Could not save the scan cache: {}", e);
+                }
+            },
+            |_| Message::None,
+        )
+    }
+
+    /// Add entries to the activity history and append them to its log
+    ///
+    /// Nothing is recorded while the library is encrypted, since the log
+    /// would keep game names in plain text beside it.
+    fn record_activity(&mut self, entries: Vec<ActivityEntry>) -> Task<Message> {
+        if entries.is_empty() || self.config.encrypt_library {
+            return Task::none();
+        }
+        self.activity.extend(entries.iter().cloned());
+        let skip = self.activity.len().saturating_sub(ACTIVITY_HISTORY_LIMIT);
+        self.activity.drain(..skip);
+
+        let path = self.activity_path();
+        Task::perform(
+            async move {
+                if let Err(e) = append_activity(&path, &entries, MAX_ACTIVITY_LOG_BYTES).await {
+                    tracing::warn!("Failed to write activity log: {}", e);
+                }
+            },
+            |()| Message::None,
+        )
+    }
+
+    /// Forget the activity log and scan cache, on disk and on screen, once
+    /// the library is encrypted
+    fn delete_plaintext_history(&mut self) -> Task<Message> {
+        self.activity.clear();
+        self.scan_cache = ScanCache::default();
+        let path = self.activity_path();
+        let scan_cache_path = self.scan_cache_path();
+        Task::perform(
+            async move {
+                if let Err(e) = delete_activity(&path).await {
+                    tracing::warn!("Failed to delete the activity log: {}", e);
+                }
+                match tokio::fs::remove_file(&scan_cache_path).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        tracing::warn!("Failed to delete the scan cache: {}", e);
+                    }
+                    _ => {}
+                }
+            },
+            |()| Message::None,
+        )
+    }
+
+    /// Save config to disk
+    fn save_config(&self) -> Task<Message> {
+        let config = self.config.clone();
+        let path = self.config_path();
+
+        Task::perform(
+            async move {
+                config.save_to_file(&path).await.map_err(|e| e.to_string())
+            },
+            |_| Message::None,
+        )
+    }
+
+    /// Title of each of the launcher's windows
+    pub fn title(&self, window: window::Id) -> String {
+        if Some(window) == self.now_playing_window {
+            "Now Playing".to_string()
+        } else {
+            APP_NAME.to_string()
+        }
+    }
+
+    /// Render the UI of one of the launcher's windows
+    pub fn view(&self, window: window::Id) -> Element<'_, Message> {
+        if Some(window) == self.now_playing_window {
+            return self.view_now_playing();
+        }
+        if let Some(unlock) = &self.unlock {
+            return self.view_unlock(unlock);
+        }
+        if let Some(error) = &self.load_error {
+            return self.view_load_error(error);
+        }
+
+        let content = match &self.current_view {
+            View::Library => self.view_library(),
+            View::GameDetail(id) => self.view_game_detail(*id),
+            View::Settings => self.view_settings(),
+            View::Import => self.view_import(),
+            View::AddGame => self.view_add_game(),
+            View::YearInReview => self.view_year_in_review(),
+            View::Activity => self.view_activity(),
+        };
+
+        let content = column![
+            self.quarantined_library.as_deref().map(|path| self.view_quarantine_banner(path)),
+            self.view_data_dir_banner(),
+            content,
+        ];
+
+        let content = container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(0);
+
+        match &self.confirm_dialog {
+            Some(dialog) => stack![content, self.view_confirm_dialog(dialog)].into(),
+            None => content.into(),
+        }
+    }
+
+    /// View: The now playing window, each running game with its session time
+    fn view_now_playing(&self) -> Element<'_, Message> {
+        let games = self.running_games.iter().filter_map(|(id, running)| {
+            let game = self.library.get_game(id)?;
+            let secs = running.started.elapsed().as_secs();
+            let session = format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+            let mut info = column![
+                text(game.display_name_truncated(MAX_CARD_NAME_CHARS)).size(16),
+                text(format!("This session: {}", session)).size(12),
+            ]
+            .spacing(4)
+            .width(Length::Fill);
+            if let Some(usage) = self.running_usage(id) {
+                info = info.push(text(usage.display()).size(12));
+            }
+            let stop = button(text("Stop"))
+                .on_press(Message::StopGame(*id))
+                .style(button::danger);
+            Some(row![info, stop].spacing(10).align_y(iced::Alignment::Center).into())
+        });
+
+        container(scrollable(column(games).spacing(12)))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(12)
+            .into()
+    }
+
+    /// View: Banner explaining that the data directory isn't the usual one
+    fn view_data_dir_banner(&self) -> Option<Element<'_, Message>> {
+        let warning = self.data_dir.warning()?;
+        let read_only = self.data_dir.is_read_only();
+
+        let banner = container(text(warning).size(13))
+            .width(Length::Fill)
+            .padding([8, 15])
+            .style(move |theme: &Theme| {
+                let palette = theme.palette();
+                let color = if read_only { palette.danger } else { palette.warning };
+                container::Style {
+                    background: Some(color.scale_alpha(0.2).into()),
+                    text_color: Some(color),
+                    ..Default::default()
+                }
+            });
+        Some(banner.into())
+    }
+
+    /// View: Banner explaining that a corrupt library file was set aside
+    fn view_quarantine_banner(&self, path: &Path) -> Element<'_, Message> {
+        let message = format!(
+            "Your library file couldn't be read, so a new one was started. The old file was kept as {}.",
+            path.display()
+        );
+        container(
+            row![
+                text(message).size(13).width(Length::Fill),
+                button(text("Open file").size(13))
+                    .on_press(Message::RevealPath(path.to_path_buf()))
+                    .style(button::secondary),
+                button(text("Dismiss").size(13))
+                    .on_press(Message::DismissQuarantineNotice)
+                    .style(button::text),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding([8, 15])
+        .style(|theme: &Theme| {
+            let danger = theme.palette().danger;
+            container::Style {
+                background: Some(danger.scale_alpha(0.2).into()),
+                text_color: Some(danger),
+                ..Default::default()
+            }
+        })
+        .into()
+    }
+
+    /// View: Modal confirmation dialog drawn over the current page
+    fn view_confirm_dialog<'a>(&'a self, dialog: &'a ConfirmDialog) -> Element<'a, Message> {
+        let can_confirm = !dialog.needs_pin || !dialog.pin_input.is_empty();
+        let mut body = column![text(&dialog.title).size(20), text(&dialog.body)].spacing(15);
+        if dialog.needs_pin {
+            body = body.push(
+                text_input("PIN", &dialog.pin_input)
+                    .style(self.input_style())
+                    .on_input(Message::ConfirmPinChanged)
+                    .on_submit(Message::ConfirmAccepted)
+                    .secure(true)
+                    .width(200),
+            );
+        }
+        if let Some(error) = &dialog.error {
+            body = body.push(text(error).style(|theme: &Theme| text::Style {
+                color: Some(theme.palette().danger),
+            }));
+        }
+        body = body.push(
+            row![
+                button(text("Cancel"))
+                    .on_press(Message::ConfirmCancelled)
+                    .style(button::secondary),
+                button(text("Continue"))
+                    .on_press_maybe(can_confirm.then_some(Message::ConfirmAccepted)),
+            ]
+            .spacing(10),
+        );
+
+        let card = container(body)
+            .padding(20)
+            .max_width(400)
+            .style(container::rounded_box);
+
+        // The backdrop swallows clicks so the page behind can't be used
+        opaque(
+            container(card)
+                .center(Length::Fill)
+                .style(|_theme: &Theme| {
+                    container::Style::default().background(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.6))
+                }),
+        )
+    }
+
+    /// View: Passphrase prompt for an encrypted library
+    fn view_unlock<'a>(&'a self, unlock: &'a UnlockPrompt) -> Element<'a, Message> {
+        let mut content = column![
+            text("Library is encrypted").size(24),
+            text("Enter your passphrase to open it."),
+            text_input("Passphrase", &unlock.input)
+                .style(self.input_style())
+                .on_input(Message::UnlockPassphraseChanged)
+                .on_submit(Message::UnlockLibrary)
+                .secure(true)
+                .width(300),
+            button(text(if unlock.checking { "Unlocking..." } else { "Unlock" }))
+                .on_press_maybe((!unlock.checking).then_some(Message::UnlockLibrary)),
+        ]
+        .spacing(15)
+        .align_x(iced::Alignment::Center);
+
+        if let Some(error) = &unlock.error {
+            content = content.push(text(error).style(|theme: &Theme| text::Style {
+                color: Some(theme.palette().danger),
+            }));
+        }
+
+        container(content).center(Length::Fill).into()
+    }
+
+    /// View: Library file that exists but couldn't be loaded
+    fn view_load_error<'a>(&'a self, error: &'a str) -> Element<'a, Message> {
+        let content = column![
+            text("Could not load the library").size(24),
+            text(format!(
+                "{} couldn't be read. Nothing will be saved over it until it loads.",
+                self.library_path().display()
+            )),
+            text(error).style(|theme: &Theme| text::Style {
+                color: Some(theme.palette().danger),
+            }),
+            button(text("Try again")).on_press(Message::LoadLibrary),
+        ]
+        .spacing(15)
+        .align_x(iced::Alignment::Center);
+
+        container(content).center(Length::Fill).into()
+    }
+
+    /// View: Main library
+    fn view_library(&self) -> Element<'_, Message> {
+        let m = self.config.ui_density.metrics();
+
+        // Header
+        let header = self.view_header();
+
+        // Game grid/list
+        let games = self.get_filtered_games();
+        let game_grid = column![self.view_featured_row(), self.view_game_grid(&games)]
+            .spacing(m.spacing * 2.0);
+
+        // Status bar
+        let status = self.view_status_bar();
+
+        // Layout; narrow windows give the games the full width and open the
+        // sidebar from the header instead
+        let games_pane = container(scrollable(game_grid))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(m.padding + 5.0);
+        let main_content: Element<'_, Message> = if !self.is_narrow_layout() {
+            row![self.view_sidebar(), self.view_sidebar_splitter(), games_pane].into()
+        } else if self.sidebar_menu_open {
+            stack![games_pane, opaque(self.view_sidebar_panel(true))].into()
+        } else {
+            games_pane.into()
+        };
+
+        let library = column![header, self.view_bulk_actions(), main_content, status]
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        // Clicks the menu itself doesn't capture land here and close it
+        if self.context_menu.is_some() {
+            mouse_area(library)
+                .on_press(Message::CloseContextMenu)
+                .on_right_press(Message::CloseContextMenu)
+                .into()
+        } else {
+            library.into()
+        }
+    }
+
+    /// View: Pinned games, shown above the library whatever the sort or filter
+    fn view_featured_row(&self) -> Element<'_, Message> {
+        let pinned: Vec<&Game> = self
+            .library
+            .pinned_games()
+            .into_iter()
+            .filter(|game| self.show_hidden || !game.hidden)
+            .collect();
+        if pinned.is_empty() {
+            return Space::new().into();
+        }
+        let m = self.config.ui_density.metrics();
+
+        let tiles = pinned.into_iter().map(|game| {
+            let art = game
+                .banner_path
+                .as_deref()
+                .map(|path| (path, game.banner_shape))
+                .or_else(|| game.hero_path.as_deref().map(|path| (path, Some(ArtShape::Hero))))
+                .map(|(path, shape)| self.view_art(path, shape, 120.0, 56.0));
+            let tile = column![
+                art,
+                text(game.display_name_truncated(MAX_CARD_NAME_CHARS / 2)).size(m.small_text_size),
+                row![
+                    button(text("Play").size(m.small_text_size)).on_press_maybe(
+                        self.can_launch(&game.id).then_some(Message::LaunchGame(game.id)),
+                    ),
+                    button(text("Unpin").size(m.small_text_size))
+                        .on_press(Message::TogglePinned(game.id))
+                        .style(button::text),
+                ]
+                .spacing(m.spacing / 2.0),
+            ]
+            .spacing(m.spacing / 2.0)
+            .width(140);
+
+            // Clicks outside the buttons open the game's page
+            mouse_area(
+                container(tile)
+                    .padding(m.padding / 2.0)
+                    .style(container::rounded_box),
+            )
+            .on_press(Message::EditGame(game.id))
+            .into()
+        });
+
+        column![
+            text("Pinned").size(m.text_size),
+            scrollable(row(tiles).spacing(m.spacing)).direction(
+                scrollable::Direction::Horizontal(scrollable::Scrollbar::new()),
+            ),
+        ]
+        .spacing(m.spacing / 2.0)
+        .into()
+    }
+
+    /// View: Actions for the selected games, shown while any are selected
+    fn view_bulk_actions(&self) -> Element<'_, Message> {
+        if self.selected_games.is_empty() {
+            return Space::new().into();
+        }
+        let m = self.config.ui_density.metrics();
+
+        let export_status: Element<'_, Message> = match &self.bundle_export {
+            None => Space::new().into(),
+            Some(Ok((path, report))) => {
+                let mut status = format!("Exported {} games to {}", report.games, path.display());
+                if !report.missing_art.is_empty() {
+                    status.push_str(&format!(
+                        " ({} missing art files skipped)",
+                        report.missing_art.len()
+                    ));
+                }
+                text(status).size(m.small_text_size).into()
+            }
+            Some(Err(e)) => text(format!("Export failed: {}", e))
+                .size(m.small_text_size)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+                .into(),
+        };
+
+        container(
+            row![
+                text(format!("{} selected", self.selected_games.len())).size(m.text_size),
+                button(text("Export selected…").size(m.text_size))
+                    .on_press(Message::ExportSelected),
+                tooltip(
+                    button(text("Merge into imported").size(m.text_size)).on_press_maybe(
+                        self.manual_merge_pair().map(|_| Message::MergeSelected)
+                    ),
+                    container(text("Select a manual entry and the imported copy of the same game"))
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::Bottom,
+                ),
+                button(text("Clear selection").size(m.text_size))
+                    .on_press(Message::ClearSelection)
+                    .style(button::secondary),
+                export_status,
+            ]
+            .spacing(m.spacing)
+            .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(m.padding / 2.0)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    /// View: Header bar
+    fn view_header(&self) -> Element<'_, Message> {
+        let m = self.config.ui_density.metrics();
+        let title = text("618-Launcher").size(m.title_size);
+
+        let search = text_input("Search games...", &self.search_query)
+            .style(self.input_style())
+            .on_input(Message::SearchChanged)
+            .size(m.text_size)
+            .width(300);
+
+        let sort_picker =
+            pick_list(SortOrder::all(), Some(self.effective_sort()), Message::SortChanged);
+
+        let group_picker = pick_list(GroupBy::all(), Some(self.group_by), Message::GroupByChanged)
+            .placeholder("Group by");
+
+        // The current mode stands out, the other is a plain button
+        let view_toggle = row(ViewMode::all().iter().map(|mode| {
+            let style = if *mode == self.view_mode {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text(mode.label()).size(m.text_size))
+                .on_press(Message::ViewModeChanged(*mode))
+                .style(style)
+                .into()
+        }))
+        .spacing(m.spacing / 4.0);
+        let view_toggle = tooltip(
+            view_toggle,
+            container(text("Ctrl+Shift+L")).padding(5).style(container::rounded_box),
+            tooltip::Position::Bottom,
+        );
+
+        let settings_btn = button(text("Settings"))
+            .on_press(Message::NavigateTo(View::Settings));
+
+        let menu_btn = self.is_narrow_layout().then(|| {
+            let style = if self.sidebar_menu_open {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text("☰ Menu").size(m.text_size))
+                .on_press(Message::ToggleSidebarMenu)
+                .style(style)
+        });
+
+        row![
+            menu_btn,
+            title,
+            Space::new().width(Length::Fill),
+            search,
+            sort_picker,
+            group_picker,
+            view_toggle,
+            settings_btn,
+        ]
+        .spacing(m.spacing * 2.0)
+        .padding(m.padding)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// View: Handle between the sidebar and the library for resizing it
+    fn view_sidebar_splitter(&self) -> Element<'_, Message> {
+        if self.config.sidebar_collapsed {
+            return Space::new().into();
+        }
+        let dragging = self.sidebar_dragging;
+        let handle = container(Space::new().width(4).height(Length::Fill)).style(
+            move |theme: &Theme| {
+                let palette = theme.extended_palette();
+                let color = if dragging {
+                    palette.primary.base.color
+                } else {
+                    palette.background.strong.color
+                };
+                container::Style {
+                    background: Some(color.into()),
+                    ..Default::default()
+                }
+            },
+        );
+        mouse_area(handle)
+            .on_press(Message::SidebarDragStarted)
+            .interaction(mouse::Interaction::ResizingHorizontally)
+            .into()
+    }
+
+    /// View: Collapsed sidebar, just a button to expand it and category icons
+    fn view_sidebar_rail(&self) -> Element<'_, Message> {
+        let m = self.config.ui_density.metrics();
+        let rail_button = |label: Element<'static, Message>, tip: String, message: Message| {
+            tooltip(
+                button(label).on_press(message).style(button::text),
+                container(text(tip)).padding(5).style(container::rounded_box),
+                tooltip::Position::Right,
+            )
+            .into()
+        };
+
+        let mut items: Vec<Element<Message>> = vec![
+            rail_button(
+                text("»").size(m.text_size).into(),
+                "Show sidebar".to_string(),
+                Message::ToggleSidebar,
+            ),
+            rail_button(
+                text("∗").size(m.text_size).into(),
+                "All Games".to_string(),
+                Message::FilterSelected(None),
+            ),
+        ];
+        items.extend(self.library.all_categories().iter().map(|cat| {
+            tooltip(
+                button(self.view_category_icon(cat, m.text_size))
+                    .on_press(Message::FilterSelected(Some(Filter::Category(cat.id))))
+                    .style(button::text),
+                container(text(&cat.name)).padding(5).style(container::rounded_box),
+                tooltip::Position::Right,
+            )
+            .into()
+        }));
+
+        container(scrollable(
+            column(items)
+                .spacing(m.spacing / 2.0)
+                .padding(m.padding / 2.0)
+                .align_x(iced::Alignment::Center),
+        ))
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Whether the window is narrow enough that the sidebar moves into a
+    /// header menu
+    fn is_narrow_layout(&self) -> bool {
+        self.config
+            .narrow_layout_width
+            .is_some_and(|width| self.window_width < width as f32)
+    }
+
+    /// View: Sidebar with categories, or the rail it collapses to
+    fn view_sidebar(&self) -> Element<'_, Message> {
+        if self.config.sidebar_collapsed {
+            self.view_sidebar_rail()
+        } else {
+            self.view_sidebar_panel(false)
+        }
+    }
+
+    /// View: Sidebar contents, beside the library or dropped down from the
+    /// header menu in narrow windows
+    fn view_sidebar_panel(&self, in_menu: bool) -> Element<'_, Message> {
+        use crate::message::{SettingKey, SettingValue};
+
+        let m = self.config.ui_density.metrics();
+
+        let (collapse_label, collapse_message) = if in_menu {
+            ("« Close menu", Message::ToggleSidebarMenu)
+        } else {
+            ("« Hide sidebar", Message::ToggleSidebar)
+        };
+        let collapse_btn = button(text(collapse_label).size(m.small_text_size))
+            .on_press(collapse_message)
+            .style(button::text);
+
+        // The filter being shown stands out from the others
+        let style = |filter: Option<Filter>| {
+            if self.filter == filter { button::primary } else { button::secondary }
+        };
+
+        let all_games_btn = button(
+            text(format!("All Games ({})", self.library.game_count())).size(m.text_size),
+        )
+        .width(Length::Fill)
+        .on_press(Message::FilterSelected(None))
+        .style(style(None));
+
+        let favorites_btn = button(
+            text(format!("Favorites ({})", self.library.favorite_games().len())).size(m.text_size),
+        )
+        .width(Length::Fill)
+        .on_press(Message::FilterSelected(Some(Filter::Favorites)))
+        .style(style(Some(Filter::Favorites)));
+
+        // Empty categories are only clutter, but keep the selected one visible
+        let mut category_buttons: Vec<Element<Message>> = self
+            .library
+            .all_categories()
+            .iter()
+            .map(|cat| {
+                (cat, self.library.games_in_category_cached(&cat.id, &self.smart_members).len())
+            })
+            .filter(|(cat, count)| {
+                !self.config.hide_empty_categories
+                    || *count > 0
+                    || self.filter == Some(Filter::Category(cat.id))
+            })
+            .map(|(cat, count)| {
+                button(
+                    row![
+                        self.view_category_icon(cat, m.text_size),
+                        text(format!("{} ({})", cat.name, count)).size(m.text_size),
+                    ]
+                    .spacing(m.spacing / 2.0),
+                )
+                .width(Length::Fill)
+                    .on_press(Message::FilterSelected(Some(Filter::Category(cat.id))))
+                    .style(style(Some(Filter::Category(cat.id))))
+                    .into()
+            })
+            .collect();
+
+        let mut status_buttons: Vec<Element<Message>> = PlayStatus::all()
+            .iter()
+            .map(|status| {
+                let count = self.library.games_with_status(*status).len();
+                button(text(format!("{} ({})", status.label(), count)).size(m.text_size))
+                    .width(Length::Fill)
+                    .on_press(Message::FilterSelected(Some(Filter::Status(*status))))
+                    .style(style(Some(Filter::Status(*status))))
+                    .into()
+            })
+            .collect();
+
+        let all_games = self.library.all_games();
+        let count = |missing: fn(&Game) -> bool| all_games.iter().filter(|g| missing(g)).count();
+        let maintenance = [
+            ("Missing art", Filter::MissingArt, count(Game::missing_art)),
+            ("Missing info", Filter::MissingMetadata, count(Game::missing_metadata)),
+        ];
+        let mut maintenance_buttons: Vec<Element<Message>> = maintenance
+            .into_iter()
+            .map(|(label, filter, count)| {
+                button(text(format!("{} ({})", label, count)).size(m.text_size))
+                    .width(Length::Fill)
+                    .on_press(Message::FilterSelected(Some(filter)))
+                    .style(style(Some(filter)))
+                    .into()
+            })
+            .collect();
+
+        let show_all_toggle = checkbox(!self.config.hide_empty_categories)
+            .label("Show all categories")
+            .text_size(m.small_text_size)
+            .on_toggle(|v| {
+                Message::SettingChanged(SettingKey::HideEmptyCategories, SettingValue::Bool(!v))
+            });
+
+        let add_game_btn = button(text("+ Add Game").size(m.text_size))
+            .width(Length::Fill)
+            .on_press(Message::AddGamePressed);
+
+        let import_btn = button(text("Import Games").size(m.text_size))
+            .width(Length::Fill)
+            .on_press(Message::NavigateTo(View::Import));
+
+        let review_btn = button(text("Year in Review").size(m.text_size))
+            .width(Length::Fill)
+            .on_press(Message::NavigateTo(View::YearInReview));
+
+        let activity_btn = button(text("Activity").size(m.text_size))
+            .width(Length::Fill)
+            .on_press(Message::NavigateTo(View::Activity));
+
+        let mut sidebar_items = vec![
+            collapse_btn.into(),
+            all_games_btn.into(),
+            favorites_btn.into(),
+        ];
+        sidebar_items.append(&mut category_buttons);
+        sidebar_items.append(&mut status_buttons);
+        sidebar_items.append(&mut maintenance_buttons);
+        sidebar_items.push(add_game_btn.into());
+        sidebar_items.push(import_btn.into());
+        sidebar_items.push(review_btn.into());
+        sidebar_items.push(activity_btn.into());
+        sidebar_items.push(show_all_toggle.into());
+
+        let hidden_count = self.library.all_games().iter().filter(|g| g.hidden).count();
+        if hidden_count > 0 {
+            sidebar_items.push(
+                checkbox(self.show_hidden)
+                    .label(format!("Show hidden games ({})", hidden_count))
+                    .text_size(m.small_text_size)
+                    .on_toggle(Message::ShowHiddenToggled)
+                    .into(),
+            );
+        }
+
+        let panel = container(
+            scrollable(
+                column(sidebar_items)
+                    .spacing(m.spacing / 2.0)
+                    .padding(m.padding * 2.0 / 3.0)
+                    .width(Length::Fill),
+            ),
+        )
+        .width(self.config.sidebar_width.clamp(SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH))
+        .height(Length::Fill);
+
+        // Over the games the menu needs a background of its own
+        if in_menu {
+            panel.style(container::bordered_box).into()
+        } else {
+            panel.into()
+        }
+    }
+
+    /// Stats for the current calendar year so far
+    fn year_in_review(&self) -> (i32, PlayStats) {
+        let now = self.clock.now();
+        let start = Utc
+            .with_ymd_and_hms(now.year(), 1, 1, 0, 0, 0)
+            .single()
+            .unwrap_or(now);
+        (now.year(), PlayStats::compute(self.library.all_games(), start, now))
+    }
+
+    /// View: Summary of the year's play habits
+    fn view_year_in_review(&self) -> Element<'_, Message> {
+        let (year, stats) = self.year_in_review();
+
+        let back_btn = button(text("Back")).on_press(Message::NavigateTo(View::Library));
+        let title = text(format!("{} in Review", year)).size(28);
+
+        let tile = |value: String, label: &'static str| -> Element<'_, Message> {
+            container(
+                column![
+                    text(value).size(32).style(|theme: &Theme| text::Style {
+                        color: Some(theme.palette().primary),
+                    }),
+                    text(label).size(14),
+                ]
+                .spacing(5)
+                .align_x(iced::Alignment::Center),
+            )
+            .padding(20)
+            .width(Length::Fill)
+            .style(container::rounded_box)
+            .into()
+        };
+
+        let body: Element<'_, Message> = if stats.is_empty() {
+            text("No games played yet this year. Launch something and check back!")
+                .size(16)
+                .into()
+        } else {
+            let most_played = stats
+                .most_played
+                .as_ref()
+                .map(|(name, minutes)| format!("{} ({}h)", name, minutes / 60))
+                .unwrap_or_else(|| "-".to_string());
+            let busiest_day = stats.busiest_day.map(weekday_name).unwrap_or("-");
+
+            column![
+                row![
+                    tile(stats.games_played.to_string(), "Games played"),
+                    tile(stats.total_hours().to_string(), "Hours played"),
+                ]
+                .spacing(15),
+                row![
+                    tile(most_played, "Most played"),
+                    tile(busiest_day.to_string(), "Busiest day"),
+                ]
+                .spacing(15),
+                text("Hours are lifetime totals for games played this year.").size(12),
+            ]
+            .spacing(15)
+            .into()
+        };
+
+        let export_status: Element<'_, Message> = match &self.review_export {
+            None => Space::new().into(),
+            Some(Ok(path)) => text(format!("Saved to {}", path.display()))
+                .size(12)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().success),
+                })
+                .into(),
+            Some(Err(e)) => text(format!("Export failed: {}", e))
+                .size(12)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+                .into(),
+        };
+
+        let export_btn = button(text("Export as text")).on_press(Message::ExportYearInReview);
+
+        scrollable(
+            column![
+                back_btn,
+                title,
+                body,
+                row![export_btn, export_status]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
+            ]
+            .spacing(20)
+            .padding(20)
+            .max_width(700),
+        )
+        .into()
+    }
+
+    /// View: Recent launcher activity, newest first
+    fn view_activity(&self) -> Element<'_, Message> {
+        let back_btn = button(text("Back")).on_press(Message::NavigateTo(View::Library));
+        let title = text("Activity").size(28);
+
+        let filters = std::iter::once(None)
+            .chain(ActivityKind::all().iter().copied().map(Some))
+            .map(|kind| {
+                let label = kind.map_or("All", |kind| kind.label());
+                let btn = button(text(label)).on_press(Message::ActivityFilterChanged(kind));
+                if kind == self.activity_filter {
+                    btn.style(button::primary).into()
+                } else {
+                    btn.style(button::secondary).into()
+                }
+            });
+
+        let entries: Vec<Element<'_, Message>> = self
+            .activity
+            .iter()
+            .rev()
+            .filter(|entry| self.activity_filter.is_none_or(|kind| entry.kind == kind))
+            .map(|entry| {
+                let time = entry.time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                row![
+                    text(time.to_string()).size(13).width(140),
+                    text(entry.describe()),
+                ]
+                .spacing(15)
+                .into()
+            })
+            .collect();
+
+        let body: Element<'_, Message> = if self.config.encrypt_library {
+            text("Activity isn't recorded while the library is encrypted.").size(16).into()
+        } else if entries.is_empty() {
+            text("Nothing here yet. Launches, sessions and imports will show up as they happen.")
+                .size(16)
+                .into()
+        } else {
+            column(entries).spacing(8).into()
+        };
+
+        scrollable(
+            column![back_btn, title, row(filters).spacing(10), body]
+                .spacing(20)
+                .padding(20)
+                .max_width(700),
+        )
+        .into()
+    }
+
+    /// View: Game grid
+    fn view_game_grid(&self, games: &[&Game]) -> Element<'_, Message> {
+        if games.is_empty() {
+            return container(
+                text("No games in library. Add some games to get started!")
+                    .size(18),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into();
+        }
+
+        let m = self.config.ui_density.metrics();
+        let cards = |games: &[&Game]| -> Element<Message> {
+            let game_cards: Vec<Element<Message>> = games
+                .iter()
+                .map(|game| self.view_game_card(game))
+                .collect();
+
+            match self.view_mode {
+                // Grid cards are fixed-size tiles, so flow them into rows that
+                // reflow as the window is resized
+                ViewMode::Grid => row(game_cards)
+                    .spacing(m.spacing)
+                    .wrap()
+                    .vertical_spacing(m.spacing)
+                    .into(),
+                ViewMode::List => column(game_cards).spacing(m.spacing).width(Length::Fill).into(),
+            }
+        };
+
+        if self.group_by == GroupBy::None {
+            return cards(games);
+        }
+
+        let sections: Vec<Element<Message>> = group_games(&self.library, games, self.group_by)
+            .into_iter()
+            .map(|(title, games)| {
+                column![
+                    text(format!("{} ({})", title, games.len())).size(m.text_size + 2.0),
+                    cards(&games),
+                ]
+                .spacing(m.spacing)
+                .into()
+            })
+            .collect();
+
+        column(sections).spacing(m.spacing * 2.5).width(Length::Fill).into()
+    }
+
+    /// View: Individual game card
+    fn view_game_card(&self, game: &Game) -> Element<'_, Message> {
+        if self.uses_cover_cards() {
+            return self.view_cover_card(game);
+        }
+        let m = self.config.ui_density.metrics();
+        // Grid tiles are as wide as their cover, so names get half the room
+        let max_name_chars = match self.view_mode {
+            ViewMode::Grid => MAX_CARD_NAME_CHARS / 2,
+            ViewMode::List => MAX_CARD_NAME_CHARS,
+        };
+        let display_name = game.display_name_truncated(max_name_chars);
+        let name: Element<'_, Message> = if display_name != game.display_name() {
+            // Truncated, keep the full name reachable on hover
+            tooltip(
+                text(display_name).size(m.text_size),
+                container(text(game.display_name()))
+                    .padding(5)
+                    .style(container::rounded_box),
+                tooltip::Position::Top,
+            )
+            .into()
+        } else {
+            text(display_name).size(m.text_size).into()
+        };
+        let source = self
+            .config
+            .shows_source(game.source)
+            .then(|| text(game.source.label()).size(m.small_text_size));
+        let title = row![name, self.view_new_badge(game, m.small_text_size)]
+            .spacing(m.spacing / 2.0)
+            .align_y(iced::Alignment::Center);
+        let mut details = column![title, source].spacing(m.spacing / 2.0);
+        // List rows have room for when the game was last played
+        if self.view_mode == ViewMode::List && game.last_played.is_some() {
+            details = details.push(
+                text(game.last_played_display(self.clock.now())).size(m.small_text_size),
+            );
+        }
+        if let Some(usage) = self.running_usage(&game.id) {
+            details = details.push(text(usage.display()).size(m.small_text_size));
+        }
+
+        let game_id = game.id;
+        let play_btn = button(text("Play").size(m.text_size))
+            .on_press_maybe(self.can_launch(&game_id).then_some(Message::LaunchGame(game_id)));
+
+        let fav_icon = if game.favorite { "★" } else { "☆" };
+        let fav_btn = button(text(fav_icon).size(m.text_size))
+            .on_press(Message::ToggleFavorite(game_id));
+
+        // Grid cards show the portrait cover, list rows a wide thumbnail
+        let size = self.config.card_size;
+        let art = match self.view_mode {
+            ViewMode::Grid => {
+                let art = game
+                    .banner_path
+                    .as_deref()
+                    .map(|path| (path, game.banner_shape))
+                    .or_else(|| game.hero_path.as_deref().map(|path| (path, Some(ArtShape::Hero))))
+                    .map(|(path, shape)| self.view_art(path, shape, size.width(), size.height()))
+                    .unwrap_or_else(|| {
+                        // Keep tiles without art the same size as the rest
+                        container(Space::new())
+                            .width(size.width())
+                            .height(size.height())
+                            .style(|theme: &Theme| {
+                                container::Style::default()
+                                    .background(theme.extended_palette().background.strong.color)
+                            })
+                            .into()
+                    });
+                Some(art)
+            }
+            ViewMode::List => game
+                .hero_path
+                .as_deref()
+                .map(|path| (path, Some(ArtShape::Hero)))
+                .or_else(|| game.banner_path.as_deref().map(|path| (path, game.banner_shape)))
+                .map(|(path, shape)| self.view_art(path, shape, 120.0, 56.0)),
+        };
+
+        // Once anything is selected, every card gets a checkbox to add to it
+        let select_box = (!self.selected_games.is_empty()).then(|| {
+            checkbox(self.selected_games.contains(&game_id))
+                .on_toggle(move |_| Message::ToggleGameSelected(game_id))
+        });
+
+        let card_content: Element<'_, Message> = match self.view_mode {
+            ViewMode::Grid => column![
+                art,
+                details,
+                row![select_box, Space::new().width(Length::Fill), fav_btn, play_btn]
+                    .spacing(m.spacing / 2.0)
+                    .align_y(iced::Alignment::Center),
+            ]
+            .spacing(m.spacing / 2.0)
+            .padding(m.padding)
+            .width(size.width() + 2.0 * m.padding)
+            .into(),
+            ViewMode::List => row![
+                select_box,
+                art,
+                details,
+                Space::new().width(Length::Fill),
+                fav_btn,
+                play_btn,
+            ]
+            .spacing(m.spacing)
+            .padding(m.padding)
+            .align_y(iced::Alignment::Center)
+            .width(Length::Fill)
+            .into(),
+        };
+
+        let card = mouse_area(container(card_content))
+            .on_right_press(Message::OpenContextMenu(game_id));
+
+        if self.context_menu == Some(game_id) {
+            column![card, self.view_card_menu(game)].into()
+        } else {
+            card.into()
         }
     }
 
-    /// Save library to disk
-    fn save_library(&self) -> Task<Message> {
-        let library = self.library.clone();
-        let path = self.library_path();
+    /// Whether the library shows cover cards rather than classic rows
+    fn uses_cover_cards(&self) -> bool {
+        self.view_mode == ViewMode::Grid && self.config.card_style == CardStyle::Cover
+    }
 
-        Task::perform(
-            async move { library.save_to_file(&path).await.map_err(|e| e.to_string()) },
-            Message::LibrarySaved,
+    /// View: Card filled by the cover art, with the name on a scrim at the
+    /// bottom and Play/Favorite shown while hovered
+    fn view_cover_card(&self, game: &Game) -> Element<'_, Message> {
+        let m = self.config.ui_density.metrics();
+        let size = self.config.card_size;
+        let game_id = game.id;
+
+        let art = game
+            .banner_path
+            .as_deref()
+            .map(|path| (path, game.banner_shape))
+            .or_else(|| game.hero_path.as_deref().map(|path| (path, Some(ArtShape::Hero))))
+            .map(|(path, shape)| self.view_art(path, shape, Length::Fill, Length::Fill))
+            .unwrap_or_else(|| {
+                // No art yet, so the overlaid name is all there is
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Style::default()
+                            .background(theme.extended_palette().background.strong.color)
+                    })
+                    .into()
+            });
+
+        let badge = self.config.shows_source(game.source).then(|| {
+            container(text(game.source.label()).size(m.small_text_size))
+                .padding([1, 6])
+                .style(|theme: &Theme| container::Style {
+                    background: Some(theme.palette().primary.scale_alpha(0.8).into()),
+                    text_color: Some(Color::WHITE),
+                    border: iced::border::rounded(4),
+                    ..Default::default()
+                })
+        });
+        let caption = container(
+            column![
+                text(game.display_name_truncated(MAX_CARD_NAME_CHARS / 2)).size(m.text_size),
+                row![badge, self.view_new_badge(game, m.small_text_size)].spacing(m.spacing / 2.0),
+            ]
+            .spacing(m.spacing / 2.0),
         )
+        .width(Length::Fill)
+        .padding([m.padding, m.padding / 2.0])
+        .style(|_: &Theme| {
+            let scrim = iced::gradient::Linear::new(iced::Radians::PI)
+                .add_stop(0.0, Color::TRANSPARENT)
+                .add_stop(1.0, Color::from_rgba(0.0, 0.0, 0.0, 0.85));
+            container::Style {
+                background: Some(iced::Background::Gradient(scrim.into())),
+                text_color: Some(Color::WHITE),
+                ..Default::default()
+            }
+        });
+
+        let select_box = (!self.selected_games.is_empty()).then(|| {
+            checkbox(self.selected_games.contains(&game_id))
+                .on_toggle(move |_| Message::ToggleGameSelected(game_id))
+        });
+        let actions = (self.hovered_card == Some(game_id)).then(|| {
+            let fav_icon = if game.favorite { "★" } else { "☆" };
+            row![
+                button(text(fav_icon).size(m.small_text_size))
+                    .on_press(Message::ToggleFavorite(game_id))
+                    .style(button::secondary),
+                button(text("Play").size(m.small_text_size)).on_press_maybe(
+                    self.can_launch(&game_id).then_some(Message::LaunchGame(game_id)),
+                ),
+            ]
+            .spacing(m.spacing / 2.0)
+        });
+        let top_bar = row![select_box, Space::new().width(Length::Fill), actions]
+            .padding(m.padding / 2.0)
+            .align_y(iced::Alignment::Center);
+
+        let card = stack![
+            art,
+            column![Space::new().height(Length::Fill), caption],
+            top_bar,
+        ]
+        .width(size.width())
+        .height(size.height());
+
+        let card = mouse_area(container(card).clip(true))
+            .on_enter(Message::CardHovered(game_id))
+            .on_exit(Message::CardUnhovered(game_id))
+            .on_right_press(Message::OpenContextMenu(game_id));
+
+        if self.context_menu == Some(game_id) {
+            column![card, self.view_card_menu(game)].into()
+        } else {
+            card.into()
+        }
     }
 
-    /// Save config to disk
-    fn save_config(&self) -> Task<Message> {
-        let config = self.config.clone();
-        let path = self.config_path();
+    /// View: Right-click menu anchored below a game card
+    fn view_card_menu(&self, game: &Game) -> Element<'_, Message> {
+        let id = game.id;
+        let item = |label: &'static str, action: Message| {
+            button(text(label).size(14))
+                .width(Length::Fill)
+                .style(button::text)
+                .on_press(Message::ContextMenuAction(Box::new(action)))
+        };
 
-        Task::perform(
-            async move {
-                config.save_to_file(&path).await.map_err(|e| e.to_string())
-            },
-            |_| Message::None,
+        // Links have no folder of their own
+        let folder = game.install_path.clone().or_else(|| {
+            game.uri_target()
+                .is_none()
+                .then(|| game.executable_path.parent().map(|p| p.to_path_buf()))
+                .flatten()
+        });
+
+        let mut menu = column![
+            item("Play", Message::LaunchGame(id)),
+            item(
+                if game.favorite { "Unfavorite" } else { "Favorite" },
+                Message::ToggleFavorite(id),
+            ),
+            item("Edit", Message::EditGame(id)),
+            item(
+                if self.library.is_pinned(&id) { "Unpin" } else { "Pin to top" },
+                Message::TogglePinned(id),
+            ),
+            item(
+                if self.selected_games.contains(&id) { "Deselect" } else { "Select" },
+                Message::ToggleGameSelected(id),
+            ),
+        ]
+        .spacing(2)
+        .width(220);
+
+        // Tags toggle in place so several can be changed in one go
+        let categories = self.library.all_categories();
+        if !categories.is_empty() {
+            menu = menu.push(text("Tags").size(12));
+            for category in categories {
+                let category_id = category.id;
+                let tag = checkbox(self.library.in_category(game, &category_id))
+                    .label(category.name.as_str())
+                    .text_size(14);
+                // Smart categories pick their own games, but a tag added by hand
+                // before the rule can still be taken off
+                let editable = category.rule.is_none() || game.has_category(&category_id);
+                menu = menu.push(tag.on_toggle_maybe(editable.then_some(
+                    move |on| {
+                        if on {
+                            Message::AssignCategory(id, category_id)
+                        } else {
+                            Message::UnassignCategory(id, category_id)
+                        }
+                    },
+                )));
+            }
+        }
+
+        let open_folder = button(text("Open Folder").size(14))
+            .width(Length::Fill)
+            .style(button::text)
+            .on_press_maybe(
+                folder
+                    .map(Message::RevealPath)
+                    .map(|action| Message::ContextMenuAction(Box::new(action))),
+            );
+
+        menu = menu
+            .push(open_folder)
+            .push(item(
+                if game.hidden { "Unhide" } else { "Hide" },
+                Message::ToggleHidden(id),
+            ))
+            .push(item("Remove", Message::RemoveGame(id)).style(button::danger));
+
+        // Swallow clicks on the menu's padding so they don't close it
+        opaque(
+            container(menu)
+                .padding(5)
+                .style(container::rounded_box),
+        )
+    }
+
+    /// View: A "New" pill for games added since the badge cutoff
+    fn view_new_badge(&self, game: &Game, size: f32) -> Option<Element<'_, Message>> {
+        let cutoff = self.config.new_badge_cutoff(self.clock.now())?;
+        (game.added_date > cutoff).then(|| {
+            container(text("New").size(size))
+                .padding([1, 6])
+                .style(|theme: &Theme| container::Style {
+                    background: Some(theme.palette().success.scale_alpha(0.8).into()),
+                    text_color: Some(Color::WHITE),
+                    border: iced::border::rounded(4),
+                    ..Default::default()
+                })
+                .into()
+        })
+    }
+
+    /// Games that still carry a "New" badge
+    fn new_games(&self) -> Vec<&Game> {
+        let now = self.clock.now();
+        let Some(cutoff) = self.config.new_badge_cutoff(now) else {
+            return Vec::new();
+        };
+        let mut games = self.library.recently_added(now - cutoff, self.clock.as_ref());
+        games.retain(|game| self.show_hidden || !game.hidden);
+        games
+    }
+
+    /// View: Status bar
+    fn view_status_bar(&self) -> Element<'_, Message> {
+        let game_count = text(format!("{} games", self.library.game_count())).size(12);
+
+        let mut status = row![game_count].spacing(20).padding(10).align_y(iced::Alignment::Center);
+        let new_count = self.new_games().len();
+        if new_count > 0 {
+            status = status.push(
+                row![
+                    text(format!("{} new", new_count)).size(12),
+                    button(text("Clear new badges").size(12))
+                        .on_press(Message::ClearNewBadges)
+                        .padding([2, 8])
+                        .style(button::secondary),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+        for (id, running) in &self.running_games {
+            if let (Some(game), Some(usage)) = (self.library.get_game(id), running.usage) {
+                let name = game.display_name_truncated(MAX_CARD_NAME_CHARS);
+                status = status.push(text(format!("{}: {}", name, usage.display())).size(12));
+            }
+        }
+
+        if let Some(error) = &self.save_error {
+            status = status.push(
+                text(format!("Changes not saved: {}", error))
+                    .size(12)
+                    .style(|theme: &Theme| text::Style {
+                        color: Some(theme.palette().danger),
+                    }),
+            );
+        }
+
+        status.into()
+    }
+
+    /// View: Game detail page
+    fn view_game_detail(&self, id: GameId) -> Element<'_, Message> {
+        if let Some(game) = self.library.get_game(&id) {
+            let title = text(game.display_name()).size(28);
+            let source = self
+                .config
+                .shows_source(game.source)
+                .then(|| text(format!("Source: {}", game.source.label())));
+            let path = match game.uri_target() {
+                Some(uri) => copyable_line("URL", uri.to_string()),
+                None => copyable_line("Path", game.executable_path.display().to_string()),
+            };
+            let playtime = text(format!("Playtime: {}", game.playtime_display()));
+            let chips = self
+                .library
+                .categories_of(game)
+                .into_iter()
+                .map(|category| {
+                    container(
+                        row![
+                            self.view_category_icon(category, 14.0),
+                            text(&category.name).size(14),
+                        ]
+                        .spacing(5)
+                        .align_y(iced::Alignment::Center),
+                    )
+                    .padding([2, 8])
+                    .style(container::rounded_box)
+                    .into()
+                });
+            let chips = row(chips).spacing(5);
+            let last_played =
+                text(format!("Last played: {}", game.last_played_display(self.clock.now())));
+            let status = row![
+                text("Status:"),
+                pick_list(PlayStatus::all(), Some(game.status), move |status| {
+                    Message::UpdateGame(
+                        id,
+                        GameUpdate {
+                            status: Some(status),
+                            ..Default::default()
+                        },
+                    )
+                }),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center);
+
+            let back_btn = button(text("Back"))
+                .on_press(Message::NavigateTo(View::Library));
+
+            let can_launch = self.can_launch(&id);
+            let play_btn: Element<'_, Message> = if game.store_links.is_empty() {
+                button(text(if game.is_installed() { "Play" } else { "Install" }))
+                    .on_press_maybe(can_launch.then_some(Message::LaunchGame(id)))
+                    .into()
+            } else {
+                // Owned on several stores, let the user pick which one to launch from
+                let mut launch_row = row![
+                    text("Play via:"),
+                    button(text(game.source.label()))
+                        .on_press_maybe(can_launch.then_some(Message::LaunchGame(id))),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center);
+                for (link_source, _) in &game.store_links {
+                    launch_row = launch_row.push(
+                        button(text(link_source.label())).on_press_maybe(
+                            can_launch.then_some(Message::LaunchGameVia(id, *link_source)),
+                        ),
+                    );
+                }
+                launch_row.into()
+            };
+
+            // Wide hero art suits the banner slot best, covers are letterboxed
+            let banner = game
+                .hero_path
+                .as_deref()
+                .map(|path| (path, Some(ArtShape::Hero)))
+                .or_else(|| game.banner_path.as_deref().map(|path| (path, game.banner_shape)))
+                .map(|(path, shape)| self.view_art(path, shape, Length::Fill, 220.0));
+
+            let content = column![
+                back_btn,
+                banner,
+                title,
+                source,
+                path,
+                self.view_install_path(game),
+                playtime,
+                last_played,
+                status,
+                chips,
+                row![
+                    play_btn,
+                    button(text("Duplicate"))
+                        .on_press(Message::DuplicateGame(id))
+                        .style(button::secondary),
+                    self.failed_launch_logs.get(&id).map(|path| {
+                        button(text("Open last launch log"))
+                            .on_press(Message::RevealPath(path.clone()))
+                            .style(button::secondary)
+                    }),
+                    game.uri_target().is_none().then(|| {
+                        button(text("Preview launch command"))
+                            .on_press(Message::PreviewLaunchCommand(id))
+                            .style(button::secondary)
+                    }),
+                    game.install_path.is_some().then(|| {
+                        button(text("Re-detect executable"))
+                            .on_press(Message::RedetectExecutable(id))
+                            .style(button::secondary)
+                    }),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                self.launch_preview
+                    .as_ref()
+                    .filter(|(preview_id, _)| *preview_id == id)
+                    .map(|(_, resolved)| self.view_launch_preview(resolved)),
+                self.exe_candidates
+                    .as_ref()
+                    .filter(|(candidates_id, _)| *candidates_id == id)
+                    .map(|(_, candidates)| self.view_exe_candidates(game, candidates)),
+                game.emulator_command.is_some().then(|| self.view_emulator_command(id)),
+                self.view_saves(game),
+                self.view_display_prefs(game),
+            ]
+            .spacing(15)
+            .padding(20);
+
+            #[cfg(feature = "steamgriddb")]
+            let content = content.push(self.view_artwork_picker(id));
+
+            content.into()
+        } else {
+            column![
+                text("Game not found"),
+                button(text("Back")).on_press(Message::NavigateTo(View::Library)),
+            ]
+            .spacing(15)
+            .padding(20)
+            .into()
+        }
+    }
+
+    /// View: Executables found in the install folder, to replace a wrong guess
+    fn view_exe_candidates<'a>(
+        &'a self,
+        game: &Game,
+        candidates: &'a [PathBuf],
+    ) -> Element<'a, Message> {
+        let id = game.id;
+        let install_path = game.install_path.as_deref();
+        let body: Element<'_, Message> = if candidates.is_empty() {
+            text("No executables found in the install folder.").size(13).into()
+        } else {
+            column(candidates.iter().map(|path| {
+                // Relative to the install folder, which is the same for all of them
+                let shown = install_path
+                    .and_then(|dir| path.strip_prefix(dir).ok())
+                    .unwrap_or(path);
+                let current = *path == game.executable_path;
+                row![
+                    text(shown.display().to_string()).size(13).width(Length::Fill),
+                    button(text(if current { "Current" } else { "Use" }))
+                        .on_press_maybe(
+                            (!current).then(|| Message::ExecutableChosen(id, path.clone())),
+                        )
+                        .style(if current { button::primary } else { button::secondary }),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            }))
+            .spacing(5)
+            .into()
+        };
+
+        container(
+            column![
+                row![
+                    text("Executables in the install folder").size(16),
+                    Space::new().width(Length::Fill),
+                    button(text("Close"))
+                        .on_press(Message::CloseExecutableCandidates)
+                        .style(button::secondary),
+                ]
+                .align_y(iced::Alignment::Center),
+                text("Most likely first. The one you pick is kept when the store is synced again.")
+                    .size(12),
+                body,
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    /// View: The emulator command an emulated game runs through
+    fn view_emulator_command(&self, id: GameId) -> Element<'_, Message> {
+        let can_set = !self.emulator_command_input.trim().is_empty();
+        column![
+            text("Emulator").size(16),
+            row![
+                text_input("Emulator command", &self.emulator_command_input)
+                    .style(self.input_style())
+                    .on_input(Message::EmulatorCommandInputChanged)
+                    .on_submit(Message::SetEmulatorCommand(id))
+                    .font(iced::Font::MONOSPACE)
+                    .width(Length::Fill),
+                button(text("Set"))
+                    .on_press_maybe(can_set.then_some(Message::SetEmulatorCommand(id))),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            text("{rom} is replaced with the game's path.").size(12),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View: A game's install folder, which imports stop refreshing once set here
+    fn view_install_path(&self, game: &Game) -> Element<'_, Message> {
+        let id = game.id;
+        let input = self.install_path_input.trim();
+        let can_set = !input.is_empty() && game.install_path.as_deref() != Some(Path::new(input));
+        row![
+            text("Install folder:"),
+            text_input("Install folder", &self.install_path_input)
+                .style(self.input_style())
+                .on_input(Message::InstallPathInputChanged)
+                .on_submit(Message::SetInstallPath(id))
+                .width(Length::Fill),
+            button(text("Set")).on_press_maybe(can_set.then_some(Message::SetInstallPath(id))),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// View: A game's save folder, with backup and restore
+    fn view_saves<'a>(&'a self, game: &'a Game) -> Element<'a, Message> {
+        let id = game.id;
+        let can_set = !self.save_path_input.trim().is_empty()
+            && game.save_path.as_deref() != Some(Path::new(self.save_path_input.trim()));
+        let has_path = game.save_path.is_some();
+
+        let mut section = column![
+            text("Saves").size(16),
+            row![
+                text_input("Save folder", &self.save_path_input)
+                    .style(self.input_style())
+                    .on_input(Message::SavePathInputChanged)
+                    .on_submit(Message::SetSavePath(id))
+                    .width(Length::Fill),
+                button(text("Set"))
+                    .on_press_maybe(can_set.then_some(Message::SetSavePath(id))),
+                button(text("Find save folder"))
+                    .on_press(Message::FindSaveFolders(id))
+                    .style(button::secondary),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ]
+        .spacing(10);
+
+        if let Some((_, guesses)) = self.save_folder_guesses.as_ref().filter(|(g, _)| *g == id) {
+            if guesses.is_empty() {
+                section = section.push(text("No likely save folders found.").size(13));
+            }
+            for guess in guesses {
+                let current = game.save_path.as_ref() == Some(guess);
+                section = section.push(
+                    row![
+                        text(guess.display().to_string()).size(13).width(Length::Fill),
+                        button(text(if current { "Current" } else { "Use" }))
+                            .on_press_maybe((!current).then(|| {
+                                Message::UpdateGame(
+                                    id,
+                                    GameUpdate {
+                                        save_path: Some(guess.clone()),
+                                        ..Default::default()
+                                    },
+                                )
+                            }))
+                            .style(if current { button::primary } else { button::secondary }),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
+                );
+            }
+        }
+
+        section = section.push(
+            row![
+                button(text("Back up saves"))
+                    .on_press_maybe(has_path.then_some(Message::BackupSaves(id))),
+                button(text("Restore saves"))
+                    .on_press_maybe(has_path.then_some(Message::ShowSaveBackups(id)))
+                    .style(button::secondary),
+                game.save_path.clone().map(|path| {
+                    button(text("Open save folder"))
+                        .on_press(Message::RevealPath(path))
+                        .style(button::secondary)
+                }),
+            ]
+            .spacing(10),
+        );
+
+        if let Some((_, status)) = self.save_status.as_ref().filter(|(s, _)| *s == id) {
+            section = section.push(match status {
+                Ok(message) => text(message).size(12),
+                Err(message) => text(message).size(12).style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                }),
+            });
+        }
+
+        if let Some((_, backups)) = self.save_backups.as_ref().filter(|(b, _)| *b == id) {
+            let list: Element<'_, Message> = if backups.is_empty() {
+                text("No backups yet.").size(13).into()
+            } else {
+                column(backups.iter().map(|backup| {
+                    row![
+                        text(backup.taken.format("%Y-%m-%d %H:%M:%S").to_string())
+                            .size(13)
+                            .width(Length::Fill),
+                        button(text("Restore"))
+                            .on_press(Message::AskRestoreSaves(id, backup.path.clone()))
+                            .style(button::secondary),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+                }))
+                .spacing(5)
+                .into()
+            };
+            section = section.push(
+                container(
+                    column![
+                        row![
+                            text("Backups").size(14),
+                            Space::new().width(Length::Fill),
+                            button(text("Close"))
+                                .on_press(Message::CloseSaveBackups)
+                                .style(button::secondary),
+                        ]
+                        .align_y(iced::Alignment::Center),
+                        text("The current saves are backed up before a restore replaces them.")
+                            .size(12),
+                        list,
+                    ]
+                    .spacing(10),
+                )
+                .padding(10)
+                .width(Length::Fill)
+                .style(container::rounded_box),
+            );
+        }
+
+        section.into()
+    }
+
+    /// View: The command a launch would run, for troubleshooting arguments
+    fn view_launch_preview(
+        &self,
+        resolved: &Result<ResolvedCommand, LaunchError>,
+    ) -> Element<'_, Message> {
+        let body: Element<'_, Message> = match resolved {
+            Ok(command) => {
+                let mut lines = column![
+                    text(command.command_line()).font(iced::Font::MONOSPACE).size(13),
+                ]
+                .spacing(5);
+                if let Some(dir) = &command.working_dir {
+                    let dir = format!("Working directory: {}", dir.display());
+                    lines = lines.push(text(dir).size(12));
+                }
+                for (key, value) in &command.env {
+                    lines = lines.push(text(format!("{}={}", key, value)).size(12));
+                }
+                if command.elevated {
+                    lines = lines.push(text("Runs with administrator rights").size(12));
+                }
+                if let Some(log) = &command.log_path {
+                    lines = lines.push(text(format!("Output saved to {}", log.display())).size(12));
+                }
+                lines.into()
+            }
+            Err(e) => text(format!("Can't launch: {}", e))
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+                .into(),
+        };
+
+        container(
+            column![
+                row![
+                    text("Launch command").size(16),
+                    Space::new().width(Length::Fill),
+                    button(text("Close"))
+                        .on_press(Message::CloseLaunchPreview)
+                        .style(button::secondary),
+                ]
+                .align_y(iced::Alignment::Center),
+                body,
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    /// View: Artwork scaled into a fixed box, cropped or letterboxed per settings
+    fn view_art(
+        &self,
+        path: &Path,
+        shape: Option<ArtShape>,
+        width: impl Into<Length>,
+        height: impl Into<Length>,
+    ) -> Element<'_, Message> {
+        let fit = art_fit(shape, self.config.crop_card_art);
+        container(
+            iced::widget::image(path)
+                .content_fit(fit)
+                .width(Length::Fill)
+                .height(Length::Fill),
         )
+        .width(width)
+        .height(height)
+        .center(Length::Shrink)
+        .clip(true)
+        .style(|_theme: &Theme| container::Style::default().background(iced::Color::BLACK))
+        .into()
+    }
+
+    /// View: Sample library card laid out with the current density
+    fn view_density_preview(&self) -> Element<'_, Message> {
+        let m = self.config.ui_density.metrics();
+        let card = row![
+            column![
+                text("Example Game").size(m.text_size),
+                text(GameSource::Steam.label()).size(m.small_text_size),
+            ]
+            .spacing(m.spacing / 2.0),
+            Space::new().width(Length::Fill),
+            button(text("☆").size(m.text_size)).on_press(Message::None),
+            button(text("Play").size(m.text_size)).on_press(Message::None),
+        ]
+        .spacing(m.spacing)
+        .padding(m.padding)
+        .align_y(iced::Alignment::Center);
+
+        container(card)
+            .width(400)
+            .style(container::rounded_box)
+            .into()
+    }
+
+    /// View: Monitor and window mode preferences for the detail page
+    fn view_display_prefs(&self, game: &Game) -> Element<'_, Message> {
+        let id = game.id;
+        let prefs = game.display_prefs;
+        let update = move |prefs: DisplayPrefs| {
+            Message::UpdateGame(
+                id,
+                GameUpdate {
+                    display_prefs: Some(prefs),
+                    ..Default::default()
+                },
+            )
+        };
+        let style = |selected: bool| if selected { button::primary } else { button::secondary };
+
+        let monitors = [None, Some(0), Some(1), Some(2), Some(3)].into_iter().map(|monitor| {
+            let label = match monitor {
+                None => "Any".to_string(),
+                Some(m) => (m + 1).to_string(),
+            };
+            button(text(label))
+                .on_press(update(DisplayPrefs { monitor, ..prefs }))
+                .style(style(prefs.monitor == monitor))
+                .into()
+        });
+        let modes = WindowModePref::all().iter().map(|mode| {
+            button(text(mode.label()))
+                .on_press(update(DisplayPrefs {
+                    window_mode: *mode,
+                    ..prefs
+                }))
+                .style(style(prefs.window_mode == *mode))
+                .into()
+        });
+
+        let in_terminal = game
+            .launch_in_terminal
+            .unwrap_or(self.config.launch_in_terminal);
+        let confirm_toggle = checkbox(game.confirm_before_launch)
+            .label("Confirm before launching")
+            .on_toggle(move |v| {
+                Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        confirm_before_launch: Some(v),
+                        ..Default::default()
+                    },
+                )
+            });
+        let admin_toggle = checkbox(game.run_as_admin)
+            .label("Run as administrator")
+            .on_toggle(move |v| {
+                Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        run_as_admin: Some(v),
+                        ..Default::default()
+                    },
+                )
+            });
+        let playtime_toggle = checkbox(game.track_playtime)
+            .label("Track playtime")
+            .on_toggle(move |v| {
+                Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        track_playtime: Some(v),
+                        ..Default::default()
+                    },
+                )
+            });
+        let terminal_toggle = checkbox(in_terminal)
+            .label("Launch in a terminal")
+            .on_toggle(move |v| {
+                Message::UpdateGame(
+                    id,
+                    GameUpdate {
+                        launch_in_terminal: Some(v),
+                        ..Default::default()
+                    },
+                )
+            });
+
+        column![
+            text("Display").size(18),
+            row![text("Monitor:")].extend(monitors).spacing(10).align_y(iced::Alignment::Center),
+            row![text("Window:")].extend(modes).spacing(10).align_y(iced::Alignment::Center),
+            text("Applied as launch options for Unity and Unreal games; other games may ignore it.")
+                .size(12),
+            terminal_toggle,
+            admin_toggle,
+            confirm_toggle,
+            playtime_toggle,
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View: SteamGridDB artwork picker for the detail page
+    #[cfg(feature = "steamgriddb")]
+    fn view_artwork_picker(&self, id: GameId) -> Element<'_, Message> {
+        let find_btn = button(text("Find artwork…")).on_press(Message::FindArtwork(id));
+
+        let Some(picker) = self.artwork_picker.as_ref().filter(|p| p.game_id == id) else {
+            return find_btn.into();
+        };
+
+        let body: Element<'_, Message> = match &picker.status {
+            ArtworkPickerStatus::Searching => text("Searching SteamGridDB...").into(),
+            ArtworkPickerStatus::Downloading => text("Downloading artwork...").into(),
+            ArtworkPickerStatus::Error(e) => text(format!("Error: {}", e))
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+                .into(),
+            ArtworkPickerStatus::Ready(candidates) => {
+                let thumbnails = row(candidates.iter().enumerate().map(|(index, (_, handle))| {
+                    button(iced::widget::image(handle.clone()).width(120).height(180))
+                        .on_press(Message::ArtworkCandidateChosen(id, index))
+                        .into()
+                }))
+                .spacing(10)
+                .wrap();
+                let remaining = picker.pending.len();
+                if remaining == 0 {
+                    thumbnails.into()
+                } else {
+                    let more = button(text(format!("Show more ({} left)", remaining)))
+                        .on_press_maybe((!picker.loading_more).then_some(Message::MoreArtwork(id)));
+                    column![thumbnails, more].spacing(10).into()
+                }
+            }
+        };
+
+        column![
+            row![find_btn, button(text("Close")).on_press(Message::CloseArtworkPicker)]
+                .spacing(10),
+            body,
+        ]
+        .spacing(10)
+        .into()
     }
 
-    /// Render the UI
-    pub fn view(&self) -> Element<'_, Message> {
-        let content = match &self.current_view {
-            View::Library => self.view_library(),
-            View::GameDetail(id) => self.view_game_detail(*id),
-            View::Settings => self.view_settings(),
-            View::Import => self.view_import(),
-            View::AddGame => self.view_add_game(),
+    /// View: Settings page
+    fn view_settings(&self) -> Element<'_, Message> {
+        let title = text("Settings").size(24);
+
+        let back_btn = button(text("Back"))
+            .on_press(Message::NavigateTo(View::Library));
+
+        let nav_buttons = SettingsSection::all().iter().map(|section| {
+            let btn = button(text(section.label()))
+                .width(Length::Fill)
+                .on_press(Message::SettingsSectionSelected(*section));
+            if *section == self.settings_section {
+                btn.style(button::primary).into()
+            } else {
+                btn.style(button::text).into()
+            }
+        });
+        let nav = column![
+            column(nav_buttons).spacing(5),
+            text("↑/↓ to switch sections, Tab to move between fields").size(12),
+        ]
+        .spacing(20)
+        .width(180);
+
+        let content = match self.settings_section {
+            SettingsSection::Appearance => self.view_settings_appearance(),
+            SettingsSection::Library => self.view_settings_library(),
+            SettingsSection::Import => self.view_settings_import(),
+            SettingsSection::Launching => self.view_settings_launching(),
+            SettingsSection::Advanced => self.view_settings_advanced(),
         };
 
-        container(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .padding(0)
-            .into()
+        column![
+            row![back_btn, title].spacing(20),
+            row![
+                nav,
+                scrollable(
+                    column![text(self.settings_section.label()).size(22), content]
+                        .spacing(20)
+                        .padding([0, 20]),
+                )
+                .width(Length::Fill),
+            ]
+            .spacing(20)
+            .height(Length::Fill),
+        ]
+        .spacing(20)
+        .padding(20)
+        .into()
     }
 
-    /// View: Main library
-    fn view_library(&self) -> Element<'_, Message> {
-        // Header
-        let header = self.view_header();
+    /// Settings: Theme, layout and how cards look
+    fn view_settings_appearance(&self) -> Element<'_, Message> {
+        use crate::message::{SettingKey, SettingValue};
 
-        // Sidebar
-        let sidebar = self.view_sidebar();
+        let theme_section = column![
+            text("Theme").size(18),
+            row![
+                button(text("Dark")).on_press(Message::ThemeChanged("dark".to_string())),
+                button(text("Light")).on_press(Message::ThemeChanged("light".to_string())),
+                button(text("System")).on_press(Message::ThemeChanged("system".to_string())),
+                button(text("High contrast"))
+                    .on_press(Message::ThemeChanged("high-contrast".to_string())),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
 
-        // Game grid/list
-        let games = self.get_filtered_games();
-        let game_grid = self.view_game_grid(&games);
+        let font_scale_buttons = FONT_SCALE_CHOICES.iter().map(|&scale| {
+            let label = format!("{:.0}%", scale * 100.0);
+            let btn = button(text(label)).on_press(Message::FontScaleChanged(scale));
+            if (scale - self.config.font_scale).abs() < f32::EPSILON {
+                btn.style(button::primary).into()
+            } else {
+                btn.style(button::secondary).into()
+            }
+        });
+        let font_scale_section = column![
+            text("Interface size").size(18),
+            text("Scales the whole interface, not only the text; bigger fits fewer cards per row.")
+                .size(12),
+            row(font_scale_buttons).spacing(10),
+        ]
+        .spacing(10);
 
-        // Status bar
-        let status = self.view_status_bar();
+        let density_buttons: Vec<Element<'_, Message>> = Density::all()
+            .iter()
+            .map(|density| {
+                let btn = button(text(density.label())).on_press(Message::DensityChanged(*density));
+                if *density == self.config.ui_density {
+                    btn.style(button::primary).into()
+                } else {
+                    btn.style(button::secondary).into()
+                }
+            })
+            .collect();
 
-        // Layout
-        let main_content = row![
-            sidebar,
-            container(scrollable(game_grid))
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .padding(20),
-        ];
+        let card_style_buttons = CardStyle::all().iter().map(|style| {
+            let btn = button(text(style.label())).on_press(Message::CardStyleChanged(*style));
+            if *style == self.config.card_style {
+                btn.style(button::primary).into()
+            } else {
+                btn.style(button::secondary).into()
+            }
+        });
 
-        column![header, main_content, status]
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .into()
-    }
+        let density_section = column![
+            text("Layout density").size(18),
+            row(density_buttons).spacing(10),
+            self.view_density_preview(),
+        ]
+        .spacing(10);
 
-    /// View: Header bar
-    fn view_header(&self) -> Element<'_, Message> {
-        let title = text("618-Launcher").size(24);
+        let narrow_widths = std::iter::once(None)
+            .chain(NARROW_LAYOUT_WIDTH_CHOICES.iter().copied().map(Some))
+            .map(|width| {
+                let label = match width {
+                    None => "Never".to_string(),
+                    Some(w) => format!("Below {} px", w),
+                };
+                let btn = button(text(label)).on_press(Message::NarrowLayoutWidthChanged(width));
+                if width == self.config.narrow_layout_width {
+                    btn.style(button::primary).into()
+                } else {
+                    btn.style(button::secondary).into()
+                }
+            });
+        let narrow_section = column![
+            text("Narrow windows").size(18),
+            text("Below this window width the sidebar moves into a menu in the header.").size(12),
+            row(narrow_widths).spacing(10),
+        ]
+        .spacing(10);
 
-        let search = text_input("Search games...", &self.search_query)
-            .on_input(Message::SearchChanged)
-            .width(300);
+        let source_toggles = GameSource::all().iter().map(|source| {
+            let source = *source;
+            checkbox(!self.config.hidden_sources.contains(&source))
+                .label(source.label())
+                .on_toggle_maybe(
+                    self.config
+                        .show_sources
+                        .then_some(move |v| Message::SourceBadgeToggled(source, v)),
+                )
+                .into()
+        });
+        let sources_section = column![
+            text("Source badges").size(18),
+            checkbox(self.config.show_sources)
+                .label("Show which store each game comes from")
+                .on_toggle(|v| Message::SettingChanged(SettingKey::ShowSources, SettingValue::Bool(v))),
+            row(source_toggles).spacing(15),
+        ]
+        .spacing(10);
 
-        let settings_btn = button(text("Settings"))
-            .on_press(Message::NavigateTo(View::Settings));
+        let cards_section = column![
+            text("Grid cards").size(18),
+            row(card_style_buttons).spacing(10),
+            checkbox(self.config.crop_card_art)
+                .label("Crop artwork to fill cards (otherwise show it whole with bars)")
+                .on_toggle(|v| Message::SettingChanged(SettingKey::CropCardArt, SettingValue::Bool(v))),
+        ]
+        .spacing(10);
 
-        row![
-            title,
-            Space::new().width(Length::Fill),
-            search,
-            settings_btn,
+        let badge_days = std::iter::once(None)
+            .chain(NEW_BADGE_DAY_CHOICES.iter().copied().map(Some))
+            .map(|days| {
+                let label = match days {
+                    None => "Off".to_string(),
+                    Some(1) => "1 day".to_string(),
+                    Some(d) => format!("{} days", d),
+                };
+                let btn = button(text(label)).on_press(Message::NewBadgeDaysChanged(days));
+                if days == self.config.new_badge_days {
+                    btn.style(button::primary).into()
+                } else {
+                    btn.style(button::secondary).into()
+                }
+            });
+        let new_badge_section = column![
+            text("New badges").size(18),
+            text("How long newly added games are marked \"New\".").size(12),
+            row(badge_days).spacing(10),
+            button(text("Clear new badges"))
+                .on_press(Message::ClearNewBadges)
+                .style(button::secondary),
+        ]
+        .spacing(10);
+
+        column![
+            theme_section,
+            font_scale_section,
+            density_section,
+            narrow_section,
+            cards_section,
+            sources_section,
+            new_badge_section,
         ]
         .spacing(20)
-        .padding(15)
         .into()
     }
 
-    /// View: Sidebar with categories
-    fn view_sidebar(&self) -> Element<'_, Message> {
-        let all_games_btn = button(
-            text(format!("All Games ({})", self.library.game_count())),
-        )
-        .width(Length::Fill)
-        .on_press(Message::CategorySelected(None));
+    /// Settings: Categories and artwork
+    fn view_settings_library(&self) -> Element<'_, Message> {
+        #[cfg(feature = "steamgriddb")]
+        use crate::message::{SettingKey, SettingValue};
 
-        let favorites_btn = button(
-            text(format!("Favorites ({})", self.library.favorite_games().len())),
-        )
-        .width(Length::Fill)
-        .on_press(Message::CategorySelected(None)); // TODO: Filter favorites
+        let is_downloading = matches!(self.art_status, ArtStatus::Downloading(_));
+        let art_status: Element<'_, Message> = match &self.art_status {
+            ArtStatus::Idle => Space::new().into(),
+            ArtStatus::Downloading(None) => text("Looking for artwork...").into(),
+            ArtStatus::Downloading(Some(progress)) => text(format!(
+                "Fetching artwork {}/{}: {}",
+                progress.current,
+                progress.total,
+                progress.current_game.as_deref().unwrap_or_default()
+            ))
+            .into(),
+            ArtStatus::Complete { updated, total } => {
+                text(format!("Found artwork for {} of {} games", updated, total))
+                    .style(|theme: &Theme| text::Style {
+                        color: Some(theme.palette().success),
+                    })
+                    .into()
+            }
+        };
 
-        let mut category_buttons: Vec<Element<Message>> = self
-            .library
-            .all_categories()
+        let art_section = column![
+            text("Artwork").size(18),
+            row![
+                button(text("Download missing artwork"))
+                    .on_press_maybe((!is_downloading).then_some(Message::DownloadMissingArt)),
+                checkbox(self.art_force_redownload)
+                    .label("Force re-download")
+                    .on_toggle(Message::ArtForceRedownloadToggled),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            art_status,
+        ]
+        .spacing(10);
+
+        #[cfg(feature = "steamgriddb")]
+        let art_section = {
+            let key = self
+                .steamgriddb_key_draft
+                .as_deref()
+                .or(self.config.steamgriddb_api_key.as_deref())
+                .unwrap_or_default();
+            // Saved on submit, so a half-typed key never reaches the config file
+            let save = self.steamgriddb_key_draft.clone().map(|key| {
+                Message::SettingChanged(SettingKey::SteamGridDbApiKey, SettingValue::String(key))
+            });
+            art_section.push(
+                row![
+                    text_input("SteamGridDB API key", key)
+                        .style(self.input_style())
+                        .on_input(Message::SteamGridDbKeyEdited)
+                        .on_submit_maybe(save.clone())
+                        .secure(true)
+                        .width(400),
+                    button(text("Save")).on_press_maybe(save),
+                ]
+                .spacing(10),
+            )
+        };
+
+        column![self.view_category_settings(), art_section]
+            .spacing(20)
+            .into()
+    }
+
+    /// Settings: Store scanning and where games are looked for
+    fn view_settings_import(&self) -> Element<'_, Message> {
+        use crate::message::{SettingKey, SettingValue};
+
+        let steam_paths: Vec<Element<'_, Message>> = self
+            .config
+            .steam_library_paths
             .iter()
-            .map(|cat| {
-                button(text(&cat.name))
-                    .width(Length::Fill)
-                    .on_press(Message::CategorySelected(Some(cat.id)))
-                    .into()
+            .map(|path| {
+                row![
+                    text(path.display().to_string()),
+                    Space::new().width(Length::Fill),
+                    button(text("Remove"))
+                        .on_press(Message::RemoveSteamLibraryPath(path.clone()))
+                        .style(button::danger),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
             })
             .collect();
 
-        let add_game_btn = button(text("+ Add Game"))
-            .width(Length::Fill)
-            .on_press(Message::AddGamePressed);
-
-        let import_btn = button(text("Import Games"))
-            .width(Length::Fill)
-            .on_press(Message::NavigateTo(View::Import));
+        let interval_choices = std::iter::once(None)
+            .chain(AUTO_SCAN_INTERVAL_CHOICES.iter().copied().map(Some));
+        let auto_scan_buttons = interval_choices.map(|minutes| {
+            let label = match minutes {
+                None => "Off".to_string(),
+                Some(m) if m % 60 == 0 => format!("Every {} h", m / 60),
+                Some(m) => format!("Every {} min", m),
+            };
+            let btn = button(text(label)).on_press(Message::AutoScanIntervalChanged(minutes));
+            if minutes == self.config.auto_scan_interval_minutes {
+                btn.style(button::primary).into()
+            } else {
+                btn.style(button::secondary).into()
+            }
+        });
 
-        let mut sidebar_items = vec![
-            all_games_btn.into(),
-            favorites_btn.into(),
-        ];
-        sidebar_items.append(&mut category_buttons);
-        sidebar_items.push(add_game_btn.into());
-        sidebar_items.push(import_btn.into());
+        let threshold_buttons = IMPORT_WARN_THRESHOLD_CHOICES.iter().map(|&games| {
+            let btn = button(text(format!("{} games", games)))
+                .on_press(Message::ImportWarnThresholdChanged(games));
+            if games == self.config.import_warn_threshold {
+                btn.style(button::primary).into()
+            } else {
+                btn.style(button::secondary).into()
+            }
+        });
 
-        container(
-            scrollable(
-                column(sidebar_items)
-                    .spacing(5)
-                    .padding(10)
-                    .width(Length::Fill),
-            ),
-        )
-        .width(200)
-        .height(Length::Fill)
-        .into()
-    }
+        let scan_section = column![
+            checkbox(self.config.merge_cross_store)
+                .label("Link the same game from different stores into one entry")
+                .on_toggle(|v| Message::SettingChanged(SettingKey::MergeCrossStore, SettingValue::Bool(v))),
+            checkbox(self.config.import_steam_collections)
+                .label("Add Steam collections as categories (reads Steam's local user config)")
+                .on_toggle(|v| {
+                    Message::SettingChanged(SettingKey::ImportSteamCollections, SettingValue::Bool(v))
+                }),
+            text("Background scan").size(18),
+            text("Re-scan installed stores while the launcher is open. New games are added; missing ones are only listed on the next import.").size(12),
+            row(auto_scan_buttons).spacing(10),
+            text("Large imports").size(18),
+            text("Ask before an import adds more games than this, in case it scanned the wrong folder.").size(12),
+            row(threshold_buttons).spacing(10),
+        ]
+        .spacing(10);
 
-    /// View: Game grid
-    fn view_game_grid(&self, games: &[&Game]) -> Element<'_, Message> {
-        if games.is_empty() {
-            return container(
-                text("No games in library. Add some games to get started!")
-                    .size(18),
-            )
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
-            .into();
+        let can_add_path = !self.steam_path_input.trim().is_empty();
+        let mut steam_section = column![
+            text("Steam library folders").size(18),
+            column(steam_paths).spacing(5),
+            row![
+                text_input("Path to a Steam library folder", &self.steam_path_input)
+                    .style(self.input_style())
+                    .on_input(Message::SteamLibraryPathInputChanged)
+                    .on_submit(Message::AddSteamLibraryPath)
+                    .width(400),
+                button(text("Add"))
+                    .on_press_maybe(can_add_path.then_some(Message::AddSteamLibraryPath)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
+        if let Some(error) = &self.steam_path_error {
+            steam_section = steam_section.push(text(error).style(|theme: &Theme| text::Style {
+                color: Some(theme.palette().danger),
+            }));
         }
 
-        let game_cards: Vec<Element<Message>> = games
+        let exclude_patterns: Vec<Element<'_, Message>> = self
+            .config
+            .import_exclude_patterns
             .iter()
-            .map(|game| self.view_game_card(game))
+            .map(|pattern| {
+                row![
+                    text(pattern),
+                    Space::new().width(Length::Fill),
+                    button(text("Remove"))
+                        .on_press(Message::RemoveExcludePattern(pattern.clone()))
+                        .style(button::danger),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
             .collect();
+        let can_add_pattern = !self.exclude_pattern_input.trim().is_empty();
+        let exclude_section = column![
+            text("Skipped folders").size(18),
+            text("Executables in matching folders are ignored. Use * and ? as wildcards, or plain text to match part of a name.").size(12),
+            column(exclude_patterns).spacing(5),
+            row![
+                text_input("e.g. *Redist* or Tools", &self.exclude_pattern_input)
+                    .style(self.input_style())
+                    .on_input(Message::ExcludePatternInputChanged)
+                    .on_submit(Message::AddExcludePattern)
+                    .width(400),
+                button(text("Add"))
+                    .on_press_maybe(can_add_pattern.then_some(Message::AddExcludePattern)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
 
-        // Simple vertical list for now (grid layout will come later)
-        column(game_cards)
-            .spacing(10)
-            .width(Length::Fill)
+        let emulators: Vec<Element<'_, Message>> = self
+            .config
+            .emulators
+            .iter()
+            .map(|(extension, template)| {
+                row![
+                    text(format!(".{}", extension)).width(80),
+                    text(template).font(iced::Font::MONOSPACE).size(13),
+                    Space::new().width(Length::Fill),
+                    button(text("Remove"))
+                        .on_press(Message::RemoveEmulator(extension.clone()))
+                        .style(button::danger),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect();
+        let can_add_emulator = !self.emulator_extension_input.trim().is_empty()
+            && !self.emulator_template_input.trim().is_empty();
+        let emulator_section = column![
+            text("Emulators").size(18),
+            text("ROM imports run each file through the emulator for its extension. {rom} stands for the file's path.").size(12),
+            column(emulators).spacing(5),
+            row![
+                text_input("sfc", &self.emulator_extension_input)
+                    .style(self.input_style())
+                    .on_input(Message::EmulatorExtensionInputChanged)
+                    .on_submit(Message::AddEmulator)
+                    .width(80),
+                text_input("e.g. retroarch -L snes9x.so \"{rom}\"", &self.emulator_template_input)
+                    .style(self.input_style())
+                    .on_input(Message::EmulatorTemplateInputChanged)
+                    .on_submit(Message::AddEmulator)
+                    .width(400),
+                button(text("Add"))
+                    .on_press_maybe(can_add_emulator.then_some(Message::AddEmulator)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
+
+        column![scan_section, steam_section, exclude_section, emulator_section]
+            .spacing(20)
             .into()
     }
 
-    /// View: Individual game card
-    fn view_game_card(&self, game: &Game) -> Element<'_, Message> {
-        let name = text(game.name.clone()).size(16);
-        let source = text(game.source.label()).size(12);
+    /// Settings: What happens around starting a game
+    fn view_settings_launching(&self) -> Element<'_, Message> {
+        use crate::message::{SettingKey, SettingValue};
 
-        let game_id = game.id;
-        let play_btn = button(text("Play"))
-            .on_press(Message::LaunchGame(game_id));
+        let launch_buttons: Vec<Element<'_, Message>> = LaunchWindowBehavior::all()
+            .iter()
+            .map(|behavior| {
+                let btn = button(text(behavior.label()))
+                    .on_press(Message::LaunchBehaviorChanged(*behavior));
+                if *behavior == self.config.on_launch {
+                    btn.style(button::primary).into()
+                } else {
+                    btn.style(button::secondary).into()
+                }
+            })
+            .collect();
 
-        let fav_icon = if game.favorite { "★" } else { "☆" };
-        let fav_btn = button(text(fav_icon))
-            .on_press(Message::ToggleFavorite(game_id));
+        let window_section = column![
+            text("When a game launches").size(18),
+            row(launch_buttons).spacing(10),
+            checkbox(self.config.restore_window_on_game_exit)
+                .label("Restore window when the last running game exits")
+                .on_toggle(|v| {
+                    Message::SettingChanged(SettingKey::RestoreWindowOnGameExit, SettingValue::Bool(v))
+                }),
+            checkbox(self.config.show_now_playing_window)
+                .label("Show a small always-on-top window with the running game and a Stop button")
+                .on_toggle(|v| {
+                    Message::SettingChanged(SettingKey::ShowNowPlayingWindow, SettingValue::Bool(v))
+                }),
+            checkbox(self.config.prefer_big_picture)
+                .label("Start Steam games in Big Picture mode, for controllers")
+                .on_toggle(|v| {
+                    Message::SettingChanged(SettingKey::PreferBigPicture, SettingValue::Bool(v))
+                }),
+            checkbox(self.config.confirm_install_on_launch)
+                .label("Ask before installing a game that isn't installed when it's launched")
+                .on_toggle(|v| {
+                    let value = SettingValue::Bool(v);
+                    Message::SettingChanged(SettingKey::ConfirmInstallOnLaunch, value)
+                }),
+            checkbox(self.config.resolve_executable_folders)
+                .label("Find the game's program when its executable path is a folder")
+                .on_toggle(|v| {
+                    let value = SettingValue::Bool(v);
+                    Message::SettingChanged(SettingKey::ResolveExecutableFolders, value)
+                }),
+        ]
+        .spacing(10);
 
-        let card_content = row![
-            column![name, source].spacing(5),
-            Space::new().width(Length::Fill),
-            fav_btn,
-            play_btn,
+        let pin_section = column![
+            text("Launch PIN").size(18),
+            row![
+                text_input(
+                    if self.config.launch_pin.is_some() { "New launch PIN" } else { "Launch PIN" },
+                    &self.launch_pin_input,
+                )
+                .style(self.input_style())
+                .on_input(Message::LaunchPinInputChanged)
+                .on_submit(Message::SetLaunchPin)
+                .secure(true)
+                .width(200),
+                button(text("Set PIN"))
+                    .on_press_maybe((!self.launch_pin_input.is_empty()).then_some(Message::SetLaunchPin)),
+                button(text("Remove PIN"))
+                    .on_press_maybe(self.config.launch_pin.is_some().then_some(Message::ClearLaunchPin))
+                    .style(button::danger),
+            ]
+            .spacing(10),
+            text("Games marked \"Confirm before launching\" need the PIN when one is set.").size(12),
+            text("Changing or removing the PIN, or turning a game's confirmation off, asks for it.")
+                .size(12),
         ]
-        .spacing(10)
-        .padding(15)
-        .align_y(iced::Alignment::Center);
+        .spacing(10);
 
-        container(card_content)
-            .width(Length::Fill)
-            .into()
-    }
+        let output_section = column![
+            text("Game output").size(18),
+            checkbox(self.config.launch_in_terminal)
+                .label("Launch games in a terminal to show their console output")
+                .on_toggle(|v| Message::SettingChanged(SettingKey::LaunchInTerminal, SettingValue::Bool(v))),
+            checkbox(self.config.capture_game_output)
+                .label("Save game output to a log file for troubleshooting")
+                .on_toggle(|v| {
+                    Message::SettingChanged(SettingKey::CaptureGameOutput, SettingValue::Bool(v))
+                }),
+            checkbox(self.config.write_status_file)
+                .label("Write the game being played to a status file for overlays")
+                .on_toggle(|v| {
+                    Message::SettingChanged(SettingKey::WriteStatusFile, SettingValue::Bool(v))
+                }),
+            checkbox(self.config.show_resource_usage)
+                .label("Show CPU/RAM usage of running games")
+                .on_toggle(|v| Message::SettingChanged(SettingKey::ShowResourceUsage, SettingValue::Bool(v))),
+        ]
+        .spacing(10);
 
-    /// View: Status bar
-    fn view_status_bar(&self) -> Element<'_, Message> {
-        let game_count = text(format!("{} games", self.library.game_count())).size(12);
+        #[cfg(feature = "discord")]
+        let output_section = output_section
+            .push(
+                checkbox(self.config.discord_rpc_enabled)
+                    .label("Show the running game on Discord")
+                    .on_toggle(|v| {
+                        Message::SettingChanged(SettingKey::DiscordRpcEnabled, SettingValue::Bool(v))
+                    }),
+            )
+            .push(
+                text_input(
+                    "Discord application ID",
+                    self.config.discord_client_id.as_deref().unwrap_or_default(),
+                )
+                .style(self.input_style())
+                .on_input(|v| Message::SettingChanged(SettingKey::DiscordClientId, SettingValue::String(v)))
+                .width(400),
+            );
 
-        row![game_count]
-            .padding(10)
+        column![window_section, pin_section, output_section]
+            .spacing(20)
             .into()
     }
 
-    /// View: Game detail page
-    fn view_game_detail(&self, id: GameId) -> Element<'_, Message> {
-        if let Some(game) = self.library.get_game(&id) {
-            let title = text(&game.name).size(28);
-            let source = text(format!("Source: {}", game.source.label()));
-            let path = text(format!("Path: {:?}", game.executable_path)).size(12);
-            let playtime = text(format!("Playtime: {}", game.playtime_display()));
-
-            let back_btn = button(text("Back"))
-                .on_press(Message::NavigateTo(View::Library));
+    /// Settings: Encryption and the launcher's own files
+    fn view_settings_advanced(&self) -> Element<'_, Message> {
+        use crate::message::{SettingKey, SettingValue};
 
-            let play_btn = button(text("Play"))
-                .on_press(Message::LaunchGame(id));
+        let mut privacy_section = column![
+            text("Privacy").size(18),
+            checkbox(self.config.encrypt_library)
+                .label("Encrypt the library file with a passphrase")
+                .on_toggle(|v| Message::SettingChanged(SettingKey::EncryptLibrary, SettingValue::Bool(v))),
+        ]
+        .spacing(10);
+        if self.config.encrypt_library {
+            let label = if self.library_passphrase.is_some() {
+                "Change passphrase"
+            } else {
+                "Set passphrase"
+            };
+            privacy_section = privacy_section.push(
+                row![
+                    text_input("Passphrase", &self.passphrase_input)
+                        .style(self.input_style())
+                        .on_input(Message::LibraryPassphraseInputChanged)
+                        .on_submit(Message::SetLibraryPassphrase)
+                        .secure(true)
+                        .width(300),
+                    button(text(label)).on_press_maybe(
+                        (!self.passphrase_input.is_empty()).then_some(Message::SetLibraryPassphrase)
+                    ),
+                ]
+                .spacing(10),
+            );
+            if self.library_passphrase.is_none() {
+                privacy_section = privacy_section.push(
+                    text("The library stays unencrypted until a passphrase is set.").size(12),
+                );
+            }
+        }
 
-            column![
-                back_btn,
-                title,
-                source,
-                path,
-                playtime,
-                play_btn,
+        let files_section = column![
+            text("Files").size(18),
+            row![
+                button(text("Open data folder"))
+                    .on_press(Message::RevealPath(self.data_dir.path.clone()))
+                    .style(button::secondary),
+                button(text("Show config file"))
+                    .on_press(Message::RevealPath(self.config_path()))
+                    .style(button::secondary),
+                button(text("Open themes folder"))
+                    .on_press(Message::RevealPath(self.themes_dir()))
+                    .style(button::secondary),
             ]
-            .spacing(15)
-            .padding(20)
-            .into()
-        } else {
-            column![
-                text("Game not found"),
-                button(text("Back")).on_press(Message::NavigateTo(View::Library)),
+            .spacing(10),
+        ]
+        .spacing(10);
+
+        // Switching reloads everything, which would lose track of running games
+        let can_switch = self.running_games.is_empty();
+        let mut libraries = column![].spacing(6);
+        for dir in &self.recent_data_dirs.dirs {
+            let current = same_path(dir, &self.data_dir.path);
+            let switch = (can_switch && !current).then(|| Message::SwitchLibrary(dir.clone()));
+            libraries = libraries.push(
+                button(text(dir.display().to_string()).size(13))
+                    .on_press_maybe(switch)
+                    .style(if current { button::primary } else { button::secondary }),
+            );
+        }
+        let mut libraries_section = column![
+            text("Switch library").size(18),
+            text(format!("Open now: {}", self.data_dir.path.display())).size(12),
+            libraries,
+            row![
+                text_input("Another data folder", &self.switch_library_input)
+                    .style(self.input_style())
+                    .on_input(Message::SwitchLibraryInputChanged)
+                    .width(400),
+                button(text("Open")).on_press_maybe(
+                    (can_switch && !self.switch_library_input.trim().is_empty()).then(|| {
+                        Message::SwitchLibrary(PathBuf::from(self.switch_library_input.trim()))
+                    })
+                ),
             ]
-            .spacing(15)
-            .padding(20)
-            .into()
+            .spacing(10),
+        ]
+        .spacing(10);
+        if !can_switch {
+            libraries_section = libraries_section
+                .push(text("Close running games before switching libraries.").size(12));
+        }
+        if let Some(error) = &self.switch_library_error {
+            libraries_section = libraries_section.push(text(error.clone()).size(12));
         }
-    }
 
-    /// View: Settings page
-    fn view_settings(&self) -> Element<'_, Message> {
-        let title = text("Settings").size(24);
+        column![privacy_section, files_section, libraries_section].spacing(20).into()
+    }
 
-        let back_btn = button(text("Back"))
-            .on_press(Message::NavigateTo(View::Library));
+    /// View: Category list with icon pickers
+    fn view_category_settings(&self) -> Element<'_, Message> {
+        let mut categories = self.library.all_categories();
+        categories.sort_by_key(|category| category.name.to_lowercase());
 
-        let theme_section = column![
-            text("Theme").size(18),
+        let rows = categories.into_iter().map(|category| {
+            let id = category.id;
+            let selected = category.icon.as_deref().and_then(CategoryIcon::by_name).copied();
             row![
-                button(text("Dark")).on_press(Message::ThemeChanged("dark".to_string())),
-                button(text("Light")).on_press(Message::ThemeChanged("light".to_string())),
+                self.view_category_icon(category, 18.0),
+                text(&category.name).width(150),
+                pick_list(CategoryIcon::all(), selected, move |icon| {
+                    Message::SetCategoryIcon(id, Some(icon.name.to_string()))
+                })
+                .placeholder("Letter"),
+                button(text("No icon"))
+                    .on_press_maybe(
+                        category.icon.is_some().then_some(Message::SetCategoryIcon(id, None)),
+                    )
+                    .style(button::secondary),
+                pick_list(SortOrder::all(), category.default_sort, move |order| {
+                    Message::SetCategorySort(id, Some(order))
+                })
+                .placeholder("Library sort"),
+                button(text("Library sort"))
+                    .on_press_maybe(
+                        category
+                            .default_sort
+                            .is_some()
+                            .then_some(Message::SetCategorySort(id, None)),
+                    )
+                    .style(button::secondary),
+                button(text(if category.rule.is_some() { "Edit rule" } else { "Smart rule" }))
+                    .on_press(Message::EditCategoryRule(Some(id)))
+                    .style(if self.editing_rule == Some(id) {
+                        button::primary
+                    } else {
+                        button::secondary
+                    }),
+                button(text("Remove"))
+                    .on_press(Message::RemoveCategory(id))
+                    .style(button::danger),
             ]
-            .spacing(10),
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+        let rule_editor = self
+            .editing_rule
+            .and_then(|id| self.library.get_category(&id))
+            .map(|category| self.view_rule_editor(category));
+
+        let name = self.category_name_input.trim();
+        let add_row = row![
+            text_input("New category", &self.category_name_input)
+                .style(self.input_style())
+                .on_input(Message::CategoryNameInputChanged)
+                .on_submit(Message::AddCategory(name.to_string()))
+                .width(250),
+            button(text("Add"))
+                .on_press_maybe((!name.is_empty()).then(|| Message::AddCategory(name.to_string()))),
         ]
         .spacing(10);
 
+        let mut names: Vec<(String, CategoryId)> = self
+            .library
+            .all_categories()
+            .into_iter()
+            .map(|category| (category.name.clone(), category.id))
+            .collect();
+        names.sort_by_key(|(name, _)| name.to_lowercase());
+        let selected = self
+            .config
+            .default_manual_category
+            .and_then(|id| self.library.get_category(&id))
+            .map(|category| category.name.clone());
+        let choices: Vec<String> = names.iter().map(|(name, _)| name.clone()).collect();
+        let manual_row = row![
+            text("Games added by hand go in"),
+            pick_list(choices, selected, move |name| {
+                let id = names.iter().find(|(n, _)| *n == name).map(|(_, id)| *id);
+                Message::DefaultManualCategoryChanged(id)
+            })
+            .placeholder("No category"),
+            button(text("None"))
+                .on_press_maybe(
+                    self.config
+                        .default_manual_category
+                        .is_some()
+                        .then_some(Message::DefaultManualCategoryChanged(None)),
+                )
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
         column![
-            row![back_btn, title].spacing(20),
-            theme_section,
+            text("Categories").size(18),
+            column(rows).spacing(5),
+            rule_editor,
+            add_row,
+            manual_row,
         ]
-        .spacing(20)
-        .padding(20)
+        .spacing(10)
+        .into()
+    }
+
+    /// Settings: Conditions of a smart category, with a picker to add more
+    fn view_rule_editor<'a>(&'a self, category: &'a Category) -> Element<'a, Message> {
+        let id = category.id;
+        let conditions = category.rule.iter().flat_map(|rule| &rule.conditions);
+        let rows: Vec<Element<'a, Message>> = conditions
+            .enumerate()
+            .map(|(index, condition)| {
+                row![
+                    text(self.rule_condition_label(condition)),
+                    Space::new().width(Length::Fill),
+                    button(text("Remove"))
+                        .on_press(Message::RemoveRuleCondition(id, index))
+                        .style(button::secondary),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect();
+
+        let hours = RULE_PLAYTIME_HOUR_CHOICES.iter().map(|h| h * 60);
+        let mut offered: Vec<RuleCondition> = GameSource::all()
+            .iter()
+            .copied()
+            .map(RuleCondition::Source)
+            .chain([RuleCondition::PlaytimeAtMost(0), RuleCondition::PlaytimeAtMost(60)])
+            .chain(hours.map(RuleCondition::PlaytimeAtLeast))
+            .chain([true, false].map(RuleCondition::Installed))
+            .chain([true, false].map(RuleCondition::Favorite))
+            .collect();
+        // Tags only count hand-picked members, so smart categories aren't offered
+        let mut tags: Vec<&Category> = self
+            .library
+            .all_categories()
+            .into_iter()
+            .filter(|other| other.id != id && other.rule.is_none())
+            .collect();
+        tags.sort_by_key(|other| other.name.to_lowercase());
+        offered.extend(tags.into_iter().map(|other| RuleCondition::Tag(other.id)));
+        let choices: Vec<RuleChoice> = offered
+            .into_iter()
+            .map(|condition| RuleChoice {
+                label: self.rule_condition_label(&condition),
+                condition,
+            })
+            .collect();
+
+        let controls = row![
+            pick_list(choices, None::<RuleChoice>, move |choice| {
+                Message::AddRuleCondition(id, choice.condition)
+            })
+            .placeholder("Add condition"),
+            Space::new().width(Length::Fill),
+            button(text("Pick games by hand"))
+                .on_press_maybe(category.rule.is_some().then_some(Message::ClearCategoryRule(id)))
+                .style(button::secondary),
+            button(text("Done")).on_press(Message::EditCategoryRule(None)),
+        ]
+        .spacing(10);
+
+        container(
+            column![
+                text(format!("Smart rule for {}", category.name)).size(16),
+                text("Games meeting every condition are added automatically, and the category's games can't be changed by hand.").size(12),
+                column(rows).spacing(5),
+                controls,
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .style(container::rounded_box)
         .into()
     }
 
+    /// How a smart category condition reads in the rule editor
+    fn rule_condition_label(&self, condition: &RuleCondition) -> String {
+        let duration = |minutes: u64| match minutes {
+            m if m % 60 == 0 => format!("{} h", m / 60),
+            m => format!("{} min", m),
+        };
+        match *condition {
+            RuleCondition::Source(source) => format!("From {}", source.label()),
+            RuleCondition::PlaytimeAtMost(0) => "Never played".to_string(),
+            RuleCondition::PlaytimeAtMost(minutes) => {
+                format!("Played {} or less", duration(minutes))
+            }
+            RuleCondition::PlaytimeAtLeast(minutes) => {
+                format!("Played {} or more", duration(minutes))
+            }
+            RuleCondition::Installed(true) => "Installed".to_string(),
+            RuleCondition::Installed(false) => "Not installed".to_string(),
+            RuleCondition::Favorite(true) => "Favorite".to_string(),
+            RuleCondition::Favorite(false) => "Not a favorite".to_string(),
+            RuleCondition::Tag(id) => match self.library.get_category(&id) {
+                Some(category) => format!("Tagged {}", category.name),
+                None => "Tagged with a removed category".to_string(),
+            },
+        }
+    }
+
+    /// A category's icon, or its first letter in the category color
+    fn view_category_icon(&self, category: &Category, size: f32) -> Element<'_, Message> {
+        let color = category
+            .color
+            .as_deref()
+            .map(|hex| Color::from(HexColor::from_hex(hex)));
+        let label = match category.icon.as_deref().and_then(CategoryIcon::by_name) {
+            Some(icon) => icon.glyph.to_string(),
+            None => category.initial(),
+        };
+        text(label)
+            .size(size)
+            .style(move |_: &Theme| text::Style { color })
+            .into()
+    }
+
     /// View: Import page
     fn view_import(&self) -> Element<'_, Message> {
         let title = text("Import Games").size(24);
@@ -676,13 +6234,31 @@ impl App {
                     })
                     .into()
             }
-            ImportStatus::Complete { count, source } => {
-                text(format!("Imported {} games from {}", count, source.label()))
-                    .style(|theme: &Theme| text::Style {
-                        color: Some(theme.palette().success),
-                    })
-                    .into()
+            ImportStatus::Preview { source, entries, cached_at, .. } => {
+                let found = text(format!(
+                    "Found {} games in {}. Review them before importing:",
+                    entries.len(),
+                    source.label()
+                ));
+                match cached_at {
+                    None => found.into(),
+                    Some(scanned_at) => {
+                        let scanned_at = scanned_at.with_timezone(&chrono::Local);
+                        let when = scanned_at.format("%Y-%m-%d %H:%M");
+                        row![
+                            found,
+                            text(format!("From the scan at {}", when)).size(12),
+                            button(text("Rescan").size(12))
+                                .on_press(Message::RescanImport(*source))
+                                .style(button::secondary),
+                        ]
+                        .spacing(10)
+                        .align_y(iced::Alignment::Center)
+                        .into()
+                    }
+                }
             }
+            ImportStatus::Complete(summary) => self.view_import_summary(summary),
             ImportStatus::Error(e) => {
                 text(format!("Error: {}", e))
                     .style(|theme: &Theme| text::Style {
@@ -692,8 +6268,11 @@ impl App {
             }
         };
 
-        // Disable buttons while importing
-        let is_importing = matches!(self.import_status, ImportStatus::Importing(_));
+        // Disable buttons while importing or reviewing a preview
+        let is_importing = matches!(
+            self.import_status,
+            ImportStatus::Importing(_) | ImportStatus::Preview { .. }
+        );
 
         let steam_btn = button(text("Import from Steam"))
             .on_press_maybe(if is_importing { None } else { Some(Message::StartImport(GameSource::Steam)) });
@@ -704,6 +6283,121 @@ impl App {
         let gog_btn = button(text("Import from GOG Galaxy"))
             .on_press_maybe(if is_importing { None } else { Some(Message::StartImport(GameSource::GOG)) });
 
+        let ubisoft_btn = button(text("Import from Ubisoft Connect"))
+            .on_press_maybe(if is_importing { None } else { Some(Message::StartImport(GameSource::Ubisoft)) });
+
+        let itch_btn = button(text("Import from itch.io"))
+            .on_press_maybe((!is_importing).then_some(Message::StartImport(GameSource::Itch)));
+
+        let xbox_btn = button(text("Import from Xbox"))
+            .on_press_maybe((!is_importing).then_some(Message::StartImport(GameSource::Xbox)));
+
+        let import_shortcuts = Message::StartImport(GameSource::SteamShortcut);
+        let shortcuts_btn = button(text("Import non-Steam shortcuts"))
+            .on_press_maybe((!is_importing).then_some(import_shortcuts));
+
+        let can_import_csv =
+            !is_importing && !self.csv_importing && !self.csv_path_input.trim().is_empty();
+        let mut csv_section = column![
+            row![
+                text_input("CSV file: name, executable, args, categories", &self.csv_path_input)
+                    .style(self.input_style())
+                    .on_input(Message::CsvPathInputChanged)
+                    .on_submit(Message::ImportCsv)
+                    .width(400),
+                button(text("Import from CSV"))
+                    .on_press_maybe(can_import_csv.then_some(Message::ImportCsv)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(5);
+        match &self.csv_import {
+            Some(Ok(report)) => {
+                csv_section = csv_section.push(text(format!(
+                    "Added {} of {} rows",
+                    report.added(),
+                    report.rows.len()
+                )));
+                for row in report.skipped() {
+                    let reason = match &row.outcome {
+                        CsvRowOutcome::Duplicate => "already in library".to_string(),
+                        CsvRowOutcome::Invalid(reason) => reason.clone(),
+                        CsvRowOutcome::Added => continue,
+                    };
+                    csv_section = csv_section.push(
+                        text(format!("Line {}: {} skipped, {}", row.line, row.name, reason)).size(12),
+                    );
+                }
+            }
+            Some(Err(e)) => {
+                csv_section = csv_section.push(text(format!("CSV import failed: {}", e)).style(
+                    |theme: &Theme| text::Style {
+                        color: Some(theme.palette().danger),
+                    },
+                ));
+            }
+            None => {}
+        }
+
+        let can_import_bundle = !is_importing
+            && !self.bundle_importing
+            && !self.bundle_path_input.trim().is_empty();
+        let mut bundle_section = column![
+            row![
+                text_input("Game bundle folder", &self.bundle_path_input)
+                    .style(self.input_style())
+                    .on_input(Message::BundlePathInputChanged)
+                    .on_submit(Message::ImportBundle)
+                    .width(400),
+                button(text("Import bundle"))
+                    .on_press_maybe(can_import_bundle.then_some(Message::ImportBundle)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(5);
+        match &self.bundle_import {
+            Some(Ok(report)) => {
+                bundle_section = bundle_section.push(text(format!(
+                    "Added {} games, {} already in library",
+                    report.added.len(),
+                    report.duplicates.len()
+                )));
+                for name in &report.duplicates {
+                    bundle_section = bundle_section
+                        .push(text(format!("{} skipped, already in library", name)).size(12));
+                }
+            }
+            Some(Err(e)) => {
+                bundle_section = bundle_section.push(
+                    text(format!("Bundle import failed: {}", e)).style(|theme: &Theme| text::Style {
+                        color: Some(theme.palette().danger),
+                    }),
+                );
+            }
+            None => {}
+        }
+
+        let can_import_roms = !is_importing
+            && !self.rom_folder_input.trim().is_empty()
+            && !self.config.emulators.is_empty();
+        let mut rom_section = column![
+            row![
+                text_input("ROM folder", &self.rom_folder_input)
+                    .style(self.input_style())
+                    .on_input(Message::RomFolderInputChanged)
+                    .on_submit(Message::StartRomImport)
+                    .width(400),
+                button(text("Import ROMs"))
+                    .on_press_maybe(can_import_roms.then_some(Message::StartRomImport)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(5);
+        if self.config.emulators.is_empty() {
+            rom_section = rom_section
+                .push(text("Add an emulator for your ROMs' file type in Settings first.").size(12));
+        }
+
         // Library stats and clear button
         let game_count = self.library.game_count();
         let stats_row = row![
@@ -720,12 +6414,38 @@ impl App {
         .spacing(10)
         .align_y(iced::Alignment::Center);
 
+        if let ImportStatus::Preview { source, entries, tab, confirm, .. } = &self.import_status {
+            let body = match confirm {
+                Some(large) => self.view_large_import(*source, large),
+                None => self.view_import_preview(*source, entries, *tab),
+            };
+            return column![
+                row![back_btn, title].spacing(20),
+                status_text,
+                body,
+            ]
+            .spacing(15)
+            .padding(20)
+            .into();
+        }
+
         column![
             row![back_btn, title].spacing(20),
             status_text,
-            steam_btn,
+            row![steam_btn, self.view_not_installed_toggle(GameSource::Steam)]
+                .spacing(15)
+                .align_y(iced::Alignment::Center),
             epic_btn,
             gog_btn,
+            row![ubisoft_btn, self.view_not_installed_toggle(GameSource::Ubisoft)]
+                .spacing(15)
+                .align_y(iced::Alignment::Center),
+            xbox_btn,
+            itch_btn,
+            shortcuts_btn,
+            csv_section,
+            bundle_section,
+            rom_section,
             Space::new().height(20),
             stats_row,
         ]
@@ -734,6 +6454,236 @@ impl App {
         .into()
     }
 
+    /// A store's "include games that aren't installed" option, beside its import button
+    fn view_not_installed_toggle(&self, source: GameSource) -> Element<'_, Message> {
+        checkbox(self.config.source_import.get(source).include_not_installed)
+            .label("Include games that aren't installed")
+            .text_size(14)
+            .on_toggle(move |v| Message::IncludeNotInstalledToggled(source, v))
+            .into()
+    }
+
+    /// View: Dismissible summary of what the last import changed
+    fn view_import_summary<'a>(&'a self, summary: &'a ImportSummary) -> Element<'a, Message> {
+        let headline = if summary.is_empty() {
+            format!("{} is up to date, nothing changed", summary.source.label())
+        } else {
+            format!(
+                "{}: {} added, {} updated, {} no longer found",
+                summary.source.label(),
+                summary.added.len(),
+                summary.updated.len(),
+                summary.missing.len()
+            )
+        };
+
+        let mut panel = column![row![
+            text(headline).style(|theme: &Theme| text::Style {
+                color: Some(theme.palette().success),
+            }),
+            Space::new().width(Length::Fill),
+            button(text("Dismiss"))
+                .on_press(Message::DismissImportSummary)
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)]
+        .spacing(8);
+
+        let section = |title: String, lines: Vec<String>| {
+            column(
+                std::iter::once(text(title).size(16).into())
+                    .chain(lines.into_iter().map(|line| text(line).size(13).into())),
+            )
+            .spacing(3)
+        };
+        if !summary.added.is_empty() {
+            panel = panel.push(section(format!("Added ({})", summary.added.len()), summary.added.clone()));
+        }
+        if !summary.updated.is_empty() {
+            let lines = summary
+                .updated
+                .iter()
+                .map(|u| format!("{} ({})", u.name, u.fields.join(", ")))
+                .collect();
+            panel = panel.push(section(format!("Updated ({})", summary.updated.len()), lines));
+        }
+        if !summary.missing.is_empty() {
+            panel = panel.push(section(
+                format!("No longer found in {} ({})", summary.source.label(), summary.missing.len()),
+                summary.missing.clone(),
+            ));
+        }
+        if summary.unchanged > 0 {
+            panel = panel.push(text(format!("{} games unchanged", summary.unchanged)).size(12));
+        }
+        if !summary.scan.skipped.is_empty() {
+            let counts = summary
+                .scan
+                .skip_counts()
+                .into_iter()
+                .map(|(reason, count)| format!("{} {}", count, reason.label()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let lines = summary
+                .scan
+                .skipped
+                .iter()
+                .map(|(candidate, reason)| format!("{}: {}", candidate, reason.label()))
+                .collect();
+            panel = panel.push(section(
+                format!(
+                    "Skipped {} of {} scanned ({})",
+                    summary.scan.skipped.len(),
+                    summary.scan.candidates,
+                    counts
+                ),
+                lines,
+            ));
+        }
+        if !summary.scan.unreachable.is_empty() {
+            let lines = summary
+                .scan
+                .unreachable
+                .iter()
+                .map(|(folder, why)| format!("{}: {}", folder, why))
+                .collect();
+            panel = panel.push(section(
+                format!("Library folders not reachable ({})", summary.scan.unreachable.len()),
+                lines,
+            ));
+        }
+
+        container(scrollable(panel).height(Length::Shrink))
+            .padding(10)
+            .max_height(300)
+            .style(container::rounded_box)
+            .into()
+    }
+
+    /// View: Import preview with per-game selection
+    fn view_import_preview<'a>(
+        &'a self,
+        source: GameSource,
+        entries: &'a [PreviewEntry],
+        tab: ImportPreviewTab,
+    ) -> Element<'a, Message> {
+        let new_count = entries.iter().filter(|e| !e.existing).count();
+        let existing_count = entries.len() - new_count;
+
+        let tab_btn = |label: String, this_tab: ImportPreviewTab| {
+            button(text(label))
+                .on_press(Message::ImportPreviewTabChanged(this_tab))
+                .style(if tab == this_tab {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+        };
+        let tabs = row![
+            tab_btn(format!("New ({})", new_count), ImportPreviewTab::New),
+            tab_btn(
+                format!("Already in library ({})", existing_count),
+                ImportPreviewTab::InLibrary
+            ),
+            Space::new().width(Length::Fill),
+            button(text("Select all")).on_press(Message::ImportPreviewSelectAll(true)),
+            button(text("Select none")).on_press(Message::ImportPreviewSelectAll(false)),
+        ]
+        .spacing(10);
+
+        let show_existing = tab == ImportPreviewTab::InLibrary;
+        let rows: Vec<Element<'a, Message>> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.existing == show_existing)
+            .map(|(index, entry)| {
+                let link_note: Element<'a, Message> = match &entry.link_target {
+                    Some(target) => text(format!("Will link to {}", target)).size(12).into(),
+                    None => Space::new().into(),
+                };
+                row![
+                    checkbox(entry.selected)
+                        .label(truncate_display_name(&entry.game.name, MAX_CARD_NAME_CHARS))
+                        .on_toggle(move |v| Message::ImportPreviewToggled(index, v)),
+                    link_note,
+                    Space::new().width(Length::Fill),
+                    text(entry.game.install_path.display().to_string()).size(12),
+                ]
+                .spacing(10)
+                .into()
+            })
+            .collect();
+
+        let selected: Vec<DetectedGame> = entries
+            .iter()
+            .filter(|e| e.selected)
+            .map(|e| e.game.clone())
+            .collect();
+        let commit_label = format!("Add selected ({})", selected.len());
+        let commit_btn = button(text(commit_label)).on_press_maybe(
+            (!selected.is_empty()).then_some(Message::CommitImport(source, selected)),
+        );
+
+        column![
+            tabs,
+            container(scrollable(column(rows).spacing(5)))
+                .height(Length::Fill)
+                .width(Length::Fill),
+            row![
+                button(text("Cancel")).on_press(Message::CancelImport),
+                Space::new().width(Length::Fill),
+                commit_btn,
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View: Warning before an import adds an unusually large number of games
+    fn view_large_import<'a>(
+        &'a self,
+        source: GameSource,
+        large: &'a LargeImport,
+    ) -> Element<'a, Message> {
+        let warning = text(format!(
+            "This {} import would add {} games, more than your limit of {}. An import folder pointing somewhere too broad, like a whole drive, picks up installers and tools that aren't games.",
+            source.label(),
+            large.count,
+            self.config.import_warn_threshold
+        ))
+        .style(|theme: &Theme| text::Style {
+            color: Some(theme.palette().danger),
+        });
+
+        let sample: Vec<Element<'a, Message>> = large
+            .sample
+            .iter()
+            .map(|name| text(name.as_str()).size(12).into())
+            .collect();
+        let remaining = large.count.saturating_sub(large.sample.len());
+
+        column![
+            warning,
+            text("Including:").size(14),
+            column(sample).spacing(3),
+            (remaining > 0).then(|| text(format!("...and {} more", remaining)).size(12)),
+            row![
+                button(text("Cancel import"))
+                    .on_press(Message::CancelImport)
+                    .style(button::danger),
+                button(text("Back to list")).on_press(Message::ReviewLargeImport),
+                Space::new().width(Length::Fill),
+                button(text(format!("Add {} games anyway", large.count)))
+                    .on_press(Message::ConfirmLargeImport),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+        .into()
+    }
+
     /// View: Add game form
     fn view_add_game(&self) -> Element<'_, Message> {
         let title = text("Add Game").size(24);
@@ -742,22 +6692,81 @@ impl App {
             .on_press(Message::NavigateTo(View::Library));
 
         let name_input = text_input("Game name", &self.new_game_name)
+            .style(self.input_style())
             .on_input(Message::NewGameNameChanged)
             .padding(10);
 
-        let path_input = text_input("Executable path (e.g., C:\\Games\\game.exe)", &self.new_game_path)
-            .on_input(Message::NewGamePathChanged)
+        let path_input = text_input(
+            "Executable path or URL (e.g., C:\\Games\\game.exe, steam://rungameid/400)",
+            &self.new_game_path,
+        )
+        .style(self.input_style())
+        .on_input(Message::NewGamePathChanged)
+        .padding(10);
+        let browse_btn = button(text("Browse..."))
+            .on_press(Message::SelectExecutable)
+            .style(button::secondary)
             .padding(10);
 
+        // Links skip the file checks, the system handler opens them at launch
+        let path = self.new_game_path.trim();
+        let path_check: Option<Result<&str, String>> = if path.is_empty() {
+            None
+        } else if is_uri(path) {
+            Some(Ok("Link detected, it will open with the system's default handler"))
+        } else {
+            validate_executable(Path::new(path)).err().map(|e| Err(e.to_string()))
+        };
+        let path_status: Element<'_, Message> = match path_check.clone() {
+            None => Space::new().into(),
+            Some(Ok(note)) => text(note).size(12).into(),
+            Some(Err(e)) => text(e)
+                .size(12)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+                .into(),
+        };
+
         let can_add = !self.new_game_name.trim().is_empty()
-            && !self.new_game_path.trim().is_empty();
+            && !path.is_empty()
+            && !matches!(path_check, Some(Err(_)));
+
+        // Adding anyway is allowed, but point at the entry that already exists
+        let existing: Vec<Element<'_, Message>> = if path.is_empty() {
+            Vec::new()
+        } else {
+            self.library
+                .games_with_executable(Path::new(path))
+                .into_iter()
+                .map(|game| {
+                    row![
+                        text(format!(
+                            "This executable is already used by {} ({})",
+                            game.display_name(),
+                            game.source.label()
+                        ))
+                        .style(|theme: &Theme| text::Style {
+                            color: Some(theme.palette().warning),
+                        }),
+                        button(text("Open existing"))
+                            .on_press(Message::EditGame(game.id))
+                            .style(button::secondary),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+                })
+                .collect()
+        };
 
         let add_btn = button(text("Add Game")).on_press_maybe(
             if can_add {
-                Some(Message::AddGame(Game::new(
+                Some(Message::AddGame(create_manual_game(
                     self.new_game_name.trim().to_string(),
                     PathBuf::from(self.new_game_path.trim()),
-                    GameSource::Manual,
+                    self.config.default_manual_category,
+                    self.clock.as_ref(),
                 )))
             } else {
                 None
@@ -768,8 +6777,10 @@ impl App {
             row![back_btn, title].spacing(20),
             text("Game Name:"),
             name_input,
-            text("Executable Path:"),
-            path_input,
+            text("Executable Path or URL:"),
+            row![path_input, browse_btn].spacing(10),
+            path_status,
+            column(existing).spacing(5),
             add_btn,
         ]
         .spacing(15)
@@ -779,43 +6790,327 @@ impl App {
 
     /// Get filtered and sorted games based on current filters
     fn get_filtered_games(&self) -> Vec<&Game> {
-        let mut games = if let Some(category_id) = &self.selected_category {
-            self.library.games_in_category(category_id)
-        } else if !self.search_query.is_empty() {
-            self.library.search_games(&self.search_query)
-        } else {
-            self.library.all_games()
+        let searched = || -> Vec<&Game> {
+            if self.search_query.is_empty() {
+                return self.library.all_games();
+            }
+            self.search_index
+                .search(&self.search_query)
+                .iter()
+                .filter_map(|id| self.library.get_game(id))
+                .collect()
         };
 
-        // Apply sorting
-        match self.sort_order {
-            SortOrder::NameAsc => {
-                games.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-            }
-            SortOrder::NameDesc => {
-                games.sort_by(|a, b| b.name.to_lowercase().cmp(&a.name.to_lowercase()));
-            }
-            SortOrder::LastPlayed => {
-                games.sort_by(|a, b| b.last_played.cmp(&a.last_played));
+        let mut games = match self.filter {
+            Some(Filter::Favorites) => self.library.favorite_games(),
+            Some(Filter::Category(category_id)) => {
+                self.library.games_in_category_cached(&category_id, &self.smart_members)
             }
-            SortOrder::RecentlyAdded => {
-                games.sort_by(|a, b| b.added_date.cmp(&a.added_date));
+            Some(Filter::Status(status)) => self.library.games_with_status(status),
+            // Worklists narrow the search, so one fix can be found by name
+            Some(Filter::MissingArt) => {
+                searched().into_iter().filter(|game| game.missing_art()).collect()
             }
-            SortOrder::MostPlayed => {
-                games.sort_by(|a, b| b.playtime_minutes.cmp(&a.playtime_minutes));
+            Some(Filter::MissingMetadata) => {
+                searched().into_iter().filter(|game| game.missing_metadata()).collect()
             }
-        }
+            None => searched(),
+        };
 
+        if !self.show_hidden {
+            games.retain(|game| !game.hidden);
+        }
+        sort_games(&mut games, self.effective_sort());
         games
     }
 
-    /// Get the current theme
-    pub fn theme(&self) -> Theme {
+    /// The header's pick, else the filtered category's own sort, else the
+    /// library sort
+    fn effective_sort(&self) -> SortOrder {
+        self.sort_override
+            .or_else(|| self.library.category_sort(self.filter))
+            .unwrap_or(self.sort_order)
+    }
+
+    /// Get the current theme, the same for every window
+    pub fn theme(&self, _window: window::Id) -> Theme {
         self.theme.to_iced_theme()
     }
 
+    /// Scale every window by the configured interface size
+    ///
+    /// Scaling the whole interface rather than only the text keeps cards and
+    /// rows in proportion; the wrapping grid fits fewer cards per row instead.
+    pub fn scale_factor(&self, _window: window::Id) -> f32 {
+        self.config.font_scale.clamp(FONT_SCALE_MIN, FONT_SCALE_MAX)
+    }
+
+    /// Text input style, with a thick outline on the focused field in the
+    /// high-contrast theme
+    fn input_style(&self) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
+        let high_contrast = self.theme.is_high_contrast();
+        move |theme, status| {
+            let mut style = text_input::default(theme, status);
+            if high_contrast {
+                style.border.width = match status {
+                    text_input::Status::Focused { .. } => 3.0,
+                    _ => 2.0,
+                };
+            }
+            style
+        }
+    }
+
+    /// Latest sampled resource usage for a running game, if enabled
+    fn running_usage(&self, id: &GameId) -> Option<ResourceUsage> {
+        if !self.config.show_resource_usage {
+            return None;
+        }
+        self.running_games.get(id).and_then(|r| r.usage)
+    }
+
+    /// Whether the current screen shows "… ago" times that need refreshing
+    fn shows_relative_times(&self) -> bool {
+        match self.current_view {
+            View::GameDetail(_) => true,
+            View::Library => self.view_mode == ViewMode::List,
+            _ => false,
+        }
+    }
+
     /// Handle subscriptions (for async events, timers, etc.)
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::none()
+        let mut subscriptions = Vec::new();
+
+        // Polling has a cost, so only sample while it is enabled and useful
+        if self.config.show_resource_usage && !self.running_games.is_empty() {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs(RESOURCE_POLL_INTERVAL_SECS))
+                    .map(|_| Message::SampleResources),
+            );
+        }
+
+        // Relative times only go stale on screens that show them
+        if self.shows_relative_times() {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs(RELATIVE_TIME_REFRESH_SECS))
+                    .map(|_| Message::Tick),
+            );
+        }
+
+        // Keep the now playing window's session times ticking
+        if self.now_playing_window.is_some() {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs(NOW_PLAYING_REFRESH_SECS))
+                    .map(|_| Message::Tick),
+            );
+        }
+
+        // Closing the main window quits, closing the others just forgets them
+        subscriptions.push(window::close_events().map(Message::WindowClosed));
+
+        // The library switches layout when the window gets narrow
+        subscriptions.push(
+            window::resize_events().map(|(id, size)| Message::WindowResized(id, size)),
+        );
+
+        // Re-enable Play buttons once their cooldown runs out
+        if !self.launch_cooldowns.is_empty() {
+            subscriptions.push(
+                iced::time::every(Duration::from_millis(LAUNCH_COOLDOWN_POLL_MS))
+                    .map(|_| Message::Tick),
+            );
+        }
+
+        // Check now and then whether a background scan is due
+        if self.config.auto_scan_interval_minutes.is_some() {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs(AUTO_SCAN_POLL_SECS))
+                    .map(|_| Message::AutoScanDue),
+            );
+        }
+
+        // Follow the mouse anywhere in the window while the splitter is held
+        if self.sidebar_dragging {
+            subscriptions.push(iced::event::listen_with(|event, _, _| match event {
+                iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::SidebarDragged(position.x))
+                }
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::SidebarDragEnded)
+                }
+                _ => None,
+            }));
+        }
+
+        // Only needed to notice the user reopening a window we hid
+        if self.window_hidden_for_game {
+            subscriptions.push(window::events().filter_map(|(id, event)| {
+                matches!(event, window::Event::Focused).then_some(Message::WindowFocused(id))
+            }));
+        }
+
+        // Follow the OS when the user flips between light and dark
+        if CustomTheme::follows_system(&self.config.theme) {
+            subscriptions.push(iced::system::theme_changes().map(Message::SystemThemeChanged));
+        }
+
+        // Ctrl+Shift+L flips between grid and list
+        subscriptions.push(keyboard::listen().filter_map(|event| match event {
+            keyboard::Event::KeyPressed {
+                key: keyboard::Key::Character(c),
+                modifiers,
+                ..
+            } if modifiers.command() && modifiers.shift() && c.eq_ignore_ascii_case("l") => {
+                Some(Message::ToggleViewMode)
+            }
+            _ => None,
+        }));
+
+        // Arrows walk the settings sections, Tab walks the fields in one
+        if self.current_view == View::Settings {
+            subscriptions.push(keyboard::listen().filter_map(|event| match event {
+                keyboard::Event::KeyPressed {
+                    key: keyboard::Key::Named(named),
+                    modifiers,
+                    ..
+                } => match named {
+                    keyboard::key::Named::ArrowDown => Some(Message::SettingsSectionStep(true)),
+                    keyboard::key::Named::ArrowUp => Some(Message::SettingsSectionStep(false)),
+                    keyboard::key::Named::Tab => Some(Message::FocusStep(!modifiers.shift())),
+                    _ => None,
+                },
+                _ => None,
+            }));
+        }
+
+        // Escape dismisses the card context menu
+        if self.context_menu.is_some() {
+            subscriptions.push(keyboard::listen().filter_map(|event| match event {
+                keyboard::Event::KeyPressed {
+                    key: keyboard::Key::Named(keyboard::key::Named::Escape),
+                    ..
+                } => Some(Message::CloseContextMenu),
+                _ => None,
+            }));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+}
+
+/// How artwork should be scaled into a box of a different aspect ratio
+///
+/// Icons are never cropped, filling a tall card with one would show only a
+/// sliver of it.
+fn art_fit(shape: Option<ArtShape>, crop: bool) -> ContentFit {
+    match shape {
+        Some(ArtShape::Icon) => ContentFit::Contain,
+        _ if crop => ContentFit::Cover,
+        _ => ContentFit::Contain,
     }
 }
+
+/// Stores `scan_source` can scan, with the app each one needs, in the order
+/// background scans go through them
+const SCANNABLE_SOURCES: &[(GameSource, &str)] = &[
+    (GameSource::Steam, "Steam"),
+    (GameSource::SteamShortcut, "Steam"),
+    (GameSource::GOG, "GOG Galaxy"),
+    (GameSource::Itch, "The itch.io app"),
+    (GameSource::Xbox, "The Xbox app"),
+    (GameSource::Ubisoft, "Ubisoft Connect"),
+];
+
+/// Whether the app `source` is imported from is installed
+///
+/// Blocking, like `scan_source`.
+fn source_available(source: GameSource, steam_library_paths: &[PathBuf]) -> bool {
+    match source {
+        GameSource::Steam => SteamImporter::new()
+            .with_extra_library_paths(steam_library_paths.to_vec())
+            .is_available(),
+        GameSource::SteamShortcut => SteamImporter::new().steam_path().is_some(),
+        GameSource::GOG => GOGImporter::new().is_available(),
+        GameSource::Itch => ItchImporter::new().is_available(),
+        GameSource::Xbox => XboxImporter::new().is_available(),
+        GameSource::Ubisoft => UbisoftImporter::new().is_available(),
+        _ => false,
+    }
+}
+
+/// Scan one store for installed games
+///
+/// Blocking, since importers read manifests and the registry directly, so it
+/// runs on tokio's blocking pool rather than the UI executor.
+fn scan_source(
+    source: GameSource,
+    steam_library_paths: Vec<PathBuf>,
+    exclude: ExcludePatterns,
+    include_not_installed: bool,
+) -> ScanResult {
+    if let Some((_, app)) = SCANNABLE_SOURCES.iter().find(|(s, _)| *s == source) {
+        if !source_available(source, &steam_library_paths) {
+            return Err(format!("{} is not installed", app));
+        }
+    }
+    let scanned = match source {
+        GameSource::Steam => SteamImporter::new()
+            .with_extra_library_paths(steam_library_paths)
+            .with_exclude_patterns(exclude)
+            .with_not_installed(include_not_installed)
+            .scan_games(),
+        GameSource::SteamShortcut => SteamImporter::new().scan_shortcuts(),
+        GameSource::GOG => GOGImporter::new().scan_games(),
+        GameSource::Itch => ItchImporter::new().with_exclude_patterns(exclude).scan_games(),
+        GameSource::Xbox => XboxImporter::new().scan_games(),
+        GameSource::Ubisoft => UbisoftImporter::new()
+            .with_exclude_patterns(exclude)
+            .with_not_installed(include_not_installed)
+            .scan_games(),
+        _ => return Err(format!("{:?} import not yet implemented", source)),
+    };
+    scanned.map_err(|e| e.to_string())
+}
+
+/// A small `label: value` line with a button copying the value, for paths
+/// users paste elsewhere while troubleshooting
+fn copyable_line(label: &str, value: String) -> Element<'static, Message> {
+    row![
+        text(format!("{}: {}", label, value)).size(12),
+        button(text("Copy").size(12))
+            .on_press(Message::CopyToClipboard(value))
+            .padding([0, 6])
+            .style(button::secondary),
+    ]
+    .spacing(5)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Activity entry for an import, summarizing what it changed
+fn import_activity(summary: &ImportSummary, clock: &dyn Clock) -> ActivityEntry {
+    let detail = format!("{} added, {} updated", summary.added.len(), summary.updated.len());
+    ActivityEntry::import(summary.source.label(), detail, clock)
+}
+
+/// Launch a game through a URL or store protocol link
+///
+/// The system handler owns whatever it opens, so there is no process to track.
+fn open_uri(id: GameId, uri: String, big_picture: bool) -> Task<Message> {
+    Task::perform(
+        async move { crate::launcher::launch_store_uri(&uri, big_picture) },
+        move |result| Message::GameLaunched(id, result.map(|()| None)),
+    )
+}
+
+/// Bring the main window back after it was hidden or minimized for a game
+fn restore_window() -> Task<Message> {
+    window::oldest().and_then(|id| {
+        Task::batch([
+            window::set_mode(id, window::Mode::Windowed),
+            window::minimize(id, false),
+            window::gain_focus(id),
+        ])
+    })
+}
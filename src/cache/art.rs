@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use super::download::Downloader;
+use crate::constants::ART_DIR;
+use crate::data::{ArtShape, Game, GameId, GameSource};
+
+/// Base URL for Steam's public store artwork
+const STEAM_CDN: &str = "https://cdn.cloudflare.steamstatic.com/steam/apps";
+
+/// Artwork paths resolved for a single game
+#[derive(Debug, Clone, Default)]
+pub struct ArtPaths {
+    pub banner_path: Option<PathBuf>,
+    pub banner_shape: Option<ArtShape>,
+    pub icon_path: Option<PathBuf>,
+}
+
+impl ArtPaths {
+    pub fn is_empty(&self) -> bool {
+        self.banner_path.is_none() && self.icon_path.is_none()
+    }
+}
+
+/// A game that needs artwork resolved
+#[derive(Debug, Clone)]
+pub struct ArtRequest {
+    pub game_id: GameId,
+    pub name: String,
+    pub source: GameSource,
+    pub source_id: Option<String>,
+    pub needs_banner: bool,
+    pub needs_icon: bool,
+}
+
+impl ArtRequest {
+    /// Build a request for a game, or `None` if it already has all its art
+    ///
+    /// When `force` is set, art is requested even if the game already has some.
+    pub fn from_game(game: &Game, force: bool) -> Option<Self> {
+        let needs_banner = force || game.banner_path.is_none();
+        let needs_icon = force || game.icon_path.is_none();
+
+        if !needs_banner && !needs_icon {
+            return None;
+        }
+
+        Some(Self {
+            game_id: game.id,
+            name: game.name.clone(),
+            source: game.source,
+            source_id: game.source_id.clone(),
+            needs_banner,
+            needs_icon,
+        })
+    }
+}
+
+/// Get the directory downloaded artwork is stored in
+pub fn art_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(ART_DIR)
+}
+
+/// Resolve artwork for a game, preferring local launcher caches over downloads
+///
+/// Failures are logged and leave the corresponding path as `None`.
+pub async fn fetch_art(
+    downloader: Downloader,
+    request: ArtRequest,
+    art_dir: PathBuf,
+    steam_path: Option<PathBuf>,
+) -> (ArtRequest, ArtPaths) {
+    let mut paths = ArtPaths::default();
+
+    if request.source == GameSource::Steam {
+        if let Some(app_id) = request.source_id.as_deref() {
+            if let Some(steam_path) = &steam_path {
+                let (banner, icon) = steam_cached_art(steam_path, app_id);
+                if request.needs_banner {
+                    paths.banner_path = banner;
+                }
+                if request.needs_icon {
+                    paths.icon_path = icon;
+                }
+            }
+
+            if request.needs_banner && paths.banner_path.is_none() {
+                let url = format!("{}/{}/library_600x900.jpg", STEAM_CDN, app_id);
+                let dest = art_dir.join(format!("{}_banner.jpg", request.game_id.0));
+                match downloader.download_to_file(&url, &dest, None, None).await {
+                    Ok(()) => paths.banner_path = Some(dest),
+                    Err(e) => tracing::debug!("No banner for {}: {}", request.name, e),
+                }
+            }
+        }
+    }
+
+    paths.banner_shape = paths.banner_path.as_deref().and_then(art_shape);
+    (request, paths)
+}
+
+/// Detect the shape of an image from its header, without decoding it fully
+pub fn art_shape(path: &Path) -> Option<ArtShape> {
+    match image::image_dimensions(path) {
+        Ok((width, height)) => ArtShape::from_dimensions(width, height),
+        Err(e) => {
+            tracing::debug!("Could not read dimensions of {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Find cover art and icon in Steam's local library cache
+pub fn steam_cached_art(steam_path: &Path, app_id: &str) -> (Option<PathBuf>, Option<PathBuf>) {
+    let cache = steam_path.join("appcache").join("librarycache");
+
+    // Older clients keep flat files, newer ones use a folder per app
+    let banner = [
+        cache.join(format!("{}_library_600x900.jpg", app_id)),
+        cache.join(app_id).join("library_600x900.jpg"),
+    ]
+    .into_iter()
+    .find(|p| p.is_file());
+
+    let icon = [cache.join(format!("{}_icon.jpg", app_id))]
+        .into_iter()
+        .find(|p| p.is_file());
+
+    (banner, icon)
+}
@@ -0,0 +1,571 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Semaphore;
+
+use crate::constants::{
+    ART_DOWNLOAD_CONCURRENCY, DOWNLOAD_BACKOFF_MS, DOWNLOAD_CONNECT_TIMEOUT_SECS,
+    DOWNLOAD_READ_TIMEOUT_SECS, DOWNLOAD_RETRIES,
+};
+
+/// Timeouts, retries and pool size for a [`Downloader`]
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    pub connect_timeout: Duration,
+    /// How long a response may stall between reads
+    pub read_timeout: Duration,
+    /// Extra attempts after the first one fails
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub backoff: Duration,
+    /// Downloads running at once; the rest wait their turn
+    pub concurrency: usize,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(DOWNLOAD_CONNECT_TIMEOUT_SECS),
+            read_timeout: Duration::from_secs(DOWNLOAD_READ_TIMEOUT_SECS),
+            retries: DOWNLOAD_RETRIES,
+            backoff: Duration::from_millis(DOWNLOAD_BACKOFF_MS),
+            concurrency: ART_DOWNLOAD_CONCURRENCY,
+        }
+    }
+}
+
+/// HTTP downloads that survive flaky connections
+///
+/// Failed requests are retried with backoff, interrupted downloads resume
+/// from a `.part` file, and a file only lands at its destination once its
+/// length, and digest if known, check out. Clones share the client and the
+/// concurrency pool.
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    client: reqwest::Client,
+    permits: Arc<Semaphore>,
+    options: DownloadOptions,
+}
+
+impl Downloader {
+    pub fn new(options: DownloadOptions) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(options.connect_timeout)
+            .read_timeout(options.read_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!("Could not configure HTTP client, using defaults: {}", e);
+                reqwest::Client::new()
+            });
+
+        Self {
+            client,
+            permits: Arc::new(Semaphore::new(options.concurrency.max(1))),
+            options,
+        }
+    }
+
+    /// The downloader every art fetch shares, so they all count against one pool
+    pub fn shared() -> Self {
+        static SHARED: OnceLock<Downloader> = OnceLock::new();
+        SHARED.get_or_init(|| Self::new(DownloadOptions::default())).clone()
+    }
+
+    /// The underlying client, for API calls that don't need retries
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Fetch a small response into memory
+    pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>, DownloadError> {
+        let _permit = self.permits.acquire().await.expect("download pool is never closed");
+        self.with_retries(url, || async {
+            let response = self.client.get(url).send().await?;
+            if !response.status().is_success() {
+                return Err(DownloadError::Status(response.status()));
+            }
+            let expected = response.content_length();
+            let bytes = response.bytes().await?;
+            check_length(expected, bytes.len() as u64)?;
+            Ok(bytes.to_vec())
+        })
+        .await
+    }
+
+    /// Download a URL to a file, creating parent directories as needed
+    ///
+    /// `expected_len` is checked on top of the server's own length, and the
+    /// file's SHA-256 against `expected_sha256` (in hex), when the caller
+    /// knows them. Whatever arrived is kept in `<dest>.part` on failure, so
+    /// the next attempt picks up where this one stopped; a file with the
+    /// wrong digest is thrown away instead.
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected_len: Option<u64>,
+        expected_sha256: Option<&str>,
+    ) -> Result<(), DownloadError> {
+        let _permit = self.permits.acquire().await.expect("download pool is never closed");
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let part = partial_path(dest);
+        let len = self
+            .with_retries(url, || self.download_part(url, &part, expected_len))
+            .await?;
+        // The part file may have been touched between attempts; only a
+        // complete one is moved into place
+        let on_disk = fs::metadata(&part).await?.len();
+        if let Err(e) = check_length(Some(len), on_disk).and(check_length(expected_len, on_disk)) {
+            fs::remove_file(&part).await?;
+            return Err(e);
+        }
+        if let Some(expected) = expected_sha256 {
+            let got = sha256_hex(&part).await?;
+            if !got.eq_ignore_ascii_case(expected.trim()) {
+                fs::remove_file(&part).await?;
+                let _ = fs::remove_file(validator_path(&part)).await;
+                let expected = expected.trim().to_lowercase();
+                return Err(DownloadError::DigestMismatch { expected, got });
+            }
+        }
+        fs::rename(&part, dest).await?;
+        let _ = fs::remove_file(validator_path(&part)).await;
+
+        tracing::debug!("Downloaded {} to {:?}", url, dest);
+        Ok(())
+    }
+
+    /// Run `attempt` until it succeeds, fails for good, or runs out of retries
+    async fn with_retries<T, F, Fut>(&self, url: &str, mut attempt: F) -> Result<T, DownloadError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DownloadError>>,
+    {
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Err(e) if e.is_retryable() && retries < self.options.retries => {
+                    let delay = self.options.backoff * 2u32.pow(retries);
+                    retries += 1;
+                    tracing::debug!("Retrying {} in {:?}: {}", url, delay, e);
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// One attempt at completing `part`, resuming from what it already holds,
+    /// returning its length
+    ///
+    /// A resume sends `If-Range` with the validator the part was started
+    /// with, so a file that changed on the server comes back whole instead of
+    /// being spliced onto the old bytes. Without a validator it starts over.
+    async fn download_part(
+        &self,
+        url: &str,
+        part: &Path,
+        expected_len: Option<u64>,
+    ) -> Result<u64, DownloadError> {
+        let validator = fs::read_to_string(validator_path(part)).await.ok();
+        let offset = match &validator {
+            Some(_) => fs::metadata(part).await.map(|m| m.len()).unwrap_or(0),
+            None => 0,
+        };
+        let mut request = self.client.get(url);
+        if let Some(validator) = validator.filter(|_| offset > 0) {
+            request = request
+                .header(RANGE, format!("bytes={}-", offset))
+                .header(IF_RANGE, validator.trim());
+        }
+        let mut response = request.send().await?;
+
+        let status = response.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file doesn't match what the server has now
+            fs::remove_file(part).await?;
+            return Err(DownloadError::Status(status));
+        }
+        if !status.is_success() {
+            return Err(DownloadError::Status(status));
+        }
+
+        // A server that ignores the range sends the whole file again
+        let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+        let (mut file, mut written, total) = if resumed {
+            let file = fs::OpenOptions::new().append(true).open(part).await?;
+            let total = content_range_total(&response)
+                .or(response.content_length().map(|len| offset + len));
+            (file, offset, total)
+        } else {
+            match response_validator(&response) {
+                Some(validator) => fs::write(validator_path(part), validator).await?,
+                None => {
+                    let _ = fs::remove_file(validator_path(part)).await;
+                }
+            }
+            (fs::File::create(part).await?, 0, response.content_length())
+        };
+
+        // Flush what arrived even if the connection drops, so it can be resumed
+        let received = async {
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+            }
+            Ok::<_, DownloadError>(())
+        }
+        .await;
+        file.flush().await?;
+        received?;
+
+        // Cut short: keep what arrived for the next attempt
+        if let Some(total) = total.filter(|&total| written < total) {
+            return Err(DownloadError::Incomplete { expected: total, got: written });
+        }
+        // Anything else resuming can't fix, so start over next time
+        for expected in [total, expected_len] {
+            if let Err(e) = check_length(expected, written) {
+                fs::remove_file(part).await?;
+                return Err(e);
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// Where the validator a `.part` file was started with is kept
+fn validator_path(part: &Path) -> PathBuf {
+    let mut name = part.file_name().unwrap_or_default().to_os_string();
+    name.push(".validator");
+    part.with_file_name(name)
+}
+
+/// What `If-Range` can compare against: a strong ETag, else the last
+/// modified date; weak ETags aren't allowed there
+fn response_validator(response: &reqwest::Response) -> Option<String> {
+    let header = |name| response.headers().get(name)?.to_str().ok();
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+        .map(str::to_string)
+}
+
+/// Where a download is kept until it is complete
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// The full size from a `Content-Range: bytes start-end/total` header
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// SHA-256 of a file, as lowercase hex
+async fn sha256_hex(path: &Path) -> Result<String, DownloadError> {
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn check_length(expected: Option<u64>, got: u64) -> Result<(), DownloadError> {
+    match expected {
+        Some(expected) if expected != got => Err(DownloadError::Incomplete { expected, got }),
+        _ => Ok(()),
+    }
+}
+
+/// Errors that can occur while downloading
+#[derive(Debug, Error)]
+pub enum DownloadError {
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Invalid request: {0}")]
+    Request(String),
+
+    #[error("HTTP status {0}")]
+    Status(StatusCode),
+
+    #[error("Incomplete download: got {got} of {expected} bytes")]
+    Incomplete { expected: u64, got: u64 },
+
+    #[error("Download doesn't match its SHA-256: expected {expected}, got {got}")]
+    DigestMismatch { expected: String, got: String },
+
+    #[error("IO error: {0}")]
+    Io(String),
+}
+
+impl DownloadError {
+    /// Whether trying again might succeed
+    fn is_retryable(&self) -> bool {
+        match self {
+            DownloadError::Network(_) | DownloadError::Incomplete { .. } => true,
+            DownloadError::Status(status) => {
+                status.is_server_error()
+                    || matches!(
+                        *status,
+                        StatusCode::REQUEST_TIMEOUT
+                            | StatusCode::TOO_MANY_REQUESTS
+                            | StatusCode::RANGE_NOT_SATISFIABLE
+                    )
+            }
+            DownloadError::Request(_)
+            | DownloadError::DigestMismatch { .. }
+            | DownloadError::Io(_) => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_builder() {
+            DownloadError::Request(e.to_string())
+        } else {
+            DownloadError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<std::io::Error> for DownloadError {
+    fn from(e: std::io::Error) -> Self {
+        DownloadError::Io(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// What the mock server does with one connection
+    enum Reply {
+        /// Write these bytes and close the connection
+        Raw(&'static str),
+        /// Read the request and never answer
+        Hang,
+    }
+
+    /// Serve one reply per connection on localhost, recording each request
+    async fn mock_server(replies: Vec<Reply>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/art.jpg", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+                seen.lock().unwrap().push(String::from_utf8_lossy(&request).to_lowercase());
+
+                match reply {
+                    Reply::Raw(response) => {
+                        let _ = socket.write_all(response.as_bytes()).await;
+                    }
+                    Reply::Hang => {
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(30)).await;
+                            drop(socket);
+                        });
+                    }
+                }
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn test_downloader() -> Downloader {
+        Downloader::new(DownloadOptions {
+            connect_timeout: Duration::from_secs(1),
+            read_timeout: Duration::from_millis(200),
+            retries: 2,
+            backoff: Duration::from_millis(10),
+            concurrency: 1,
+        })
+    }
+
+    fn test_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("launcher-download-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let (url, requests) = mock_server(vec![
+            Reply::Raw(
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            ),
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nart"),
+        ])
+        .await;
+
+        let bytes = test_downloader().fetch_bytes(&url).await.unwrap();
+        assert_eq!(bytes, b"art");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // Missing art won't show up by asking again
+        let (url, requests) = mock_server(vec![Reply::Raw(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        )])
+        .await;
+        let result = test_downloader().fetch_bytes(&url).await;
+        assert!(matches!(result, Err(DownloadError::Status(StatusCode::NOT_FOUND))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_times_out_stalled_responses() {
+        let (url, requests) = mock_server(vec![Reply::Hang, Reply::Hang, Reply::Hang]).await;
+        let dest = test_dir().join("art.jpg");
+
+        let started = Instant::now();
+        let result = test_downloader().download_to_file(&url, &dest, None, None).await;
+        assert!(matches!(result, Err(DownloadError::Network(_))), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert!(!dest.exists());
+    }
+
+    #[tokio::test]
+    async fn test_resumes_interrupted_download() {
+        let (url, requests) = mock_server(vec![
+            Reply::Raw(
+                "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 10\r\n\
+                 Connection: close\r\n\r\nhello",
+            ),
+            Reply::Raw(
+                "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 5-9/10\r\n\
+                 Content-Length: 5\r\nConnection: close\r\n\r\nworld",
+            ),
+        ])
+        .await;
+        let dir = test_dir();
+        let dest = dir.join("art.jpg");
+
+        test_downloader().download_to_file(&url, &dest, Some(10), None).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"helloworld");
+        assert!(!partial_path(&dest).exists());
+        assert!(!validator_path(&partial_path(&dest)).exists());
+
+        let requests = requests.lock().unwrap();
+        assert!(!requests[0].contains("range:"));
+        assert!(requests[1].contains("range: bytes=5-"));
+        assert!(requests[1].contains("if-range: \"v1\""));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_changed_file_restarts_download() {
+        let (url, requests) = mock_server(vec![
+            // No validator to resume against, so the second try starts over
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nhello"),
+            Reply::Raw(
+                "HTTP/1.1 200 OK\r\nETag: \"v2\"\r\nContent-Length: 10\r\n\
+                 Connection: close\r\n\r\nHELLO",
+            ),
+            // The file changed since, so If-Range gets the whole new one
+            Reply::Raw(
+                "HTTP/1.1 200 OK\r\nETag: \"v3\"\r\nContent-Length: 10\r\n\
+                 Connection: close\r\n\r\nnewnewnew!",
+            ),
+        ])
+        .await;
+        let dir = test_dir();
+        let dest = dir.join("art.jpg");
+
+        test_downloader().download_to_file(&url, &dest, None, None).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"newnewnew!");
+
+        let requests = requests.lock().unwrap();
+        assert!(!requests[1].contains("range:"));
+        assert!(requests[2].contains("if-range: \"v2\""));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_length_is_not_cached() {
+        let (url, _) = mock_server(vec![
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nart"),
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nart"),
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nart"),
+        ])
+        .await;
+        let dir = test_dir();
+        let dest = dir.join("art.jpg");
+
+        let result = test_downloader().download_to_file(&url, &dest, Some(4), None).await;
+        assert!(matches!(result, Err(DownloadError::Incomplete { expected: 4, got: 3 })));
+        assert!(!dest.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_digest_is_not_cached() {
+        let (url, requests) = mock_server(vec![
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nart"),
+            Reply::Raw("HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nart"),
+        ])
+        .await;
+        let dir = test_dir();
+        let dest = dir.join("art.jpg");
+        let sha256 = "d3cec99112255db9bf4963f7798562b6a36f1bd0b2016918629e007d474deb70";
+
+        let wrong = sha256.replace('2', "3");
+        let result = test_downloader().download_to_file(&url, &dest, None, Some(&wrong)).await;
+        assert!(
+            matches!(&result, Err(DownloadError::DigestMismatch { got, .. }) if got == sha256),
+            "{:?}",
+            result
+        );
+        // Not retried, and nothing left behind to resume from
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(!dest.exists());
+        assert!(!partial_path(&dest).exists());
+
+        let upper = sha256.to_uppercase();
+        test_downloader().download_to_file(&url, &dest, None, Some(&upper)).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"art");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
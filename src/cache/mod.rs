@@ -0,0 +1,10 @@
+//! Artwork cache and downloads
+
+mod art;
+mod download;
+#[cfg(feature = "steamgriddb")]
+pub mod steamgriddb;
+
+pub use art::{art_dir, fetch_art, steam_cached_art, ArtPaths, ArtRequest};
+pub use art::art_shape;
+pub use download::Downloader;
@@ -0,0 +1,231 @@
+//! SteamGridDB artwork lookups (requires the `steamgriddb` feature)
+
+use std::path::{Path, PathBuf};
+
+use iced::futures::{stream, StreamExt};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::download::Downloader;
+use crate::constants::ART_DOWNLOAD_CONCURRENCY;
+use crate::data::GameSource;
+
+const API_BASE: &str = "https://www.steamgriddb.com/api/v2";
+
+/// Number of candidates whose thumbnails are fetched at a time
+pub const CANDIDATES_PER_PAGE: usize = 12;
+
+/// Kind of artwork offered by SteamGridDB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtKind {
+    Grid,
+    Hero,
+    Icon,
+}
+
+impl ArtKind {
+    fn endpoint(&self) -> &'static str {
+        match self {
+            ArtKind::Grid => "grids",
+            ArtKind::Hero => "heroes",
+            ArtKind::Icon => "icons",
+        }
+    }
+}
+
+/// An artwork SteamGridDB offers, before its thumbnail is fetched
+#[derive(Debug, Clone)]
+pub struct ArtEntry {
+    pub kind: ArtKind,
+    pub url: String,
+    pub thumb: String,
+}
+
+/// A single artwork candidate with its downloaded thumbnail
+#[derive(Debug, Clone)]
+pub struct ArtCandidate {
+    pub kind: ArtKind,
+    pub url: String,
+    pub thumbnail: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct GameEntry {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct ImageEntry {
+    url: String,
+    thumb: String,
+}
+
+/// Search SteamGridDB for every artwork it has for a game
+///
+/// Steam games are looked up by AppID, everything else by name. Thumbnails
+/// are left to `fetch_candidates`, a page at a time.
+pub async fn find_artwork(
+    api_key: String,
+    name: String,
+    source: GameSource,
+    source_id: Option<String>,
+) -> Result<Vec<ArtEntry>, SteamGridDbError> {
+    let downloader = Downloader::shared();
+    let client = downloader.client();
+
+    let game_id = match (source, source_id) {
+        (GameSource::Steam, Some(app_id)) => {
+            let url = format!("{}/games/steam/{}", API_BASE, app_id);
+            get_json::<GameEntry>(client, &api_key, &url).await?.id
+        }
+        _ => {
+            let url = format!("{}/search/autocomplete/{}", API_BASE, encode_term(&name));
+            get_json::<Vec<GameEntry>>(client, &api_key, &url)
+                .await?
+                .first()
+                .map(|g| g.id)
+                .ok_or(SteamGridDbError::NotFound)?
+        }
+    };
+
+    let mut entries = Vec::new();
+    for kind in [ArtKind::Grid, ArtKind::Hero, ArtKind::Icon] {
+        let url = format!("{}/{}/game/{}", API_BASE, kind.endpoint(), game_id);
+        let images = get_json::<Vec<ImageEntry>>(client, &api_key, &url).await?;
+        entries.extend(images.into_iter().map(|image| ArtEntry {
+            kind,
+            url: image.url,
+            thumb: image.thumb,
+        }));
+    }
+
+    if entries.is_empty() {
+        return Err(SteamGridDbError::NotFound);
+    }
+    Ok(entries)
+}
+
+/// Download the thumbnails for a page of entries
+///
+/// Thumbnails are small, so they're fetched concurrently. Any that fail,
+/// including those the server answers with an error status, are dropped.
+pub async fn fetch_candidates(entries: Vec<ArtEntry>) -> Vec<ArtCandidate> {
+    let downloader = Downloader::shared();
+    stream::iter(entries)
+        .map(|entry| {
+            let downloader = downloader.clone();
+            async move {
+                let thumbnail = downloader.fetch_bytes(&entry.thumb).await.ok()?;
+                Some(ArtCandidate {
+                    kind: entry.kind,
+                    url: entry.url,
+                    thumbnail,
+                })
+            }
+        })
+        .buffered(ART_DOWNLOAD_CONCURRENCY)
+        .filter_map(|c| async move { c })
+        .collect()
+        .await
+}
+
+/// Download a chosen candidate into the art directory
+pub async fn download_candidate(
+    candidate: ArtCandidate,
+    art_dir: &Path,
+    file_stem: &str,
+) -> Result<PathBuf, SteamGridDbError> {
+    let extension = Path::new(&candidate.url)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png");
+    let dest = art_dir.join(format!("{}.{}", file_stem, extension));
+
+    Downloader::shared()
+        .download_to_file(&candidate.url, &dest, None, None)
+        .await
+        .map_err(|e| SteamGridDbError::Http(e.to_string()))?;
+
+    Ok(dest)
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    api_key: &str,
+    url: &str,
+) -> Result<T, SteamGridDbError> {
+    let response = client
+        .get(url)
+        .bearer_auth(api_key)
+        .send()
+        .await
+        .map_err(|e| SteamGridDbError::Http(e.to_string()))?;
+
+    match response.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            return Err(SteamGridDbError::InvalidKey)
+        }
+        StatusCode::TOO_MANY_REQUESTS => return Err(SteamGridDbError::RateLimited),
+        StatusCode::NOT_FOUND => return Err(SteamGridDbError::NotFound),
+        _ => {}
+    }
+
+    let bytes = response
+        .error_for_status()
+        .map_err(|e| SteamGridDbError::Http(e.to_string()))?
+        .bytes()
+        .await
+        .map_err(|e| SteamGridDbError::Http(e.to_string()))?;
+
+    serde_json::from_slice::<Response<T>>(&bytes)
+        .map(|r| r.data)
+        .map_err(|e| SteamGridDbError::Http(e.to_string()))
+}
+
+/// Percent-encode a search term for use in a URL path
+fn encode_term(term: &str) -> String {
+    term.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Errors that can occur when talking to SteamGridDB
+#[derive(Debug, Clone, Error)]
+pub enum SteamGridDbError {
+    #[error("No SteamGridDB API key configured")]
+    MissingKey,
+
+    #[error("SteamGridDB rejected the API key")]
+    InvalidKey,
+
+    #[error("SteamGridDB rate limit reached, try again in a minute")]
+    RateLimited,
+
+    #[error("No artwork found")]
+    NotFound,
+
+    #[error("HTTP error: {0}")]
+    Http(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_term() {
+        assert_eq!(encode_term("Half-Life 2"), "Half-Life%202");
+        assert_eq!(encode_term("Tom & Jerry"), "Tom%20%26%20Jerry");
+    }
+}
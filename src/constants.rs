@@ -12,10 +12,127 @@ pub const APP_APPLICATION: &str = "Launcher";
 pub const DEFAULT_WINDOW_WIDTH: f32 = 1200.0;
 pub const DEFAULT_WINDOW_HEIGHT: f32 = 800.0;
 
+/// Environment variable naming a data directory to use when the default
+/// one isn't writable
+pub const DATA_DIR_ENV: &str = "LAUNCHER_DATA_DIR";
+
 /// File names for persistence
 pub const LIBRARY_FILE: &str = "library.json";
 pub const CONFIG_FILE: &str = "config.json";
+pub const ACTIVITY_FILE: &str = "activity.jsonl";
+pub const SCAN_CACHE_FILE: &str = "scan_cache.json";
+
+/// Data dirs switched between, kept in the config dir rather than any one of them
+pub const RECENT_DATA_DIRS_FILE: &str = "recent_libraries.json";
+
+/// Most data dirs offered under "Switch library"
+pub const MAX_RECENT_DATA_DIRS: usize = 8;
+
+/// Size the activity log is rotated at, in bytes
+pub const MAX_ACTIVITY_LOG_BYTES: u64 = 512 * 1024;
+
+/// Most activity entries kept in memory and shown
+pub const ACTIVITY_HISTORY_LIMIT: usize = 500;
+
+/// Directory (inside the data dir) for user themes
+pub const THEMES_DIR: &str = "themes";
+
+/// Directory (inside the data dir) for captured game output
+pub const GAME_LOGS_DIR: &str = "game-logs";
+
+/// File (inside the data dir) describing the game being played, for external tools
+pub const LAUNCH_STATUS_FILE: &str = "launch_status.json";
+
+/// Directory (inside the data dir) for zipped save folders, one folder per game
+pub const SAVE_BACKUPS_DIR: &str = "save-backups";
+
+/// Largest captured game log, in bytes; later output is dropped
+pub const MAX_GAME_LOG_BYTES: u64 = 1024 * 1024;
+
+/// How many folders deep "Re-detect executable" looks inside an install folder
+pub const EXECUTABLE_SEARCH_DEPTH: usize = 3;
+
+/// How many folders deep a ROM import looks, for collections sorted by system
+pub const ROM_SCAN_DEPTH: usize = 3;
+
+/// An import adding more games than this asks first, in case it scanned the wrong folder
+pub const IMPORT_WARN_THRESHOLD: usize = 500;
+
+/// Large-import warning thresholds offered in settings, in games
+pub const IMPORT_WARN_THRESHOLD_CHOICES: &[usize] = &[100, 500, 1000, 5000];
+
+/// How many of the games a large import would add are named in its warning
+pub const IMPORT_WARN_SAMPLE_SIZE: usize = 10;
+
+/// How long a library folder may take to answer before a scan skips it, in
+/// seconds; network shares can stall for much longer
+pub const LIBRARY_FOLDER_TIMEOUT_SECS: u64 = 5;
+
+/// Directory (inside the data dir) for downloaded artwork
+pub const ART_DIR: &str = "art";
+
+/// Maximum number of artwork downloads running at once
+pub const ART_DOWNLOAD_CONCURRENCY: usize = 4;
+
+/// How long a download may take to connect, and to stall mid-response, in seconds
+pub const DOWNLOAD_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const DOWNLOAD_READ_TIMEOUT_SECS: u64 = 30;
+
+/// How long Discord may take to accept the connection or answer a command,
+/// in seconds; a hung client would otherwise hold the presence lock forever
+pub const DISCORD_IPC_TIMEOUT_SECS: u64 = 5;
+
+/// Retries for a failed download, waiting twice as long before each one
+pub const DOWNLOAD_RETRIES: u32 = 3;
+pub const DOWNLOAD_BACKOFF_MS: u64 = 500;
 
 /// Theme names
 pub const THEME_DARK: &str = "dark";
 pub const THEME_LIGHT: &str = "light";
+
+/// How often running games' resource usage is sampled, in seconds
+pub const RESOURCE_POLL_INTERVAL_SECS: u64 = 2;
+
+/// How often relative times like "5 minutes ago" are refreshed, in seconds
+pub const RELATIVE_TIME_REFRESH_SECS: u64 = 60;
+
+/// How often launch cooldowns are checked for expiry, in milliseconds
+pub const LAUNCH_COOLDOWN_POLL_MS: u64 = 250;
+
+/// How often the now playing window's session times tick, in seconds
+pub const NOW_PLAYING_REFRESH_SECS: u64 = 1;
+
+/// Size of the now playing window
+pub const NOW_PLAYING_WINDOW_WIDTH: f32 = 320.0;
+pub const NOW_PLAYING_WINDOW_HEIGHT: f32 = 160.0;
+
+/// How long a store's cached scan is shown before the import view scans again, in hours
+pub const SCAN_CACHE_MAX_AGE_HOURS: i64 = 24;
+
+/// How often the background scan checks whether a store is due, in seconds
+pub const AUTO_SCAN_POLL_SECS: u64 = 60;
+
+/// Background scan intervals offered in settings, in minutes
+pub const AUTO_SCAN_INTERVAL_CHOICES: &[u32] = &[15, 30, 60, 180];
+
+/// Playtime thresholds offered as smart category conditions, in hours
+pub const RULE_PLAYTIME_HOUR_CHOICES: &[u64] = &[1, 10, 50];
+
+/// How long newly added games keep their "New" badge, offered in settings, in days
+pub const NEW_BADGE_DAY_CHOICES: &[u32] = &[1, 7, 30];
+
+/// Interface size multipliers offered in settings, and the range a config may hold
+pub const FONT_SCALE_CHOICES: &[f32] = &[0.8, 1.0, 1.25, 1.5, 2.0];
+pub const FONT_SCALE_MIN: f32 = 0.8;
+pub const FONT_SCALE_MAX: f32 = 2.0;
+
+/// Window widths below which the sidebar folds into a header menu, offered in
+/// settings, in logical pixels
+pub const NARROW_LAYOUT_WIDTH_CHOICES: &[u32] = &[640, 800, 1000];
+
+/// Range the sidebar can be dragged to, in logical pixels
+pub const SIDEBAR_MIN_WIDTH: f32 = 150.0;
+pub const SIDEBAR_MAX_WIDTH: f32 = 400.0;
+
+/// Longest game name shown on cards and list rows before truncating
+pub const MAX_CARD_NAME_CHARS: usize = 40;
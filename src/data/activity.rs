@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use super::{Clock, GameId};
+
+/// What an activity log entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Launched,
+    Exited,
+    Added,
+    Removed,
+    Imported,
+}
+
+impl ActivityKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActivityKind::Launched => "Launched",
+            ActivityKind::Exited => "Sessions",
+            ActivityKind::Added => "Added",
+            ActivityKind::Removed => "Removed",
+            ActivityKind::Imported => "Imports",
+        }
+    }
+
+    pub fn all() -> &'static [ActivityKind] {
+        &[
+            ActivityKind::Launched,
+            ActivityKind::Exited,
+            ActivityKind::Added,
+            ActivityKind::Removed,
+            ActivityKind::Imported,
+        ]
+    }
+}
+
+/// One line of the activity log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub time: DateTime<Utc>,
+    pub kind: ActivityKind,
+    /// The game's name when it happened, or the store for imports
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<GameId>,
+    /// Session length, for exits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutes: Option<u64>,
+    /// What an import changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ActivityEntry {
+    /// An entry about a game, happening now
+    pub fn game(kind: ActivityKind, id: GameId, name: &str, clock: &dyn Clock) -> Self {
+        Self {
+            time: clock.now(),
+            kind,
+            name: name.to_string(),
+            game_id: Some(id),
+            minutes: None,
+            detail: None,
+        }
+    }
+
+    /// An import from `source`, happening now
+    pub fn import(source: &str, detail: String, clock: &dyn Clock) -> Self {
+        Self {
+            time: clock.now(),
+            kind: ActivityKind::Imported,
+            name: source.to_string(),
+            game_id: None,
+            minutes: None,
+            detail: Some(detail),
+        }
+    }
+
+    /// A sentence describing the entry, for the activity view
+    pub fn describe(&self) -> String {
+        match self.kind {
+            ActivityKind::Launched => format!("Launched {}", self.name),
+            ActivityKind::Exited => match self.minutes {
+                Some(minutes) if minutes >= 60 => {
+                    format!("Played {} for {}h {}m", self.name, minutes / 60, minutes % 60)
+                }
+                Some(minutes) => format!("Played {} for {} min", self.name, minutes),
+                None => format!("Stopped {}", self.name),
+            },
+            ActivityKind::Added => format!("Added {}", self.name),
+            ActivityKind::Removed => format!("Removed {}", self.name),
+            ActivityKind::Imported => match &self.detail {
+                Some(detail) => format!("Imported from {}: {}", self.name, detail),
+                None => format!("Imported from {}", self.name),
+            },
+        }
+    }
+}
+
+/// Append entries to the log, rotating it once it grows past `max_bytes`
+///
+/// Only the previous log is kept when rotating, so history is bounded to
+/// about twice `max_bytes`.
+pub async fn append_activity(
+    path: &Path,
+    entries: &[ActivityEntry],
+    max_bytes: u64,
+) -> std::io::Result<()> {
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).map_err(std::io::Error::other)?);
+        lines.push('\n');
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let size = fs::metadata(path).await.map(|m| m.len()).unwrap_or(0);
+    if size > 0 && size + lines.len() as u64 > max_bytes {
+        fs::rename(path, rotated_path(path)).await?;
+    }
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path).await?;
+    file.write_all(lines.as_bytes()).await?;
+    file.flush().await
+}
+
+/// Up to `limit` of the most recent entries, oldest first
+///
+/// Lines that can't be read, like one cut short by a crash, are skipped.
+pub async fn read_activity(path: &Path, limit: usize) -> Vec<ActivityEntry> {
+    let mut entries = Vec::new();
+    for path in [rotated_path(path), path.to_path_buf()] {
+        let Ok(content) = fs::read_to_string(&path).await else {
+            continue;
+        };
+        entries.extend(content.lines().filter_map(|line| {
+            serde_json::from_str::<ActivityEntry>(line)
+                .inspect_err(|e| tracing::debug!("Skipping activity line in {:?}: {}", path, e))
+                .ok()
+        }));
+    }
+    let skip = entries.len().saturating_sub(limit);
+    entries.drain(..skip);
+    entries
+}
+
+/// Delete the log and its rotated copy
+///
+/// Used when the library gets encrypted, since the log keeps game names in
+/// plain text. Logs that aren't there are fine.
+pub async fn delete_activity(path: &Path) -> std::io::Result<()> {
+    for path in [path.to_path_buf(), rotated_path(path)] {
+        match fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Where the previous log goes when the current one is rotated
+fn rotated_path(path: &Path) -> PathBuf {
+    path.with_extension("1.jsonl")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::MockClock;
+
+    #[tokio::test]
+    async fn test_append_rotates_and_reads_back() {
+        let dir = std::env::temp_dir().join(format!("launcher-activity-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("activity.jsonl");
+        let id = GameId::new();
+        let clock = MockClock::fixed();
+
+        for name in ["Portal", "Portal 2", "Half-Life"] {
+            let entry = ActivityEntry::game(ActivityKind::Launched, id, name, &clock);
+            append_activity(&path, &[entry], 200).await.unwrap();
+        }
+        assert!(rotated_path(&path).exists());
+
+        let names = |entries: Vec<ActivityEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.name).collect()
+        };
+        // The oldest entry went with the first rotation
+        assert_eq!(names(read_activity(&path, 10).await), ["Portal 2", "Half-Life"]);
+        assert_eq!(names(read_activity(&path, 1).await), ["Half-Life"]);
+
+        // A torn line doesn't lose the rest
+        std::fs::write(&path, "{\"time\":\n").unwrap();
+        assert_eq!(names(read_activity(&path, 10).await), ["Portal 2"]);
+
+        delete_activity(&path).await.unwrap();
+        assert!(!path.exists() && !rotated_path(&path).exists());
+        delete_activity(&path).await.unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_describe() {
+        let clock = MockClock::fixed();
+        let mut entry =
+            ActivityEntry::game(ActivityKind::Exited, GameId::new(), "Portal", &clock);
+        entry.minutes = Some(95);
+        assert_eq!(entry.describe(), "Played Portal for 1h 35m");
+        entry.minutes = Some(5);
+        assert_eq!(entry.describe(), "Played Portal for 5 min");
+
+        let import = ActivityEntry::import("Steam", "3 added".to_string(), &clock);
+        assert_eq!(import.describe(), "Imported from Steam: 3 added");
+    }
+}
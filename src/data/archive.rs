@@ -0,0 +1,223 @@
+//! ZIP reading and writing for save backups, through the `zip` crate
+
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Pack every file under `dir` into a ZIP archive at `dest`
+///
+/// Returns how many files were packed. Empty folders are kept too. The
+/// archive is written next to `dest` and only moved there once complete.
+pub fn zip_dir(dir: &Path, dest: &Path) -> Result<usize, ArchiveError> {
+    let mut entries = Vec::new();
+    collect_entries(dir, dir, &mut entries)?;
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut part = dest.as_os_str().to_os_string();
+    part.push(".part");
+    let part = PathBuf::from(part);
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(File::create(&part)?);
+    let mut files = 0;
+    for (name, path) in &entries {
+        match path {
+            Some(path) => {
+                let mut file = File::open(path)?;
+                let large = file.metadata()?.len() >= u64::from(u32::MAX);
+                writer.start_file(name.as_str(), options.large_file(large))?;
+                std::io::copy(&mut file, &mut writer)?;
+                files += 1;
+            }
+            None => writer.add_directory(name.as_str(), options)?,
+        }
+    }
+    writer.finish()?;
+    std::fs::rename(&part, dest)?;
+    Ok(files)
+}
+
+/// Unpack a ZIP archive into `dir`, replacing files that already exist
+///
+/// Returns how many files were unpacked. Entries that would land outside
+/// `dir` are refused.
+pub fn unzip_to(archive: &Path, dir: &Path) -> Result<usize, ArchiveError> {
+    let mut zip = open_checked(archive, dir)?;
+    write_entries(&mut zip, dir)
+}
+
+/// Replace everything in `dir` with a ZIP archive's contents
+///
+/// Files the archive doesn't have are removed. The archive is read and
+/// checked before anything in `dir` is touched.
+pub fn replace_with_zip(archive: &Path, dir: &Path) -> Result<usize, ArchiveError> {
+    let mut zip = open_checked(archive, dir)?;
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                std::fs::remove_dir_all(&path)?;
+            } else {
+                std::fs::remove_file(&path)?;
+            }
+        }
+    }
+    write_entries(&mut zip, dir)
+}
+
+/// Open an archive to unpack under `dir`, reading every entry once first
+///
+/// Reading an entry to the end checks its CRC, so a bad archive is caught
+/// before anything is written.
+fn open_checked(archive: &Path, dir: &Path) -> Result<ZipArchive<File>, ArchiveError> {
+    let mut zip = ZipArchive::new(File::open(archive)?)?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        if safe_join(dir, entry.name()).is_none() {
+            return Err(ArchiveError::UnsafePath(entry.name().to_string()));
+        }
+        std::io::copy(&mut entry, &mut std::io::sink())?;
+    }
+    Ok(zip)
+}
+
+fn write_entries(zip: &mut ZipArchive<File>, dir: &Path) -> Result<usize, ArchiveError> {
+    let mut files = 0;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let target = safe_join(dir, entry.name())
+            .ok_or_else(|| ArchiveError::UnsafePath(entry.name().to_string()))?;
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut File::create(&target)?)?;
+        files += 1;
+    }
+    Ok(files)
+}
+
+/// Files (`Some`) and folders (`None`) under `dir`, named relative to `root`
+///
+/// Symlinks are left out, so a link to a folder elsewhere (or to the save
+/// folder itself) can't pull in files that aren't part of the saves.
+fn collect_entries(
+    root: &Path,
+    dir: &Path,
+    out: &mut Vec<(String, Option<PathBuf>)>,
+) -> Result<(), ArchiveError> {
+    let mut children: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    children.sort();
+    for path in children {
+        let relative = path.strip_prefix(root).expect("children are under the root");
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let file_type = std::fs::symlink_metadata(&path)?.file_type();
+        if file_type.is_dir() {
+            out.push((format!("{}/", name), None));
+            collect_entries(root, &path, out)?;
+        } else if file_type.is_file() {
+            out.push((name, Some(path)));
+        }
+    }
+    Ok(())
+}
+
+/// `dir` joined with an archive entry name, unless the name escapes it
+fn safe_join(dir: &Path, name: &str) -> Option<PathBuf> {
+    let relative = Path::new(name.trim_end_matches('/'));
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| dir.join(relative))
+}
+
+/// Errors that can occur while packing or unpacking an archive
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Archive is damaged: {0}")]
+    Corrupt(#[from] zip::result::ZipError),
+
+    #[error("Refusing to unpack {0:?} outside the save folder")]
+    UnsafePath(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zip_round_trip() {
+        let root = std::env::temp_dir().join(format!("launcher-archive-{}", uuid::Uuid::new_v4()));
+        let saves = root.join("saves");
+        std::fs::create_dir_all(saves.join("slot1")).unwrap();
+        std::fs::create_dir_all(saves.join("empty")).unwrap();
+        std::fs::write(saves.join("settings.ini"), "volume=7").unwrap();
+        std::fs::write(saves.join("slot1").join("save.dat"), vec![42u8; 10_000]).unwrap();
+
+        let archive = root.join("backup.zip");
+        assert_eq!(zip_dir(&saves, &archive).unwrap(), 2);
+
+        let restored = root.join("restored");
+        assert_eq!(unzip_to(&archive, &restored).unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(restored.join("settings.ini")).unwrap(), "volume=7");
+        assert_eq!(std::fs::read(restored.join("slot1").join("save.dat")).unwrap(), [42u8; 10_000]);
+        assert!(restored.join("empty").is_dir());
+
+        // A damaged archive is refused before anything is written
+        let mut data = std::fs::read(&archive).unwrap();
+        let name = b"settings.ini";
+        let at = data.windows(name.len()).position(|w| w == name).unwrap() + name.len();
+        data[at] ^= 0xff;
+        std::fs::write(&archive, data).unwrap();
+        assert!(unzip_to(&archive, &root.join("damaged")).is_err());
+        assert!(!root.join("damaged").exists());
+        assert!(replace_with_zip(&archive, &restored).is_err());
+        assert!(restored.join("settings.ini").is_file());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_left_out() {
+        let root = std::env::temp_dir().join(format!("launcher-archive-{}", uuid::Uuid::new_v4()));
+        let saves = root.join("saves");
+        std::fs::create_dir_all(&saves).unwrap();
+        std::fs::write(saves.join("save.dat"), "progress").unwrap();
+        std::os::unix::fs::symlink(&root, saves.join("outside")).unwrap();
+        std::os::unix::fs::symlink(saves.join("save.dat"), saves.join("link.dat")).unwrap();
+
+        let archive = root.join("backup.zip");
+        assert_eq!(zip_dir(&saves, &archive).unwrap(), 1);
+        let restored = root.join("restored");
+        assert_eq!(unzip_to(&archive, &restored).unwrap(), 1);
+        assert!(!restored.join("outside").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_safe_join_refuses_escapes() {
+        let dir = Path::new("saves");
+        assert_eq!(safe_join(dir, "slot1/save.dat"), Some(dir.join("slot1").join("save.dat")));
+        assert_eq!(safe_join(dir, "../outside.dat"), None);
+        assert_eq!(safe_join(dir, "/etc/passwd"), None);
+    }
+}
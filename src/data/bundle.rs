@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Category, Game};
+
+/// File inside a bundle folder listing its games
+pub const BUNDLE_MANIFEST: &str = "bundle.json";
+
+/// Folder inside a bundle holding the games' artwork
+pub const BUNDLE_ART_DIR: &str = "art";
+
+/// Bumped when the manifest changes in a way older launchers can't read
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Contents of a bundle's manifest
+///
+/// Art paths on the games are relative to the bundle folder.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub games: Vec<Game>,
+    /// Categories the games use, matched by name on import
+    pub categories: Vec<Category>,
+}
+
+/// Result of exporting games to a bundle
+#[derive(Debug, Clone, Default)]
+pub struct BundleExportReport {
+    pub games: usize,
+    /// Game names and art files that no longer existed and were left out
+    pub missing_art: Vec<(String, PathBuf)>,
+}
+
+/// A bundle read from disk with its art already copied, ready to add
+#[derive(Debug, Clone, Default)]
+pub struct BundleContents {
+    /// Games with fresh IDs, still using the bundle's category IDs
+    pub games: Vec<Game>,
+    pub categories: Vec<Category>,
+    /// Games skipped because the library already had them when read
+    pub duplicates: Vec<String>,
+}
+
+/// Result of importing a bundle into the library
+#[derive(Debug, Clone, Default)]
+pub struct BundleImportReport {
+    pub added: Vec<String>,
+    /// Games skipped because the library already has them
+    pub duplicates: Vec<String>,
+}
+
+/// The art slots of a game, with the kind used to name bundled files
+pub(super) fn art_slots(game: &mut Game) -> [(&'static str, &mut Option<PathBuf>); 3] {
+    [
+        ("icon", &mut game.icon_path),
+        ("banner", &mut game.banner_path),
+        ("hero", &mut game.hero_path),
+    ]
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::{Game, GameSource};
+use crate::message::SortOrder;
+
 /// Unique identifier for a category
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CategoryId(pub Uuid);
@@ -31,6 +34,14 @@ pub struct Category {
 
     /// Optional icon name
     pub icon: Option<String>,
+
+    /// Sort used while this category is the filter, instead of the library's
+    #[serde(default)]
+    pub default_sort: Option<SortOrder>,
+
+    /// Pulls in every game matching it; membership can't be edited by hand
+    #[serde(default)]
+    pub rule: Option<SmartRule>,
 }
 
 impl Category {
@@ -41,6 +52,8 @@ impl Category {
             name,
             color: None,
             icon: None,
+            default_sort: None,
+            rule: None,
         }
     }
 
@@ -51,19 +64,97 @@ impl Category {
             name,
             color: Some(color),
             icon: None,
+            default_sort: None,
+            rule: None,
+        }
+    }
+
+    /// Set the icon, by its name in the built-in icon set
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// Letter shown when the category has no icon
+    pub fn initial(&self) -> String {
+        self.name
+            .trim()
+            .chars()
+            .next()
+            .map(|c| c.to_uppercase().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Conditions a game must all meet to be in a smart category
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SmartRule {
+    pub conditions: Vec<RuleCondition>,
+}
+
+impl SmartRule {
+    /// Whether a game meets every condition
+    ///
+    /// A rule without conditions matches nothing, so a category doesn't
+    /// swallow the whole library while its rule is being set up.
+    pub fn matches(&self, game: &Game) -> bool {
+        !self.conditions.is_empty() && self.conditions.iter().all(|c| c.matches(game))
+    }
+}
+
+/// One condition of a [`SmartRule`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// Imported from this source
+    Source(GameSource),
+    /// Played for at most this many minutes; 0 is never played
+    PlaytimeAtMost(u64),
+    /// Played for at least this many minutes
+    PlaytimeAtLeast(u64),
+    /// Whether there is something to launch
+    Installed(bool),
+    Favorite(bool),
+    /// Assigned to another category by hand
+    Tag(CategoryId),
+}
+
+impl RuleCondition {
+    pub fn matches(&self, game: &Game) -> bool {
+        match *self {
+            RuleCondition::Source(source) => game.source == source,
+            RuleCondition::PlaytimeAtMost(minutes) => game.playtime_minutes <= minutes,
+            RuleCondition::PlaytimeAtLeast(minutes) => game.playtime_minutes >= minutes,
+            RuleCondition::Installed(installed) => game.is_installed() == installed,
+            RuleCondition::Favorite(favorite) => game.favorite == favorite,
+            RuleCondition::Tag(id) => game.has_category(&id),
         }
     }
 }
 
+/// What to do when adding a category whose name is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CategoryCollision {
+    /// Keep the existing category untouched and drop the new one
+    Reject,
+    /// Keep the existing category but take the new one's color and icon
+    Merge,
+}
+
+/// Whether two category names are the same, ignoring case and outer spaces
+pub fn same_category_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
 /// Default categories provided with a fresh library
 pub fn default_categories() -> Vec<Category> {
     vec![
-        Category::with_color("Action".to_string(), "#E74C3C".to_string()),
-        Category::with_color("RPG".to_string(), "#9B59B6".to_string()),
-        Category::with_color("Strategy".to_string(), "#3498DB".to_string()),
-        Category::with_color("Puzzle".to_string(), "#2ECC71".to_string()),
-        Category::with_color("Simulation".to_string(), "#F39C12".to_string()),
-        Category::with_color("Sports".to_string(), "#1ABC9C".to_string()),
-        Category::with_color("Indie".to_string(), "#E91E63".to_string()),
+        Category::with_color("Action".to_string(), "#E74C3C".to_string()).with_icon("sword"),
+        Category::with_color("RPG".to_string(), "#9B59B6".to_string()).with_icon("dragon"),
+        Category::with_color("Strategy".to_string(), "#3498DB".to_string()).with_icon("chess"),
+        Category::with_color("Puzzle".to_string(), "#2ECC71".to_string()).with_icon("puzzle"),
+        Category::with_color("Simulation".to_string(), "#F39C12".to_string()).with_icon("gear"),
+        Category::with_color("Sports".to_string(), "#1ABC9C".to_string()).with_icon("ball"),
+        Category::with_color("Indie".to_string(), "#E91E63".to_string()).with_icon("heart"),
     ]
 }
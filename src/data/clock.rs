@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+
+/// Where the current time comes from
+///
+/// Anything that stamps or compares against "now" (added dates, last played,
+/// sync throttling, backups) asks a clock, so tests can pin the time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system's wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to
+#[cfg(test)]
+#[derive(Debug)]
+pub struct MockClock(std::sync::Mutex<DateTime<Utc>>);
+
+#[cfg(test)]
+impl MockClock {
+    pub fn at(now: DateTime<Utc>) -> Self {
+        Self(std::sync::Mutex::new(now))
+    }
+
+    /// A fixed, arbitrary moment, for tests that only need time to stand still
+    pub fn fixed() -> Self {
+        Self::at(DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp"))
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_only_moves_when_advanced() {
+        let clock = MockClock::fixed();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+
+        clock.advance(chrono::Duration::minutes(5));
+        assert_eq!(clock.now() - start, chrono::Duration::minutes(5));
+    }
+}
@@ -1,13 +1,16 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
 
+use crate::constants::IMPORT_WARN_THRESHOLD;
+use crate::data::{write_file, CategoryId, GameSource, Library};
 use crate::message::{SortOrder, ViewMode};
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// Name of the current theme
     pub theme: String,
@@ -27,14 +30,139 @@ pub struct Config {
     /// Size of game cards in grid view (small, medium, large)
     pub card_size: CardSize,
 
+    /// How game cards are drawn in grid view
+    pub card_style: CardStyle,
+
+    /// How tightly the library UI is laid out
+    pub ui_density: Density,
+
+    /// Multiplier for the whole interface's size, from 0.8 to 2.0; text,
+    /// spacing and cards all scale so they keep their proportions
+    pub font_scale: f32,
+
+    /// Width of the library sidebar, in logical pixels
+    pub sidebar_width: f32,
+
+    /// Whether the sidebar is folded down to an icon rail
+    pub sidebar_collapsed: bool,
+
+    /// Window width, in logical pixels, below which the sidebar folds into a
+    /// menu in the header; `None` keeps it beside the library at any width
+    pub narrow_layout_width: Option<u32>,
+
     /// Whether to show game sources in the library
     pub show_sources: bool,
 
+    /// Sources whose badge stays hidden even when sources are shown
+    pub hidden_sources: Vec<GameSource>,
+
+    /// How many days newly added games get a "New" badge; `None` turns the
+    /// badges off
+    pub new_badge_days: Option<u32>,
+
+    /// When the user last cleared the "New" badges
+    pub new_badges_cleared_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Whether card art is cropped to fill its box instead of letterboxed
+    pub crop_card_art: bool,
+
     /// Paths to additional Steam library folders (for manual configuration)
     pub steam_library_paths: Vec<PathBuf>,
 
     /// Last import sync time for each source
     pub last_sync: LastSyncTimes,
+
+    /// What each store contributes to an import
+    pub source_import: SourceImportSettings,
+
+    /// How often installed stores are re-scanned in the background while the
+    /// launcher is open, in minutes; `None` turns background scans off
+    pub auto_scan_interval_minutes: Option<u32>,
+
+    /// What happens to the launcher window after a game starts
+    pub on_launch: LaunchWindowBehavior,
+
+    /// How long Play is ignored for a game after launching it, in milliseconds,
+    /// so a double click doesn't start two copies
+    pub launch_cooldown_ms: u64,
+
+    /// Whether to bring the launcher window back once the last running game exits
+    #[serde(alias = "restore_on_exit")]
+    pub restore_window_on_game_exit: bool,
+
+    /// Whether a small always-on-top window shows running games while they play
+    pub show_now_playing_window: bool,
+
+    /// Whether Steam games start in Big Picture, Steam's controller-friendly mode
+    pub prefer_big_picture: bool,
+
+    /// Whether launching a game that isn't installed asks before installing it
+    pub confirm_install_on_launch: bool,
+
+    /// Whether an executable path that is a folder is searched for the game's binary
+    ///
+    /// Off by default: the search is a guess, and a wrong one runs some other
+    /// program in the folder.
+    pub resolve_executable_folders: bool,
+
+    /// Emulator command templates by ROM extension (lowercase, no dot), for ROM imports
+    pub emulators: BTreeMap<String, String>,
+
+    /// API key for SteamGridDB artwork lookups
+    pub steamgriddb_api_key: Option<String>,
+
+    /// Whether to poll and show CPU/RAM usage of running games
+    pub show_resource_usage: bool,
+
+    /// Whether imports link the same game from different stores into one entry
+    pub merge_cross_store: bool,
+
+    /// Category games added by hand (the Add Game form or a CSV file) start in
+    pub default_manual_category: Option<CategoryId>,
+
+    /// Whether Steam imports turn the user's Steam collections into categories
+    pub import_steam_collections: bool,
+
+    /// Whether the library file is encrypted with a passphrase
+    pub encrypt_library: bool,
+
+    /// Whether games run inside a terminal window unless they say otherwise
+    pub launch_in_terminal: bool,
+
+    /// Whether game output is written to a log file in the data dir, for
+    /// troubleshooting games that crash on start
+    pub capture_game_output: bool,
+
+    /// Whether launches and exits are written to a status file in the data
+    /// dir, for overlays and other tools to poll
+    pub write_status_file: bool,
+
+    /// Whether the running game is shown as Discord Rich Presence
+    pub discord_rpc_enabled: bool,
+
+    /// Discord application the presence is shown as
+    pub discord_client_id: Option<String>,
+
+    /// Folder relative executable paths are resolved against (portable
+    /// setups); defaults to the launcher's own folder
+    pub portable_base_dir: Option<PathBuf>,
+
+    /// Whether the sidebar leaves out categories with no games
+    pub hide_empty_categories: bool,
+
+    /// Argon2 hash of the PIN required to launch games flagged for confirmation
+    pub launch_pin: Option<String>,
+
+    /// Folders and files importers skip when looking for executables (globs
+    /// like `*Redist*`, or plain substrings)
+    pub import_exclude_patterns: Vec<String>,
+
+    /// An import that would add more games than this stops and asks first
+    pub import_warn_threshold: usize,
+
+    /// Set once the first library has been created, so the default
+    /// categories are seeded on the very first run and never again
+    pub first_run_complete: bool,
 }
 
 impl Default for Config {
@@ -46,31 +174,87 @@ impl Default for Config {
             default_sort: SortOrder::NameAsc,
             default_view_mode: ViewMode::Grid,
             card_size: CardSize::Medium,
+            card_style: CardStyle::Classic,
+            ui_density: Density::Comfortable,
+            font_scale: 1.0,
+            sidebar_width: 200.0,
+            sidebar_collapsed: false,
+            narrow_layout_width: Some(800),
             show_sources: true,
+            hidden_sources: Vec::new(),
+            new_badge_days: Some(7),
+            new_badges_cleared_at: None,
+            crop_card_art: false,
             steam_library_paths: Vec::new(),
             last_sync: LastSyncTimes::default(),
+            source_import: SourceImportSettings::default(),
+            auto_scan_interval_minutes: None,
+            on_launch: LaunchWindowBehavior::DoNothing,
+            launch_cooldown_ms: 3000,
+            restore_window_on_game_exit: false,
+            show_now_playing_window: false,
+            prefer_big_picture: false,
+            confirm_install_on_launch: true,
+            resolve_executable_folders: false,
+            emulators: BTreeMap::new(),
+            steamgriddb_api_key: None,
+            show_resource_usage: false,
+            merge_cross_store: false,
+            default_manual_category: None,
+            import_steam_collections: false,
+            encrypt_library: false,
+            launch_in_terminal: false,
+            capture_game_output: false,
+            write_status_file: false,
+            discord_rpc_enabled: false,
+            discord_client_id: None,
+            portable_base_dir: None,
+            hide_empty_categories: true,
+            launch_pin: None,
+            import_exclude_patterns: vec![
+                "*Redist*".to_string(),
+                "*DirectX*".to_string(),
+                "*vcredist*".to_string(),
+            ],
+            import_warn_threshold: IMPORT_WARN_THRESHOLD,
+            first_run_complete: false,
         }
     }
 }
 
 impl Config {
+    /// Whether games from `source` are labeled with it
+    pub fn shows_source(&self, source: GameSource) -> bool {
+        self.show_sources && !self.hidden_sources.contains(&source)
+    }
+
+    /// Games added after this get a "New" badge; `None` while badges are off
+    pub fn new_badge_cutoff(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        let window_start = now - chrono::Duration::days(self.new_badge_days?.into());
+        Some(self.new_badges_cleared_at.map_or(window_start, |cleared| cleared.max(window_start)))
+    }
+
+    /// Drop the default manual category if it was deleted, returning whether
+    /// it was
+    pub fn forget_missing_manual_category(&mut self, library: &Library) -> bool {
+        let missing = self
+            .default_manual_category
+            .is_some_and(|id| library.get_category(&id).is_none());
+        if missing {
+            self.default_manual_category = None;
+        }
+        missing
+    }
+
     /// Save config to a JSON file
     pub async fn save_to_file(&self, path: &Path) -> Result<(), ConfigError> {
         let json = serde_json::to_string_pretty(self)
             .map_err(|e| ConfigError::Serialization(e.to_string()))?;
 
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .await
-                .map_err(|e| ConfigError::Io(e.to_string()))?;
-        }
-
-        let mut file = fs::File::create(path)
-            .await
-            .map_err(|e| ConfigError::Io(e.to_string()))?;
-
-        file.write_all(json.as_bytes())
+        write_file(path, json.as_bytes())
             .await
             .map_err(|e| ConfigError::Io(e.to_string()))?;
 
@@ -130,12 +314,224 @@ impl CardSize {
     }
 }
 
+/// Look of game cards in grid view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CardStyle {
+    /// A row with the art, name and buttons side by side
+    #[default]
+    Classic,
+    /// Cover art filling the card, the name overlaid and buttons shown on hover
+    Cover,
+}
+
+impl CardStyle {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CardStyle::Classic => "Classic",
+            CardStyle::Cover => "Cover",
+        }
+    }
+
+    pub fn all() -> &'static [CardStyle] {
+        &[CardStyle::Classic, CardStyle::Cover]
+    }
+}
+
+/// How much padding and text size the library UI uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Density {
+    Compact,
+    #[default]
+    Comfortable,
+    Spacious,
+}
+
+/// Layout sizes for a [`Density`], in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DensityMetrics {
+    /// Padding inside bars, cards and rows
+    pub padding: f32,
+    /// Gap between neighbouring items
+    pub spacing: f32,
+    pub text_size: f32,
+    /// Secondary text like sources and counts
+    pub small_text_size: f32,
+    pub title_size: f32,
+}
+
+impl Density {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Density::Compact => "Compact",
+            Density::Comfortable => "Comfortable",
+            Density::Spacious => "Spacious",
+        }
+    }
+
+    pub fn all() -> &'static [Density] {
+        &[Density::Compact, Density::Comfortable, Density::Spacious]
+    }
+
+    /// Compact still leaves buttons at least ~28px tall, so they stay easy
+    /// to hit on touch screens
+    pub fn metrics(&self) -> DensityMetrics {
+        match self {
+            Density::Compact => DensityMetrics {
+                padding: 6.0,
+                spacing: 5.0,
+                text_size: 14.0,
+                small_text_size: 11.0,
+                title_size: 20.0,
+            },
+            Density::Comfortable => DensityMetrics {
+                padding: 15.0,
+                spacing: 10.0,
+                text_size: 16.0,
+                small_text_size: 12.0,
+                title_size: 24.0,
+            },
+            Density::Spacious => DensityMetrics {
+                padding: 22.0,
+                spacing: 16.0,
+                text_size: 18.0,
+                small_text_size: 14.0,
+                title_size: 28.0,
+            },
+        }
+    }
+}
+
+/// Launcher window behavior after a game is launched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LaunchWindowBehavior {
+    #[default]
+    DoNothing,
+    Minimize,
+    HideToTray,
+    Close,
+}
+
+impl LaunchWindowBehavior {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LaunchWindowBehavior::DoNothing => "Do nothing",
+            LaunchWindowBehavior::Minimize => "Minimize",
+            LaunchWindowBehavior::HideToTray => "Hide to tray",
+            LaunchWindowBehavior::Close => "Close launcher",
+        }
+    }
+
+    pub fn all() -> &'static [LaunchWindowBehavior] {
+        &[
+            LaunchWindowBehavior::DoNothing,
+            LaunchWindowBehavior::Minimize,
+            LaunchWindowBehavior::HideToTray,
+            LaunchWindowBehavior::Close,
+        ]
+    }
+}
+
 /// Timestamps for last sync with each game source
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LastSyncTimes {
     pub steam: Option<chrono::DateTime<chrono::Utc>>,
     pub epic: Option<chrono::DateTime<chrono::Utc>>,
     pub gog: Option<chrono::DateTime<chrono::Utc>>,
+    pub ubisoft: Option<chrono::DateTime<chrono::Utc>>,
+    pub xbox: Option<chrono::DateTime<chrono::Utc>>,
+    pub itch: Option<chrono::DateTime<chrono::Utc>>,
+    pub steam_shortcut: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl LastSyncTimes {
+    pub fn get(&self, source: GameSource) -> Option<chrono::DateTime<chrono::Utc>> {
+        match source {
+            GameSource::Steam => self.steam,
+            GameSource::Epic => self.epic,
+            GameSource::GOG => self.gog,
+            GameSource::Ubisoft => self.ubisoft,
+            GameSource::Xbox => self.xbox,
+            GameSource::Itch => self.itch,
+            GameSource::SteamShortcut => self.steam_shortcut,
+            GameSource::Manual | GameSource::Emulator => None,
+        }
+    }
+
+    /// Record a sync; manual and emulated games have no store to sync with
+    pub fn set(&mut self, source: GameSource, time: chrono::DateTime<chrono::Utc>) {
+        match source {
+            GameSource::Steam => self.steam = Some(time),
+            GameSource::Epic => self.epic = Some(time),
+            GameSource::GOG => self.gog = Some(time),
+            GameSource::Ubisoft => self.ubisoft = Some(time),
+            GameSource::Xbox => self.xbox = Some(time),
+            GameSource::Itch => self.itch = Some(time),
+            GameSource::SteamShortcut => self.steam_shortcut = Some(time),
+            GameSource::Manual | GameSource::Emulator => {}
+        }
+    }
+
+    /// Whether `source` hasn't been synced within `max_age` of `now`
+    pub fn is_stale(
+        &self,
+        source: GameSource,
+        max_age: chrono::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        self.get(source).is_none_or(|synced| now - synced >= max_age)
+    }
+}
+
+/// Import options for one store
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceImportOptions {
+    /// Whether games the store lists but that aren't on disk are imported,
+    /// to be installed from the launcher later
+    pub include_not_installed: bool,
+}
+
+/// Import options for the stores that can list games that aren't installed;
+/// everything starts installed-only
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceImportSettings {
+    pub steam: SourceImportOptions,
+    pub ubisoft: SourceImportOptions,
+}
+
+impl SourceImportSettings {
+    pub fn get(&self, source: GameSource) -> SourceImportOptions {
+        match source {
+            GameSource::Steam => self.steam,
+            GameSource::Ubisoft => self.ubisoft,
+            GameSource::Epic
+            | GameSource::GOG
+            | GameSource::Xbox
+            | GameSource::Itch
+            | GameSource::SteamShortcut
+            | GameSource::Manual
+            | GameSource::Emulator => SourceImportOptions::default(),
+        }
+    }
+
+    /// Options to change; Epic and GOG only leave manifests for installed
+    /// games, and Xbox packages, itch games, shortcuts, manual and emulated
+    /// games have no list of uninstalled games to import
+    pub fn get_mut(&mut self, source: GameSource) -> Option<&mut SourceImportOptions> {
+        match source {
+            GameSource::Steam => Some(&mut self.steam),
+            GameSource::Ubisoft => Some(&mut self.ubisoft),
+            GameSource::Epic
+            | GameSource::GOG
+            | GameSource::Xbox
+            | GameSource::Itch
+            | GameSource::SteamShortcut
+            | GameSource::Manual
+            | GameSource::Emulator => None,
+        }
+    }
 }
 
 /// Errors that can occur with config operations
@@ -150,3 +546,71 @@ pub enum ConfigError {
     #[error("Deserialization error: {0}")]
     Deserialization(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shows_source() {
+        let mut config = Config {
+            hidden_sources: vec![GameSource::Manual],
+            ..Config::default()
+        };
+        assert!(config.shows_source(GameSource::Steam));
+        assert!(!config.shows_source(GameSource::Manual));
+
+        config.show_sources = false;
+        assert!(!config.shows_source(GameSource::Steam));
+    }
+
+    #[test]
+    fn test_forget_missing_manual_category() {
+        let library = Library::new();
+        let existing = library.all_categories()[0].id;
+        let mut config = Config {
+            default_manual_category: Some(existing),
+            ..Config::default()
+        };
+        assert!(!config.forget_missing_manual_category(&library));
+        assert_eq!(config.default_manual_category, Some(existing));
+
+        config.default_manual_category = Some(CategoryId::new());
+        assert!(config.forget_missing_manual_category(&library));
+        assert_eq!(config.default_manual_category, None);
+    }
+
+    #[test]
+    fn test_new_badge_cutoff() {
+        let now = chrono::Utc::now();
+        let mut config = Config::default();
+        assert_eq!(config.new_badge_cutoff(now), Some(now - chrono::Duration::days(7)));
+
+        // Clearing moves the cutoff up, but an old clear doesn't widen the window
+        config.new_badges_cleared_at = Some(now - chrono::Duration::hours(1));
+        assert_eq!(config.new_badge_cutoff(now), config.new_badges_cleared_at);
+        config.new_badges_cleared_at = Some(now - chrono::Duration::days(30));
+        assert_eq!(config.new_badge_cutoff(now), Some(now - chrono::Duration::days(7)));
+
+        config.new_badge_days = None;
+        assert_eq!(config.new_badge_cutoff(now), None);
+    }
+
+    #[test]
+    fn test_last_sync_staleness() {
+        let now = chrono::Utc::now();
+        let hour = chrono::Duration::hours(1);
+        let mut times = LastSyncTimes::default();
+        assert!(times.is_stale(GameSource::Steam, hour, now));
+
+        times.set(GameSource::Steam, now - chrono::Duration::minutes(10));
+        assert!(!times.is_stale(GameSource::Steam, hour, now));
+        assert!(times.is_stale(GameSource::Steam, hour, now + hour));
+        assert!(times.is_stale(GameSource::Ubisoft, hour, now));
+
+        // Configs from before Ubisoft was tracked still load
+        let json = r#"{"steam":null,"epic":null,"gog":null}"#;
+        let times: LastSyncTimes = serde_json::from_str(json).unwrap();
+        assert!(times.ubisoft.is_none());
+    }
+}
@@ -0,0 +1,122 @@
+//! Passphrase-based encryption for the library file, and PIN hashing
+//!
+//! Encrypted files are laid out as `MAGIC | salt | nonce | ciphertext`. The
+//! key is derived from the passphrase with Argon2 and a fresh salt on every
+//! save, and the contents are sealed with AES-256-GCM.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use super::library::LibraryError;
+
+/// Header marking an encrypted library file
+pub const MAGIC: &[u8] = b"618ENC\x01";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Whether the bytes look like an encrypted library file
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, LibraryError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| LibraryError::Encryption(e.to_string()))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt data produced by [`encrypt`]
+///
+/// A failed authentication check means the passphrase is wrong (or the file
+/// was tampered with), which is reported as [`LibraryError::WrongPassphrase`].
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, LibraryError> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| LibraryError::Encryption("Not an encrypted library".to_string()))?;
+    if body.len() < SALT_LEN + NONCE_LEN {
+        return Err(LibraryError::Encryption("Encrypted library is truncated".to_string()));
+    }
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| LibraryError::WrongPassphrase)
+}
+
+/// Hash a launch PIN for storage in the config
+pub fn hash_pin(pin: &str) -> Result<String, LibraryError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| LibraryError::Encryption(e.to_string()))
+}
+
+/// Check a PIN against a hash from [`hash_pin`]
+pub fn verify_pin(pin: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|hash| Argon2::default().verify_password(pin.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>, LibraryError> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| LibraryError::Encryption(e.to_string()))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = encrypt(b"{\"games\":{}}", "hunter2").unwrap();
+        assert!(is_encrypted(&data));
+        assert_eq!(decrypt(&data, "hunter2").unwrap(), b"{\"games\":{}}");
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let data = encrypt(b"secret", "hunter2").unwrap();
+        assert!(matches!(
+            decrypt(&data, "hunter3"),
+            Err(LibraryError::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn test_pin_hash() {
+        let hash = hash_pin("1234").unwrap();
+        assert!(!hash.contains("1234"));
+        assert!(verify_pin("1234", &hash));
+        assert!(!verify_pin("4321", &hash));
+        assert!(!verify_pin("1234", "not a hash"));
+    }
+
+    #[test]
+    fn test_plaintext_is_not_encrypted() {
+        assert!(!is_encrypted(b"{\"games\":{}}"));
+    }
+}
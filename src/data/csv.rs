@@ -0,0 +1,155 @@
+use super::Game;
+
+/// Split CSV text into records of fields
+///
+/// Handles quoted fields with embedded commas, newlines and doubled quotes,
+/// and both `\n` and `\r\n` line endings. Blank lines are skipped. Each record
+/// carries the 1-based line it starts on, for error reporting.
+pub fn parse_csv(content: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, record_line, std::mem::take(&mut record));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        push_record(&mut records, record_line, record);
+    }
+
+    records
+}
+
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, line: usize, record: Vec<String>) {
+    let record: Vec<String> = record.into_iter().map(|f| f.trim().to_string()).collect();
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push((line, record));
+    }
+}
+
+/// Outcome of importing one CSV row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvRowOutcome {
+    Added,
+    /// The executable already backs a library entry
+    Duplicate,
+    /// The row was skipped, with the reason
+    Invalid(String),
+}
+
+/// Result for a single data row of an imported CSV file
+#[derive(Debug, Clone)]
+pub struct CsvRowResult {
+    /// Line the row starts on in the file
+    pub line: usize,
+    pub name: String,
+    pub outcome: CsvRowOutcome,
+}
+
+/// A CSV file read and checked against the disk, ready to add
+#[derive(Debug, Clone, Default)]
+pub struct CsvContents {
+    pub rows: Vec<CsvRow>,
+}
+
+/// A data row of a CSV file, before it meets the library
+#[derive(Debug, Clone)]
+pub struct CsvRow {
+    /// Line the row starts on in the file
+    pub line: usize,
+    pub name: String,
+    /// The game to add and its category names, or why the row is skipped
+    pub game: Result<(Game, Vec<String>), String>,
+}
+
+/// Per-row results of a CSV import
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportReport {
+    pub rows: Vec<CsvRowResult>,
+}
+
+impl CsvImportReport {
+    /// Number of rows that became new games
+    pub fn added(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.outcome == CsvRowOutcome::Added)
+            .count()
+    }
+
+    /// Rows that were not imported, duplicates included
+    pub fn skipped(&self) -> impl Iterator<Item = &CsvRowResult> {
+        self.rows.iter().filter(|row| row.outcome != CsvRowOutcome::Added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(content: &str) -> Vec<Vec<String>> {
+        parse_csv(content).into_iter().map(|(_, record)| record).collect()
+    }
+
+    #[test]
+    fn test_plain_and_quoted_fields() {
+        let records = fields("name,path\r\nDoom,C:\\Games\\doom.exe\n\"Portal, 2\",\"say \"\"hi\"\"\"\n");
+        assert_eq!(
+            records,
+            vec![
+                vec!["name", "path"],
+                vec!["Doom", "C:\\Games\\doom.exe"],
+                vec!["Portal, 2", "say \"hi\""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiline_quotes_and_line_numbers() {
+        let records = parse_csv("\u{feff}a,\"multi\nline\"\n\n  \nb,c");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], (1, vec!["a".to_string(), "multi\nline".to_string()]));
+        assert_eq!(records[1], (5, vec!["b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn test_trailing_empty_field() {
+        assert_eq!(fields("Doom,doom.exe,\n"), vec![vec!["Doom", "doom.exe", ""]]);
+    }
+}
@@ -0,0 +1,295 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{normalize_path, same_path};
+use crate::constants::APP_NAME;
+
+/// File written and removed to check a directory accepts writes
+const PROBE_FILE: &str = ".write-probe";
+
+/// Where the launcher keeps its files, and whether that is where it wanted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataDir {
+    pub path: PathBuf,
+    pub status: DataDirStatus,
+}
+
+/// How the data directory was chosen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataDirStatus {
+    /// The preferred directory is writable
+    Writable,
+    /// The preferred directory wasn't writable, so a fallback is used
+    Fallback { preferred: PathBuf, reason: String },
+    /// Nothing was writable; the preferred directory is used and saves will fail
+    ReadOnly { reason: String },
+}
+
+impl DataDir {
+    /// Warning to show the user, if the data directory isn't the usual one
+    pub fn warning(&self) -> Option<String> {
+        match &self.status {
+            DataDirStatus::Writable => None,
+            DataDirStatus::Fallback { preferred, reason } => Some(format!(
+                "Can't write to {} ({}), saving to {} instead",
+                preferred.display(),
+                reason,
+                self.path.display()
+            )),
+            DataDirStatus::ReadOnly { reason } => Some(format!(
+                "Changes won't be saved: {} isn't writable ({})",
+                self.path.display(),
+                reason
+            )),
+        }
+    }
+
+    /// Whether saves are expected to fail
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.status, DataDirStatus::ReadOnly { .. })
+    }
+}
+
+/// Data directories the user has opened, most recent first
+///
+/// Kept in a file outside every data dir, so each library can be switched
+/// back to from the others.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentDataDirs {
+    pub dirs: Vec<PathBuf>,
+}
+
+impl RecentDataDirs {
+    /// Move `dir` to the front, forgetting the oldest beyond `limit`
+    pub fn touch(&mut self, dir: &Path, limit: usize) {
+        self.dirs.retain(|known| !same_path(known, dir));
+        self.dirs.insert(0, normalize_path(dir));
+        self.dirs.truncate(limit);
+    }
+
+    pub fn remove(&mut self, dir: &Path) {
+        self.dirs.retain(|known| !same_path(known, dir));
+    }
+
+    /// The data dir that was open last
+    pub fn last(&self) -> Option<&Path> {
+        self.dirs.first().map(PathBuf::as_path)
+    }
+
+    /// Read the list, starting empty if the file is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+}
+
+/// Create `dir` if needed and check a file can be written inside it
+pub fn probe_writable(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(PROBE_FILE);
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+/// Last-resort data directory in the shared temp dir, one per user
+pub fn temp_data_dir() -> PathBuf {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("{}-{}", APP_NAME, user))
+}
+
+/// `probe_writable` for a directory somewhere other users can write
+///
+/// On Unix the directory is created readable by its owner only, and one
+/// someone else got to first is refused: whoever owns it could read the
+/// library or swap its files.
+pub fn probe_private(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+        match fs::DirBuilder::new().mode(0o700).create(dir) {
+            Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+            _ => {}
+        }
+        // Not followed, so a planted link can't point somewhere else
+        let meta = fs::symlink_metadata(dir)?;
+        if !meta.is_dir() || meta.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "directory is open to other users",
+            ));
+        }
+        let probe = dir.join(PROBE_FILE);
+        fs::write(&probe, b"")?;
+        let owner = fs::metadata(&probe).map(|probe| probe.uid());
+        fs::remove_file(&probe)?;
+        if owner? != meta.uid() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "directory belongs to another user",
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        probe_writable(dir)
+    }
+}
+
+/// Pick the first writable directory out of `preferred` and `fallbacks`
+///
+/// `check` is given each candidate in order; the error from the preferred
+/// directory is kept so the warning explains why it was skipped.
+pub fn choose_data_dir(
+    preferred: PathBuf,
+    fallbacks: impl IntoIterator<Item = PathBuf>,
+    check: impl Fn(&Path) -> io::Result<()>,
+) -> DataDir {
+    let reason = match check(&preferred) {
+        Ok(()) => {
+            return DataDir {
+                path: preferred,
+                status: DataDirStatus::Writable,
+            }
+        }
+        Err(e) => e.to_string(),
+    };
+
+    for fallback in fallbacks {
+        match check(&fallback) {
+            Ok(()) => {
+                return DataDir {
+                    path: fallback,
+                    status: DataDirStatus::Fallback { preferred, reason },
+                }
+            }
+            Err(e) => tracing::warn!("Fallback data directory {:?} not writable: {}", fallback, e),
+        }
+    }
+
+    DataDir {
+        path: preferred,
+        status: DataDirStatus::ReadOnly { reason },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_against(writable: &'static [&'static str]) -> impl Fn(&Path) -> io::Result<()> {
+        move |path| {
+            if writable.iter().any(|w| path == Path::new(w)) {
+                Ok(())
+            } else {
+                Err(io::Error::new(io::ErrorKind::PermissionDenied, "read-only"))
+            }
+        }
+    }
+
+    #[test]
+    fn test_choose_data_dir() {
+        let fallbacks = || vec![PathBuf::from("/custom"), PathBuf::from("/tmp/launcher")];
+
+        let dir = choose_data_dir(PathBuf::from("/data"), fallbacks(), check_against(&["/data"]));
+        assert_eq!(dir.path, PathBuf::from("/data"));
+        assert_eq!(dir.status, DataDirStatus::Writable);
+        assert!(dir.warning().is_none());
+
+        // The first writable fallback wins
+        let dir = choose_data_dir(
+            PathBuf::from("/data"),
+            fallbacks(),
+            check_against(&["/tmp/launcher", "/custom"]),
+        );
+        assert_eq!(dir.path, PathBuf::from("/custom"));
+        assert!(matches!(
+            &dir.status,
+            DataDirStatus::Fallback { preferred, .. } if preferred == Path::new("/data")
+        ));
+        assert!(dir.warning().unwrap().contains("saving to /custom"));
+
+        let dir = choose_data_dir(PathBuf::from("/data"), fallbacks(), check_against(&[]));
+        assert_eq!(dir.path, PathBuf::from("/data"));
+        assert!(dir.is_read_only());
+        assert!(dir.warning().unwrap().starts_with("Changes won't be saved"));
+    }
+
+    #[test]
+    fn test_recent_data_dirs() {
+        let mut recent = RecentDataDirs::default();
+        for dir in ["/libraries/work", "/libraries/home", "/libraries/lan"] {
+            recent.touch(Path::new(dir), 2);
+        }
+        assert_eq!(
+            recent.dirs,
+            [PathBuf::from("/libraries/lan"), PathBuf::from("/libraries/home")]
+        );
+
+        // Opening a known dir again moves it up instead of listing it twice
+        recent.touch(Path::new("/libraries/home/"), 2);
+        assert_eq!(recent.last(), Some(Path::new("/libraries/home")));
+        assert_eq!(recent.dirs.len(), 2);
+
+        recent.remove(Path::new("/libraries/lan"));
+        assert_eq!(recent.dirs, [PathBuf::from("/libraries/home")]);
+
+        let dir = std::env::temp_dir().join(format!("launcher-recent-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("recent_libraries.json");
+        assert_eq!(RecentDataDirs::load(&path), RecentDataDirs::default());
+        recent.save(&path).unwrap();
+        assert_eq!(RecentDataDirs::load(&path), recent);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_probe_writable() {
+        let dir = std::env::temp_dir().join(format!("launcher-probe-{}", uuid::Uuid::new_v4()));
+        probe_writable(&dir.join("nested")).unwrap();
+        assert!(!dir.join("nested").join(PROBE_FILE).exists());
+
+        // A directory can't be created underneath a file
+        let file = dir.join("file");
+        fs::write(&file, b"").unwrap();
+        assert!(probe_writable(&file.join("data")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("launcher-private-{}", uuid::Uuid::new_v4()));
+        probe_private(&dir).unwrap();
+        assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        // Already there and private is fine
+        probe_private(&dir).unwrap();
+
+        // One anybody can get into is refused
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(probe_private(&dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
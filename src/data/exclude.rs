@@ -0,0 +1,112 @@
+use std::path::Path;
+
+/// User-configured patterns for folders and files the executable search
+/// should skip, both in imports and at launch
+///
+/// Patterns containing `*` or `?` are globs matched against a whole path
+/// component; anything else matches as a substring. Matching ignores case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExcludePatterns {
+    patterns: Vec<String>,
+}
+
+impl ExcludePatterns {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Whether any component of `path` matches a pattern
+    ///
+    /// Pass paths relative to the folder being scanned, so the library root
+    /// itself can't exclude everything below it.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        path.components().any(|component| {
+            let component = component.as_os_str().to_string_lossy().to_lowercase();
+            self.patterns.iter().any(|pattern| matches_pattern(pattern, &component))
+        })
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    if pattern.contains(['*', '?']) {
+        glob_match(pattern.as_bytes(), name.as_bytes())
+    } else {
+        name.contains(pattern)
+    }
+}
+
+/// Match `*` (any run) and `?` (any single byte) against the whole name
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Where to resume after the last `*`: (pattern index, name index)
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn patterns(list: &[&str]) -> ExcludePatterns {
+        ExcludePatterns::new(&list.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_glob_matches_whole_component() {
+        let exclude = patterns(&["*Redist*", "tool?"]);
+        assert!(exclude.is_excluded(&PathBuf::from("_CommonRedist/vcredist_x64.exe")));
+        assert!(exclude.is_excluded(&PathBuf::from("Tools/editor.exe")));
+        assert!(!exclude.is_excluded(&PathBuf::from("Toolset/editor.exe")));
+        assert!(!exclude.is_excluded(&PathBuf::from("bin/Game.exe")));
+    }
+
+    #[test]
+    fn test_substring_and_case() {
+        let exclude = patterns(&["directx", "  ", "DLC"]);
+        assert!(exclude.is_excluded(&PathBuf::from("Installers/DirectX/DXSETUP.exe")));
+        assert!(exclude.is_excluded(&PathBuf::from("dlc_pack/content.exe")));
+        assert!(!exclude.is_excluded(&PathBuf::from("Game.exe")));
+    }
+
+    #[test]
+    fn test_glob_edge_cases() {
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"a*b*c", b"axxbyyc"));
+        assert!(glob_match(b"*.exe", b"game.exe"));
+        assert!(!glob_match(b"*.exe", b"game.exe.bak"));
+        assert!(!glob_match(b"a?c", b"ac"));
+        assert!(patterns(&[]).patterns.is_empty());
+    }
+}
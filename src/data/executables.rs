@@ -0,0 +1,207 @@
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+use super::ExcludePatterns;
+
+/// Executables shipped next to games that aren't the game itself
+const HELPER_EXE_MARKERS: &[&str] = &[
+    "unins",
+    "uninst",
+    "crash",
+    "report",
+    "setup",
+    "redist",
+    "vcredist",
+    "dxsetup",
+    "launcher",
+    "easyanticheat",
+    "battleye",
+    "uplay",
+    "upc",
+];
+
+/// Try to find a main executable in a game directory
+pub fn find_executable_in_dir(dir: &Path, exclude: &ExcludePatterns) -> Option<PathBuf> {
+    executable_candidates(dir, exclude, 0).into_iter().next()
+}
+
+/// Executables in a game directory, most likely main executable first
+///
+/// Subfolders are searched `depth` levels deep, since many games keep their
+/// binary in something like `bin/win64`. Uninstallers, crash reporters,
+/// setup tools and other helpers are left out. Of the rest, native binaries
+/// come before Windows ones, then the largest file wins: the game is nearly
+/// always the biggest thing in its folder. Ties go to files nearer the top
+/// and then to shorter names.
+pub fn executable_candidates(dir: &Path, exclude: &ExcludePatterns, depth: usize) -> Vec<PathBuf> {
+    fn collect(
+        root: &Path,
+        dir: &Path,
+        exclude: &ExcludePatterns,
+        depth: usize,
+        out: &mut Vec<Candidate>,
+    ) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            if exclude.is_excluded(relative) {
+                continue;
+            }
+            // Symlinked folders aren't followed, so a link loop can't trap the search
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() && depth > 0 {
+                collect(root, &path, exclude, depth - 1, out);
+            } else if path.is_file() && !is_helper_executable(&path) {
+                if let Some(kind) = executable_kind(&path) {
+                    out.push(Candidate {
+                        kind,
+                        size: path.metadata().map(|meta| meta.len()).unwrap_or_default(),
+                        level: relative.components().count(),
+                        path,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut candidates = Vec::new();
+    collect(dir, dir, exclude, depth, &mut candidates);
+    candidates.sort_by_cached_key(|c| {
+        let name_len = c.path.file_name().map(|n| n.len()).unwrap_or(usize::MAX);
+        (c.kind, Reverse(c.size), c.level, name_len, c.path.clone())
+    });
+    candidates.into_iter().map(|c| c.path).collect()
+}
+
+struct Candidate {
+    kind: ExecutableKind,
+    size: u64,
+    /// How many folders down from the searched directory, counting the file
+    level: usize,
+    path: PathBuf,
+}
+
+/// What kind of program a file is, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ExecutableKind {
+    Native,
+    /// A Windows build outside Windows, which runs through Wine or Proton
+    Windows,
+}
+
+/// Whether an executable is a helper shipped alongside the game
+fn is_helper_executable(path: &Path) -> bool {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    HELPER_EXE_MARKERS.iter().any(|marker| stem.contains(marker))
+}
+
+/// Check if a file is executable (platform-specific)
+#[cfg(target_os = "windows")]
+fn executable_kind(path: &Path) -> Option<ExecutableKind> {
+    is_exe(path).then_some(ExecutableKind::Native)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_kind(path: &Path) -> Option<ExecutableKind> {
+    use std::os::unix::fs::PermissionsExt;
+    if is_exe(path) {
+        return Some(ExecutableKind::Windows);
+    }
+    let mode = path.metadata().ok()?.permissions().mode();
+    (mode & 0o111 != 0).then_some(ExecutableKind::Native)
+}
+
+fn is_exe(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A folder holding the given files and their sizes, `*` marking the
+    /// ones with the executable bit
+    fn folder_with(files: &[(&str, usize)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("launcher-exe-{}", uuid::Uuid::new_v4()));
+        for (file, size) in files {
+            let executable = file.starts_with('*');
+            let path = root.join(file.trim_start_matches('*'));
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, vec![0u8; *size]).unwrap();
+            #[cfg(unix)]
+            if executable {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::Permissions::from_mode(0o755);
+                std::fs::set_permissions(&path, mode).unwrap();
+            }
+            #[cfg(not(unix))]
+            let _ = executable;
+        }
+        root
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_executable_candidates_ranked() {
+        let root = folder_with(&[
+            ("*launcher_helper", 64),
+            ("*crash", 32),
+            ("*tool", 16),
+            ("*bin/win64/game", 4096),
+            ("*_CommonRedist/big", 65536),
+            ("readme", 8192),
+        ]);
+        let exclude = ExcludePatterns::new(&["*Redist*".to_string()]);
+
+        // Helpers are dropped and the biggest binary wins, wherever it is
+        assert_eq!(
+            executable_candidates(&root, &exclude, 2),
+            [root.join("bin/win64/game"), root.join("tool")]
+        );
+        // Importing only looks at the top of the folder
+        assert_eq!(find_executable_in_dir(&root, &exclude), Some(root.join("tool")));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_windows_builds_skip_helpers() {
+        let root = folder_with(&[
+            ("unins000.exe", 4096),
+            ("bin/UplayCrashReporter.exe", 8192),
+            ("bin/FarCry5.exe", 2048),
+            ("Tools/Editor.exe", 16384),
+            ("readme.txt", 16384),
+        ]);
+        let exclude = ExcludePatterns::new(&["tools".to_string()]);
+
+        assert_eq!(executable_candidates(&root, &exclude, 1), [root.join("bin/FarCry5.exe")]);
+        // Nothing but helpers at the top
+        assert_eq!(find_executable_in_dir(&root, &exclude), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_folders_are_not_followed() {
+        let root = folder_with(&[("*game", 16)]);
+        std::os::unix::fs::symlink(&root, root.join("loop")).unwrap();
+
+        let candidates = executable_candidates(&root, &ExcludePatterns::default(), 8);
+        assert_eq!(candidates, [root.join("game")]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,12 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 use uuid::Uuid;
 
-use super::CategoryId;
+use super::{CategoryId, Clock};
 
 /// Unique identifier for a game
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct GameId(pub Uuid);
 
 impl GameId {
@@ -22,12 +23,22 @@ impl Default for GameId {
 }
 
 /// Source/origin of a game
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameSource {
     Steam,
     Epic,
     GOG,
+    Ubisoft,
+    /// Xbox app and Microsoft Store games, by app user model ID (`<PFN>!<App>`)
+    Xbox,
+    /// Games installed by the itch.io app
+    Itch,
+    /// Games added to Steam by hand ("Add a Non-Steam Game")
+    SteamShortcut,
     Manual,
+    /// ROMs run through an emulator
+    Emulator,
 }
 
 impl GameSource {
@@ -36,7 +47,54 @@ impl GameSource {
             GameSource::Steam => "Steam",
             GameSource::Epic => "Epic Games",
             GameSource::GOG => "GOG Galaxy",
+            GameSource::Ubisoft => "Ubisoft Connect",
+            GameSource::Xbox => "Xbox",
+            GameSource::Itch => "itch.io",
+            GameSource::SteamShortcut => "Non-Steam shortcut",
             GameSource::Manual => "Manual",
+            GameSource::Emulator => "Emulator",
+        }
+    }
+
+    /// Store protocol URI that launches a game through its launcher
+    pub fn launch_uri(&self, source_id: &str) -> Option<String> {
+        match self {
+            GameSource::Steam => Some(format!("steam://rungameid/{}", source_id)),
+            GameSource::Epic => Some(format!(
+                "com.epicgames.launcher://apps/{}?action=launch&silent=true",
+                source_id
+            )),
+            GameSource::GOG => Some(format!("goggalaxy://openGameView/{}", source_id)),
+            GameSource::Ubisoft => Some(format!("uplay://launch/{}/0", source_id)),
+            GameSource::Xbox => Some(format!("shell:AppsFolder\\{}", source_id)),
+            // The itch app only launches from its own window, and Steam only
+            // knows shortcuts by its own ID; these run their executable directly
+            GameSource::Itch
+            | GameSource::SteamShortcut
+            | GameSource::Manual
+            | GameSource::Emulator => None,
+        }
+    }
+
+    /// Store protocol URI that starts installing a game
+    ///
+    /// GOG Galaxy and the itch app have no install link, so their game page is
+    /// opened instead, as is the package's Microsoft Store page for Xbox games.
+    pub fn install_uri(&self, source_id: &str) -> Option<String> {
+        match self {
+            GameSource::Steam => Some(format!("steam://install/{}", source_id)),
+            GameSource::Epic => Some(format!(
+                "com.epicgames.launcher://apps/{}?action=install",
+                source_id
+            )),
+            GameSource::GOG => Some(format!("goggalaxy://openGameView/{}", source_id)),
+            GameSource::Ubisoft => Some(format!("uplay://install/{}", source_id)),
+            GameSource::Xbox => {
+                let family_name = source_id.split('!').next().unwrap_or(source_id);
+                Some(format!("ms-windows-store://pdp/?PFN={}", family_name))
+            }
+            GameSource::Itch => Some(format!("itch://games/{}", source_id)),
+            GameSource::SteamShortcut | GameSource::Manual | GameSource::Emulator => None,
         }
     }
 
@@ -45,9 +103,144 @@ impl GameSource {
             GameSource::Steam,
             GameSource::Epic,
             GameSource::GOG,
+            GameSource::Ubisoft,
+            GameSource::Xbox,
+            GameSource::Itch,
+            GameSource::SteamShortcut,
             GameSource::Manual,
+            GameSource::Emulator,
         ]
     }
+
+    /// Whether games come from a store that other stores might also sell
+    pub fn is_store(&self) -> bool {
+        !matches!(self, GameSource::SteamShortcut | GameSource::Manual | GameSource::Emulator)
+    }
+}
+
+/// How a game should present itself when launched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WindowModePref {
+    /// Leave it to the game
+    #[default]
+    Default,
+    Fullscreen,
+    Borderless,
+    Windowed,
+}
+
+impl WindowModePref {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowModePref::Default => "Game default",
+            WindowModePref::Fullscreen => "Fullscreen",
+            WindowModePref::Borderless => "Borderless",
+            WindowModePref::Windowed => "Windowed",
+        }
+    }
+
+    pub fn all() -> &'static [WindowModePref] {
+        &[
+            WindowModePref::Default,
+            WindowModePref::Fullscreen,
+            WindowModePref::Borderless,
+            WindowModePref::Windowed,
+        ]
+    }
+}
+
+/// Where a game stands in the user's play-through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PlayStatus {
+    #[default]
+    Backlog,
+    Playing,
+    Completed,
+    Dropped,
+    OnHold,
+}
+
+impl PlayStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlayStatus::Backlog => "Backlog",
+            PlayStatus::Playing => "Playing",
+            PlayStatus::Completed => "Completed",
+            PlayStatus::Dropped => "Dropped",
+            PlayStatus::OnHold => "On Hold",
+        }
+    }
+
+    pub fn all() -> &'static [PlayStatus] {
+        &[
+            PlayStatus::Backlog,
+            PlayStatus::Playing,
+            PlayStatus::Completed,
+            PlayStatus::Dropped,
+            PlayStatus::OnHold,
+        ]
+    }
+}
+
+impl std::fmt::Display for PlayStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Fields a store import can also set, tracked so imports know what the
+/// user has changed by hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum GameField {
+    Name,
+    ExecutablePath,
+    InstallPath,
+    LaunchArgs,
+    EmulatorCommand,
+}
+
+/// Per-game display preferences, translated into launch args/env at launch time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DisplayPrefs {
+    /// Preferred monitor, zero-based
+    pub monitor: Option<u32>,
+
+    /// Preferred window mode
+    pub window_mode: WindowModePref,
+}
+
+impl DisplayPrefs {
+    /// Whether nothing has been set
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Intended layout of a piece of artwork, judged from its aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArtShape {
+    /// Tall grid cover, like Steam's 600x900 library art
+    Portrait,
+    /// Wide banner, like Steam's 920x430 header or a SteamGridDB hero
+    Hero,
+    /// Roughly square
+    Icon,
+}
+
+impl ArtShape {
+    pub fn from_dimensions(width: u32, height: u32) -> Option<Self> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let ratio = width as f32 / height as f32;
+        Some(if ratio < 0.85 {
+            ArtShape::Portrait
+        } else if ratio > 1.3 {
+            ArtShape::Hero
+        } else {
+            ArtShape::Icon
+        })
+    }
 }
 
 /// A game in the library
@@ -83,6 +276,14 @@ pub struct Game {
     /// Path to the game banner/cover image
     pub banner_path: Option<PathBuf>,
 
+    /// Shape of the banner image, detected when it was set
+    #[serde(default)]
+    pub banner_shape: Option<ArtShape>,
+
+    /// Path to wide hero art for the detail page
+    #[serde(default)]
+    pub hero_path: Option<PathBuf>,
+
     /// Last time the game was played
     pub last_played: Option<DateTime<Utc>>,
 
@@ -94,11 +295,69 @@ pub struct Game {
 
     /// Additional launch arguments
     pub launch_args: Option<String>,
+
+    /// The same game owned on other stores, as (source, source ID) pairs
+    #[serde(default)]
+    pub store_links: Vec<(GameSource, String)>,
+
+    /// Preferred monitor and window mode
+    #[serde(default)]
+    pub display_prefs: DisplayPrefs,
+
+    /// Run in a terminal window; `None` follows the global setting
+    #[serde(default)]
+    pub launch_in_terminal: Option<bool>,
+
+    /// Ask (or require the launch PIN) before starting this game
+    #[serde(default)]
+    pub confirm_before_launch: bool,
+
+    /// Start with administrator rights (UAC on Windows, pkexec on Linux)
+    #[serde(default)]
+    pub run_as_admin: bool,
+
+    /// Kept out of the library view unless hidden games are shown
+    #[serde(default)]
+    pub hidden: bool,
+
+    /// Record sessions in playtime and last played; off for tools and benchmarks
+    #[serde(default = "default_track_playtime")]
+    pub track_playtime: bool,
+
+    /// Backlog, playing, completed and so on
+    #[serde(default)]
+    pub status: PlayStatus,
+
+    /// Import-provided fields the user has since edited, which re-syncs keep
+    #[serde(default)]
+    pub user_modified: BTreeSet<GameField>,
+
+    /// False for games saved before edits were tracked, whose first re-sync
+    /// takes any field that differs from the store as edited
+    #[serde(default)]
+    pub user_edits_tracked: bool,
+
+    /// Where the game keeps its saves, for backing them up
+    #[serde(default)]
+    pub save_path: Option<PathBuf>,
+
+    /// Runs the executable path through an emulator; `{rom}` stands for the path
+    #[serde(default)]
+    pub emulator_command: Option<String>,
+}
+
+fn default_track_playtime() -> bool {
+    true
 }
 
 impl Game {
     /// Create a new game with minimal required fields
-    pub fn new(name: String, executable_path: PathBuf, source: GameSource) -> Self {
+    pub fn new(
+        name: String,
+        executable_path: PathBuf,
+        source: GameSource,
+        clock: &dyn Clock,
+    ) -> Self {
         Self {
             id: GameId::new(),
             name,
@@ -110,10 +369,24 @@ impl Game {
             favorite: false,
             icon_path: None,
             banner_path: None,
+            banner_shape: None,
+            hero_path: None,
             last_played: None,
             playtime_minutes: 0,
-            added_date: Utc::now(),
+            added_date: clock.now(),
             launch_args: None,
+            store_links: Vec::new(),
+            display_prefs: DisplayPrefs::default(),
+            launch_in_terminal: None,
+            confirm_before_launch: false,
+            run_as_admin: false,
+            hidden: false,
+            status: PlayStatus::default(),
+            track_playtime: true,
+            user_modified: BTreeSet::new(),
+            user_edits_tracked: true,
+            save_path: None,
+            emulator_command: None,
         }
     }
 
@@ -124,6 +397,7 @@ impl Game {
         install_path: PathBuf,
         source: GameSource,
         source_id: String,
+        clock: &dyn Clock,
     ) -> Self {
         Self {
             id: GameId::new(),
@@ -136,16 +410,88 @@ impl Game {
             favorite: false,
             icon_path: None,
             banner_path: None,
+            banner_shape: None,
+            hero_path: None,
             last_played: None,
             playtime_minutes: 0,
-            added_date: Utc::now(),
+            added_date: clock.now(),
             launch_args: None,
+            store_links: Vec::new(),
+            display_prefs: DisplayPrefs::default(),
+            launch_in_terminal: None,
+            confirm_before_launch: false,
+            run_as_admin: false,
+            hidden: false,
+            status: PlayStatus::default(),
+            track_playtime: true,
+            user_modified: BTreeSet::new(),
+            user_edits_tracked: true,
+            save_path: None,
+            emulator_command: None,
+        }
+    }
+
+    /// Copy this game as a starting point for a new entry
+    ///
+    /// The copy gets a fresh ID and a " (Copy)" suffix, keeps paths, categories
+    /// and launch settings, and starts with no favorite, playtime, history or
+    /// play status.
+    /// It is a manual entry so imports never mistake it for the store's copy.
+    pub fn duplicate(&self, clock: &dyn Clock) -> Self {
+        Self {
+            id: GameId::new(),
+            name: format!("{} (Copy)", self.name),
+            source: GameSource::Manual,
+            source_id: None,
+            store_links: Vec::new(),
+            favorite: false,
+            last_played: None,
+            playtime_minutes: 0,
+            added_date: clock.now(),
+            status: PlayStatus::default(),
+            ..self.clone()
         }
     }
 
+    /// Note that the user edited a field, so imports stop refreshing it
+    pub fn mark_user_modified(&mut self, field: GameField) {
+        self.user_modified.insert(field);
+    }
+
+    /// Whether the user edited a field, so imports must leave it alone
+    pub fn is_user_modified(&self, field: GameField) -> bool {
+        self.user_modified.contains(&field)
+    }
+
+    /// The URL or protocol link this entry opens instead of an executable
+    pub fn uri_target(&self) -> Option<&str> {
+        self.executable_path
+            .to_str()
+            .map(str::trim)
+            .filter(|target| super::is_uri(target))
+    }
+
+    /// Whether there is something to launch: a link, or an executable on disk
+    ///
+    /// This runs for every game on screen, so the path has to match exactly;
+    /// a launch still finds an executable whose case differs.
+    pub fn is_installed(&self) -> bool {
+        self.uri_target().is_some() || self.executable_path.exists()
+    }
+
+    /// The store, and its link, to install this game through
+    ///
+    /// The store the game was imported from comes first, then linked copies.
+    pub fn install_uri(&self) -> Option<(GameSource, String)> {
+        let own = self.source_id.as_deref().map(|id| (self.source, id));
+        own.into_iter()
+            .chain(self.store_links.iter().map(|(s, id)| (*s, id.as_str())))
+            .find_map(|(source, id)| source.install_uri(id).map(|uri| (source, uri)))
+    }
+
     /// Update the last played time to now
-    pub fn mark_played(&mut self) {
-        self.last_played = Some(Utc::now());
+    pub fn mark_played(&mut self, clock: &dyn Clock) {
+        self.last_played = Some(clock.now());
     }
 
     /// Add playtime in minutes
@@ -158,6 +504,21 @@ impl Game {
         self.favorite = !self.favorite;
     }
 
+    /// Toggle whether the game is hidden from the library view
+    pub fn toggle_hidden(&mut self) {
+        self.hidden = !self.hidden;
+    }
+
+    /// Whether the game still lacks its cover art or its icon
+    pub fn missing_art(&self) -> bool {
+        self.banner_path.is_none() || self.icon_path.is_none()
+    }
+
+    /// Whether the game is uncategorized or its install folder is unknown
+    pub fn missing_metadata(&self) -> bool {
+        self.categories.is_empty() || self.install_path.is_none()
+    }
+
     /// Check if game has a specific category
     pub fn has_category(&self, category_id: &CategoryId) -> bool {
         self.categories.contains(category_id)
@@ -175,6 +536,96 @@ impl Game {
         self.categories.retain(|c| c != category_id);
     }
 
+    /// Link another store's copy of this game
+    pub fn add_store_link(&mut self, source: GameSource, source_id: String) {
+        if !self.has_store_link(source, &source_id) {
+            self.store_links.push((source, source_id));
+        }
+    }
+
+    /// Check if another store's copy is linked to this game
+    pub fn has_store_link(&self, source: GameSource, source_id: &str) -> bool {
+        self.store_links
+            .iter()
+            .any(|(s, id)| *s == source && id == source_id)
+    }
+
+    /// Take over a manual entry for the same game, keeping its user data
+    ///
+    /// This entry's source and source ID stay. Fields the user edited on the
+    /// manual entry win and stay marked as edited, so re-syncs keep them;
+    /// other import-owned fields keep the store's values. Categories, art,
+    /// favorite, status and launch settings come from the manual entry. Its
+    /// playtime is added on, since the store never saw those sessions, unless
+    /// it launched through this entry's store, which then counted them too.
+    pub fn absorb_manual(&mut self, manual: Game) {
+        let store_uri = self.source_id.as_deref().and_then(|id| self.source.launch_uri(id));
+        let same_store = manual.uri_target().zip(store_uri).is_some_and(|(link, store)| {
+            let scheme = |uri: &str| uri.split(':').next().unwrap_or_default().to_lowercase();
+            scheme(link) == scheme(&store)
+        });
+
+        for field in &manual.user_modified {
+            match field {
+                GameField::Name => self.name = manual.name.clone(),
+                GameField::ExecutablePath => {
+                    self.executable_path = manual.executable_path.clone()
+                }
+                GameField::InstallPath => self.install_path = manual.install_path.clone(),
+                GameField::LaunchArgs => self.launch_args = manual.launch_args.clone(),
+                GameField::EmulatorCommand => {
+                    self.emulator_command = manual.emulator_command.clone()
+                }
+            }
+            self.mark_user_modified(*field);
+        }
+
+        for category in manual.categories {
+            self.add_category(category);
+        }
+        for (source, source_id) in manual.store_links {
+            self.add_store_link(source, source_id);
+        }
+        if manual.banner_path.is_some() {
+            self.banner_path = manual.banner_path;
+            self.banner_shape = manual.banner_shape;
+        }
+        self.icon_path = manual.icon_path.or(self.icon_path.take());
+        self.hero_path = manual.hero_path.or(self.hero_path.take());
+        self.save_path = manual.save_path.or(self.save_path.take());
+
+        self.favorite |= manual.favorite;
+        self.hidden |= manual.hidden;
+        self.confirm_before_launch |= manual.confirm_before_launch;
+        self.run_as_admin |= manual.run_as_admin;
+        self.track_playtime &= manual.track_playtime;
+        if manual.status != PlayStatus::default() {
+            self.status = manual.status;
+        }
+        if !manual.display_prefs.is_default() {
+            self.display_prefs = manual.display_prefs;
+        }
+        self.launch_in_terminal = manual.launch_in_terminal.or(self.launch_in_terminal);
+
+        self.playtime_minutes = if same_store {
+            self.playtime_minutes.max(manual.playtime_minutes)
+        } else {
+            self.playtime_minutes + manual.playtime_minutes
+        };
+        self.last_played = self.last_played.max(manual.last_played);
+        self.added_date = self.added_date.min(manual.added_date);
+    }
+
+    /// Name cleaned up for display, see [`sanitize_display_name`]
+    pub fn display_name(&self) -> String {
+        sanitize_display_name(&self.name)
+    }
+
+    /// Display name shortened to at most `max_chars` characters with an ellipsis
+    pub fn display_name_truncated(&self, max_chars: usize) -> String {
+        truncate_display_name(&self.name, max_chars)
+    }
+
     /// Get formatted playtime string
     pub fn playtime_display(&self) -> String {
         let hours = self.playtime_minutes / 60;
@@ -185,4 +636,214 @@ impl Game {
             format!("{}m", mins)
         }
     }
+
+    /// When the game was last played, relative to `now` (e.g. "3 hours ago")
+    ///
+    /// Older than a week falls back to the date.
+    pub fn last_played_display(&self, now: DateTime<Utc>) -> String {
+        let Some(last_played) = self.last_played else {
+            return "Never".to_string();
+        };
+        let elapsed = now.signed_duration_since(last_played);
+        let plural = |n: i64, unit: &str| {
+            format!("{} {}{} ago", n, unit, if n == 1 { "" } else { "s" })
+        };
+
+        match elapsed.num_minutes() {
+            ..1 => "Just now".to_string(),
+            minutes @ 1..60 => plural(minutes, "minute"),
+            _ => match elapsed.num_hours() {
+                hours @ ..24 => plural(hours, "hour"),
+                _ => match elapsed.num_days() {
+                    1 => "Yesterday".to_string(),
+                    days @ ..7 => plural(days, "day"),
+                    _ => last_played.format("%Y-%m-%d").to_string(),
+                },
+            },
+        }
+    }
+}
+
+/// Clean up a game name for display
+///
+/// Control characters (newlines, tabs) are treated as spaces and runs of
+/// whitespace collapsed. Stored names are left untouched so exports and
+/// searches keep the real value.
+pub fn sanitize_display_name(name: &str) -> String {
+    let cleaned = name
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if cleaned.is_empty() {
+        "(Untitled)".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Sanitize a game name and shorten it to `max_chars` characters with an ellipsis
+pub fn truncate_display_name(name: &str, max_chars: usize) -> String {
+    let name = sanitize_display_name(name);
+    if name.chars().count() <= max_chars {
+        return name;
+    }
+    let truncated: String = name.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::MockClock;
+
+    fn game_named(name: &str) -> Game {
+        Game::new(name.to_string(), PathBuf::from("game"), GameSource::Manual, &MockClock::fixed())
+    }
+
+    #[test]
+    fn test_duplicate_resets_history() {
+        let mut game = game_named("Portal");
+        game.launch_args = Some("-novid".to_string());
+        game.favorite = true;
+        game.add_playtime(90);
+        let clock = MockClock::fixed();
+        game.mark_played(&clock);
+        assert_eq!(game.last_played, Some(clock.now()));
+
+        clock.advance(chrono::Duration::days(1));
+        let copy = game.duplicate(&clock);
+        assert_ne!(copy.id, game.id);
+        assert_eq!(copy.name, "Portal (Copy)");
+        assert_eq!(copy.executable_path, game.executable_path);
+        assert_eq!(copy.launch_args.as_deref(), Some("-novid"));
+        assert!(!copy.favorite);
+        assert_eq!(copy.playtime_minutes, 0);
+        assert!(copy.last_played.is_none());
+        assert_eq!(copy.added_date, clock.now());
+    }
+
+    #[test]
+    fn test_status_defaults_for_older_libraries() {
+        let mut game = game_named("Portal");
+        game.status = PlayStatus::Completed;
+        let mut value = serde_json::to_value(&game).unwrap();
+        assert_eq!(value["status"], "Completed");
+
+        // Saved before the field existed
+        value.as_object_mut().unwrap().remove("status");
+        let loaded: Game = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.status, PlayStatus::Backlog);
+    }
+
+    #[test]
+    fn test_playtime_tracked_for_older_libraries() {
+        let mut value = serde_json::to_value(game_named("Portal")).unwrap();
+        assert_eq!(value["track_playtime"], true);
+
+        // Saved before the field existed
+        value.as_object_mut().unwrap().remove("track_playtime");
+        let loaded: Game = serde_json::from_value(value).unwrap();
+        assert!(loaded.track_playtime);
+    }
+
+    #[test]
+    fn test_missing_art_and_metadata() {
+        let mut game = game_named("Portal");
+        assert!(game.missing_art());
+        assert!(game.missing_metadata());
+
+        game.banner_path = Some(PathBuf::from("cover.png"));
+        assert!(game.missing_art());
+        game.icon_path = Some(PathBuf::from("icon.png"));
+        assert!(!game.missing_art());
+
+        game.add_category(CategoryId::new());
+        assert!(game.missing_metadata());
+        game.install_path = Some(PathBuf::from("games/portal"));
+        assert!(!game.missing_metadata());
+    }
+
+    #[test]
+    fn test_install_uri() {
+        let mut game = game_named("Portal");
+        game.executable_path = std::env::temp_dir().join("launcher-missing").join("portal.exe");
+        assert!(!game.is_installed());
+        assert_eq!(game.install_uri(), None);
+
+        game.add_store_link(GameSource::Epic, "Fennec".to_string());
+        assert_eq!(
+            game.install_uri(),
+            Some((
+                GameSource::Epic,
+                "com.epicgames.launcher://apps/Fennec?action=install".to_string()
+            ))
+        );
+
+        game.source = GameSource::Steam;
+        game.source_id = Some("400".to_string());
+        assert_eq!(
+            game.install_uri(),
+            Some((GameSource::Steam, "steam://install/400".to_string()))
+        );
+
+        // Links are handed to the store, which knows whether the game is there
+        game.executable_path = PathBuf::from("steam://rungameid/400");
+        assert!(game.is_installed());
+    }
+
+    #[test]
+    fn test_last_played_display() {
+        let now = MockClock::fixed().now();
+        let mut game = game_named("Portal");
+        assert_eq!(game.last_played_display(now), "Never");
+
+        let ago = |game: &mut Game, duration: chrono::Duration| {
+            game.last_played = Some(now - duration);
+            game.last_played_display(now)
+        };
+        assert_eq!(ago(&mut game, chrono::Duration::seconds(30)), "Just now");
+        assert_eq!(ago(&mut game, chrono::Duration::minutes(1)), "1 minute ago");
+        assert_eq!(ago(&mut game, chrono::Duration::minutes(59)), "59 minutes ago");
+        assert_eq!(ago(&mut game, chrono::Duration::hours(3)), "3 hours ago");
+        assert_eq!(ago(&mut game, chrono::Duration::hours(30)), "Yesterday");
+        assert_eq!(ago(&mut game, chrono::Duration::days(6)), "6 days ago");
+        let old = (now - chrono::Duration::days(30)).format("%Y-%m-%d").to_string();
+        assert_eq!(ago(&mut game, chrono::Duration::days(30)), old);
+    }
+
+    #[test]
+    fn test_display_name_strips_newlines_and_tabs() {
+        assert_eq!(game_named("Half-Life\n2").display_name(), "Half-Life 2");
+        assert_eq!(game_named("\tPortal\t\t 2 \r\n").display_name(), "Portal 2");
+    }
+
+    #[test]
+    fn test_display_name_empty_after_sanitizing() {
+        assert_eq!(game_named(" \n\t ").display_name(), "(Untitled)");
+    }
+
+    #[test]
+    fn test_display_name_truncated() {
+        let game = game_named("The Elder Scrolls V: Skyrim");
+        assert_eq!(
+            game.display_name_truncated(100),
+            "The Elder Scrolls V: Skyrim"
+        );
+        assert_eq!(game.display_name_truncated(10), "The Elder…");
+        // The stored name is kept intact
+        assert_eq!(game.name, "The Elder Scrolls V: Skyrim");
+    }
+
+    #[test]
+    fn test_art_shape_from_dimensions() {
+        assert_eq!(ArtShape::from_dimensions(600, 900), Some(ArtShape::Portrait));
+        assert_eq!(ArtShape::from_dimensions(920, 430), Some(ArtShape::Hero));
+        assert_eq!(ArtShape::from_dimensions(3840, 1240), Some(ArtShape::Hero));
+        assert_eq!(ArtShape::from_dimensions(256, 256), Some(ArtShape::Icon));
+        assert_eq!(ArtShape::from_dimensions(0, 256), None);
+    }
 }
@@ -1,12 +1,29 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tokio::fs;
-use tokio::io::AsyncWriteExt;
 
-use super::{Category, CategoryId, Game, GameId};
-use crate::message::SortOrder;
+use super::bundle::{
+    art_slots, BundleContents, BundleExportReport, BundleImportReport, BundleManifest,
+    BUNDLE_ART_DIR, BUNDLE_MANIFEST, BUNDLE_VERSION,
+};
+use super::csv::{parse_csv, CsvContents, CsvImportReport, CsvRow, CsvRowOutcome, CsvRowResult};
+use super::store::{is_encrypted_file, EncryptedFileStore, FileStore, LibraryStore};
+use super::category::same_category_name;
+use super::{
+    normalize_name, same_path, Category, CategoryCollision, CategoryId, Clock, Game, GameId,
+    GameSource, PlayStatus, RuleCondition,
+};
+use crate::import::validate_executable;
+use crate::message::{Filter, GroupBy, SortOrder};
+
+/// The games each smart category's rule matches, by category
+pub type SmartMembers = HashMap<CategoryId, HashSet<GameId>>;
+
+/// Column order assumed when a CSV file has no header row
+const CSV_DEFAULT_COLUMNS: [&str; 4] = ["name", "executable_path", "launch_args", "categories"];
 
 /// The game library containing all games and categories
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +33,10 @@ pub struct Library {
 
     /// All categories indexed by their ID
     pub categories: HashMap<CategoryId, Category>,
+
+    /// Games pinned to the featured row, in the order they were pinned
+    #[serde(default)]
+    pub pinned: Vec<GameId>,
 }
 
 impl Default for Library {
@@ -25,7 +46,7 @@ impl Default for Library {
 }
 
 impl Library {
-    /// Create a new empty library with default categories
+    /// Create a first-run library: no games, but the default categories
     pub fn new() -> Self {
         let default_cats = super::category::default_categories();
         let categories = default_cats
@@ -34,8 +55,30 @@ impl Library {
             .collect();
 
         Self {
-            games: HashMap::new(),
             categories,
+            ..Self::empty()
+        }
+    }
+
+    /// Create a library with no games and no categories
+    pub fn empty() -> Self {
+        Self {
+            games: HashMap::new(),
+            categories: HashMap::new(),
+            pinned: Vec::new(),
+        }
+    }
+
+    /// What to start with when there's no library to load
+    ///
+    /// Only a genuine first run gets the default categories. Otherwise the
+    /// file went missing or was unreadable, and seeding would bring back
+    /// categories the user deleted.
+    fn fresh(first_run: bool) -> Self {
+        if first_run {
+            Self::new()
+        } else {
+            Self::empty()
         }
     }
 
@@ -44,11 +87,381 @@ impl Library {
         self.games.insert(game.id, game);
     }
 
+    /// Add manual games from a CSV file of name, executable path and
+    /// optional launch args and `;`-separated categories
+    ///
+    /// A header row naming those columns may reorder them. Rows with a
+    /// missing or invalid executable are skipped, as are executables already
+    /// in the library; every row is reported either way. Unknown categories
+    /// are created. Every added game also goes in `default_category`, if given.
+    pub fn import_csv(
+        &mut self,
+        path: &Path,
+        default_category: Option<CategoryId>,
+        clock: &dyn Clock,
+    ) -> Result<CsvImportReport, LibraryError> {
+        let contents = Self::read_csv(path, clock)?;
+        Ok(self.add_csv(contents, default_category))
+    }
+
+    /// Read a CSV file for [`Library::import_csv`], checking each executable
+    ///
+    /// This is the slow half of the import and needs no library, so it can
+    /// run away from the UI.
+    pub fn read_csv(path: &Path, clock: &dyn Clock) -> Result<CsvContents, LibraryError> {
+        let content = std::fs::read_to_string(path).map_err(|e| LibraryError::Io(e.to_string()))?;
+        let mut records = parse_csv(&content).into_iter().peekable();
+
+        let header_key = |h: &String| h.to_lowercase().replace([' ', '-'], "_");
+        let is_header = records.peek().is_some_and(|(_, record)| {
+            record.iter().map(header_key).any(|h| h == "name" || h == "title")
+        });
+        let columns: Vec<String> = match records.next_if(|_| is_header) {
+            Some((_, header)) => header.iter().map(header_key).collect(),
+            None => CSV_DEFAULT_COLUMNS.iter().map(|c| c.to_string()).collect(),
+        };
+        let column = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+        let name_col = column(&["name", "title"]);
+        let exe_col = column(&["executable_path", "executable", "path", "exe"]);
+        let args_col = column(&["launch_args", "args", "arguments"]);
+        let categories_col = column(&["categories", "category", "tags"]);
+
+        let mut contents = CsvContents::default();
+        for (line, record) in records {
+            let field = |col: Option<usize>| {
+                col.and_then(|i| record.get(i))
+                    .map(String::as_str)
+                    .filter(|f| !f.is_empty())
+            };
+            let name = field(name_col).unwrap_or_default().to_string();
+            let game = if name.is_empty() {
+                Err("Missing name".to_string())
+            } else if let Some(exe) = field(exe_col) {
+                validate_executable(Path::new(exe))
+                    .map(|()| {
+                        let executable_path = PathBuf::from(exe);
+                        let mut game =
+                            Game::new(name.clone(), executable_path, GameSource::Manual, clock);
+                        game.launch_args = field(args_col).map(str::to_string);
+                        let categories = field(categories_col)
+                            .unwrap_or_default()
+                            .split(';')
+                            .map(str::trim)
+                            .filter(|category| !category.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                        (game, categories)
+                    })
+                    .map_err(|e| format!("{}: {}", e, exe))
+            } else {
+                Err("Missing executable path".to_string())
+            };
+            contents.rows.push(CsvRow { line, name, game });
+        }
+
+        Ok(contents)
+    }
+
+    /// Add the games of a CSV file read by [`Library::read_csv`]
+    ///
+    /// Executables already in the library, including ones added by earlier
+    /// rows, are reported as duplicates.
+    pub fn add_csv(
+        &mut self,
+        contents: CsvContents,
+        default_category: Option<CategoryId>,
+    ) -> CsvImportReport {
+        let mut report = CsvImportReport::default();
+        for row in contents.rows {
+            let outcome = match row.game {
+                Err(reason) => CsvRowOutcome::Invalid(reason),
+                Ok((game, _)) if self.find_by_executable(&game.executable_path).is_some() => {
+                    CsvRowOutcome::Duplicate
+                }
+                Ok((mut game, categories)) => {
+                    for category in &categories {
+                        game.add_category(self.category_named(category));
+                    }
+                    if let Some(id) = default_category {
+                        game.add_category(id);
+                    }
+                    self.add_game(game);
+                    CsvRowOutcome::Added
+                }
+            };
+            report.rows.push(CsvRowResult {
+                line: row.line,
+                name: row.name,
+                outcome,
+            });
+        }
+        report
+    }
+
+    /// Write the given games, their categories and art into a bundle folder
+    ///
+    /// Art files that no longer exist are left out and reported. Unknown IDs
+    /// are ignored.
+    pub fn export_subset(
+        &self,
+        ids: &[GameId],
+        path: &Path,
+    ) -> Result<BundleExportReport, LibraryError> {
+        let io = |e: std::io::Error| LibraryError::Io(e.to_string());
+        let art_dir = path.join(BUNDLE_ART_DIR);
+        std::fs::create_dir_all(&art_dir).map_err(io)?;
+
+        let mut report = BundleExportReport::default();
+        let mut games = Vec::new();
+        let mut category_ids = HashSet::new();
+        for id in ids {
+            let Some(game) = self.games.get(id) else {
+                continue;
+            };
+            let mut game = game.clone();
+            let name = game.display_name();
+            let game_id = game.id;
+
+            for (kind, slot) in art_slots(&mut game) {
+                let Some(source) = slot.take() else {
+                    continue;
+                };
+                if !source.is_file() {
+                    tracing::warn!("Skipping missing art for {}: {:?}", name, source);
+                    report.missing_art.push((name.clone(), source));
+                    continue;
+                }
+                let mut file_name = format!("{}-{}", game_id.0, kind);
+                if let Some(ext) = source.extension() {
+                    file_name = format!("{}.{}", file_name, ext.to_string_lossy());
+                }
+                std::fs::copy(&source, art_dir.join(&file_name)).map_err(io)?;
+                *slot = Some(PathBuf::from(BUNDLE_ART_DIR).join(file_name));
+            }
+
+            category_ids.extend(game.categories.iter().copied());
+            games.push(game);
+        }
+
+        report.games = games.len();
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            games,
+            categories: category_ids
+                .iter()
+                .filter_map(|id| self.categories.get(id).cloned())
+                .collect(),
+        };
+        let content = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| LibraryError::Serialization(e.to_string()))?;
+        std::fs::write(path.join(BUNDLE_MANIFEST), content).map_err(io)?;
+
+        Ok(report)
+    }
+
+    /// Add the games from a bundle folder, copying their art into `art_dir`
+    ///
+    /// Games the library already has (same store ID or executable) are
+    /// skipped. Added games keep their launch settings but start with no
+    /// playtime, history or favorite, and categories are matched by name.
+    pub fn import_bundle(
+        &mut self,
+        path: &Path,
+        art_dir: &Path,
+        clock: &dyn Clock,
+    ) -> Result<BundleImportReport, LibraryError> {
+        let contents = self.read_bundle(path, art_dir, clock)?;
+        Ok(self.add_bundle(contents))
+    }
+
+    /// Read a bundle folder and copy the art of the games it would add
+    ///
+    /// This is the slow half of [`Library::import_bundle`] and leaves the
+    /// library as it is, so it can run on a copy away from the UI.
+    pub fn read_bundle(
+        &self,
+        path: &Path,
+        art_dir: &Path,
+        clock: &dyn Clock,
+    ) -> Result<BundleContents, LibraryError> {
+        let io = |e: std::io::Error| LibraryError::Io(e.to_string());
+        let content = std::fs::read_to_string(path.join(BUNDLE_MANIFEST)).map_err(io)?;
+        let manifest: BundleManifest = serde_json::from_str(&content)
+            .map_err(|e| LibraryError::Deserialization(e.to_string()))?;
+        if manifest.version > BUNDLE_VERSION {
+            return Err(LibraryError::Deserialization(format!(
+                "Bundle version {} is newer than this launcher supports",
+                manifest.version
+            )));
+        }
+        std::fs::create_dir_all(art_dir).map_err(io)?;
+
+        let mut contents = BundleContents {
+            categories: manifest.categories,
+            ..Default::default()
+        };
+        for bundled in manifest.games {
+            let name = bundled.display_name();
+            if self.has_bundled_game(&bundled) {
+                contents.duplicates.push(name);
+                continue;
+            }
+
+            let mut game = Game {
+                id: GameId::new(),
+                favorite: false,
+                hidden: false,
+                last_played: None,
+                playtime_minutes: 0,
+                added_date: clock.now(),
+                ..bundled
+            };
+            let game_id = game.id;
+
+            for (_, slot) in art_slots(&mut game) {
+                // Only the file name is trusted, so a manifest can't reach
+                // outside the bundle
+                let Some(file_name) = slot.take().and_then(|p| p.file_name().map(|f| f.to_owned()))
+                else {
+                    continue;
+                };
+                let source = path.join(BUNDLE_ART_DIR).join(&file_name);
+                if !source.is_file() {
+                    tracing::warn!("Skipping missing art for {}: {:?}", name, source);
+                    continue;
+                }
+                let dest = art_dir.join(format!("{}-{}", game_id.0, file_name.to_string_lossy()));
+                std::fs::copy(&source, &dest).map_err(io)?;
+                *slot = Some(dest);
+            }
+
+            contents.games.push(game);
+        }
+
+        Ok(contents)
+    }
+
+    /// Add the games of a bundle read by [`Library::read_bundle`]
+    ///
+    /// Games added to the library since the bundle was read are skipped
+    /// like any other duplicate.
+    pub fn add_bundle(&mut self, contents: BundleContents) -> BundleImportReport {
+        let categories: HashMap<CategoryId, CategoryId> = contents
+            .categories
+            .into_iter()
+            .map(|category| {
+                let bundled_id = category.id;
+                let fresh = Category {
+                    id: CategoryId::new(),
+                    ..category
+                };
+                (bundled_id, self.add_category(fresh, CategoryCollision::Reject))
+            })
+            .collect();
+
+        let mut report = BundleImportReport {
+            duplicates: contents.duplicates,
+            ..Default::default()
+        };
+        for mut game in contents.games {
+            let name = game.display_name();
+            if self.has_bundled_game(&game) {
+                report.duplicates.push(name);
+                continue;
+            }
+            game.categories = game
+                .categories
+                .iter()
+                .filter_map(|id| categories.get(id).copied())
+                .collect();
+            self.add_game(game);
+            report.added.push(name);
+        }
+        report
+    }
+
+    /// Whether the library already has a game from a bundle, by store ID or
+    /// executable
+    fn has_bundled_game(&self, game: &Game) -> bool {
+        let known_source = game
+            .source_id
+            .as_deref()
+            .is_some_and(|id| self.find_by_source(game.source, id).is_some());
+        known_source || self.find_by_executable(&game.executable_path).is_some()
+    }
+
+    /// Find a category by name, ignoring case, creating it if needed
+    fn category_named(&mut self, name: &str) -> CategoryId {
+        self.add_category(Category::new(name.to_string()), CategoryCollision::Reject)
+    }
+
+    /// Add a copy of a game, returning the new game's ID
+    pub fn duplicate_game(&mut self, id: &GameId, clock: &dyn Clock) -> Option<GameId> {
+        let copy = self.games.get(id)?.duplicate(clock);
+        let copy_id = copy.id;
+        self.add_game(copy);
+        Some(copy_id)
+    }
+
+    /// Fold a manually added game into the imported entry for the same game
+    ///
+    /// The imported entry survives and takes over the manual one's user data
+    /// and pin, see [`Game::absorb_manual`]. Returns false, changing nothing,
+    /// unless `manual` is a manual entry and `imported` came from a source.
+    pub fn merge_manual_into(&mut self, manual: &GameId, imported: &GameId) -> bool {
+        let is_source = |id: &GameId, manual: bool| {
+            self.games
+                .get(id)
+                .is_some_and(|g| (g.source == GameSource::Manual) == manual)
+        };
+        if !is_source(manual, true) || !is_source(imported, false) {
+            return false;
+        }
+
+        // A pinned manual entry hands its spot over, unless both were pinned
+        if self.is_pinned(imported) {
+            self.pinned.retain(|pinned| pinned != manual);
+        } else if let Some(pin) = self.pinned.iter_mut().find(|pinned| *pinned == manual) {
+            *pin = *imported;
+        }
+
+        if let Some(manual_game) = self.games.remove(manual) {
+            if let Some(game) = self.games.get_mut(imported) {
+                game.absorb_manual(manual_game);
+            }
+        }
+        true
+    }
+
     /// Remove a game from the library
     pub fn remove_game(&mut self, id: &GameId) -> Option<Game> {
+        self.pinned.retain(|pinned| pinned != id);
         self.games.remove(id)
     }
 
+    /// Pin a game to the featured row, or unpin it, returning whether it is
+    /// now pinned
+    pub fn toggle_pinned(&mut self, id: GameId) -> bool {
+        if self.is_pinned(&id) {
+            self.pinned.retain(|pinned| *pinned != id);
+            false
+        } else if self.games.contains_key(&id) {
+            self.pinned.push(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_pinned(&self, id: &GameId) -> bool {
+        self.pinned.contains(id)
+    }
+
+    /// Pinned games in pin order, skipping any that no longer exist
+    pub fn pinned_games(&self) -> Vec<&Game> {
+        self.pinned.iter().filter_map(|id| self.games.get(id)).collect()
+    }
+
     /// Get a game by ID
     pub fn get_game(&self, id: &GameId) -> Option<&Game> {
         self.games.get(id)
@@ -59,33 +472,73 @@ impl Library {
         self.games.get_mut(id)
     }
 
+    /// Find a game imported from a source by its source-specific ID
+    pub fn find_by_source(&self, source: GameSource, source_id: &str) -> Option<&Game> {
+        self.games
+            .values()
+            .find(|g| g.source == source && g.source_id.as_deref() == Some(source_id))
+    }
+
+    /// Find a game launched from the given executable
+    ///
+    /// Games that aren't installed have no executable and never match.
+    pub fn find_by_executable(&self, executable_path: &Path) -> Option<&Game> {
+        if executable_path.as_os_str().is_empty() {
+            return None;
+        }
+        self.games
+            .values()
+            .find(|g| same_path(&g.executable_path, executable_path))
+    }
+
+    /// All games launched from the given executable, sorted by name
+    pub fn games_with_executable(&self, executable_path: &Path) -> Vec<&Game> {
+        let mut games: Vec<&Game> = self
+            .games
+            .values()
+            .filter(|g| same_path(&g.executable_path, executable_path))
+            .collect();
+        games.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        games
+    }
+
+    /// Find a game that already links to a source's copy as another store
+    pub fn find_by_store_link(&self, source: GameSource, source_id: &str) -> Option<&Game> {
+        self.games
+            .values()
+            .find(|g| g.has_store_link(source, source_id))
+    }
+
+    /// Find a game from a different store whose normalized name matches
+    pub fn find_cross_store_match(&self, name: &str, source: GameSource) -> Option<&Game> {
+        if !source.is_store() {
+            return None;
+        }
+        let normalized = normalize_name(name);
+        self.games
+            .values()
+            .filter(|g| g.source != source && g.source.is_store())
+            .find(|g| normalize_name(&g.name) == normalized)
+    }
+
     /// Get all games as a vector
     pub fn all_games(&self) -> Vec<&Game> {
         self.games.values().collect()
     }
 
+    /// Games added within `within` of now, newest first
+    pub fn recently_added(&self, within: chrono::Duration, clock: &dyn Clock) -> Vec<&Game> {
+        let cutoff = clock.now() - within;
+        let mut games: Vec<&Game> =
+            self.games.values().filter(|g| g.added_date > cutoff).collect();
+        sort_games(&mut games, SortOrder::RecentlyAdded);
+        games
+    }
+
     /// Get games sorted by the specified order
     pub fn games_sorted(&self, order: SortOrder) -> Vec<&Game> {
         let mut games: Vec<&Game> = self.games.values().collect();
-
-        match order {
-            SortOrder::NameAsc => {
-                games.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-            }
-            SortOrder::NameDesc => {
-                games.sort_by(|a, b| b.name.to_lowercase().cmp(&a.name.to_lowercase()));
-            }
-            SortOrder::LastPlayed => {
-                games.sort_by(|a, b| b.last_played.cmp(&a.last_played));
-            }
-            SortOrder::RecentlyAdded => {
-                games.sort_by(|a, b| b.added_date.cmp(&a.added_date));
-            }
-            SortOrder::MostPlayed => {
-                games.sort_by(|a, b| b.playtime_minutes.cmp(&a.playtime_minutes));
-            }
-        }
-
+        sort_games(&mut games, order);
         games
     }
 
@@ -98,14 +551,68 @@ impl Library {
             .collect()
     }
 
-    /// Get games in a specific category
+    /// Get games in a specific category, assigned or matching its rule
     pub fn games_in_category(&self, category_id: &CategoryId) -> Vec<&Game> {
         self.games
             .values()
-            .filter(|g| g.has_category(category_id))
+            .filter(|g| self.in_category(g, category_id))
+            .collect()
+    }
+
+    /// [`Library::games_in_category`], with rule matches looked up in
+    /// `smart` instead of checked again
+    pub fn games_in_category_cached(
+        &self,
+        category_id: &CategoryId,
+        smart: &SmartMembers,
+    ) -> Vec<&Game> {
+        let matched = smart.get(category_id);
+        self.games
+            .values()
+            .filter(|g| g.has_category(category_id) || matched.is_some_and(|m| m.contains(&g.id)))
+            .collect()
+    }
+
+    /// The games every smart category's rule matches
+    ///
+    /// Rules can look at the disk, so this is worked out when the library
+    /// changes rather than on every redraw.
+    pub fn smart_members(&self) -> SmartMembers {
+        self.categories
+            .values()
+            .filter_map(|category| Some((category.id, category.rule.as_ref()?)))
+            .map(|(id, rule)| {
+                let matched = self.games.values().filter(|g| rule.matches(g)).map(|g| g.id);
+                (id, matched.collect())
+            })
             .collect()
     }
 
+    /// Whether a game is in a category, assigned by hand or by its rule
+    pub fn in_category(&self, game: &Game, category_id: &CategoryId) -> bool {
+        game.has_category(category_id)
+            || self
+                .categories
+                .get(category_id)
+                .and_then(|category| category.rule.as_ref())
+                .is_some_and(|rule| rule.matches(game))
+    }
+
+    /// Categories a game is in: assigned ones first, then rule matches
+    pub fn categories_of(&self, game: &Game) -> Vec<&Category> {
+        let assigned = game.categories.iter().filter_map(|id| self.categories.get(id));
+        let matched = self.categories.values().filter(|category| {
+            !game.has_category(&category.id)
+                && category.rule.as_ref().is_some_and(|rule| rule.matches(game))
+        });
+        assigned.chain(matched).collect()
+    }
+
+    /// Get games with a given play status
+    pub fn games_with_status(&self, status: PlayStatus) -> Vec<&Game> {
+        self.games.values().filter(|g| g.status == status).collect()
+    }
+
     /// Get favorite games
     pub fn favorite_games(&self) -> Vec<&Game> {
         self.games.values().filter(|g| g.favorite).collect()
@@ -116,17 +623,101 @@ impl Library {
         self.games.len()
     }
 
-    /// Add a category to the library
-    pub fn add_category(&mut self, category: Category) {
-        self.categories.insert(category.id, category);
+    /// Add a category to the library, returning the ID games should use
+    ///
+    /// If a category with the same name (ignoring case) exists, no new one is
+    /// created and the existing ID is returned; `on_collision` decides
+    /// whether the new category's color and icon are carried over.
+    pub fn add_category(
+        &mut self,
+        category: Category,
+        on_collision: CategoryCollision,
+    ) -> CategoryId {
+        let existing = self
+            .categories
+            .values_mut()
+            .find(|c| same_category_name(&c.name, &category.name));
+
+        match existing {
+            Some(existing) => {
+                if on_collision == CategoryCollision::Merge {
+                    existing.color = category.color.or(existing.color.take());
+                    existing.icon = category.icon.or(existing.icon.take());
+                }
+                existing.id
+            }
+            None => {
+                let id = category.id;
+                self.categories.insert(id, category);
+                id
+            }
+        }
+    }
+
+    /// Move every game in `remove` into `keep` and delete `remove`
+    ///
+    /// Returns false, changing nothing, if either category is missing or
+    /// they are the same.
+    pub fn merge_categories(&mut self, keep: &CategoryId, remove: &CategoryId) -> bool {
+        if keep == remove
+            || !self.categories.contains_key(keep)
+            || !self.categories.contains_key(remove)
+        {
+            return false;
+        }
+
+        for game in self.games.values_mut() {
+            if game.has_category(remove) {
+                game.remove_category(remove);
+                game.add_category(*keep);
+            }
+        }
+        for rule in self.categories.values_mut().filter_map(|c| c.rule.as_mut()) {
+            for condition in &mut rule.conditions {
+                if *condition == RuleCondition::Tag(*remove) {
+                    *condition = RuleCondition::Tag(*keep);
+                }
+            }
+        }
+        self.categories.remove(remove);
+        true
+    }
+
+    /// Merge categories that share a name, returning how many were removed
+    ///
+    /// Libraries saved before names were checked can hold duplicates. The
+    /// category with the most games survives.
+    pub fn merge_duplicate_categories(&mut self) -> usize {
+        let mut ids: Vec<CategoryId> = self.categories.keys().copied().collect();
+        // Most used first, so it is the one kept
+        ids.sort_by_key(|id| {
+            (Reverse(self.games_in_category(id).len()), id.0)
+        });
+
+        let mut merged = 0;
+        for (i, keep) in ids.iter().enumerate() {
+            for remove in &ids[i + 1..] {
+                let same_name = match (self.categories.get(keep), self.categories.get(remove)) {
+                    (Some(a), Some(b)) => same_category_name(&a.name, &b.name),
+                    _ => false,
+                };
+                if same_name && self.merge_categories(keep, remove) {
+                    merged += 1;
+                }
+            }
+        }
+        merged
     }
 
     /// Remove a category from the library
     pub fn remove_category(&mut self, id: &CategoryId) -> Option<Category> {
-        // Also remove this category from all games
+        // Also remove this category from all games, and from rules naming it
         for game in self.games.values_mut() {
             game.remove_category(id);
         }
+        for rule in self.categories.values_mut().filter_map(|c| c.rule.as_mut()) {
+            rule.conditions.retain(|condition| *condition != RuleCondition::Tag(*id));
+        }
         self.categories.remove(id)
     }
 
@@ -135,6 +726,19 @@ impl Library {
         self.categories.get(id)
     }
 
+    /// Get a mutable category by ID
+    pub fn get_category_mut(&mut self, id: &CategoryId) -> Option<&mut Category> {
+        self.categories.get_mut(id)
+    }
+
+    /// The sort a filter brings with it, when it is a category that has one
+    pub fn category_sort(&self, filter: Option<Filter>) -> Option<SortOrder> {
+        match filter {
+            Some(Filter::Category(id)) => self.get_category(&id)?.default_sort,
+            _ => None,
+        }
+    }
+
     /// Get all categories as a vector
     pub fn all_categories(&self) -> Vec<&Category> {
         self.categories.values().collect()
@@ -142,55 +746,218 @@ impl Library {
 
     /// Save the library to a JSON file
     pub async fn save_to_file(&self, path: &Path) -> Result<(), LibraryError> {
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| LibraryError::Serialization(e.to_string()))?;
-
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .await
-                .map_err(|e| LibraryError::Io(e.to_string()))?;
-        }
-
-        let mut file = fs::File::create(path)
-            .await
-            .map_err(|e| LibraryError::Io(e.to_string()))?;
-
-        file.write_all(json.as_bytes())
-            .await
-            .map_err(|e| LibraryError::Io(e.to_string()))?;
-
-        tracing::info!("Library saved to {:?}", path);
-        Ok(())
+        FileStore::new(path).save(self).await
     }
 
     /// Load the library from a JSON file
     pub async fn load_from_file(path: &Path) -> Result<Self, LibraryError> {
-        let content = fs::read_to_string(path)
-            .await
-            .map_err(|e| LibraryError::Io(e.to_string()))?;
+        FileStore::new(path).load().await
+    }
 
-        let library: Library = serde_json::from_str(&content)
-            .map_err(|e| LibraryError::Deserialization(e.to_string()))?;
+    /// Save the library encrypted with `passphrase`
+    pub async fn save_to_file_encrypted(
+        &self,
+        path: &Path,
+        passphrase: &str,
+    ) -> Result<(), LibraryError> {
+        EncryptedFileStore::new(path, passphrase).save(self).await
+    }
 
-        tracing::info!("Library loaded from {:?} ({} games)", path, library.game_count());
-        Ok(library)
+    /// Load the library, decrypting it when the file is encrypted
+    ///
+    /// Plaintext files go through [`Library::load_or_create`]. An encrypted
+    /// file without the right passphrase is an error: resetting it would
+    /// overwrite the user's data on the next save.
+    pub async fn load_or_create_protected(
+        path: &Path,
+        passphrase: Option<&str>,
+        first_run: bool,
+        clock: &dyn Clock,
+    ) -> Result<(Self, Option<PathBuf>), LibraryError> {
+        if !is_encrypted_file(path).await {
+            return Self::load_or_create(path, first_run, clock).await;
+        }
+        match passphrase {
+            Some(passphrase) => {
+                let library = EncryptedFileStore::new(path, passphrase).load().await?;
+                Ok((library, None))
+            }
+            None => Err(LibraryError::PassphraseRequired),
+        }
     }
 
     /// Load from file or create new if file doesn't exist
-    pub async fn load_or_create(path: &Path) -> Self {
-        match Self::load_from_file(path).await {
+    ///
+    /// A file that exists but can't be parsed (say, a stray comma from hand
+    /// editing) is moved aside to `<name>.corrupt-<timestamp>.json` first so
+    /// the next save can't destroy it; its new path is returned, and the
+    /// timestamp comes from `clock`. A file that can't be read or moved aside
+    /// is an error, never replaced by a new library. The new library only
+    /// gets the default categories on the `first_run`.
+    pub async fn load_or_create(
+        path: &Path,
+        first_run: bool,
+        clock: &dyn Clock,
+    ) -> Result<(Self, Option<PathBuf>), LibraryError> {
+        match FileStore::new(path).load().await {
+            Ok(library) => Ok((library, None)),
+            Err(LibraryError::Deserialization(e)) => {
+                let quarantine = quarantine_path(path, clock.now());
+                if let Err(rename_error) = tokio::fs::rename(path, &quarantine).await {
+                    tracing::error!(
+                        "Library file {:?} is corrupt ({}) and could not be moved aside: {}",
+                        path,
+                        e,
+                        rename_error
+                    );
+                    return Err(LibraryError::Io(format!(
+                        "{} is corrupt and could not be moved aside: {}",
+                        path.display(),
+                        rename_error
+                    )));
+                }
+                tracing::error!(
+                    "Library file {:?} is corrupt ({}). Moved it to {:?} and started a new library.",
+                    path,
+                    e,
+                    quarantine
+                );
+                Ok((Self::fresh(first_run), Some(quarantine)))
+            }
+            Err(e) => match tokio::fs::metadata(path).await {
+                Err(missing) if missing.kind() == std::io::ErrorKind::NotFound => {
+                    tracing::info!("No library at {:?}, creating a new one", path);
+                    Ok((Self::fresh(first_run), None))
+                }
+                _ => {
+                    tracing::error!("Could not load library {:?}: {}", path, e);
+                    Err(e)
+                }
+            },
+        }
+    }
+
+    /// Load from a store or create new if nothing can be loaded
+    pub async fn load_or_create_from(store: &impl LibraryStore, first_run: bool) -> Self {
+        match store.load().await {
             Ok(library) => library,
             Err(e) => {
                 tracing::warn!("Could not load library: {}. Creating new library.", e);
-                Self::new()
+                Self::fresh(first_run)
             }
         }
     }
 }
 
+/// Where a corrupt library file is moved, next to the original
+fn quarantine_path(path: &Path, now: chrono::DateTime<Utc>) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.corrupt-{}.json", stem, now.format("%Y%m%d-%H%M%S")))
+}
+
+/// Sort games by `order`, breaking ties by name and then ID
+///
+/// Without the tiebreakers, games with equal keys (e.g. never played) would
+/// come out in HashMap order and reshuffle on every launch.
+pub fn sort_games(games: &mut [&Game], order: SortOrder) {
+    // Keys are worked out once per game, not on every comparison
+    games.sort_by_cached_key(|&game| {
+        let name = game.name.to_lowercase();
+        let primary = match order {
+            SortOrder::NameAsc => SortKey::None,
+            SortOrder::NameDesc => SortKey::Name(Reverse(name.clone())),
+            SortOrder::LastPlayed => SortKey::Date(Reverse(game.last_played)),
+            SortOrder::RecentlyAdded => SortKey::Date(Reverse(Some(game.added_date))),
+            SortOrder::MostPlayed => SortKey::Minutes(Reverse(game.playtime_minutes)),
+        };
+        (primary, name, game.name.as_str(), game.id)
+    });
+}
+
+/// The part of a game's sort key that depends on the order, newest and
+/// biggest first
+///
+/// Every game in one sort gets the same variant.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    None,
+    Name(Reverse<String>),
+    Date(Reverse<Option<DateTime<Utc>>>),
+    Minutes(Reverse<u64>),
+}
+
+/// Split already-sorted games into titled sections, keeping their order
+///
+/// With [`GroupBy::Category`] a game shows up under each of its categories.
+/// [`GroupBy::None`] yields a single untitled section.
+pub fn group_games<'a>(
+    library: &Library,
+    games: &[&'a Game],
+    group_by: GroupBy,
+) -> Vec<(String, Vec<&'a Game>)> {
+    // (sort key, title) for each section a game belongs to
+    let sections_of = |game: &Game| -> Vec<(String, String)> {
+        match group_by {
+            GroupBy::None => vec![(String::new(), String::new())],
+            GroupBy::Source => {
+                let rank = GameSource::all()
+                    .iter()
+                    .position(|s| *s == game.source)
+                    .unwrap_or_default();
+                vec![(format!("{:02}", rank), game.source.label().to_string())]
+            }
+            GroupBy::Category => {
+                let mut names: Vec<String> = library
+                    .categories_of(game)
+                    .into_iter()
+                    .map(|cat| cat.name.clone())
+                    .collect();
+                if names.is_empty() {
+                    // Sort uncategorized games last
+                    return vec![("\u{10FFFF}".to_string(), "Uncategorized".to_string())];
+                }
+                names.dedup();
+                names
+                    .into_iter()
+                    .map(|name| (name.to_lowercase(), name))
+                    .collect()
+            }
+            GroupBy::FirstLetter => {
+                let letter = game
+                    .display_name()
+                    .chars()
+                    .next()
+                    .filter(|c| c.is_alphabetic())
+                    .map(|c| c.to_uppercase().to_string())
+                    .unwrap_or_else(|| "#".to_string());
+                vec![(letter.clone(), letter)]
+            }
+            GroupBy::Installed => {
+                if game.is_installed() {
+                    vec![("0".to_string(), "Installed".to_string())]
+                } else {
+                    vec![("1".to_string(), "Not installed".to_string())]
+                }
+            }
+        }
+    };
+
+    let mut sections: std::collections::BTreeMap<(String, String), Vec<&'a Game>> =
+        std::collections::BTreeMap::new();
+    for game in games {
+        for key in sections_of(game) {
+            sections.entry(key).or_default().push(game);
+        }
+    }
+
+    sections
+        .into_iter()
+        .map(|((_, title), games)| (title, games))
+        .collect()
+}
+
 /// Errors that can occur with library operations
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum LibraryError {
     #[error("IO error: {0}")]
     Io(String),
@@ -200,4 +967,464 @@ pub enum LibraryError {
 
     #[error("Deserialization error: {0}")]
     Deserialization(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("The library is encrypted and needs a passphrase")]
+    PassphraseRequired,
+
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{GameField, MockClock, SmartRule};
+    use std::path::PathBuf;
+
+    fn library_with(names: &[&str]) -> Library {
+        let mut library = Library::new();
+        let clock = MockClock::fixed();
+        for name in names {
+            let game = Game::new(name.to_string(), PathBuf::from(name), GameSource::Manual, &clock);
+            library.add_game(game);
+        }
+        library
+    }
+
+    fn names(games: &[&Game]) -> Vec<String> {
+        games.iter().map(|g| g.name.clone()).collect()
+    }
+
+    fn category_id(library: &Library, name: &str) -> CategoryId {
+        library
+            .all_categories()
+            .into_iter()
+            .find(|c| c.name == name)
+            .map(|c| c.id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_add_category_name_collision() {
+        let mut library = Library::new();
+        let count = library.all_categories().len();
+        let rpg = category_id(&library, "RPG");
+
+        let id =
+            library.add_category(Category::new(" rpg ".to_string()), CategoryCollision::Reject);
+        assert_eq!(id, rpg);
+        assert_eq!(library.all_categories().len(), count);
+        assert_eq!(library.get_category(&rpg).unwrap().color.as_deref(), Some("#9B59B6"));
+
+        let recolored = Category::with_color("Rpg".to_string(), "#000000".to_string());
+        assert_eq!(library.add_category(recolored, CategoryCollision::Merge), rpg);
+        assert_eq!(library.all_categories().len(), count);
+        let merged = library.get_category(&rpg).unwrap();
+        assert_eq!(merged.name, "RPG");
+        assert_eq!(merged.color.as_deref(), Some("#000000"));
+
+        let roguelike = Category::new("Roguelike".to_string());
+        let new = library.add_category(roguelike, CategoryCollision::Reject);
+        assert_ne!(new, rpg);
+        assert_eq!(library.all_categories().len(), count + 1);
+    }
+
+    #[test]
+    fn test_category_sort_only_under_its_filter() {
+        let mut library = Library::new();
+        let rpg = category_id(&library, "RPG");
+        let action = category_id(&library, "Action");
+        library.get_category_mut(&rpg).unwrap().default_sort = Some(SortOrder::MostPlayed);
+
+        let sort = library.category_sort(Some(Filter::Category(rpg)));
+        assert_eq!(sort, Some(SortOrder::MostPlayed));
+        assert_eq!(library.category_sort(Some(Filter::Category(action))), None);
+        assert_eq!(library.category_sort(Some(Filter::Status(PlayStatus::Playing))), None);
+        assert_eq!(library.category_sort(None), None);
+    }
+
+    #[test]
+    fn test_merge_categories_moves_games() {
+        let mut library = library_with(&["Doom", "Quake"]);
+        let action = category_id(&library, "Action");
+        // A duplicate from before names were checked
+        let twin = Category::new("action".to_string());
+        let twin_id = twin.id;
+        library.categories.insert(twin_id, twin);
+
+        let doom = library.find_by_executable(Path::new("Doom")).unwrap().id;
+        let quake = library.find_by_executable(Path::new("Quake")).unwrap().id;
+        library.get_game_mut(&doom).unwrap().add_category(twin_id);
+        library.get_game_mut(&quake).unwrap().add_category(twin_id);
+        library.get_game_mut(&quake).unwrap().add_category(action);
+
+        // The twin has more games, so it is the one kept
+        assert_eq!(library.merge_duplicate_categories(), 1);
+        assert!(library.get_category(&action).is_none());
+        assert_eq!(library.games_in_category(&twin_id).len(), 2);
+        assert_eq!(library.get_game(&quake).unwrap().categories, vec![twin_id]);
+
+        assert!(!library.merge_categories(&twin_id, &twin_id));
+        assert!(!library.merge_categories(&twin_id, &action));
+    }
+
+    #[test]
+    fn test_merge_manual_into_imported_keeps_user_data() {
+        let mut library = Library::new();
+        let rpg = category_id(&library, "RPG");
+        let clock = MockClock::fixed();
+        let mut manual =
+            Game::new("Hades".to_string(), PathBuf::from("hades.exe"), GameSource::Manual, &clock);
+        manual.mark_user_modified(GameField::LaunchArgs);
+        manual.launch_args = Some("-windowed".to_string());
+        manual.add_category(rpg);
+        manual.favorite = true;
+        manual.banner_path = Some(PathBuf::from("custom.png"));
+        manual.playtime_minutes = 30;
+        let mut imported = Game::from_import(
+            "Hades".to_string(),
+            PathBuf::from("steam/Hades.exe"),
+            PathBuf::from("steam"),
+            GameSource::Steam,
+            "1145360".to_string(),
+            &clock,
+        );
+        imported.playtime_minutes = 600;
+        let (manual_id, imported_id) = (manual.id, imported.id);
+        library.add_game(manual);
+        library.add_game(imported);
+        library.toggle_pinned(manual_id);
+
+        // The wrong way round is refused
+        assert!(!library.merge_manual_into(&imported_id, &manual_id));
+        assert!(library.merge_manual_into(&manual_id, &imported_id));
+
+        assert!(library.get_game(&manual_id).is_none());
+        let game = library.get_game(&imported_id).unwrap();
+        assert_eq!(game.source_id.as_deref(), Some("1145360"));
+        assert_eq!(game.executable_path, PathBuf::from("steam/Hades.exe"));
+        assert_eq!(game.launch_args.as_deref(), Some("-windowed"));
+        assert!(game.is_user_modified(GameField::LaunchArgs));
+        assert!(game.has_category(&rpg) && game.favorite);
+        assert_eq!(game.banner_path, Some(PathBuf::from("custom.png")));
+        assert_eq!(game.playtime_minutes, 630);
+        assert_eq!(names(&library.pinned_games()), ["Hades"]);
+
+        // A manual link into Steam was played through Steam, which counted it
+        let mut link = Game::new(
+            "Hades".to_string(),
+            PathBuf::from("steam://rungameid/1145360"),
+            GameSource::Manual,
+            &clock,
+        );
+        link.playtime_minutes = 700;
+        let link_id = link.id;
+        library.add_game(link);
+        assert!(library.merge_manual_into(&link_id, &imported_id));
+        assert_eq!(library.get_game(&imported_id).unwrap().playtime_minutes, 700);
+    }
+
+    #[test]
+    fn test_smart_rule_adds_matching_games() {
+        let mut library = library_with(&["Portal", "Hades", "Celeste"]);
+        let rpg = category_id(&library, "RPG");
+        let id_of = |library: &Library, name: &str| {
+            library.all_games().into_iter().find(|g| g.name == name).unwrap().id
+        };
+        let (portal, hades) = (id_of(&library, "Portal"), id_of(&library, "Hades"));
+        library.get_game_mut(&portal).unwrap().source = GameSource::Steam;
+        library.get_game_mut(&portal).unwrap().playtime_minutes = 120;
+        library.get_game_mut(&hades).unwrap().favorite = true;
+        library.get_game_mut(&hades).unwrap().add_category(rpg);
+
+        let mut unplayed = Category::new("Unplayed".to_string());
+        unplayed.rule = Some(SmartRule {
+            conditions: vec![RuleCondition::PlaytimeAtMost(0)],
+        });
+        let unplayed = library.add_category(unplayed, CategoryCollision::Reject);
+        let mut steam = Category::new("Steam".to_string());
+        steam.rule = Some(SmartRule {
+            conditions: vec![RuleCondition::Source(GameSource::Steam)],
+        });
+        let steam = library.add_category(steam, CategoryCollision::Reject);
+        let mut loved = Category::new("Loved RPGs".to_string());
+        loved.rule = Some(SmartRule {
+            conditions: vec![RuleCondition::Favorite(true), RuleCondition::Tag(rpg)],
+        });
+        let loved = library.add_category(loved, CategoryCollision::Reject);
+        let empty = library.add_category(
+            Category {
+                rule: Some(SmartRule::default()),
+                ..Category::new("Empty rule".to_string())
+            },
+            CategoryCollision::Reject,
+        );
+
+        let mut in_unplayed = names(&library.games_in_category(&unplayed));
+        in_unplayed.sort();
+        assert_eq!(in_unplayed, ["Celeste", "Hades"]);
+        assert_eq!(names(&library.games_in_category(&steam)), ["Portal"]);
+        assert_eq!(names(&library.games_in_category(&loved)), ["Hades"]);
+        assert!(library.games_in_category(&empty).is_empty());
+
+        // Explicit members stay alongside the rule's matches
+        library.get_game_mut(&portal).unwrap().add_category(loved);
+        assert_eq!(library.games_in_category(&loved).len(), 2);
+
+        // The cached rule matches give the same members
+        let smart = library.smart_members();
+        assert!(!smart.contains_key(&rpg));
+        for category in [unplayed, steam, loved, empty] {
+            let mut cached = names(&library.games_in_category_cached(&category, &smart));
+            let mut checked = names(&library.games_in_category(&category));
+            cached.sort();
+            checked.sort();
+            assert_eq!(cached, checked);
+        }
+
+        let hades = library.get_game(&hades).unwrap();
+        let mut hades_categories: Vec<&str> =
+            library.categories_of(hades).iter().map(|c| c.name.as_str()).collect();
+        hades_categories.sort();
+        assert_eq!(hades_categories, ["Loved RPGs", "RPG", "Unplayed"]);
+
+        // Removing a category drops it from the rules naming it
+        library.remove_category(&rpg);
+        let rule = library.get_category(&loved).unwrap().rule.as_ref().unwrap();
+        assert_eq!(rule.conditions, [RuleCondition::Favorite(true)]);
+    }
+
+    #[test]
+    fn test_pinned_games_keep_pin_order() {
+        let mut library = library_with(&["Doom", "Quake", "Hexen"]);
+        let id = |library: &Library, name: &str| {
+            library.find_by_executable(Path::new(name)).unwrap().id
+        };
+        let doom = id(&library, "Doom");
+        let quake = id(&library, "Quake");
+        let hexen = id(&library, "Hexen");
+
+        assert!(library.toggle_pinned(quake));
+        assert!(library.toggle_pinned(doom));
+        assert!(library.toggle_pinned(hexen));
+        assert!(!library.toggle_pinned(hexen));
+        assert!(!library.toggle_pinned(GameId::new()));
+        assert_eq!(names(&library.pinned_games()), vec!["Quake", "Doom"]);
+
+        library.remove_game(&quake);
+        assert_eq!(library.pinned, vec![doom]);
+    }
+
+    #[test]
+    fn test_export_and_import_subset() {
+        let dir = std::env::temp_dir().join(format!("launcher-bundle-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cover = dir.join("cover.png");
+        std::fs::write(&cover, b"png").unwrap();
+
+        let mut source = library_with(&["Doom", "Quake", "Hexen"]);
+        let rpg = category_id(&source, "RPG");
+        let doom = source.find_by_executable(Path::new("Doom")).unwrap().id;
+        let quake = source.find_by_executable(Path::new("Quake")).unwrap().id;
+        {
+            let doom = source.get_game_mut(&doom).unwrap();
+            doom.launch_args = Some("-fast".to_string());
+            doom.banner_path = Some(cover.clone());
+            doom.icon_path = Some(dir.join("gone.ico"));
+            doom.add_category(rpg);
+            doom.add_playtime(90);
+        }
+
+        let bundle = dir.join("bundle");
+        let export = source.export_subset(&[doom, quake], &bundle).unwrap();
+        assert_eq!(export.games, 2);
+        assert_eq!(export.missing_art, vec![("Doom".to_string(), dir.join("gone.ico"))]);
+
+        // Quake is already there, Doom comes in with its settings and art
+        let mut target = library_with(&["Quake"]);
+        let art_dir = dir.join("art");
+        let clock = MockClock::fixed();
+        let import = target.import_bundle(&bundle, &art_dir, &clock).unwrap();
+        assert_eq!(import.added, vec!["Doom"]);
+        assert_eq!(import.duplicates, vec!["Quake"]);
+
+        let imported = target.find_by_executable(Path::new("Doom")).unwrap();
+        assert_ne!(imported.id, doom);
+        assert_eq!(imported.launch_args.as_deref(), Some("-fast"));
+        assert_eq!(imported.playtime_minutes, 0);
+        assert_eq!(imported.icon_path, None);
+        let banner = imported.banner_path.as_ref().unwrap();
+        assert!(banner.starts_with(&art_dir));
+        assert_eq!(std::fs::read(banner).unwrap(), b"png");
+        assert_eq!(imported.categories, vec![category_id(&target, "RPG")]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_import_csv_reports_each_row() {
+        let dir = std::env::temp_dir().join(format!("launcher-csv-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe = |name: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, b"").unwrap();
+            path.display().to_string()
+        };
+        let (doom, quake) = (exe("doom.exe"), exe("quake.exe"));
+        let csv = dir.join("games.csv");
+        std::fs::write(
+            &csv,
+            format!(
+                "Executable,Name,Tags\n\
+                 {doom},\"Doom, Ultimate\",Shooter; retro\n\
+                 {quake},,\n\
+                 {missing},Missing,\n\
+                 {doom},Doom again,\n",
+                missing = dir.join("missing.exe").display(),
+            ),
+        )
+        .unwrap();
+
+        let mut library = Library::new();
+        let rpg = category_id(&library, "RPG");
+        let report = library.import_csv(&csv, Some(rpg), &MockClock::fixed()).unwrap();
+        let outcomes: Vec<_> = report.rows.iter().map(|r| (r.line, r.outcome.clone())).collect();
+        assert_eq!(outcomes[0], (2, CsvRowOutcome::Added));
+        assert!(matches!(outcomes[1], (3, CsvRowOutcome::Invalid(_))));
+        assert!(matches!(outcomes[2], (4, CsvRowOutcome::Invalid(_))));
+        assert_eq!(outcomes[3], (5, CsvRowOutcome::Duplicate));
+        assert_eq!(report.added(), 1);
+
+        let doom = library.find_by_executable(Path::new(&doom)).unwrap();
+        assert_eq!(doom.name, "Doom, Ultimate");
+        assert_eq!(doom.source, GameSource::Manual);
+        let tags: Vec<_> = doom
+            .categories
+            .iter()
+            .filter_map(|id| library.get_category(id))
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(tags, ["Shooter", "retro", "RPG"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_games_with_executable_finds_collisions() {
+        let mut library = Library::new();
+        let clock = MockClock::fixed();
+        let manual =
+            Game::new("Doom".to_string(), PathBuf::from("games/doom/"), GameSource::Manual, &clock);
+        library.add_game(manual);
+        let mut imported = Game::new(
+            "Doom (Steam)".to_string(),
+            PathBuf::from(" games//doom"),
+            GameSource::Steam,
+            &clock,
+        );
+        imported.source_id = Some("2280".to_string());
+        library.add_game(imported);
+
+        let sharing = library.games_with_executable(Path::new("games/doom"));
+        assert_eq!(names(&sharing), ["Doom", "Doom (Steam)"]);
+        assert!(library.games_with_executable(Path::new("Quake")).is_empty());
+    }
+
+    #[test]
+    fn test_most_played_ties_sort_by_name() {
+        let mut library = library_with(&["Celeste", "axiom verge", "Braid", "Doom"]);
+        let doom = library.games.values().find(|g| g.name == "Doom").unwrap().id;
+        library.get_game_mut(&doom).unwrap().add_playtime(30);
+
+        let sorted = library.games_sorted(SortOrder::MostPlayed);
+        assert_eq!(names(&sorted), ["Doom", "axiom verge", "Braid", "Celeste"]);
+    }
+
+    #[test]
+    fn test_group_by_first_letter_keeps_sort_order() {
+        let library = library_with(&["Braid", "bastion", "Celeste", "7 Days"]);
+        let sorted = library.games_sorted(SortOrder::NameAsc);
+        let groups = group_games(&library, &sorted, GroupBy::FirstLetter);
+
+        let titles: Vec<&str> = groups.iter().map(|(title, _)| title.as_str()).collect();
+        assert_eq!(titles, ["#", "B", "C"]);
+        assert_eq!(names(&groups[1].1), ["bastion", "Braid"]);
+    }
+
+    #[test]
+    fn test_group_by_category_lists_uncategorized_last() {
+        let mut library = library_with(&["Doom", "Tetris"]);
+        let action = library
+            .all_categories()
+            .into_iter()
+            .find(|c| c.name == "Action")
+            .unwrap()
+            .id;
+        let doom = library.games.values().find(|g| g.name == "Doom").unwrap().id;
+        library.get_game_mut(&doom).unwrap().add_category(action);
+
+        let sorted = library.games_sorted(SortOrder::NameAsc);
+        let groups = group_games(&library, &sorted, GroupBy::Category);
+        let titles: Vec<&str> = groups.iter().map(|(title, _)| title.as_str()).collect();
+        assert_eq!(titles, ["Action", "Uncategorized"]);
+        assert_eq!(names(&groups[1].1), ["Tetris"]);
+    }
+
+    #[test]
+    fn test_recently_added() {
+        let clock = MockClock::fixed();
+        let mut library = library_with(&["Old", "Newer", "Newest"]);
+        for game in library.games.values_mut() {
+            let days = match game.name.as_str() {
+                "Old" => 30,
+                "Newer" => 2,
+                _ => 0,
+            };
+            game.added_date = clock.now() - chrono::Duration::days(days);
+        }
+        clock.advance(chrono::Duration::minutes(1));
+
+        let week = chrono::Duration::days(7);
+        assert_eq!(names(&library.recently_added(week, &clock)), ["Newest", "Newer"]);
+        assert!(library.recently_added(chrono::Duration::zero(), &clock).is_empty());
+
+        // Newer falls out once it's a week old
+        clock.advance(chrono::Duration::days(5));
+        assert_eq!(names(&library.recently_added(week, &clock)), ["Newest"]);
+    }
+
+    #[test]
+    fn test_sort_is_stable_across_calls() {
+        let library = library_with(&["Same", "Same", "Same", "Other"]);
+        let ids = || -> Vec<GameId> {
+            library
+                .games_sorted(SortOrder::LastPlayed)
+                .iter()
+                .map(|g| g.id)
+                .collect()
+        };
+        assert_eq!(ids(), ids());
+        assert_eq!(library.games_sorted(SortOrder::LastPlayed)[0].name, "Other");
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let mut library = library_with(&["beta", "Alpha", "Gamma"]);
+        let id = |library: &Library, name: &str| {
+            library.games.values().find(|g| g.name == name).unwrap().id
+        };
+        let beta = id(&library, "beta");
+        let gamma = id(&library, "Gamma");
+        library.get_game_mut(&beta).unwrap().playtime_minutes = 30;
+        library.get_game_mut(&gamma).unwrap().last_played = Some(MockClock::fixed().now());
+
+        // Case doesn't decide the order
+        assert_eq!(names(&library.games_sorted(SortOrder::NameAsc)), ["Alpha", "beta", "Gamma"]);
+        assert_eq!(names(&library.games_sorted(SortOrder::NameDesc)), ["Gamma", "beta", "Alpha"]);
+        assert_eq!(names(&library.games_sorted(SortOrder::MostPlayed)), ["beta", "Alpha", "Gamma"]);
+        assert_eq!(names(&library.games_sorted(SortOrder::LastPlayed)), ["Gamma", "Alpha", "beta"]);
+    }
 }
@@ -1,9 +1,46 @@
 mod game;
+mod activity;
+mod archive;
+mod bundle;
 mod library;
 mod category;
+mod clock;
 mod config;
+mod crypto;
+mod csv;
+mod data_dir;
+mod exclude;
+mod executables;
+mod names;
+mod paths;
+mod saves;
+mod search;
+mod stats;
+mod store;
 
-pub use game::{Game, GameId, GameSource};
-pub use library::Library;
-pub use category::{Category, CategoryId};
-pub use config::Config;
+pub use game::{
+    truncate_display_name, ArtShape, DisplayPrefs, Game, GameField, GameId, GameSource,
+    PlayStatus, WindowModePref,
+};
+pub use activity::{append_activity, delete_activity, read_activity, ActivityEntry, ActivityKind};
+pub use library::{group_games, sort_games, Library, LibraryError, SmartMembers};
+pub use store::write_file;
+pub use bundle::{BundleContents, BundleExportReport, BundleImportReport};
+pub use category::{Category, CategoryCollision, CategoryId, RuleCondition, SmartRule};
+#[cfg(test)]
+pub use clock::MockClock;
+pub use clock::{Clock, SystemClock};
+pub use config::{CardStyle, Config, Density, LaunchWindowBehavior};
+pub use crypto::{hash_pin, verify_pin};
+pub use csv::{CsvContents, CsvImportReport, CsvRowOutcome};
+pub use data_dir::{
+    choose_data_dir, probe_private, probe_writable, temp_data_dir, DataDir, DataDirStatus,
+    RecentDataDirs,
+};
+pub use exclude::ExcludePatterns;
+pub use executables::{executable_candidates, find_executable_in_dir};
+pub use names::normalize_name;
+pub use paths::{find_path_ignoring_case, is_uri, normalize_path, same_path};
+pub use saves::{backup_saves, guess_save_folders, list_save_backups, restore_saves, SaveBackup};
+pub use search::SearchIndex;
+pub use stats::{weekday_name, PlayStats};
@@ -0,0 +1,87 @@
+/// Edition suffixes that don't distinguish one game from another, as word sequences
+const EDITION_SUFFIXES: &[&[&str]] = &[
+    &["game", "of", "the", "year", "edition"],
+    &["game", "of", "the", "year"],
+    &["goty", "edition"],
+    &["goty"],
+    &["definitive", "edition"],
+    &["complete", "edition"],
+    &["deluxe", "edition"],
+    &["enhanced", "edition"],
+    &["special", "edition"],
+    &["gold", "edition"],
+    &["ultimate", "edition"],
+    &["standard", "edition"],
+    &["anniversary", "edition"],
+    &["director", "s", "cut"],
+    &["directors", "cut"],
+    &["edition"],
+];
+
+/// Roman numerals (2 to 20) that are rewritten as digits
+const ROMAN_NUMERALS: &[&str] = &[
+    "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x", "xi", "xii", "xiii", "xiv", "xv",
+    "xvi", "xvii", "xviii", "xix", "xx",
+];
+
+/// Normalize a game name for matching the same game across stores
+///
+/// Lowercases, drops trademark symbols and punctuation, strips edition
+/// suffixes like "Game of the Year Edition" and turns roman numerals into
+/// digits, so "DARK SOULS™ III: Deluxe Edition" matches "Dark Souls 3".
+pub fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+
+    // Strip suffixes repeatedly, e.g. "... Complete Edition GOTY"
+    while let Some(suffix) = EDITION_SUFFIXES
+        .iter()
+        .find(|suffix| words.len() > suffix.len() && words.ends_with(suffix))
+    {
+        words.truncate(words.len() - suffix.len());
+    }
+
+    words
+        .iter()
+        .map(|word| match ROMAN_NUMERALS.iter().position(|r| r == word) {
+            Some(index) => (index + 2).to_string(),
+            None => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_strips_symbols_and_editions() {
+        assert_eq!(
+            normalize_name("DARK SOULS™ III: Deluxe Edition"),
+            normalize_name("Dark Souls 3")
+        );
+        assert_eq!(
+            normalize_name("The Witcher® 3: Wild Hunt - Game of the Year Edition"),
+            "the witcher 3 wild hunt"
+        );
+    }
+
+    #[test]
+    fn test_normalize_roman_numerals() {
+        assert_eq!(normalize_name("Grand Theft Auto V"), "grand theft auto 5");
+        assert_eq!(normalize_name("Final Fantasy XIV"), "final fantasy 14");
+        // Words that merely look roman are left alone
+        assert_eq!(normalize_name("Mix Master"), "mix master");
+    }
+
+    #[test]
+    fn test_normalize_keeps_name_that_is_only_a_suffix() {
+        assert_eq!(normalize_name("Edition"), "edition");
+    }
+}
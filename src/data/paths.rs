@@ -0,0 +1,217 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Normalize a path found in a launcher manifest or registry
+///
+/// Trims whitespace, unifies separators for the current OS, collapses
+/// repeated separators, drops a trailing separator and upper-cases Windows
+/// drive letters, so the same location always compares equal. Windows
+/// verbatim paths (`\\?\C:\…`, `\\?\UNC\server\…`) become their plain
+/// forms.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    PathBuf::from(normalize_path_str(
+        &path.to_string_lossy(),
+        cfg!(target_os = "windows"),
+    ))
+}
+
+/// Whether two paths point at the same location once normalized
+///
+/// Windows paths compare case-insensitively.
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    let a = normalize_path(a);
+    let b = normalize_path(b);
+    if cfg!(target_os = "windows") {
+        a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// Find `path` on disk, matching each part of it regardless of case
+///
+/// Windows games, often run through Proton, can name a file differently
+/// from what their manifest says (`Game.EXE` for `game.exe`), which a
+/// case-sensitive Linux filesystem won't find. When the exact path is
+/// missing, each folder along it is searched for an entry whose name only
+/// differs in case. Elsewhere the filesystem already ignores case, so only
+/// the exact path is checked.
+pub fn find_path_ignoring_case(path: &Path) -> Option<PathBuf> {
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+
+    let mut found = PathBuf::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            found.push(component);
+            continue;
+        };
+        let exact = found.join(name);
+        if exact.exists() {
+            found = exact;
+            continue;
+        }
+        let dir = if found.as_os_str().is_empty() { Path::new(".") } else { &found };
+        let wanted = name.to_string_lossy().to_lowercase();
+        // Sorted, so a folder holding both `Game.exe` and `GAME.EXE` always
+        // picks the same one
+        let mut matches: Vec<_> = std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.file_name())
+            .filter(|entry| entry.to_string_lossy().to_lowercase() == wanted)
+            .collect();
+        matches.sort();
+        found.push(matches.first()?);
+    }
+    Some(found)
+}
+
+fn normalize_path_str(path: &str, windows: bool) -> String {
+    let separator = if windows { '\\' } else { '/' };
+    let path = path.trim();
+    let verbatim = if windows { path.strip_prefix("\\\\?\\") } else { None };
+    let path = match verbatim {
+        Some(rest) if rest.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("UNC\\")) => {
+            format!("\\\\{}", &rest[4..])
+        }
+        Some(rest) => rest.to_string(),
+        None => path.to_string(),
+    };
+
+    // Keep the double separator that starts a UNC path
+    let unc = windows && (path.starts_with("\\\\") || path.starts_with("//"));
+
+    let mut normalized = String::with_capacity(path.len());
+    for c in path.chars() {
+        let c = if c == '\\' || c == '/' { separator } else { c };
+        if c == separator && normalized.ends_with(separator) {
+            continue;
+        }
+        normalized.push(c);
+    }
+    if unc {
+        normalized.insert(0, separator);
+    }
+
+    // A trailing separator only matters for the root itself
+    let is_root =
+        normalized.len() == 1 || (windows && normalized.len() == 3 && normalized.ends_with(":\\"));
+    if normalized.ends_with(separator) && !is_root {
+        normalized.pop();
+    }
+
+    if windows {
+        let mut chars = normalized.chars();
+        if let (Some(drive), Some(':')) = (chars.next(), chars.next()) {
+            if drive.is_ascii_alphabetic() {
+                normalized.replace_range(..1, &drive.to_ascii_uppercase().to_string());
+            }
+        }
+    }
+
+    normalized
+}
+
+/// Whether a launch target is a URL or protocol link (`https://…`,
+/// `steam://…`) rather than a file path
+///
+/// The scheme must be at least two characters, so Windows drive letters
+/// (`C:\`) are never mistaken for one.
+pub fn is_uri(target: &str) -> bool {
+    let Some((scheme, rest)) = target.trim().split_once(':') else {
+        return false;
+    };
+    scheme.len() >= 2
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.trim_start_matches('/').is_empty()
+        && !rest.contains(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_uri() {
+        assert!(is_uri("https://play.example.com/game"));
+        assert!(is_uri("steam://rungameid/400"));
+        assert!(is_uri("com.epicgames.launcher://apps/Fortnite?action=launch"));
+        assert!(is_uri(" uplay://launch/635/0 "));
+
+        assert!(!is_uri("C:\\Games\\doom.exe"));
+        assert!(!is_uri("C:/Games/doom.exe"));
+        assert!(!is_uri("/usr/games/doom"));
+        assert!(!is_uri("games/doom"));
+        assert!(!is_uri("https://"));
+        assert!(!is_uri("https://example.com/a game"));
+        assert!(!is_uri("1password://open"));
+    }
+
+    #[test]
+    fn test_windows_drive_casing() {
+        assert_eq!(
+            normalize_path_str("c:\\Games\\Doom\\doom.exe", true),
+            "C:\\Games\\Doom\\doom.exe"
+        );
+        assert_eq!(normalize_path_str("d:/", true), "D:\\");
+    }
+
+    #[test]
+    fn test_mixed_separators() {
+        assert_eq!(
+            normalize_path_str("  C:/Program Files\\\\Epic Games//Game\\ ", true),
+            "C:\\Program Files\\Epic Games\\Game"
+        );
+        assert_eq!(
+            normalize_path_str("/home/user\\Games//Game/", false),
+            "/home/user/Games/Game"
+        );
+        assert_eq!(normalize_path_str("/", false), "/");
+    }
+
+    #[test]
+    fn test_unc_prefix_kept() {
+        assert_eq!(
+            normalize_path_str("//server/share/game", true),
+            "\\\\server\\share\\game"
+        );
+        // Escaped, as in Steam's libraryfolders.vdf, and verbatim
+        assert_eq!(normalize_path_str("\\\\\\\\nas\\\\games", true), "\\\\nas\\games");
+        assert_eq!(normalize_path_str("\\\\?\\UNC\\nas\\games", true), "\\\\nas\\games");
+        assert_eq!(normalize_path_str("\\\\?\\d:\\Games", true), "D:\\Games");
+    }
+
+    #[test]
+    fn test_same_path() {
+        assert!(same_path(Path::new("/games/doom/"), Path::new("/games//doom")));
+        assert!(!same_path(Path::new("/games/doom"), Path::new("/games/quake")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_find_path_ignoring_case() {
+        let root = std::env::temp_dir().join(format!("launcher-case-{}", uuid::Uuid::new_v4()));
+        let exe = root.join("Game").join("Binaries").join("Win64").join("Game-Win64.EXE");
+        std::fs::create_dir_all(exe.parent().unwrap()).unwrap();
+        std::fs::write(&exe, b"").unwrap();
+
+        // The temp dir is case-sensitive, so only the search finds this
+        let asked = root.join("game").join("binaries").join("win64").join("game-win64.exe");
+        assert!(!asked.exists());
+        assert_eq!(find_path_ignoring_case(&asked), Some(exe.clone()));
+        assert_eq!(find_path_ignoring_case(&exe), Some(exe));
+        assert_eq!(find_path_ignoring_case(&root.join("game").join("other.exe")), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+use thiserror::Error;
+
+use super::archive::{replace_with_zip, zip_dir, ArchiveError};
+use super::{normalize_name, Clock, GameId};
+
+/// How backup files are named, so they sort by when they were taken
+const BACKUP_NAME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A zipped copy of a game's save folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveBackup {
+    pub path: PathBuf,
+    pub taken: NaiveDateTime,
+    /// Tells apart backups taken in the same second
+    sequence: u32,
+}
+
+/// Zip a game's save folder into its own folder under `backups_dir`
+///
+/// Returns the new backup, named after the local time it was taken.
+pub fn backup_saves(
+    backups_dir: &Path,
+    id: GameId,
+    save_path: &Path,
+    clock: &dyn Clock,
+) -> Result<PathBuf, SaveError> {
+    if !save_path.is_dir() {
+        return Err(SaveError::MissingFolder(save_path.to_path_buf()));
+    }
+    let dir = game_backups_dir(backups_dir, id);
+    let stamp = clock.now().with_timezone(&Local).format(BACKUP_NAME_FORMAT).to_string();
+    // Two backups in the same second get a counter rather than overwriting
+    let dest = (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{}.zip", stamp)),
+            n => dir.join(format!("{}-{}.zip", stamp, n)),
+        })
+        .find(|path| !path.exists())
+        .expect("some name is free");
+
+    let files = zip_dir(save_path, &dest)?;
+    tracing::info!("Backed up {} save files from {:?} to {:?}", files, save_path, dest);
+    Ok(dest)
+}
+
+/// A game's backups, newest first
+pub fn list_save_backups(backups_dir: &Path, id: GameId) -> Vec<SaveBackup> {
+    let Ok(entries) = std::fs::read_dir(game_backups_dir(backups_dir, id)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<SaveBackup> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "zip" {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            let mut parts = stem.splitn(3, '-');
+            let stamp = format!("{}-{}", parts.next()?, parts.next()?);
+            let taken = NaiveDateTime::parse_from_str(&stamp, BACKUP_NAME_FORMAT).ok()?;
+            let sequence = parts.next().map_or(Some(1), |n| n.parse().ok())?;
+            Some(SaveBackup { path, taken, sequence })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse((backup.taken, backup.sequence)));
+    backups
+}
+
+/// Replace a game's save folder with a backup
+///
+/// What's in the folder now is backed up first, so a restore can be undone,
+/// then cleared so files the backup doesn't have don't linger. Returns that
+/// backup, if there was anything to keep.
+pub fn restore_saves(
+    backups_dir: &Path,
+    id: GameId,
+    backup: &Path,
+    save_path: &Path,
+    clock: &dyn Clock,
+) -> Result<Option<PathBuf>, SaveError> {
+    let has_saves = std::fs::read_dir(save_path).is_ok_and(|mut entries| entries.next().is_some());
+    let undo = if has_saves {
+        Some(backup_saves(backups_dir, id, save_path, clock)?)
+    } else {
+        None
+    };
+
+    let files = replace_with_zip(backup, save_path)?;
+    tracing::info!("Restored {} save files from {:?} to {:?}", files, backup, save_path);
+    Ok(undo)
+}
+
+/// Folders under `roots` that look like they hold a game's saves
+///
+/// A folder matches when its name is the game's name, ignoring case,
+/// spacing and punctuation. Folders one level down are checked too, for
+/// the usual `<publisher>/<game>` layout.
+pub fn guess_save_folders(name: &str, roots: &[PathBuf]) -> Vec<PathBuf> {
+    let wanted = compact_name(name);
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+    let subfolders = |dir: &Path| -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        dirs.sort();
+        dirs
+    };
+    let matches = |path: &Path| {
+        path.file_name()
+            .is_some_and(|n| compact_name(&n.to_string_lossy()) == wanted)
+    };
+
+    let mut found = Vec::new();
+    for root in roots {
+        for dir in subfolders(root) {
+            if matches(&dir) {
+                found.push(dir);
+            } else {
+                found.extend(subfolders(&dir).into_iter().filter(|d| matches(d)));
+            }
+        }
+    }
+    found
+}
+
+/// The normalized name without spaces, so "HollowKnight" matches "Hollow Knight"
+fn compact_name(name: &str) -> String {
+    normalize_name(name).replace(' ', "")
+}
+
+fn game_backups_dir(backups_dir: &Path, id: GameId) -> PathBuf {
+    backups_dir.join(id.0.to_string())
+}
+
+/// Errors that can occur while backing up or restoring saves
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("Save folder {0:?} does not exist")]
+    MissingFolder(PathBuf),
+
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::MockClock;
+
+    #[test]
+    fn test_backup_and_restore() {
+        let root = std::env::temp_dir().join(format!("launcher-saves-{}", uuid::Uuid::new_v4()));
+        let backups = root.join("backups");
+        let saves = root.join("saves");
+        let id = GameId::new();
+        let clock = MockClock::fixed();
+        std::fs::create_dir_all(&saves).unwrap();
+        std::fs::write(saves.join("slot1.sav"), "level 3").unwrap();
+
+        assert!(matches!(
+            backup_saves(&backups, id, &root.join("missing"), &clock),
+            Err(SaveError::MissingFolder(_))
+        ));
+        let first = backup_saves(&backups, id, &saves, &clock).unwrap();
+
+        std::fs::write(saves.join("slot1.sav"), "level 9").unwrap();
+        std::fs::write(saves.join("slot2.sav"), "new game").unwrap();
+        let undo = restore_saves(&backups, id, &first, &saves, &clock).unwrap();
+        assert_eq!(std::fs::read_to_string(saves.join("slot1.sav")).unwrap(), "level 3");
+        assert!(!saves.join("slot2.sav").exists());
+
+        // The saves being replaced were kept in the same second, and come first
+        let listed = list_save_backups(&backups, id);
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].taken, clock.now().with_timezone(&Local).naive_local());
+        assert_eq!(Some(&listed[0].path), undo.as_ref());
+        assert_eq!(listed[1].path, first);
+        assert!(list_save_backups(&backups, GameId::new()).is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_guess_save_folders() {
+        let root = std::env::temp_dir().join(format!("launcher-guess-{}", uuid::Uuid::new_v4()));
+        let roaming = root.join("Roaming");
+        let low = root.join("LocalLow");
+        for dir in [
+            roaming.join("hollow_knight"),
+            roaming.join("Other Game"),
+            low.join("Team Cherry").join("Hollow Knight"),
+            low.join("Team Cherry").join("Silksong"),
+        ] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+
+        let found = guess_save_folders("Hollow Knight™", &[roaming.clone(), low.clone()]);
+        assert_eq!(
+            found,
+            [roaming.join("hollow_knight"), low.join("Team Cherry").join("Hollow Knight")]
+        );
+        assert!(guess_save_folders("Portal", &[roaming, low]).is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
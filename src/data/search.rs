@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Game, GameId, Library};
+
+/// Incrementally maintained index for searching games by name
+///
+/// Lowercased names are computed once per add/rename instead of per query,
+/// and queries first narrow candidates through the word index, so a keystroke
+/// costs roughly the size of the vocabulary rather than the whole library.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    /// Lowercased name of every indexed game
+    names: HashMap<GameId, String>,
+    /// Lowercased name word -> games whose name contains it
+    tokens: HashMap<String, HashSet<GameId>>,
+}
+
+impl SearchIndex {
+    /// Build an index for every game in the library
+    pub fn build(library: &Library) -> Self {
+        let mut index = Self::default();
+        for game in library.games.values() {
+            index.insert(game);
+        }
+        index
+    }
+
+    /// Add a game, or re-index it after its name changed
+    pub fn insert(&mut self, game: &Game) {
+        self.remove(&game.id);
+
+        let name = game.name.to_lowercase();
+        for token in tokenize(&name) {
+            self.tokens.entry(token.to_string()).or_default().insert(game.id);
+        }
+        self.names.insert(game.id, name);
+    }
+
+    /// Drop a game from the index
+    pub fn remove(&mut self, id: &GameId) {
+        let Some(name) = self.names.remove(id) else {
+            return;
+        };
+        for token in tokenize(&name) {
+            if let Some(ids) = self.tokens.get_mut(token) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.tokens.remove(token);
+                }
+            }
+        }
+    }
+
+    /// Number of indexed games
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// IDs of games whose name contains `query` (case-insensitive)
+    pub fn search(&self, query: &str) -> Vec<GameId> {
+        let query = query.to_lowercase();
+        let words: Vec<&str> = tokenize(&query).collect();
+        if words.is_empty() {
+            return self.names.keys().copied().collect();
+        }
+
+        // Every query word must appear inside some word of the name. Only the
+        // word with the fewest matches is used to collect candidates.
+        let candidates = words
+            .iter()
+            .map(|word| {
+                self.tokens
+                    .iter()
+                    .filter(|(token, _)| token.contains(word))
+                    .flat_map(|(_, ids)| ids.iter().copied())
+                    .collect::<HashSet<GameId>>()
+            })
+            .min_by_key(HashSet::len)
+            .unwrap_or_default();
+
+        // Confirm against the full name so results match a plain substring search
+        candidates
+            .into_iter()
+            .filter(|id| self.names.get(id).is_some_and(|name| name.contains(&query)))
+            .collect()
+    }
+}
+
+fn tokenize(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !c.is_alphanumeric()).filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{GameSource, MockClock};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    fn game(name: &str) -> Game {
+        Game::new(name.to_string(), PathBuf::from(name), GameSource::Manual, &MockClock::fixed())
+    }
+
+    fn sorted_names(library: &Library, ids: Vec<GameId>) -> Vec<String> {
+        let mut names: Vec<String> = ids
+            .iter()
+            .map(|id| library.get_game(id).unwrap().name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_search_matches_substrings() {
+        let mut library = Library::new();
+        for name in ["Portal 2", "Half-Life: Alyx", "Stardew Valley", "Portal"] {
+            library.add_game(game(name));
+        }
+        let index = SearchIndex::build(&library);
+
+        assert_eq!(sorted_names(&library, index.search("portal")), ["Portal", "Portal 2"]);
+        assert_eq!(sorted_names(&library, index.search("ORT")), ["Portal", "Portal 2"]);
+        assert_eq!(sorted_names(&library, index.search("life: al")), ["Half-Life: Alyx"]);
+        assert!(index.search("portal 3").is_empty());
+        assert_eq!(index.search("").len(), 4);
+    }
+
+    #[test]
+    fn test_index_follows_edits() {
+        let mut library = Library::new();
+        let mut doom = game("Doom");
+        let quake = game("Quake");
+        library.add_game(doom.clone());
+        library.add_game(quake.clone());
+        let mut index = SearchIndex::build(&library);
+
+        doom.name = "Doom Eternal".to_string();
+        index.insert(&doom);
+        assert_eq!(index.search("eternal"), vec![doom.id]);
+        assert_eq!(index.len(), 2);
+
+        index.remove(&quake.id);
+        assert!(index.search("quake").is_empty());
+        assert!(!index.tokens.contains_key("quake"));
+    }
+
+    #[test]
+    fn test_keystroke_cost_on_large_library() {
+        let mut library = Library::new();
+        for i in 0..5000 {
+            library.add_game(game(&format!("Synthetic Game {} Edition {}", i, i % 37)));
+        }
+        library.add_game(game("Portal"));
+        let index = SearchIndex::build(&library);
+
+        // Type a query one character at a time, like the search box does
+        let query = "portal";
+        let started = Instant::now();
+        for end in 1..=query.len() {
+            index.search(&query[..end]);
+        }
+        let elapsed = started.elapsed();
+
+        assert_eq!(index.search(query).len(), 1);
+        assert!(
+            elapsed < Duration::from_secs(1),
+            "typing took {:?} over 5000 games",
+            elapsed
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Datelike, Utc, Weekday};
+
+use super::Game;
+
+/// Play habits over a time window, for the year in review
+///
+/// Only the most recent session of each game is recorded, so a game counts
+/// as played if its last session falls in the window, and its hours are its
+/// lifetime playtime rather than just the time spent inside the window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayStats {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub games_played: usize,
+    pub total_minutes: u64,
+    /// Name and playtime of the game played longest
+    pub most_played: Option<(String, u64)>,
+    /// Weekday the most games were last played on
+    pub busiest_day: Option<Weekday>,
+}
+
+impl PlayStats {
+    /// Compute stats for games last played between `start` and `end`
+    pub fn compute<'a>(
+        games: impl IntoIterator<Item = &'a Game>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        let played: Vec<&Game> = games
+            .into_iter()
+            .filter(|game| game.last_played.is_some_and(|t| t >= start && t <= end))
+            .collect();
+
+        let most_played = played
+            .iter()
+            .filter(|game| game.playtime_minutes > 0)
+            // Ties go to the first name alphabetically, so the result is stable
+            .max_by(|a, b| {
+                a.playtime_minutes
+                    .cmp(&b.playtime_minutes)
+                    .then_with(|| b.display_name().cmp(&a.display_name()))
+            })
+            .map(|game| (game.display_name(), game.playtime_minutes));
+
+        let mut per_day = [0usize; 7];
+        for time in played.iter().filter_map(|game| game.last_played) {
+            per_day[time.weekday().num_days_from_monday() as usize] += 1;
+        }
+        // Earliest weekday wins ties
+        let busiest_day = per_day
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .max_by(|(a_day, a), (b_day, b)| a.cmp(b).then_with(|| b_day.cmp(a_day)))
+            .map(|(day, _)| Weekday::try_from(day as u8).expect("index is below 7"));
+
+        Self {
+            start,
+            end,
+            games_played: played.len(),
+            total_minutes: played.iter().map(|game| game.playtime_minutes).sum(),
+            most_played,
+            busiest_day,
+        }
+    }
+
+    /// Whether nothing was played in the window
+    pub fn is_empty(&self) -> bool {
+        self.games_played == 0
+    }
+
+    pub fn total_hours(&self) -> u64 {
+        self.total_minutes / 60
+    }
+
+    /// Plain-text version of the summary, for exporting
+    pub fn to_text(&self, title: &str) -> String {
+        let mut lines = vec![
+            title.to_string(),
+            format!(
+                "{} to {}",
+                self.start.format("%Y-%m-%d"),
+                self.end.format("%Y-%m-%d")
+            ),
+            String::new(),
+        ];
+
+        if self.is_empty() {
+            lines.push("No games played in this period.".to_string());
+        } else {
+            lines.push(format!("Games played: {}", self.games_played));
+            lines.push(format!("Total hours: {}", self.total_hours()));
+            if let Some((name, minutes)) = &self.most_played {
+                lines.push(format!("Most played: {} ({}h)", name, minutes / 60));
+            }
+            if let Some(day) = self.busiest_day {
+                lines.push(format!("Busiest day: {}", weekday_name(day)));
+            }
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+pub fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{GameSource, MockClock};
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    fn game(name: &str, minutes: u64, last_played: Option<DateTime<Utc>>) -> Game {
+        let clock = MockClock::fixed();
+        let mut game =
+            Game::new(name.to_string(), PathBuf::from("game"), GameSource::Manual, &clock);
+        game.playtime_minutes = minutes;
+        game.last_played = last_played;
+        game
+    }
+
+    fn day(month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, month, day, 20, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_stats_over_window() {
+        let start = day(1, 1);
+        let end = day(12, 31);
+        let games = vec![
+            // Saturdays
+            game("Portal", 600, Some(day(3, 1))),
+            game("Doom", 120, Some(day(3, 8))),
+            // A Monday
+            game("Hades", 900, Some(day(3, 3))),
+            // Outside the window or never played
+            game("Celeste", 5000, Some(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap())),
+            game("Braid", 0, None),
+        ];
+
+        let stats = PlayStats::compute(&games, start, end);
+        assert_eq!(stats.games_played, 3);
+        assert_eq!(stats.total_minutes, 1620);
+        assert_eq!(stats.total_hours(), 27);
+        assert_eq!(stats.most_played, Some(("Hades".to_string(), 900)));
+        assert_eq!(stats.busiest_day, Some(Weekday::Sat));
+    }
+
+    #[test]
+    fn test_empty_and_low_data() {
+        let stats = PlayStats::compute(&[], day(1, 1), day(12, 31));
+        assert!(stats.is_empty());
+        assert_eq!(stats.most_played, None);
+        assert_eq!(stats.busiest_day, None);
+        assert!(stats.to_text("2025 in review").contains("No games played"));
+
+        // Played but never for a full minute
+        let games = vec![game("Portal", 0, Some(day(3, 1)))];
+        let stats = PlayStats::compute(&games, day(1, 1), day(12, 31));
+        assert_eq!(stats.games_played, 1);
+        assert_eq!(stats.most_played, None);
+        assert!(stats.to_text("2025 in review").contains("Busiest day: Saturday"));
+    }
+}
@@ -0,0 +1,460 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::crypto;
+use super::library::{Library, LibraryError};
+
+/// Storage backend the library is persisted to
+pub trait LibraryStore {
+    /// Persist the library
+    fn save(&self, library: &Library) -> impl Future<Output = Result<(), LibraryError>> + Send;
+
+    /// Load a previously persisted library
+    fn load(&self) -> impl Future<Output = Result<Library, LibraryError>> + Send;
+}
+
+/// Stores the library as pretty-printed JSON on disk
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+impl LibraryStore for FileStore {
+    async fn save(&self, library: &Library) -> Result<(), LibraryError> {
+        let json = serde_json::to_string_pretty(library)
+            .map_err(|e| LibraryError::Serialization(e.to_string()))?;
+
+        write_file(&self.path, json.as_bytes())
+            .await
+            .map_err(|e| LibraryError::Io(e.to_string()))?;
+
+        tracing::info!("Library saved to {:?}", self.path);
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Library, LibraryError> {
+        let content = fs::read(&self.path)
+            .await
+            .map_err(|e| LibraryError::Io(e.to_string()))?;
+
+        // Don't let an encrypted file be mistaken for a corrupt one
+        if crypto::is_encrypted(&content) {
+            return Err(LibraryError::PassphraseRequired);
+        }
+
+        let library: Library = serde_json::from_slice(&content)
+            .map_err(|e| LibraryError::Deserialization(e.to_string()))?;
+
+        tracing::info!(
+            "Library loaded from {:?} ({} games)",
+            self.path,
+            library.game_count()
+        );
+        Ok(library)
+    }
+}
+
+/// Stores the library as passphrase-encrypted JSON on disk
+#[derive(Clone)]
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase: String,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: &Path, passphrase: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+}
+
+impl LibraryStore for EncryptedFileStore {
+    async fn save(&self, library: &Library) -> Result<(), LibraryError> {
+        let json = serde_json::to_vec(library)
+            .map_err(|e| LibraryError::Serialization(e.to_string()))?;
+
+        // Key derivation is deliberately slow, keep it off the async workers
+        let passphrase = self.passphrase.clone();
+        let data = tokio::task::spawn_blocking(move || crypto::encrypt(&json, &passphrase))
+            .await
+            .map_err(|e| LibraryError::Encryption(e.to_string()))??;
+
+        write_file(&self.path, &data)
+            .await
+            .map_err(|e| LibraryError::Io(e.to_string()))?;
+
+        tracing::info!("Encrypted library saved to {:?}", self.path);
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Library, LibraryError> {
+        let content = fs::read(&self.path)
+            .await
+            .map_err(|e| LibraryError::Io(e.to_string()))?;
+
+        let passphrase = self.passphrase.clone();
+        let json = tokio::task::spawn_blocking(move || crypto::decrypt(&content, &passphrase))
+            .await
+            .map_err(|e| LibraryError::Encryption(e.to_string()))??;
+
+        let library: Library = serde_json::from_slice(&json)
+            .map_err(|e| LibraryError::Deserialization(e.to_string()))?;
+
+        tracing::info!(
+            "Encrypted library loaded from {:?} ({} games)",
+            self.path,
+            library.game_count()
+        );
+        Ok(library)
+    }
+}
+
+/// Check whether the file at `path` is an encrypted library
+pub async fn is_encrypted_file(path: &Path) -> bool {
+    let Ok(mut file) = fs::File::open(path).await else {
+        return false;
+    };
+    let mut header = vec![0u8; crypto::MAGIC.len()];
+    file.read_exact(&mut header).await.is_ok() && crypto::is_encrypted(&header)
+}
+
+/// Held for the length of each [`write_file`], so saves land in the order
+/// they were started
+static WRITE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Write `data` to `path`, creating the parent directory if needed
+///
+/// The data goes to a temporary file that is then renamed over `path`, so a
+/// failed write never leaves a truncated file behind and the previous one
+/// stays as it was. Every write gets a temporary file of its own and writes
+/// run one at a time, so saves started together can't rename each other's
+/// half-written data into place. Transient failures (files briefly locked by
+/// antivirus or sync tools) are retried.
+pub async fn write_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = retry_transient(|| async {
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(data).await?;
+        file.sync_all().await?;
+        drop(file);
+        fs::rename(&tmp_path, path).await
+    })
+    .await;
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path).await;
+    }
+    result
+}
+
+/// Delays between attempts when a write hits a transient error (under a second)
+const RETRY_DELAYS_MS: [u64; 4] = [50, 100, 200, 400];
+
+/// Run `op`, retrying with exponential backoff while it fails transiently
+async fn retry_transient<F, Fut>(mut op: F) -> std::io::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::io::Result<()>>,
+{
+    for delay in RETRY_DELAYS_MS {
+        match op().await {
+            Err(e) if is_transient(&e) => {
+                tracing::warn!("Transient IO error, retrying in {}ms: {}", delay, e);
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            }
+            result => return result,
+        }
+    }
+    op().await
+}
+
+/// Errors caused by another process briefly holding the file
+fn is_transient(e: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    if cfg!(target_os = "windows") && matches!(e.raw_os_error(), Some(32 | 33)) {
+        return true;
+    }
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::PermissionDenied
+            | std::io::ErrorKind::ResourceBusy
+            | std::io::ErrorKind::Interrupted
+            | std::io::ErrorKind::WouldBlock
+    )
+}
+
+/// Keeps the serialized library in memory, for fast deterministic tests
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    contents: std::sync::Mutex<Option<String>>,
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a store pre-filled with raw contents (which may be invalid JSON)
+    pub fn with_contents(contents: &str) -> Self {
+        Self {
+            contents: std::sync::Mutex::new(Some(contents.to_string())),
+        }
+    }
+
+    /// Raw contents currently held by the store
+    pub fn contents(&self) -> Option<String> {
+        self.contents.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl LibraryStore for MemoryStore {
+    async fn save(&self, library: &Library) -> Result<(), LibraryError> {
+        let json = serde_json::to_string(library)
+            .map_err(|e| LibraryError::Serialization(e.to_string()))?;
+        *self.contents.lock().unwrap() = Some(json);
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Library, LibraryError> {
+        let content = self
+            .contents()
+            .ok_or_else(|| LibraryError::Io("No library stored".to_string()))?;
+        serde_json::from_str(&content).map_err(|e| LibraryError::Deserialization(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Game, GameSource, MockClock};
+
+    #[tokio::test]
+    async fn test_memory_store_round_trip() {
+        let store = MemoryStore::new();
+        let mut library = Library::new();
+        let clock = MockClock::fixed();
+        let game =
+            Game::new("Portal".to_string(), PathBuf::from("portal"), GameSource::Manual, &clock);
+        let id = game.id;
+        library.add_game(game);
+
+        store.save(&library).await.unwrap();
+        let loaded = store.load().await.unwrap();
+
+        assert_eq!(loaded.game_count(), 1);
+        assert_eq!(loaded.get_game(&id).unwrap().name, "Portal");
+        assert_eq!(loaded.categories.len(), library.categories.len());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_load_errors() {
+        assert!(matches!(
+            MemoryStore::new().load().await,
+            Err(LibraryError::Io(_))
+        ));
+        assert!(matches!(
+            MemoryStore::with_contents("{ not json").load().await,
+            Err(LibraryError::Deserialization(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_file_needs_passphrase() {
+        let path = std::env::temp_dir()
+            .join(format!("launcher-encrypted-{}", uuid::Uuid::new_v4()))
+            .join("library.json");
+        let mut library = Library::new();
+        let clock = MockClock::fixed();
+        let game =
+            Game::new("Portal".to_string(), PathBuf::from("portal"), GameSource::Manual, &clock);
+        library.add_game(game);
+        library.save_to_file_encrypted(&path, "hunter2").await.unwrap();
+
+        assert!(is_encrypted_file(&path).await);
+        assert!(matches!(
+            Library::load_or_create_protected(&path, None, false, &clock).await,
+            Err(LibraryError::PassphraseRequired)
+        ));
+        assert!(matches!(
+            Library::load_or_create_protected(&path, Some("wrong"), false, &clock).await,
+            Err(LibraryError::WrongPassphrase)
+        ));
+        let (loaded, _) = Library::load_or_create_protected(&path, Some("hunter2"), false, &clock)
+            .await
+            .unwrap();
+        assert_eq!(loaded.game_count(), 1);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_transient_failure() {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let result = retry_transient(|| async {
+            if attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < 2 {
+                Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_on_permanent_failure() {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let result = retry_transient(|| async {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join(format!("launcher-store-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("library.json");
+        FileStore::new(&path).save(&Library::new()).await.unwrap();
+        FileStore::new(&path).save(&Library::new()).await.unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().collect();
+        assert_eq!(files.len(), 1);
+        assert!(FileStore::new(&path).load().await.is_ok());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_file_is_quarantined() {
+        let dir = std::env::temp_dir().join(format!("launcher-corrupt-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("library.json");
+        let corrupt = r#"{"games": {}, "categories": {},}"#;
+        std::fs::write(&path, corrupt).unwrap();
+
+        let clock = MockClock::fixed();
+        let (library, quarantined) = Library::load_or_create(&path, false, &clock).await.unwrap();
+        assert_eq!(library.game_count(), 0);
+        let quarantined = quarantined.unwrap();
+        assert_eq!(quarantined, dir.join("library.corrupt-20231114-221320.json"));
+        assert_eq!(std::fs::read_to_string(&quarantined).unwrap(), corrupt);
+
+        // Saving the fresh library leaves the quarantined copy alone
+        library.save_to_file(&path).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&quarantined).unwrap(), corrupt);
+
+        // A missing file is just a new library
+        std::fs::remove_file(&path).unwrap();
+        assert!(Library::load_or_create(&path, false, &clock).await.unwrap().1.is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_file_is_not_replaced() {
+        let dir = std::env::temp_dir().join(format!("launcher-corrupt-{}", uuid::Uuid::new_v4()));
+        // A folder where the file should be exists but can't be read
+        let path = dir.join("library.json");
+        std::fs::create_dir_all(path.join("games")).unwrap();
+
+        assert!(matches!(
+            Library::load_or_create(&path, false, &MockClock::fixed()).await,
+            Err(LibraryError::Io(_))
+        ));
+        assert!(path.join("games").is_dir());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_load_or_create_falls_back_to_new() {
+        let library = Library::load_or_create_from(&MemoryStore::new(), true).await;
+        assert_eq!(library.game_count(), 0);
+        assert!(!library.all_categories().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_defaults_stay_deleted_after_load_miss() {
+        let dir = std::env::temp_dir().join(format!("launcher-seed-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("library.json");
+
+        // First run seeds the defaults, then the user deletes them all
+        let clock = MockClock::fixed();
+        let (mut library, _) = Library::load_or_create(&path, true, &clock).await.unwrap();
+        assert!(!library.all_categories().is_empty());
+        let ids: Vec<_> = library.all_categories().iter().map(|c| c.id).collect();
+        for id in ids {
+            library.remove_category(&id);
+        }
+        library.save_to_file(&path).await.unwrap();
+
+        // The file goes missing for a moment; later runs don't resurrect them
+        std::fs::remove_file(&path).unwrap();
+        let (library, _) = Library::load_or_create(&path, false, &clock).await.unwrap();
+        assert!(library.all_categories().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_leaves_target_alone() {
+        let dir = std::env::temp_dir().join(format!("launcher-store-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("library.json");
+
+        // A non-empty directory in the way makes the rename fail
+        std::fs::create_dir_all(path.join("inside")).unwrap();
+        assert!(write_file(&path, b"next").await.is_err());
+        assert!(path.join("inside").is_dir());
+        // The temporary file is cleaned up
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_never_mix() {
+        let dir = std::env::temp_dir().join(format!("launcher-store-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("library.json");
+        let contents: Vec<Vec<u8>> = (0..8u8).map(|i| vec![b'a' + i; 64 * 1024]).collect();
+
+        let writes = contents.iter().map(|data| write_file(&path, data));
+        for result in iced::futures::future::join_all(writes).await {
+            result.unwrap();
+        }
+
+        // The last write started is the one left, whole
+        assert_eq!(std::fs::read(&path).unwrap(), contents[7]);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
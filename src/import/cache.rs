@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{DetectedGame, ScanReport};
+use crate::data::{write_file, GameSource};
+
+/// What a store scan depends on besides the disk itself
+///
+/// A cached scan only stands in for a new one when these haven't changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanInputs {
+    /// Library folders added by hand
+    pub roots: Vec<PathBuf>,
+    pub exclude_patterns: Vec<String>,
+    pub include_not_installed: bool,
+}
+
+/// The results of one store scan, as the import preview shows them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedScan {
+    pub scanned_at: DateTime<Utc>,
+    pub inputs: ScanInputs,
+    pub games: Vec<DetectedGame>,
+    pub report: ScanReport,
+}
+
+/// The last scan of each store, kept in the data dir so the import view can
+/// show it again without walking the store's folders
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanCache {
+    scans: HashMap<GameSource, CachedScan>,
+}
+
+impl ScanCache {
+    /// The last scan of `source`, unless it is older than `max_age` or was made
+    /// with different inputs
+    pub fn get(
+        &self,
+        source: GameSource,
+        inputs: &ScanInputs,
+        max_age: chrono::Duration,
+        now: DateTime<Utc>,
+    ) -> Option<&CachedScan> {
+        self.scans
+            .get(&source)
+            .filter(|scan| scan.inputs == *inputs && now - scan.scanned_at < max_age)
+    }
+
+    /// Replace the last scan of `source`
+    pub fn insert(&mut self, source: GameSource, scan: CachedScan) {
+        self.scans.insert(source, scan);
+    }
+
+    /// Load the cache, starting empty if the file is missing or unreadable
+    pub async fn load(path: &Path) -> Self {
+        let Ok(content) = tokio::fs::read_to_string(path).await else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable scan cache {:?}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    /// Save the cache, replacing the file only once the new one is written
+    pub async fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        write_file(path, json.as_bytes()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Clock, MockClock};
+
+    #[tokio::test]
+    async fn test_cached_scan_round_trip_and_invalidation() {
+        let clock = MockClock::fixed();
+        let inputs = ScanInputs {
+            roots: vec![PathBuf::from("/mnt/games")],
+            exclude_patterns: vec!["*Redist*".to_string()],
+            include_not_installed: false,
+        };
+        let game = DetectedGame::not_installed(
+            "Portal".to_string(),
+            "400".to_string(),
+            PathBuf::from("/mnt/games/Portal"),
+        );
+        let mut cache = ScanCache::default();
+        cache.insert(
+            GameSource::Steam,
+            CachedScan {
+                scanned_at: clock.now(),
+                inputs: inputs.clone(),
+                games: vec![game],
+                report: ScanReport::default(),
+            },
+        );
+
+        let path = std::env::temp_dir()
+            .join(format!("launcher-scan-cache-{}", uuid::Uuid::new_v4()))
+            .join("scan_cache.json");
+        cache.save(&path).await.unwrap();
+        let cache = ScanCache::load(&path).await;
+
+        let day = chrono::Duration::days(1);
+        let scan = cache.get(GameSource::Steam, &inputs, day, clock.now()).unwrap();
+        assert_eq!(scan.games[0].name, "Portal");
+        assert!(cache.get(GameSource::Epic, &inputs, day, clock.now()).is_none());
+
+        // A changed library folder or an old scan means walking the disk again
+        let moved = ScanInputs {
+            roots: vec![PathBuf::from("/mnt/other")],
+            ..inputs.clone()
+        };
+        assert!(cache.get(GameSource::Steam, &moved, day, clock.now()).is_none());
+        clock.advance(day);
+        assert!(cache.get(GameSource::Steam, &inputs, day, clock.now()).is_none());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use super::{DetectedGame, GameImporter, ImportError};
-use crate::data::GameSource;
+use super::text::read_manifest;
+use super::{DetectedGame, GameImporter, ImportError, ScanReport, SkipReason};
+use crate::data::{find_path_ignoring_case, normalize_path, GameSource};
 
 /// Importer for Epic Games Store games
 pub struct EpicImporter {
@@ -49,32 +50,97 @@ impl EpicImporter {
     }
 
     /// Parse an Epic Games manifest (.item) file
-    fn parse_manifest(&self, path: &PathBuf) -> Option<DetectedGame> {
-        let content = std::fs::read_to_string(path).ok()?;
+    fn parse_manifest(&self, path: &Path) -> Result<DetectedGame, SkipReason> {
+        let content = read_manifest(path).map_err(|_| SkipReason::Unreadable)?;
+        parse_manifest_json(&content)
+    }
+}
 
-        // Parse JSON
-        let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+/// Parse the JSON contents of an Epic Games manifest
+///
+/// Fails for entries that can't be launched (DLC, plugins, missing
+/// executables).
+fn parse_manifest_json(content: &str) -> Result<DetectedGame, SkipReason> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(content).map_err(|_| SkipReason::ParseError)?;
 
-        let name = manifest.get("DisplayName")?.as_str()?.to_string();
-        let install_location = manifest.get("InstallLocation")?.as_str()?;
-        let app_name = manifest.get("AppName")?.as_str()?.to_string();
-        let launch_executable = manifest.get("LaunchExecutable")?.as_str()?;
+    // DLC and engine plugins have manifests too, but nothing to launch
+    if manifest.get("bIsApplication").and_then(|v| v.as_bool()) == Some(false) {
+        return Err(SkipReason::NotApplication);
+    }
 
-        let install_path = PathBuf::from(install_location);
-        let executable_path = install_path.join(launch_executable);
+    let field = |key| {
+        manifest
+            .get(key)
+            .and_then(|v| v.as_str())
+            .ok_or(SkipReason::ParseError)
+    };
+    let name = field("DisplayName")?.to_string();
+    let install_location = field("InstallLocation")?;
+    let app_name = field("AppName")?.to_string();
+    let launch_executable = manifest
+        .get("LaunchExecutable")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|exe| !exe.is_empty())
+        .ok_or(SkipReason::NotApplication)?;
+    let launch_args = manifest
+        .get("LaunchCommand")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|args| !args.is_empty())
+        .map(str::to_string);
 
-        if !executable_path.exists() {
-            return None;
-        }
+    let (install_path, executable_path) =
+        resolve_executable(&normalize_separators(install_location), launch_executable)
+            .ok_or(SkipReason::NoExecutable)?;
+
+    Ok(DetectedGame {
+        name,
+        source_id: app_name,
+        executable_path,
+        install_path,
+        icon_path: None,
+        banner_path: None,
+        playtime_minutes: None,
+        last_played: None,
+        launch_args,
+        emulator_command: None,
+    })
+}
 
-        Some(DetectedGame {
-            name,
-            source_id: app_name,
-            executable_path,
-            install_path,
-            icon_path: None,
-        })
+/// Find the executable a manifest points at, returning `(install_path, executable_path)`
+fn resolve_executable(
+    install_location: &Path,
+    launch_executable: &str,
+) -> Option<(PathBuf, PathBuf)> {
+    // Some manifests point InstallLocation straight at the executable
+    if install_location.is_file() {
+        let install_path = install_location.parent()?.to_path_buf();
+        return Some((install_path, install_location.to_path_buf()));
     }
+
+    let relative = normalize_separators(launch_executable);
+    let mut candidates = vec![install_location.join(&relative)];
+
+    // Others repeat the install folder at the start of LaunchExecutable
+    let install_folder = install_location.file_name();
+    let mut components = relative.components();
+    if install_folder.is_some() && components.next().map(|c| c.as_os_str()) == install_folder {
+        candidates.push(install_location.join(components.as_path()));
+    }
+
+    // Case can differ from the manifest when the game runs through Proton
+    let executable_path = candidates
+        .iter()
+        .filter_map(|path| find_path_ignoring_case(path))
+        .find(|path| path.is_file())?;
+    Some((install_location.to_path_buf(), executable_path))
+}
+
+/// Manifests use backslashes regardless of platform
+fn normalize_separators(path: &str) -> PathBuf {
+    normalize_path(Path::new(path))
 }
 
 impl Default for EpicImporter {
@@ -92,22 +158,148 @@ impl GameImporter for EpicImporter {
         self.manifests_path.is_some()
     }
 
-    fn scan_games(&self) -> Result<Vec<DetectedGame>, ImportError> {
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
         let manifests_path = self.manifests_path.as_ref().ok_or(ImportError::NotInstalled)?;
         let mut games = Vec::new();
+        let mut report = ScanReport::default();
 
         if let Ok(entries) = std::fs::read_dir(manifests_path) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.extension().map(|e| e == "item").unwrap_or(false) {
-                    if let Some(game) = self.parse_manifest(&path) {
-                        games.push(game);
-                    }
+                    let result = self.parse_manifest(&path);
+                    games.extend(report.record(&path.display().to_string(), result));
                 }
             }
         }
 
-        tracing::info!("Found {} Epic Games", games.len());
-        Ok(games)
+        tracing::info!("Found {} Epic Games, skipped {}", games.len(), report.skipped.len());
+        Ok((games, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Create an install folder containing `exe` (a `/`-separated relative path)
+    fn install_with(exe: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("launcher-epic-{}", uuid::Uuid::new_v4()))
+            .join("Game");
+        let exe_path = dir.join(normalize_separators(exe));
+        std::fs::create_dir_all(exe_path.parent().unwrap()).unwrap();
+        std::fs::write(&exe_path, b"").unwrap();
+        dir
+    }
+
+    fn cleanup(install: &Path) {
+        let _ = std::fs::remove_dir_all(install.parent().unwrap());
+    }
+
+    #[test]
+    fn test_parse_basic_manifest() {
+        let install = install_with("Game.exe");
+        let manifest = json!({
+            "DisplayName": "Test Game",
+            "AppName": "TestApp",
+            "bIsApplication": true,
+            "InstallLocation": install,
+            "LaunchExecutable": "Game.exe",
+            "LaunchCommand": "",
+        });
+
+        let game = parse_manifest_json(&manifest.to_string()).unwrap();
+        assert_eq!(game.name, "Test Game");
+        assert_eq!(game.source_id, "TestApp");
+        assert_eq!(game.executable_path, install.join("Game.exe"));
+        assert_eq!(game.install_path, install);
+        assert_eq!(game.launch_args, None);
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_parse_backslash_separators_and_launch_command() {
+        let install = install_with("Binaries/Win64/Game-Win64-Shipping.exe");
+        let manifest = json!({
+            "DisplayName": "Unreal Game",
+            "AppName": "UnrealApp",
+            "InstallLocation": install,
+            "LaunchExecutable": "Binaries\\Win64\\Game-Win64-Shipping.exe",
+            "LaunchCommand": " -nolauncher -skipintro ",
+        });
+
+        let game = parse_manifest_json(&manifest.to_string()).unwrap();
+        assert_eq!(
+            game.executable_path,
+            install.join("Binaries").join("Win64").join("Game-Win64-Shipping.exe")
+        );
+        assert_eq!(game.launch_args.as_deref(), Some("-nolauncher -skipintro"));
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_parse_executable_repeating_install_folder() {
+        let install = install_with("Bin/Game.exe");
+        let manifest = json!({
+            "DisplayName": "Nested",
+            "AppName": "NestedApp",
+            "InstallLocation": install,
+            "LaunchExecutable": "Game/Bin/Game.exe",
+        });
+
+        let game = parse_manifest_json(&manifest.to_string()).unwrap();
+        assert_eq!(game.executable_path, install.join("Bin").join("Game.exe"));
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_parse_install_location_is_executable() {
+        let install = install_with("Game.exe");
+        let manifest = json!({
+            "DisplayName": "Odd",
+            "AppName": "OddApp",
+            "InstallLocation": install.join("Game.exe"),
+            "LaunchExecutable": "Game.exe",
+        });
+
+        let game = parse_manifest_json(&manifest.to_string()).unwrap();
+        assert_eq!(game.executable_path, install.join("Game.exe"));
+        assert_eq!(game.install_path, install);
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_parse_skips_non_applications() {
+        let install = install_with("Game.exe");
+        let dlc = json!({
+            "DisplayName": "Season Pass",
+            "AppName": "DlcApp",
+            "bIsApplication": false,
+            "InstallLocation": install,
+            "LaunchExecutable": "Game.exe",
+        });
+        let plugin = json!({
+            "DisplayName": "Some Plugin",
+            "AppName": "PluginApp",
+            "InstallLocation": install,
+            "LaunchExecutable": "",
+        });
+
+        assert!(matches!(
+            parse_manifest_json(&dlc.to_string()),
+            Err(SkipReason::NotApplication)
+        ));
+        assert!(matches!(
+            parse_manifest_json(&plugin.to_string()),
+            Err(SkipReason::NotApplication)
+        ));
+
+        cleanup(&install);
     }
 }
@@ -0,0 +1,114 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::constants::LIBRARY_FOLDER_TIMEOUT_SECS;
+
+/// Where library folders are read from, so scans can be tried against slow
+/// or missing ones
+pub trait FolderStore: Send + Sync {
+    /// Whether `path` is a folder that can be reached
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// Everything directly inside `path`
+    fn list(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+}
+
+/// Folders on the local filesystem, including mounted network shares
+pub struct LocalFolders;
+
+impl FolderStore for LocalFolders {
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn list(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(std::fs::read_dir(path)?.flatten().map(|entry| entry.path()).collect())
+    }
+}
+
+/// Why a library folder was left out of a scan
+#[derive(Debug, Error)]
+pub enum FolderError {
+    #[error("no response after {} s", .0.as_secs_f32())]
+    TimedOut(Duration),
+
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// Reads library folders with a time limit on each call
+///
+/// A network share that stops answering blocks the OS call with no way to
+/// cancel it, so each call runs on its own thread. One that runs out of time
+/// is left to finish in the background while the scan moves on.
+#[derive(Clone)]
+pub struct FolderProbe {
+    store: Arc<dyn FolderStore>,
+    timeout: Duration,
+}
+
+impl FolderProbe {
+    pub fn new(store: Arc<dyn FolderStore>, timeout: Duration) -> Self {
+        Self { store, timeout }
+    }
+
+    /// Whether `path` is a folder, or why it couldn't be told
+    pub fn is_dir(&self, path: &Path) -> Result<bool, FolderError> {
+        self.bounded(path, |store, path| Ok(store.is_dir(&path)))
+    }
+
+    /// Everything directly inside `path`
+    pub fn list(&self, path: &Path) -> Result<Vec<PathBuf>, FolderError> {
+        self.bounded(path, |store, path| store.list(&path))
+    }
+
+    /// Run other work that reads from a library folder, like parsing a
+    /// manifest or searching an install folder, under the same time limit
+    pub fn run<T: Send + 'static>(
+        &self,
+        call: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, FolderError> {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The receiver is gone if the call took too long
+            let _ = tx.send(call());
+        });
+        rx.recv_timeout(self.timeout).map_err(|_| FolderError::TimedOut(self.timeout))
+    }
+
+    fn bounded<T: Send + 'static>(
+        &self,
+        path: &Path,
+        call: impl FnOnce(&dyn FolderStore, PathBuf) -> io::Result<T> + Send + 'static,
+    ) -> Result<T, FolderError> {
+        let store = Arc::clone(&self.store);
+        let path = path.to_path_buf();
+        Ok(self.run(move || call(store.as_ref(), path))??)
+    }
+}
+
+impl Default for FolderProbe {
+    fn default() -> Self {
+        Self::new(Arc::new(LocalFolders), Duration::from_secs(LIBRARY_FOLDER_TIMEOUT_SECS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_gives_up_on_stalled_work() {
+        let probe = FolderProbe::new(Arc::new(LocalFolders), Duration::from_millis(100));
+        assert_eq!(probe.run(|| 7).unwrap(), 7);
+
+        let started = std::time::Instant::now();
+        let stalled = probe.run(|| std::thread::sleep(Duration::from_secs(30)));
+        assert!(matches!(stalled, Err(FolderError::TimedOut(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
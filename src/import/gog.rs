@@ -1,17 +1,30 @@
 use std::path::PathBuf;
 
-use super::{DetectedGame, GameImporter, ImportError};
+#[cfg(target_os = "linux")]
+use super::heroic;
+#[cfg(any(feature = "gog", target_os = "linux"))]
+use super::text::read_manifest;
+#[cfg(any(feature = "gog", target_os = "linux"))]
+use super::SkipReason;
+use super::{DetectedGame, GameImporter, ImportError, ScanReport};
+#[cfg(any(feature = "gog", target_os = "linux"))]
+use crate::data::{find_path_ignoring_case, normalize_path};
 use crate::data::GameSource;
 
 /// Importer for GOG Galaxy games
 pub struct GOGImporter {
     database_path: Option<PathBuf>,
+    /// Heroic's list of the GOG games it installed, Galaxy's stand-in on Linux
+    #[cfg(target_os = "linux")]
+    heroic_installed_path: Option<PathBuf>,
 }
 
 impl GOGImporter {
     pub fn new() -> Self {
         Self {
             database_path: Self::find_database_path(),
+            #[cfg(target_os = "linux")]
+            heroic_installed_path: heroic::find_installed_list(),
         }
     }
 
@@ -45,10 +58,20 @@ impl GameImporter for GOGImporter {
     }
 
     fn is_available(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        if self.heroic_installed_path.is_some() {
+            return true;
+        }
         self.database_path.is_some()
     }
 
-    fn scan_games(&self) -> Result<Vec<DetectedGame>, ImportError> {
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        #[cfg(target_os = "linux")]
+        if self.database_path.is_none() {
+            let installed = self.heroic_installed_path.as_ref().ok_or(ImportError::NotInstalled)?;
+            return heroic::scan_installed(installed);
+        }
+
         // GOG import requires the rusqlite feature
         #[cfg(feature = "gog")]
         {
@@ -65,6 +88,7 @@ impl GameImporter for GOGImporter {
                 .map_err(|e| ImportError::DatabaseError(e.to_string()))?;
 
             let mut games = Vec::new();
+            let mut report = ScanReport::default();
 
             let rows = stmt
                 .query_map([], |row| {
@@ -74,20 +98,28 @@ impl GameImporter for GOGImporter {
                 })
                 .map_err(|e| ImportError::DatabaseError(e.to_string()))?;
 
-            for row in rows {
-                if let Ok((product_id, local_path)) = row {
-                    let install_path = PathBuf::from(&local_path);
-                    if install_path.exists() {
-                        // Try to find the game name and executable
-                        if let Some(game) = self.find_game_in_folder(&install_path, product_id) {
-                            games.push(game);
-                        }
+            let stats = read_play_stats(&conn);
+
+            for (product_id, local_path) in rows.flatten() {
+                let install_path = PathBuf::from(&local_path);
+                let result = if install_path.exists() {
+                    // Try to find the game name and executable
+                    self.find_game_in_folder(&install_path, product_id)
+                } else {
+                    Err(SkipReason::NotInstalled)
+                };
+                let candidate = format!("product {} ({})", product_id, local_path);
+                if let Some(mut game) = report.record(&candidate, result) {
+                    if let Some(stats) = stats.get(&product_id) {
+                        game.playtime_minutes = stats.playtime_minutes;
+                        game.last_played = stats.last_played;
                     }
+                    games.push(game);
                 }
             }
 
-            tracing::info!("Found {} GOG games", games.len());
-            Ok(games)
+            tracing::info!("Found {} GOG games, skipped {}", games.len(), report.skipped.len());
+            Ok((games, report))
         }
 
         #[cfg(not(feature = "gog"))]
@@ -100,40 +132,223 @@ impl GameImporter for GOGImporter {
 
 impl GOGImporter {
     #[cfg(feature = "gog")]
-    fn find_game_in_folder(&self, install_path: &PathBuf, product_id: i64) -> Option<DetectedGame> {
-        // Try to find goggame-*.info file
+    fn find_game_in_folder(
+        &self,
+        install_path: &PathBuf,
+        product_id: i64,
+    ) -> Result<DetectedGame, SkipReason> {
+        // Without a goggame-*.info file nothing says what to launch
+        let mut reason = SkipReason::NoExecutable;
+
         if let Ok(entries) = std::fs::read_dir(install_path) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.starts_with("goggame-") && name.ends_with(".info") {
-                        if let Ok(content) = std::fs::read_to_string(&path) {
-                            if let Ok(info) = serde_json::from_str::<serde_json::Value>(&content) {
-                                let game_name = info.get("name")?.as_str()?.to_string();
-                                let play_tasks = info.get("playTasks")?.as_array()?;
-
-                                // Get the primary play task
-                                if let Some(task) = play_tasks.first() {
-                                    let exe_path = task.get("path")?.as_str()?;
-                                    let executable_path = install_path.join(exe_path);
-
-                                    if executable_path.exists() {
-                                        return Some(DetectedGame {
-                                            name: game_name,
-                                            source_id: product_id.to_string(),
-                                            executable_path,
-                                            install_path: install_path.clone(),
-                                            icon_path: None,
-                                        });
-                                    }
-                                }
-                            }
-                        }
+                let is_info = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| name.starts_with("goggame-") && name.ends_with(".info"));
+                if is_info {
+                    match game_from_info(&path, install_path, product_id) {
+                        Ok(game) => return Ok(game),
+                        Err(e) => reason = e,
                     }
                 }
             }
         }
 
-        None
+        Err(reason)
+    }
+}
+
+/// Read a goggame-*.info file into a detected game
+#[cfg(any(feature = "gog", target_os = "linux"))]
+pub(super) fn game_from_info(
+    path: &std::path::Path,
+    install_path: &std::path::Path,
+    product_id: i64,
+) -> Result<DetectedGame, SkipReason> {
+    let content = read_manifest(path).map_err(|_| SkipReason::Unreadable)?;
+    let info: serde_json::Value =
+        serde_json::from_str(&content).map_err(|_| SkipReason::ParseError)?;
+    let game_name = info
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or(SkipReason::ParseError)?
+        .to_string();
+    let play_tasks = info
+        .get("playTasks")
+        .and_then(|v| v.as_array())
+        .ok_or(SkipReason::ParseError)?;
+
+    // Get the primary play task
+    let exe_path = play_tasks
+        .first()
+        .and_then(|task| task.get("path"))
+        .and_then(|v| v.as_str())
+        .ok_or(SkipReason::NoExecutable)?;
+    // Play task paths use backslashes, even in builds Heroic runs through Wine
+    let exe_path = normalize_path(std::path::Path::new(exe_path));
+    let executable_path = find_path_ignoring_case(&install_path.join(exe_path))
+        .ok_or(SkipReason::NoExecutable)?;
+
+    Ok(DetectedGame {
+        name: game_name,
+        source_id: product_id.to_string(),
+        executable_path,
+        install_path: install_path.to_path_buf(),
+        icon_path: None,
+        banner_path: None,
+        playtime_minutes: None,
+        last_played: None,
+        launch_args: None,
+        emulator_command: None,
+    })
+}
+
+/// Playtime and last-played data Galaxy keeps for a product
+#[cfg(feature = "gog")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct PlayStats {
+    playtime_minutes: Option<u64>,
+    last_played: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Read playtime stats keyed by product id
+///
+/// Galaxy keys these tables by release key (`gog_<productId>`) and their
+/// layout has changed between versions, so anything missing or unreadable
+/// is skipped rather than failing the import.
+#[cfg(feature = "gog")]
+fn read_play_stats(conn: &rusqlite::Connection) -> std::collections::HashMap<i64, PlayStats> {
+    let mut stats: std::collections::HashMap<i64, PlayStats> = std::collections::HashMap::new();
+
+    let query = |sql: &str| -> rusqlite::Result<Vec<(String, Option<String>)>> {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| {
+            let value: Option<rusqlite::types::Value> = row.get(1)?;
+            let value = match value {
+                Some(rusqlite::types::Value::Integer(i)) => Some(i.to_string()),
+                Some(rusqlite::types::Value::Text(t)) => Some(t),
+                _ => None,
+            };
+            Ok((row.get::<_, String>(0)?, value))
+        })?;
+        Ok(rows.flatten().collect())
+    };
+
+    match query("SELECT releaseKey, minutesInGame FROM GameTimes") {
+        Ok(rows) => {
+            for (key, minutes) in rows {
+                if let (Some(id), Some(minutes)) = (product_id_from_release_key(&key), minutes) {
+                    stats.entry(id).or_default().playtime_minutes = minutes.parse().ok();
+                }
+            }
+        }
+        Err(e) => tracing::debug!("No GOG playtime data: {}", e),
+    }
+
+    match query("SELECT gameReleaseKey, lastPlayedDate FROM LastPlayedDates") {
+        Ok(rows) => {
+            for (key, date) in rows {
+                if let (Some(id), Some(date)) = (product_id_from_release_key(&key), date) {
+                    stats.entry(id).or_default().last_played = parse_galaxy_date(&date);
+                }
+            }
+        }
+        Err(e) => tracing::debug!("No GOG last-played data: {}", e),
+    }
+
+    stats
+}
+
+/// Extract the product id from a GOG release key like `gog_1207658924`
+#[cfg(feature = "gog")]
+fn product_id_from_release_key(key: &str) -> Option<i64> {
+    key.strip_prefix("gog_")?.parse().ok()
+}
+
+/// Galaxy stores dates either as RFC 3339 or as `YYYY-MM-DD HH:MM:SS`
+#[cfg(feature = "gog")]
+fn parse_galaxy_date(date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(date)
+        .map(|d| d.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").map(|d| d.and_utc())
+        })
+        .ok()
+}
+
+#[cfg(all(test, feature = "gog"))]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    /// Build a minimal Galaxy install: one game folder and a database
+    fn fixture(with_stats: bool) -> (PathBuf, GOGImporter) {
+        let dir = std::env::temp_dir().join(format!("launcher-gog-{}", uuid::Uuid::new_v4()));
+        let install = dir.join("Game");
+        std::fs::create_dir_all(&install).unwrap();
+        std::fs::write(install.join("game.exe"), b"").unwrap();
+        std::fs::write(
+            install.join("goggame-1207658924.info"),
+            r#"{"name": "Test Game", "playTasks": [{"path": "game.exe"}]}"#,
+        )
+        .unwrap();
+
+        let db_path = dir.join("galaxy-2.0.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE InstalledBaseProducts (productId INTEGER, localPath TEXT);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO InstalledBaseProducts VALUES (1207658924, ?1)",
+            [install.to_string_lossy()],
+        )
+        .unwrap();
+        if with_stats {
+            conn.execute_batch(
+                "CREATE TABLE GameTimes (releaseKey TEXT, minutesInGame INTEGER);
+                 INSERT INTO GameTimes VALUES ('gog_1207658924', 125);
+                 CREATE TABLE LastPlayedDates (gameReleaseKey TEXT, lastPlayedDate TEXT);
+                 INSERT INTO LastPlayedDates VALUES ('gog_1207658924', '2024-03-01 18:30:00');",
+            )
+            .unwrap();
+        }
+
+        let importer = GOGImporter {
+            database_path: Some(db_path),
+            #[cfg(target_os = "linux")]
+            heroic_installed_path: None,
+        };
+        (dir, importer)
+    }
+
+    #[test]
+    fn test_scan_reads_playtime() {
+        let (dir, importer) = fixture(true);
+        let (games, _) = importer.scan_games().unwrap();
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].name, "Test Game");
+        assert_eq!(games[0].playtime_minutes, Some(125));
+        assert_eq!(
+            games[0].last_played.map(|d| d.to_rfc3339()),
+            Some("2024-03-01T18:30:00+00:00".to_string())
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_scan_without_stats_tables() {
+        let (dir, importer) = fixture(false);
+        let (games, _) = importer.scan_games().unwrap();
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].playtime_minutes, None);
+        assert_eq!(games[0].last_played, None);
+
+        let _ = std::fs::remove_dir_all(dir);
     }
 }
@@ -0,0 +1,62 @@
+use crate::constants::IMPORT_WARN_SAMPLE_SIZE;
+
+/// Whether an import can go into the library without asking
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportCheck {
+    Commit,
+    /// The import adds more games than the user's threshold, which usually
+    /// means an import root points somewhere like a whole drive
+    ConfirmRequired(LargeImport),
+}
+
+/// An oversized import waiting for the user to confirm or abort it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeImport {
+    /// How many games the import would add
+    pub count: usize,
+    /// The first few of their names, enough to tell games from junk
+    pub sample: Vec<String>,
+}
+
+/// Check an import that would add `new_names` against the warning threshold
+pub fn check_import_size<'a>(
+    new_names: impl IntoIterator<Item = &'a str>,
+    threshold: usize,
+) -> ImportCheck {
+    let mut count = 0;
+    let mut sample = Vec::new();
+    for name in new_names {
+        count += 1;
+        if sample.len() < IMPORT_WARN_SAMPLE_SIZE {
+            sample.push(name.to_string());
+        }
+    }
+
+    if count > threshold {
+        ImportCheck::ConfirmRequired(LargeImport { count, sample })
+    } else {
+        ImportCheck::Commit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeding_threshold_requires_confirmation() {
+        let names: Vec<String> = (0..12).map(|i| format!("setup{}.exe", i)).collect();
+
+        let at_limit = check_import_size(names.iter().take(5).map(String::as_str), 5);
+        assert_eq!(at_limit, ImportCheck::Commit);
+
+        match check_import_size(names.iter().map(String::as_str), 5) {
+            ImportCheck::ConfirmRequired(large) => {
+                assert_eq!(large.count, 12);
+                assert_eq!(large.sample.len(), IMPORT_WARN_SAMPLE_SIZE);
+                assert_eq!(large.sample[0], "setup0.exe");
+            }
+            ImportCheck::Commit => panic!("oversized import was committed without asking"),
+        }
+    }
+}
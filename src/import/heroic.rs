@@ -0,0 +1,244 @@
+//! GOG games installed through Heroic Games Launcher on Linux
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::gog::game_from_info;
+use super::text::read_manifest;
+use super::{DetectedGame, ImportError, ScanReport, SkipReason};
+use crate::data::find_path_ignoring_case;
+
+/// Heroic's `gog_store/installed.json`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct InstalledList {
+    installed: Vec<HeroicInstall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HeroicInstall {
+    #[serde(rename = "appName")]
+    app_name: String,
+    install_path: PathBuf,
+    /// Usually empty, leaving the game's own files to say what to launch
+    #[serde(default)]
+    executable: String,
+    /// `windows` for builds Heroic runs through Wine, `linux` for native ones
+    #[serde(default)]
+    platform: String,
+    #[serde(default)]
+    is_dlc: bool,
+}
+
+/// A GOG library cache, only read for the titles in it
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LibraryCache {
+    games: Vec<LibraryEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibraryEntry {
+    app_name: String,
+    title: String,
+}
+
+/// Where Heroic lists its GOG installs, for a native or a Flatpak install
+pub(super) fn find_installed_list() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+    [crate::platform::xdg_config_dir(), home.join(".var/app/com.heroicgameslauncher.hgl/config")]
+        .into_iter()
+        .map(|config| config.join("heroic").join("gog_store").join("installed.json"))
+        .find(|path| path.is_file())
+}
+
+/// Read the games in Heroic's `installed.json`
+///
+/// Heroic installs Windows builds to run through Wine and native Linux
+/// builds side by side, so both are detected. Windows builds launch through
+/// Heroic, which sets up Wine for them. Titles come from Heroic's library
+/// cache when it has one.
+pub(super) fn scan_installed(
+    installed_path: &Path,
+) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+    let content = read_manifest(installed_path)?;
+    let list: InstalledList =
+        serde_json::from_str(&content).map_err(|e| ImportError::ParseError(e.to_string()))?;
+    // `<config>/heroic/gog_store/installed.json`
+    let titles = installed_path
+        .parent()
+        .and_then(Path::parent)
+        .map(read_titles)
+        .unwrap_or_default();
+
+    let mut games = Vec::new();
+    let mut report = ScanReport::default();
+    for install in &list.installed {
+        let candidate = format!("{} ({})", install.app_name, install.install_path.display());
+        let result = detect_install(install, titles.get(&install.app_name));
+        games.extend(report.record(&candidate, result));
+    }
+
+    tracing::info!("Found {} Heroic GOG games, skipped {}", games.len(), report.skipped.len());
+    Ok((games, report))
+}
+
+fn detect_install(
+    install: &HeroicInstall,
+    title: Option<&String>,
+) -> Result<DetectedGame, SkipReason> {
+    if install.is_dlc {
+        return Err(SkipReason::NotApplication);
+    }
+    let install_path = &install.install_path;
+    if !install_path.is_dir() {
+        return Err(SkipReason::NotInstalled);
+    }
+
+    // Windows builds keep GOG's own goggame-<id>.info
+    let info = install_path.join(format!("goggame-{}.info", install.app_name));
+    let from_info = install
+        .app_name
+        .parse()
+        .ok()
+        .filter(|_| info.is_file())
+        .map(|product_id| game_from_info(&info, install_path, product_id));
+    let mut game = match from_info {
+        Some(game) => game?,
+        None => native_game(install)?,
+    };
+
+    if !install.executable.trim().is_empty() {
+        game.executable_path = find_path_ignoring_case(&install_path.join(&install.executable))
+            .ok_or(SkipReason::NoExecutable)?;
+    }
+    if let Some(title) = title {
+        game.name = title.clone();
+    }
+    if is_windows_build(install, &game.executable_path) {
+        game.executable_path = PathBuf::from(format!("heroic://launch/gog/{}", install.app_name));
+    }
+    Ok(game)
+}
+
+/// Whether Heroic runs this install through Wine; older lists don't say,
+/// but the executable does
+fn is_windows_build(install: &HeroicInstall, executable_path: &Path) -> bool {
+    match install.platform.as_str() {
+        "" => executable_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("exe")),
+        platform => platform.eq_ignore_ascii_case("windows"),
+    }
+}
+
+/// A native Linux build, launched through the `start.sh` GOG ships with it
+fn native_game(install: &HeroicInstall) -> Result<DetectedGame, SkipReason> {
+    let install_path = &install.install_path;
+    let executable_path = install_path.join("start.sh");
+    if !executable_path.is_file() && install.executable.trim().is_empty() {
+        return Err(SkipReason::NoExecutable);
+    }
+    // The first line of `gameinfo` is the title
+    let name = read_manifest(&install_path.join("gameinfo"))
+        .ok()
+        .and_then(|info| info.lines().next().map(|line| line.trim().to_string()))
+        .filter(|name| !name.is_empty())
+        .or_else(|| install_path.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .ok_or(SkipReason::ParseError)?;
+
+    Ok(DetectedGame {
+        name,
+        source_id: install.app_name.clone(),
+        executable_path,
+        install_path: install_path.clone(),
+        icon_path: None,
+        banner_path: None,
+        playtime_minutes: None,
+        last_played: None,
+        launch_args: None,
+        emulator_command: None,
+    })
+}
+
+/// Game titles by app name, from whichever library cache this Heroic
+/// version writes
+fn read_titles(heroic_dir: &Path) -> HashMap<String, String> {
+    let caches = [
+        heroic_dir.join("store_cache").join("gog_library.json"),
+        heroic_dir.join("gog_store").join("library.json"),
+    ];
+    caches
+        .iter()
+        .filter_map(|path| read_manifest(path).ok())
+        .filter_map(|content| serde_json::from_str::<LibraryCache>(&content).ok())
+        .flat_map(|cache| cache.games)
+        .map(|entry| (entry.app_name, entry.title))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scan_heroic_installs() {
+        let dir = std::env::temp_dir().join(format!("launcher-heroic-{}", uuid::Uuid::new_v4()));
+        let heroic = dir.join("heroic");
+        let store = heroic.join("gog_store");
+        std::fs::create_dir_all(&store).unwrap();
+
+        // A Windows build run through Wine
+        let windows = dir.join("Games").join("Witcher");
+        std::fs::create_dir_all(windows.join("bin")).unwrap();
+        std::fs::write(windows.join("bin").join("witcher.exe"), b"").unwrap();
+        std::fs::write(
+            windows.join("goggame-1207658924.info"),
+            r#"{"name": "The Witcher", "playTasks": [{"path": "bin\\witcher.exe"}]}"#,
+        )
+        .unwrap();
+
+        // A native build
+        let native = dir.join("Games").join("Stardew");
+        std::fs::create_dir_all(&native).unwrap();
+        std::fs::write(native.join("start.sh"), b"").unwrap();
+        std::fs::write(native.join("gameinfo"), "Stardew Valley\n1.6\n").unwrap();
+
+        let installed = json!({ "installed": [
+            { "appName": "1207658924", "install_path": windows, "platform": "windows",
+              "executable": "", "is_dlc": false },
+            { "appName": "1453375253", "install_path": native, "platform": "linux" },
+            { "appName": "1207658925", "install_path": windows, "is_dlc": true },
+            { "appName": "1207658926", "install_path": dir.join("Games").join("Gone") },
+        ]});
+        std::fs::write(store.join("installed.json"), installed.to_string()).unwrap();
+        let library = json!({ "games": [
+            { "app_name": "1207658924", "title": "The Witcher: Enhanced Edition" },
+        ]});
+        std::fs::create_dir_all(heroic.join("store_cache")).unwrap();
+        std::fs::write(heroic.join("store_cache").join("gog_library.json"), library.to_string())
+            .unwrap();
+
+        let (games, report) = scan_installed(&store.join("installed.json")).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].name, "The Witcher: Enhanced Edition");
+        assert_eq!(games[0].source_id, "1207658924");
+        assert_eq!(games[0].executable_path, PathBuf::from("heroic://launch/gog/1207658924"));
+        assert_eq!(games[0].install_path, windows);
+        assert_eq!(games[1].name, "Stardew Valley");
+        assert_eq!(games[1].executable_path, native.join("start.sh"));
+        let reasons: Vec<_> = report.skipped.iter().map(|(_, reason)| *reason).collect();
+        assert_eq!(reasons, [SkipReason::NotApplication, SkipReason::NotInstalled]);
+
+        std::fs::write(store.join("installed.json"), "not json").unwrap();
+        assert!(matches!(
+            scan_installed(&store.join("installed.json")),
+            Err(ImportError::ParseError(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
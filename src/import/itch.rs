@@ -0,0 +1,343 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+use super::{DetectedGame, GameImporter, ImportError, ScanReport, SkipReason};
+use crate::data::{executable_candidates, ExcludePatterns, GameSource};
+
+/// Launch flavors butler picks out for this platform, best first
+#[cfg(target_os = "windows")]
+const NATIVE_FLAVORS: &[&str] = &["windows", "windows-script"];
+#[cfg(target_os = "macos")]
+const NATIVE_FLAVORS: &[&str] = &["app-macos", "macos", "script"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const NATIVE_FLAVORS: &[&str] = &["linux", "script"];
+
+/// An installed game ("cave") as butler records it
+#[derive(Debug, Clone, Default)]
+struct ItchCave {
+    game_id: i64,
+    title: String,
+    /// JSON describing the install folder and what in it can be launched
+    verdict: Option<String>,
+    install_location: Option<String>,
+    install_folder_name: Option<String>,
+    seconds_run: Option<i64>,
+    last_touched_at: Option<String>,
+}
+
+/// Importer for games installed by the itch.io app
+pub struct ItchImporter {
+    database_path: Option<PathBuf>,
+    exclude: ExcludePatterns,
+}
+
+impl ItchImporter {
+    pub fn new() -> Self {
+        Self {
+            database_path: Self::find_database_path(),
+            exclude: ExcludePatterns::default(),
+        }
+    }
+
+    /// Skip executables in folders matching the user's exclusion patterns
+    pub fn with_exclude_patterns(mut self, exclude: ExcludePatterns) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Find butler's database in the itch app's config folder
+    ///
+    /// That is `%APPDATA%\itch` on Windows, `~/Library/Application Support/itch`
+    /// on macOS and `~/.config/itch` elsewhere.
+    fn find_database_path() -> Option<PathBuf> {
+        let base = directories::BaseDirs::new()?;
+        let path = base.config_dir().join("itch").join("db").join("butler.db");
+        path.is_file().then_some(path)
+    }
+
+    /// Read every installed cave with its game
+    #[cfg(feature = "itch")]
+    fn installed_caves(&self, db_path: &std::path::Path) -> rusqlite::Result<Vec<ItchCave>> {
+        use rusqlite::{Connection, OpenFlags};
+
+        // The itch app keeps the database open; reading alongside it is fine
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut stmt = conn.prepare(
+            "SELECT games.id, games.title, caves.verdict, install_locations.path,
+                    caves.install_folder_name, caves.seconds_run, caves.last_touched_at
+             FROM caves
+             JOIN games ON games.id = caves.game_id
+             LEFT JOIN install_locations ON install_locations.id = caves.install_location_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ItchCave {
+                game_id: row.get(0)?,
+                title: row.get(1)?,
+                verdict: row.get(2)?,
+                install_location: row.get(3)?,
+                install_folder_name: row.get(4)?,
+                seconds_run: row.get(5)?,
+                last_touched_at: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+/// Turn an installed cave into a detected game
+///
+/// The verdict butler stored at install time says where the game is and
+/// what to launch; the install folder is searched when it doesn't.
+fn detect_cave(cave: ItchCave, exclude: &ExcludePatterns) -> Result<DetectedGame, SkipReason> {
+    let verdict: Option<serde_json::Value> = cave
+        .verdict
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|_| SkipReason::ParseError)?;
+    let base_path = verdict
+        .as_ref()
+        .and_then(|v| v.get("basePath"))
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .or_else(|| {
+            let location = cave.install_location.as_ref()?;
+            Some(PathBuf::from(location).join(cave.install_folder_name.as_ref()?))
+        })
+        .ok_or(SkipReason::ParseError)?;
+    if !base_path.is_dir() {
+        return Err(SkipReason::NotInstalled);
+    }
+
+    let candidates: Vec<(&str, &str)> = verdict
+        .as_ref()
+        .and_then(|v| v.get("candidates"))
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| {
+            let path = c.get("path")?.as_str()?;
+            Some((path, c.get("flavor").and_then(|f| f.as_str()).unwrap_or_default()))
+        })
+        .collect();
+    // Native builds first, then anything else butler could launch
+    let ranked = NATIVE_FLAVORS
+        .iter()
+        .flat_map(|flavor| candidates.iter().filter(move |(_, f)| f == flavor))
+        .chain(&candidates);
+    let executable_path = ranked
+        .map(|(path, _)| base_path.join(path))
+        .find(|path| path.exists())
+        // Builds often keep their binary a folder or two down
+        .or_else(|| executable_candidates(&base_path, exclude, 2).into_iter().next())
+        .ok_or(SkipReason::NoExecutable)?;
+
+    let name = cave.title.trim();
+    if name.is_empty() {
+        return Err(SkipReason::ParseError);
+    }
+    Ok(DetectedGame {
+        name: name.to_string(),
+        source_id: cave.game_id.to_string(),
+        executable_path,
+        install_path: base_path,
+        icon_path: None,
+        banner_path: None,
+        playtime_minutes: cave
+            .seconds_run
+            .and_then(|s| u64::try_from(s / 60).ok())
+            .filter(|&m| m > 0),
+        last_played: cave.last_touched_at.as_deref().and_then(parse_butler_date),
+        launch_args: None,
+        emulator_command: None,
+    })
+}
+
+/// butler writes Go's `2006-01-02 15:04:05.999999999-07:00` layout, and
+/// older versions RFC 3339
+fn parse_butler_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S%.f%:z")
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+}
+
+impl Default for ItchImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameImporter for ItchImporter {
+    fn source(&self) -> GameSource {
+        GameSource::Itch
+    }
+
+    fn is_available(&self) -> bool {
+        self.database_path.is_some()
+    }
+
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        // itch.io import requires the rusqlite feature
+        #[cfg(feature = "itch")]
+        {
+            let db_path = self.database_path.as_ref().ok_or(ImportError::NotInstalled)?;
+            let caves = self
+                .installed_caves(db_path)
+                .map_err(|e| ImportError::DatabaseError(e.to_string()))?;
+
+            let mut report = ScanReport::default();
+            let games: Vec<DetectedGame> = caves
+                .into_iter()
+                .filter_map(|cave| {
+                    let candidate = format!("game {} ({})", cave.game_id, cave.title);
+                    report.record(&candidate, detect_cave(cave, &self.exclude))
+                })
+                .collect();
+
+            tracing::info!("Found {} itch.io games, skipped {}", games.len(), report.skipped.len());
+            Ok((games, report))
+        }
+
+        #[cfg(not(feature = "itch"))]
+        {
+            tracing::warn!("itch.io import requires the 'itch' feature to be enabled");
+            Err(ImportError::NotInstalled)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// An install folder holding the given files
+    fn install_with(files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("launcher-itch-{}", uuid::Uuid::new_v4()))
+            .join("celeste");
+        for file in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"").unwrap();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let executable = std::fs::Permissions::from_mode(0o755);
+                std::fs::set_permissions(&path, executable).unwrap();
+            }
+        }
+        dir
+    }
+
+    fn cleanup(install: &std::path::Path) {
+        let _ = std::fs::remove_dir_all(install.parent().unwrap());
+    }
+
+    fn cave(install: &std::path::Path, candidates: serde_json::Value) -> ItchCave {
+        let verdict = json!({ "basePath": install, "candidates": candidates });
+        ItchCave {
+            game_id: 1234,
+            title: " Celeste ".to_string(),
+            verdict: Some(verdict.to_string()),
+            seconds_run: Some(7260),
+            last_touched_at: Some("2024-03-01 18:30:00.123456789+00:00".to_string()),
+            ..ItchCave::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_cave_prefers_native_candidate() {
+        let install = install_with(&["Celeste.exe", "Celeste.bin.x86_64", "Celeste.app/Info"]);
+        let candidates = json!([
+            { "path": "Celeste.exe", "flavor": "windows" },
+            { "path": "Celeste.bin.x86_64", "flavor": "linux" },
+            { "path": "Celeste.app", "flavor": "app-macos" },
+        ]);
+
+        let game = detect_cave(cave(&install, candidates), &ExcludePatterns::default()).unwrap();
+        let native = match NATIVE_FLAVORS[0] {
+            "windows" => "Celeste.exe",
+            "app-macos" => "Celeste.app",
+            _ => "Celeste.bin.x86_64",
+        };
+        assert_eq!(game.executable_path, install.join(native));
+        assert_eq!(game.name, "Celeste");
+        assert_eq!(game.source_id, "1234");
+        assert_eq!(game.playtime_minutes, Some(121));
+        assert_eq!(
+            game.last_played.map(|d| d.to_rfc3339()),
+            Some("2024-03-01T18:30:00.123456789+00:00".to_string())
+        );
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_detect_cave_without_verdict() {
+        let install = install_with(&["game/Celeste.exe"]);
+        let location = install.parent().unwrap().to_string_lossy().into_owned();
+        let bare = ItchCave {
+            game_id: 1234,
+            title: "Celeste".to_string(),
+            install_location: Some(location),
+            install_folder_name: Some("celeste".to_string()),
+            ..ItchCave::default()
+        };
+
+        // Without candidates the install folder is searched
+        let game = detect_cave(bare.clone(), &ExcludePatterns::default()).unwrap();
+        assert_eq!(game.install_path, install);
+        assert_eq!(game.playtime_minutes, None);
+
+        cleanup(&install);
+        assert_eq!(
+            detect_cave(bare, &ExcludePatterns::default()).unwrap_err(),
+            SkipReason::NotInstalled
+        );
+    }
+
+    #[cfg(feature = "itch")]
+    #[test]
+    fn test_scan_reads_butler_database() {
+        use rusqlite::Connection;
+
+        let install = install_with(&["Celeste.exe"]);
+        let db_path = install.parent().unwrap().join("butler.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE games (id INTEGER PRIMARY KEY, title TEXT);
+             CREATE TABLE install_locations (id TEXT PRIMARY KEY, path TEXT);
+             CREATE TABLE caves (id TEXT PRIMARY KEY, game_id INTEGER, verdict TEXT,
+                 install_location_id TEXT, install_folder_name TEXT, seconds_run INTEGER,
+                 last_touched_at DATETIME);
+             INSERT INTO games VALUES (1234, 'Celeste'), (99, 'Uninstalled');",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO install_locations VALUES ('loc', ?1)",
+            [install.parent().unwrap().to_string_lossy()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO caves VALUES ('cave', 1234, NULL, 'loc', 'celeste', 600, NULL)",
+            [],
+        )
+        .unwrap();
+
+        let importer = ItchImporter {
+            database_path: Some(db_path),
+            exclude: ExcludePatterns::default(),
+        };
+        let (games, report) = importer.scan_games().unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].name, "Celeste");
+        assert_eq!(games[0].executable_path, install.join("Celeste.exe"));
+        assert_eq!(games[0].playtime_minutes, Some(10));
+        assert_eq!(report.candidates, 1);
+
+        cleanup(&install);
+    }
+}
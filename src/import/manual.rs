@@ -1,17 +1,33 @@
 // Manual game import - for games added by the user directly
 // This module provides utilities for manual game addition
 
-use std::path::PathBuf;
-
-use crate::data::{Game, GameSource};
-
-/// Create a manual game entry
-pub fn create_manual_game(name: String, executable_path: PathBuf) -> Game {
-    Game::new(name, executable_path, GameSource::Manual)
+use std::path::{Path, PathBuf};
+
+use crate::data::{is_uri, CategoryId, Clock, Game, GameSource};
+
+/// Create a manual game entry, in the default manual category if there is one
+pub fn create_manual_game(
+    name: String,
+    executable_path: PathBuf,
+    category: Option<CategoryId>,
+    clock: &dyn Clock,
+) -> Game {
+    let mut game = Game::new(name, executable_path, GameSource::Manual, clock);
+    if let Some(id) = category {
+        game.add_category(id);
+    }
+    game
 }
 
 /// Validate that an executable path exists and is a file
-pub fn validate_executable(path: &PathBuf) -> Result<(), ManualImportError> {
+///
+/// URLs and protocol links are accepted as they are, the system handler
+/// opens them at launch.
+pub fn validate_executable(path: &Path) -> Result<(), ManualImportError> {
+    if path.to_str().is_some_and(is_uri) {
+        return Ok(());
+    }
+
     if !path.exists() {
         return Err(ManualImportError::PathNotFound);
     }
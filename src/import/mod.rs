@@ -1,30 +1,71 @@
 //! Import system for detecting games from various launchers
 
+mod cache;
+mod folders;
 mod steam;
+mod steam_appinfo;
+mod steam_collections;
+mod steam_playtime;
+mod steam_shortcuts;
 mod epic;
 mod gog;
+mod itch;
+#[cfg(target_os = "linux")]
+mod heroic;
+mod ubisoft;
+mod xbox;
 mod manual;
+mod guard;
+mod report;
+mod roms;
+mod summary;
+mod text;
+mod vdf;
 
 // Re-exports - will be used when import UI is connected
+pub use cache::{CachedScan, ScanCache, ScanInputs};
+pub use guard::{check_import_size, ImportCheck, LargeImport};
+pub use summary::{refresh_from_import, ImportSummary};
 #[allow(unused_imports)]
-pub use steam::SteamImporter;
+pub use steam::{steamapps_dir, SteamImporter};
+pub use steam_collections::{read_steam_collections, SteamCollection};
 #[allow(unused_imports)]
 pub use epic::EpicImporter;
 #[allow(unused_imports)]
 pub use gog::GOGImporter;
+pub use itch::ItchImporter;
+pub use ubisoft::UbisoftImporter;
+pub use xbox::XboxImporter;
+pub use manual::{create_manual_game, validate_executable};
+pub use report::{ScanReport, SkipReason};
+pub use roms::RomImporter;
 
-use crate::data::{Game, GameSource};
+use crate::cache::art_shape;
+use crate::data::{normalize_path, Clock, Game, GameSource};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
 /// A game detected during import
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectedGame {
     pub name: String,
     pub source_id: String,
     pub executable_path: PathBuf,
     pub install_path: PathBuf,
     pub icon_path: Option<PathBuf>,
+    /// Cover art the store already has on disk
+    #[serde(default)]
+    pub banner_path: Option<PathBuf>,
+    /// Playtime recorded by the store, when it tracks it
+    pub playtime_minutes: Option<u64>,
+    /// Last time the store saw the game played
+    pub last_played: Option<DateTime<Utc>>,
+    /// Arguments the store passes when launching
+    pub launch_args: Option<String>,
+    /// Command template that runs the game through an emulator
+    pub emulator_command: Option<String>,
 }
 
 /// Trait for game importers
@@ -35,20 +76,57 @@ pub trait GameImporter {
     /// Check if the launcher is installed and available
     fn is_available(&self) -> bool;
 
-    /// Scan for installed games
-    fn scan_games(&self) -> Result<Vec<DetectedGame>, ImportError>;
+    /// Scan for installed games, with a report of everything looked at
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError>;
 }
 
 /// Convert a detected game to a library game
 impl DetectedGame {
-    pub fn into_game(self, source: GameSource) -> Game {
-        Game::from_import(
+    /// Normalize the detected paths so they compare consistently across runs
+    pub fn normalized(mut self) -> Self {
+        self.executable_path = normalize_path(&self.executable_path);
+        self.install_path = normalize_path(&self.install_path);
+        self.icon_path = self.icon_path.map(|p| normalize_path(&p));
+        self.banner_path = self.banner_path.map(|p| normalize_path(&p));
+        self
+    }
+
+    /// A game the store lists whose install folder is gone
+    ///
+    /// Its executable is unknown, so it is left empty and the game shows as
+    /// not installed until a later scan finds it on disk.
+    pub fn not_installed(name: String, source_id: String, install_path: PathBuf) -> Self {
+        Self {
+            name,
+            source_id,
+            executable_path: PathBuf::new(),
+            install_path,
+            icon_path: None,
+            banner_path: None,
+            playtime_minutes: None,
+            last_played: None,
+            launch_args: None,
+            emulator_command: None,
+        }
+    }
+
+    pub fn into_game(self, source: GameSource, clock: &dyn Clock) -> Game {
+        let mut game = Game::from_import(
             self.name,
             self.executable_path,
             self.install_path,
             source,
             self.source_id,
-        )
+            clock,
+        );
+        game.icon_path = self.icon_path;
+        game.banner_shape = self.banner_path.as_deref().and_then(art_shape);
+        game.banner_path = self.banner_path;
+        game.playtime_minutes = self.playtime_minutes.unwrap_or_default();
+        game.last_played = self.last_played;
+        game.launch_args = self.launch_args;
+        game.emulator_command = self.emulator_command;
+        game
     }
 }
 
@@ -0,0 +1,137 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::DetectedGame;
+
+/// Why something an importer looked at didn't become a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// The manifest couldn't be read from disk
+    Unreadable,
+    /// The manifest was malformed or missing required fields
+    ParseError,
+    /// DLC, plugins and other entries with nothing to launch
+    NotApplication,
+    /// The install folder no longer exists
+    NotInstalled,
+    /// No launchable executable in the install folder
+    NoExecutable,
+    /// Already found earlier in the same scan, e.g. in two library folders
+    Duplicate,
+}
+
+impl SkipReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkipReason::Unreadable => "manifest unreadable",
+            SkipReason::ParseError => "manifest parse error",
+            SkipReason::NotApplication => "not a game (DLC or plugin)",
+            SkipReason::NotInstalled => "install folder missing",
+            SkipReason::NoExecutable => "executable not found",
+            SkipReason::Duplicate => "duplicate entry",
+        }
+    }
+}
+
+/// What a scan looked at, for diagnosing games that didn't import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanReport {
+    /// Manifests, registry entries or database rows looked at
+    pub candidates: usize,
+    /// Each skipped candidate (usually its manifest path) and why
+    pub skipped: Vec<(String, SkipReason)>,
+    /// Library folders that couldn't be read, like an offline network share,
+    /// and why
+    pub unreachable: Vec<(String, String)>,
+    #[serde(skip)]
+    seen: HashSet<String>,
+}
+
+impl ScanReport {
+    /// Log and tally one candidate, returning the game if it should be kept
+    pub fn record(
+        &mut self,
+        candidate: &str,
+        result: Result<DetectedGame, SkipReason>,
+    ) -> Option<DetectedGame> {
+        self.candidates += 1;
+        let result = result.and_then(|game| {
+            if self.seen.insert(game.source_id.clone()) {
+                Ok(game)
+            } else {
+                Err(SkipReason::Duplicate)
+            }
+        });
+
+        match result {
+            Ok(game) => {
+                tracing::debug!("{}: found {} ({})", candidate, game.name, game.source_id);
+                Some(game)
+            }
+            Err(reason) => {
+                tracing::debug!("{}: skipped, {}", candidate, reason.label());
+                self.skipped.push((candidate.to_string(), reason));
+                None
+            }
+        }
+    }
+
+    /// Log and note a library folder the scan had to leave out
+    pub fn record_unreachable(&mut self, folder: &str, error: &dyn std::fmt::Display) {
+        tracing::warn!("Skipping library folder {}: {}", folder, error);
+        self.unreachable.push((folder.to_string(), error.to_string()));
+    }
+
+    /// How many candidates were skipped for each reason
+    pub fn skip_counts(&self) -> Vec<(SkipReason, usize)> {
+        let mut counts = BTreeMap::new();
+        for (_, reason) in &self.skipped {
+            *counts.entry(*reason).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn detected(source_id: &str) -> DetectedGame {
+        DetectedGame {
+            name: format!("Game {}", source_id),
+            source_id: source_id.to_string(),
+            executable_path: PathBuf::from("game.exe"),
+            install_path: PathBuf::from("game"),
+            icon_path: None,
+            banner_path: None,
+            playtime_minutes: None,
+            last_played: None,
+            launch_args: None,
+            emulator_command: None,
+        }
+    }
+
+    #[test]
+    fn test_record_tallies_skips_and_duplicates() {
+        let mut report = ScanReport::default();
+
+        assert!(report.record("a.acf", Ok(detected("1"))).is_some());
+        assert!(report.record("b.acf", Ok(detected("1"))).is_none());
+        assert!(report.record("c.acf", Err(SkipReason::NoExecutable)).is_none());
+        assert!(report.record("d.acf", Err(SkipReason::NoExecutable)).is_none());
+        assert!(report.record("e.acf", Err(SkipReason::ParseError)).is_none());
+
+        assert_eq!(report.candidates, 5);
+        assert_eq!(report.skipped[0], ("b.acf".to_string(), SkipReason::Duplicate));
+        assert_eq!(
+            report.skip_counts(),
+            vec![
+                (SkipReason::ParseError, 1),
+                (SkipReason::NoExecutable, 2),
+                (SkipReason::Duplicate, 1),
+            ]
+        );
+    }
+}
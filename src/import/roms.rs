@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::{DetectedGame, GameImporter, ImportError, ScanReport, SkipReason};
+use crate::constants::ROM_SCAN_DEPTH;
+use crate::data::{ExcludePatterns, GameSource};
+
+/// Importer for a folder of ROMs, each launched through an emulator
+///
+/// Which emulator runs a ROM is picked by its file extension, from the
+/// user's mapping of extensions to command templates.
+pub struct RomImporter {
+    rom_dir: PathBuf,
+    /// Lowercase extension, without the dot, to emulator command template
+    emulators: BTreeMap<String, String>,
+    exclude: ExcludePatterns,
+}
+
+impl RomImporter {
+    pub fn new(rom_dir: PathBuf, emulators: BTreeMap<String, String>) -> Self {
+        Self {
+            rom_dir,
+            emulators,
+            exclude: ExcludePatterns::default(),
+        }
+    }
+
+    /// Skip ROMs in folders matching the user's exclusion patterns
+    pub fn with_exclude_patterns(mut self, exclude: ExcludePatterns) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// The emulator template for a ROM, if its extension has one
+    fn emulator_for(&self, path: &Path) -> Option<&String> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        self.emulators.get(&extension)
+    }
+
+    /// Files under the ROM folder that aren't excluded, sorted
+    fn rom_files(&self) -> Vec<PathBuf> {
+        fn collect(
+            root: &Path,
+            dir: &Path,
+            exclude: &ExcludePatterns,
+            depth: usize,
+            out: &mut Vec<PathBuf>,
+        ) {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.strip_prefix(root).is_ok_and(|relative| exclude.is_excluded(relative)) {
+                    continue;
+                }
+                if path.is_dir() && depth > 0 {
+                    collect(root, &path, exclude, depth - 1, out);
+                } else if path.is_file() {
+                    out.push(path);
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        collect(&self.rom_dir, &self.rom_dir, &self.exclude, ROM_SCAN_DEPTH, &mut files);
+        files.sort();
+        files
+    }
+
+    fn detect_rom(&self, path: PathBuf) -> Result<DetectedGame, SkipReason> {
+        let command = self.emulator_for(&path).ok_or(SkipReason::NotApplication)?;
+        let stem = path.file_stem().ok_or(SkipReason::ParseError)?.to_string_lossy();
+        let name = rom_title(&stem);
+        if name.is_empty() {
+            return Err(SkipReason::ParseError);
+        }
+
+        Ok(DetectedGame {
+            name,
+            // The path is the only thing that tells two ROMs apart
+            source_id: path.to_string_lossy().into_owned(),
+            install_path: path.parent().unwrap_or(&self.rom_dir).to_path_buf(),
+            executable_path: path,
+            icon_path: None,
+            banner_path: None,
+            playtime_minutes: None,
+            last_played: None,
+            launch_args: None,
+            emulator_command: Some(command.clone()),
+        })
+    }
+}
+
+/// A ROM's title without the region and dump tags in its file name
+///
+/// "Super Mario World (USA) [!]" becomes "Super Mario World".
+fn rom_title(stem: &str) -> String {
+    let mut title = String::new();
+    let mut depth = 0usize;
+    for c in stem.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => title.push(if c == '_' { ' ' } else { c }),
+            _ => {}
+        }
+    }
+    title.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl GameImporter for RomImporter {
+    fn source(&self) -> GameSource {
+        GameSource::Emulator
+    }
+
+    fn is_available(&self) -> bool {
+        self.rom_dir.is_dir() && !self.emulators.is_empty()
+    }
+
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        if !self.rom_dir.is_dir() {
+            return Err(ImportError::PathNotFound);
+        }
+
+        let mut report = ScanReport::default();
+        let games: Vec<DetectedGame> = self
+            .rom_files()
+            .into_iter()
+            // Saves, readmes and the like aren't worth listing as skipped
+            .filter(|path| self.emulator_for(path).is_some())
+            .filter_map(|path| {
+                let candidate = path.to_string_lossy().into_owned();
+                report.record(&candidate, self.detect_rom(path))
+            })
+            .collect();
+
+        tracing::info!(
+            "Found {} ROMs in {:?}, skipped {}",
+            games.len(),
+            self.rom_dir,
+            report.skipped.len()
+        );
+        Ok((games, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_maps_extensions_to_emulators() {
+        let dir = std::env::temp_dir().join(format!("launcher-roms-{}", uuid::Uuid::new_v4()));
+        for file in [
+            "Super Mario World (USA) [!].sfc",
+            "nes/Metroid (Europe).NES",
+            "nes/Metroid (Europe).srm",
+            "BIOS/scph1001.bin",
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"").unwrap();
+        }
+        let emulators = BTreeMap::from([
+            ("sfc".to_string(), "snes9x \"{rom}\"".to_string()),
+            ("nes".to_string(), "retroarch -L nestopia.so \"{rom}\"".to_string()),
+            ("bin".to_string(), "duckstation \"{rom}\"".to_string()),
+        ]);
+
+        let importer = RomImporter::new(dir.clone(), emulators)
+            .with_exclude_patterns(ExcludePatterns::new(&["bios".to_string()]));
+        assert!(importer.is_available());
+        let (games, _) = importer.scan_games().unwrap();
+
+        let found: Vec<(&str, &str)> = games
+            .iter()
+            .map(|g| (g.name.as_str(), g.emulator_command.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Super Mario World", "snes9x \"{rom}\""),
+                ("Metroid", "retroarch -L nestopia.so \"{rom}\""),
+            ]
+        );
+        assert_eq!(games[1].install_path, dir.join("nes"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rom_title() {
+        assert_eq!(rom_title("Super Mario World (USA) [!]"), "Super Mario World");
+        assert_eq!(rom_title("Chrono_Trigger"), "Chrono Trigger");
+        assert_eq!(rom_title("(Unl) [b]"), "");
+    }
+}
@@ -1,20 +1,72 @@
-use std::path::PathBuf;
-
-use super::{DetectedGame, GameImporter, ImportError};
-use crate::data::GameSource;
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::folders::{FolderError, FolderProbe};
+use super::steam_appinfo::read_app_names;
+use super::steam_playtime::read_steam_play_stats;
+use super::steam_shortcuts::scan_shortcuts;
+use super::text::read_manifest;
+use super::{DetectedGame, GameImporter, ImportError, ScanReport, SkipReason};
+use crate::cache::steam_cached_art;
+use crate::data::{find_executable_in_dir, normalize_path, ExcludePatterns, GameSource};
 
 /// Importer for Steam games
 pub struct SteamImporter {
     steam_path: Option<PathBuf>,
+    extra_library_paths: Vec<PathBuf>,
+    exclude: ExcludePatterns,
+    include_not_installed: bool,
+    folders: FolderProbe,
 }
 
 impl SteamImporter {
     pub fn new() -> Self {
         Self {
             steam_path: Self::find_steam_path(),
+            extra_library_paths: Vec::new(),
+            exclude: ExcludePatterns::default(),
+            include_not_installed: false,
+            folders: FolderProbe::default(),
         }
     }
 
+    /// Also list games whose manifest remains but whose files are gone
+    pub fn with_not_installed(mut self, include: bool) -> Self {
+        self.include_not_installed = include;
+        self
+    }
+
+    /// Also scan user-configured library folders that Steam may not know about
+    pub fn with_extra_library_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.extra_library_paths = paths;
+        self
+    }
+
+    /// Skip executables in folders matching the user's exclusion patterns
+    pub fn with_exclude_patterns(mut self, exclude: ExcludePatterns) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Get the detected Steam installation path
+    pub fn steam_path(&self) -> Option<&Path> {
+        self.steam_path.as_deref()
+    }
+
+    /// Scan the games added to Steam by hand rather than bought there
+    pub fn scan_shortcuts(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        let steam_path = self.steam_path.as_deref().ok_or(ImportError::NotInstalled)?;
+        let mut report = ScanReport::default();
+        let games = scan_shortcuts(steam_path, &mut report);
+        tracing::info!(
+            "Found {} non-Steam shortcuts, skipped {}",
+            games.len(),
+            report.skipped.len()
+        );
+        Ok((games, report))
+    }
+
     /// Find Steam installation path
     #[cfg(target_os = "windows")]
     fn find_steam_path() -> Option<PathBuf> {
@@ -78,8 +130,35 @@ impl SteamImporter {
     }
 
     /// Get all Steam library folders
-    fn get_library_folders(&self) -> Result<Vec<PathBuf>, ImportError> {
-        let steam_path = self.steam_path.as_ref().ok_or(ImportError::NotInstalled)?;
+    ///
+    /// Folders that don't answer, like a network share that's offline, are
+    /// noted in `report` and left out rather than failing the scan.
+    fn get_library_folders(&self, report: &mut ScanReport) -> Result<Vec<PathBuf>, ImportError> {
+        let mut folders = match &self.steam_path {
+            Some(steam_path) => self.get_steam_library_folders(steam_path, report)?,
+            None if !self.extra_library_paths.is_empty() => Vec::new(),
+            None => return Err(ImportError::NotInstalled),
+        };
+
+        // Merge in manually configured folders
+        for path in &self.extra_library_paths {
+            match find_steamapps(path, |p| self.folders.is_dir(p)) {
+                Ok(Some(steamapps)) if !folders.contains(&steamapps) => folders.push(steamapps),
+                Ok(Some(_)) => {}
+                Ok(None) => tracing::warn!("Configured Steam library has no steamapps: {:?}", path),
+                Err(e) => report.record_unreachable(&path.display().to_string(), &e),
+            }
+        }
+
+        Ok(folders)
+    }
+
+    /// Get the library folders Steam itself knows about
+    fn get_steam_library_folders(
+        &self,
+        steam_path: &Path,
+        report: &mut ScanReport,
+    ) -> Result<Vec<PathBuf>, ImportError> {
         let vdf_path = steam_path.join("steamapps").join("libraryfolders.vdf");
 
         if !vdf_path.exists() {
@@ -87,7 +166,7 @@ impl SteamImporter {
         }
 
         // Parse VDF file to find additional library folders
-        let content = std::fs::read_to_string(&vdf_path)?;
+        let content = read_manifest(&vdf_path)?;
         let mut folders = vec![steam_path.join("steamapps")];
 
         // Simple VDF parsing - look for "path" entries
@@ -95,10 +174,14 @@ impl SteamImporter {
             let line = line.trim();
             if line.starts_with("\"path\"") {
                 if let Some(path_str) = extract_vdf_value(line) {
-                    let path = PathBuf::from(path_str);
-                    let steamapps = path.join("steamapps");
-                    if steamapps.exists() && !folders.contains(&steamapps) {
-                        folders.push(steamapps);
+                    let steamapps = library_path(&path_str).join("steamapps");
+                    if folders.contains(&steamapps) {
+                        continue;
+                    }
+                    match self.folders.is_dir(&steamapps) {
+                        Ok(true) => folders.push(steamapps),
+                        Ok(false) => {}
+                        Err(e) => report.record_unreachable(&steamapps.display().to_string(), &e),
                     }
                 }
             }
@@ -107,30 +190,79 @@ impl SteamImporter {
         Ok(folders)
     }
 
-    /// Parse an appmanifest file
-    fn parse_app_manifest(&self, path: &PathBuf) -> Option<DetectedGame> {
-        let content = std::fs::read_to_string(path).ok()?;
+    /// Parse an appmanifest file and find the game it describes
+    ///
+    /// Reading the manifest and searching the install folder both touch the
+    /// library folder, so they run under the folder time limit; a share that
+    /// stalls partway through a scan gives [`FolderError::TimedOut`].
+    fn parse_app_manifest(
+        &self,
+        path: &Path,
+        app_names: &OnceCell<HashMap<String, String>>,
+    ) -> Result<Result<DetectedGame, SkipReason>, FolderError> {
+        let manifest = path.to_path_buf();
+        let Ok(content) = self.folders.run(move || read_manifest(&manifest))? else {
+            return Ok(Err(SkipReason::Unreadable));
+        };
+        let game = match self.game_from_manifest(path, &content, app_names) {
+            Ok(game) => game,
+            Err(reason) => return Ok(Err(reason)),
+        };
+
+        let install_path = game.install_path.clone();
+        let exclude = self.exclude.clone();
+        let executable = self.folders.run(move || {
+            // Try to find the main executable
+            install_path.exists().then(|| find_executable_in_dir(&install_path, &exclude))
+        })?;
+        Ok(match executable {
+            Some(Some(executable_path)) => Ok(DetectedGame { executable_path, ..game }),
+            Some(None) => Err(SkipReason::NoExecutable),
+            None if self.include_not_installed => Ok(game),
+            None => Err(SkipReason::NotInstalled),
+        })
+    }
 
-        let app_id = extract_vdf_value_by_key(&content, "appid")?;
-        let name = extract_vdf_value_by_key(&content, "name")?;
-        let install_dir = extract_vdf_value_by_key(&content, "installdir")?;
+    /// The game a manifest describes, not yet looked for on disk
+    ///
+    /// A missing or placeholder name is looked up in `app_names`, which is
+    /// only read from Steam's app cache the first time one is needed.
+    fn game_from_manifest(
+        &self,
+        path: &Path,
+        content: &str,
+        app_names: &OnceCell<HashMap<String, String>>,
+    ) -> Result<DetectedGame, SkipReason> {
+
+        let value = |key| extract_vdf_value_by_key(content, key).ok_or(SkipReason::ParseError);
+        let app_id = value("appid")?;
+        let install_dir = value("installdir")?;
+        let name = match extract_vdf_value_by_key(content, "name") {
+            Some(name) if !is_placeholder_name(&name, &app_id) => name,
+            _ => {
+                let known = app_names.get_or_init(|| {
+                    self.steam_path.as_deref().map(read_app_names).unwrap_or_default()
+                });
+                let name = known.get(&app_id).cloned().ok_or(SkipReason::ParseError)?;
+                tracing::debug!("{}: no name in manifest, using {:?} from app info", app_id, name);
+                name
+            }
+        };
 
-        let library_path = path.parent()?;
+        let library_path = path.parent().ok_or(SkipReason::ParseError)?;
         let install_path = library_path.join("common").join(&install_dir);
 
-        if !install_path.exists() {
-            return None;
-        }
-
-        // Try to find the main executable
-        let executable_path = find_executable_in_dir(&install_path)?;
-
-        Some(DetectedGame {
-            name,
-            source_id: app_id,
-            executable_path,
-            install_path,
-            icon_path: None, // Steam icons are handled differently
+        // Steam keeps the art it shows in its own library, installed or not
+        let (banner_path, icon_path) = self
+            .steam_path
+            .as_deref()
+            .map(|steam_path| steam_cached_art(steam_path, &app_id))
+            .unwrap_or_default();
+
+        Ok(DetectedGame {
+            banner_path,
+            icon_path,
+            ..DetectedGame::not_installed(name, app_id, install_path)
         })
     }
 }
@@ -147,22 +279,38 @@ impl GameImporter for SteamImporter {
     }
 
     fn is_available(&self) -> bool {
-        self.steam_path.is_some()
+        self.steam_path.is_some() || !self.extra_library_paths.is_empty()
     }
 
-    fn scan_games(&self) -> Result<Vec<DetectedGame>, ImportError> {
-        let library_folders = self.get_library_folders()?;
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        let mut report = ScanReport::default();
+        let library_folders = self.get_library_folders(&mut report)?;
         let mut games = Vec::new();
+        let app_names = OnceCell::new();
 
         for folder in library_folders {
+            // One folder that can't be read doesn't stop the others
+            let entries = match self.folders.list(&folder) {
+                Ok(entries) => entries,
+                Err(FolderError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    report.record_unreachable(&folder.display().to_string(), &e);
+                    continue;
+                }
+            };
+
             // Find all appmanifest_*.acf files
-            if let Ok(entries) = std::fs::read_dir(&folder) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if name.starts_with("appmanifest_") && name.ends_with(".acf") {
-                            if let Some(game) = self.parse_app_manifest(&path) {
-                                games.push(game);
+            for path in entries {
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    if name.starts_with("appmanifest_") && name.ends_with(".acf") {
+                        match self.parse_app_manifest(&path, &app_names) {
+                            Ok(result) => {
+                                games.extend(report.record(&path.display().to_string(), result))
+                            }
+                            // The share stopped answering; the rest of it would too
+                            Err(e) => {
+                                report.record_unreachable(&folder.display().to_string(), &e);
+                                break;
                             }
                         }
                     }
@@ -170,11 +318,69 @@ impl GameImporter for SteamImporter {
             }
         }
 
-        tracing::info!("Found {} Steam games", games.len());
-        Ok(games)
+        // Manifests don't record playtime, the accounts' local configs do
+        if let Some(steam_path) = &self.steam_path {
+            let stats = read_steam_play_stats(steam_path);
+            for game in &mut games {
+                if let Some(stats) = stats.get(&game.source_id) {
+                    game.playtime_minutes = stats.playtime_minutes;
+                    game.last_played = stats.last_played;
+                }
+            }
+        }
+
+        tracing::info!(
+            "Found {} Steam games, skipped {}, {} library folders unreachable",
+            games.len(),
+            report.skipped.len(),
+            report.unreachable.len()
+        );
+        Ok((games, report))
     }
 }
 
+/// Resolve the `steamapps` folder for a library path
+///
+/// Accepts either the library root or the `steamapps` folder itself.
+pub fn steamapps_dir(path: &Path) -> Option<PathBuf> {
+    find_steamapps(path, |p| Ok(p.is_dir())).ok().flatten()
+}
+
+/// [`steamapps_dir`], asking `is_dir` whether each candidate is a folder
+fn find_steamapps(
+    path: &Path,
+    is_dir: impl Fn(&Path) -> Result<bool, FolderError>,
+) -> Result<Option<PathBuf>, FolderError> {
+    let nested = path.join("steamapps");
+    if is_dir(&nested)? {
+        return Ok(Some(nested));
+    }
+
+    let is_steamapps = path
+        .file_name()
+        .map(|n| n.eq_ignore_ascii_case("steamapps"))
+        .unwrap_or(false);
+    if is_steamapps && is_dir(path)? {
+        Ok(Some(path.to_path_buf()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// A library path from `libraryfolders.vdf`
+///
+/// VDF escapes backslashes, so a share shows up as `\\\\nas\\games`;
+/// unescaping and normalizing gives back `\\nas\games`.
+fn library_path(vdf_value: &str) -> PathBuf {
+    normalize_path(Path::new(&vdf_value.replace("\\\\", "\\")))
+}
+
+/// Whether a manifest's name is missing or only stands in for the real one
+fn is_placeholder_name(name: &str, app_id: &str) -> bool {
+    let name = name.trim();
+    name.is_empty() || name == app_id || name.eq_ignore_ascii_case(&format!("App {}", app_id))
+}
+
 /// Extract a value from a VDF line like "key" "value"
 fn extract_vdf_value(line: &str) -> Option<String> {
     let parts: Vec<&str> = line.split('"').collect();
@@ -197,43 +403,149 @@ fn extract_vdf_value_by_key(content: &str, key: &str) -> Option<String> {
     None
 }
 
-/// Try to find a main executable in a game directory
-fn find_executable_in_dir(dir: &PathBuf) -> Option<PathBuf> {
-    // Look for common executable patterns
-    if let Ok(entries) = std::fs::read_dir(dir) {
-        let mut candidates: Vec<PathBuf> = entries
-            .flatten()
-            .filter_map(|e| {
-                let path = e.path();
-                if path.is_file() && is_executable(&path) {
-                    Some(path)
-                } else {
-                    None
-                }
-            })
-            .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::folders::{FolderStore, LocalFolders};
+    use std::sync::Arc;
+    use std::time::Duration;
 
-        // Sort by name length (shorter names are often the main executable)
-        candidates.sort_by_key(|p| p.file_name().map(|n| n.len()).unwrap_or(usize::MAX));
+    #[test]
+    fn test_steamapps_dir_accepts_root_and_steamapps() {
+        let root = std::env::temp_dir().join(format!("launcher-steam-{}", uuid::Uuid::new_v4()));
+        let steamapps = root.join("steamapps");
+        std::fs::create_dir_all(&steamapps).unwrap();
 
-        candidates.into_iter().next()
-    } else {
-        None
+        assert_eq!(steamapps_dir(&root), Some(steamapps.clone()));
+        assert_eq!(steamapps_dir(&steamapps), Some(steamapps.clone()));
+        assert_eq!(steamapps_dir(&root.join("missing")), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
     }
-}
 
-/// Check if a file is executable (platform-specific)
-#[cfg(target_os = "windows")]
-fn is_executable(path: &PathBuf) -> bool {
-    path.extension()
-        .map(|ext| ext.eq_ignore_ascii_case("exe"))
-        .unwrap_or(false)
-}
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_placeholder_name_falls_back_to_app_info() {
+        use super::super::steam_appinfo::appinfo_fixture;
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("launcher-steam-{}", uuid::Uuid::new_v4()));
+        let steamapps = root.join("steamapps");
+        for (app_id, dir) in [("400", "Portal"), ("620", "Portal 2"), ("999", "Mystery")] {
+            let install = steamapps.join("common").join(dir);
+            std::fs::create_dir_all(&install).unwrap();
+            let exe = install.join("game");
+            std::fs::write(&exe, "").unwrap();
+            std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+            // Blank, or the "App <id>" Steam shows for apps it knows nothing about
+            let name = if app_id == "400" { String::new() } else { format!("App {}", app_id) };
+            let manifest = format!(
+                "\"AppState\"\n{{\n\"appid\" \"{}\"\n\"name\" \"{}\"\n\"installdir\" \"{}\"\n}}\n",
+                app_id, name, dir
+            );
+            std::fs::write(steamapps.join(format!("appmanifest_{}.acf", app_id)), manifest)
+                .unwrap();
+        }
+        std::fs::create_dir_all(root.join("appcache")).unwrap();
+        let appinfo = appinfo_fixture(&[(400, "Portal"), (620, "Portal 2")]);
+        std::fs::write(root.join("appcache").join("appinfo.vdf"), appinfo).unwrap();
+        let library_cache = root.join("appcache").join("librarycache");
+        std::fs::create_dir_all(&library_cache).unwrap();
+        std::fs::write(library_cache.join("400_library_600x900.jpg"), "").unwrap();
+
+        let importer = SteamImporter {
+            steam_path: Some(root.clone()),
+            extra_library_paths: Vec::new(),
+            exclude: ExcludePatterns::default(),
+            include_not_installed: false,
+            folders: FolderProbe::default(),
+        };
+        let (games, report) = importer.scan_games().unwrap();
+        let mut names: Vec<_> =
+            games.iter().map(|g| (g.source_id.as_str(), g.name.as_str())).collect();
+        names.sort();
+        assert_eq!(names, [("400", "Portal"), ("620", "Portal 2")]);
+        // Cached art is picked up, and missing art left unset
+        let banner = |id| games.iter().find(|g| g.source_id == id).unwrap().banner_path.clone();
+        assert_eq!(banner("400"), Some(library_cache.join("400_library_600x900.jpg")));
+        assert_eq!(banner("620"), None);
+        // Not in the app cache either, so still skipped
+        assert_eq!(report.skip_counts(), [(SkipReason::ParseError, 1)]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Local folders, except for a share that hangs and one that dropped off
+    struct FlakyShares {
+        slow: PathBuf,
+        offline: PathBuf,
+    }
 
-#[cfg(not(target_os = "windows"))]
-fn is_executable(path: &PathBuf) -> bool {
-    use std::os::unix::fs::PermissionsExt;
-    path.metadata()
-        .map(|meta| meta.permissions().mode() & 0o111 != 0)
-        .unwrap_or(false)
+    impl FolderStore for FlakyShares {
+        fn is_dir(&self, path: &Path) -> bool {
+            if path.starts_with(&self.slow) {
+                std::thread::sleep(Duration::from_secs(30));
+            }
+            path.starts_with(&self.offline) || path.is_dir()
+        }
+
+        fn list(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+            if path.starts_with(&self.offline) {
+                return Err(std::io::Error::other("the network name is no longer available"));
+            }
+            LocalFolders.list(path)
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_unreachable_library_folders_are_skipped() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("launcher-steam-{}", uuid::Uuid::new_v4()));
+        let steamapps = root.join("steamapps");
+        let install = steamapps.join("common").join("Portal");
+        std::fs::create_dir_all(&install).unwrap();
+        let exe = install.join("portal");
+        std::fs::write(&exe, "").unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(
+            steamapps.join("appmanifest_400.acf"),
+            "\"AppState\"\n{\n\"appid\" \"400\"\n\"name\" \"Portal\"\n\"installdir\" \"Portal\"\n}\n",
+        )
+        .unwrap();
+        let (slow, offline) = (root.join("nas-slow"), root.join("nas-offline"));
+        let vdf = format!(
+            "\"libraryfolders\"\n{{\n\"path\" \"{}\"\n\"path\" \"{}\"\n\"path\" \"{}\"\n}}\n",
+            root.display(),
+            slow.display(),
+            offline.display()
+        );
+        std::fs::write(steamapps.join("libraryfolders.vdf"), vdf).unwrap();
+
+        let store = FlakyShares { slow: slow.clone(), offline: offline.clone() };
+        let importer = SteamImporter {
+            steam_path: Some(root.clone()),
+            extra_library_paths: Vec::new(),
+            exclude: ExcludePatterns::default(),
+            include_not_installed: false,
+            folders: FolderProbe::new(Arc::new(store), Duration::from_millis(200)),
+        };
+        let started = std::time::Instant::now();
+        let (games, report) = importer.scan_games().unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].name, "Portal");
+        let unreachable: Vec<&str> = report.unreachable.iter().map(|(f, _)| f.as_str()).collect();
+        assert_eq!(
+            unreachable,
+            [
+                slow.join("steamapps").display().to_string(),
+                offline.join("steamapps").display().to_string(),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
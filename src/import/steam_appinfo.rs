@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use thiserror::Error;
+
+/// `appinfo.vdf` magic numbers, one per format version
+const MAGIC_V27: u32 = 0x0756_4427;
+const MAGIC_V28: u32 = 0x0756_4428;
+/// Keys moved into a string table at the end of the file
+const MAGIC_V29: u32 = 0x0756_4429;
+
+/// Binary KeyValues node types, shared with `shortcuts.vdf`
+pub(super) const KV_MAP: u8 = 0x00;
+pub(super) const KV_STRING: u8 = 0x01;
+pub(super) const KV_INT32: u8 = 0x02;
+const KV_FLOAT32: u8 = 0x03;
+const KV_POINTER: u8 = 0x04;
+const KV_COLOR: u8 = 0x06;
+const KV_UINT64: u8 = 0x07;
+pub(super) const KV_MAP_END: u8 = 0x08;
+const KV_INT64: u8 = 0x0A;
+const KV_MAP_END_ALT: u8 = 0x0B;
+
+/// Why `appinfo.vdf` (or one entry in it) couldn't be read
+#[derive(Debug, Error)]
+pub(super) enum AppInfoError {
+    #[error("unsupported format version {0:#x}")]
+    UnsupportedVersion(u32),
+
+    #[error("data ends early")]
+    Truncated,
+
+    #[error("unknown value type {0:#x}")]
+    UnknownType(u8),
+
+    #[error("key index {0} is not in the string table")]
+    UnknownKey(u32),
+}
+
+/// Canonical app names from Steam's binary `appcache/appinfo.vdf`, by AppID
+///
+/// The format is undocumented and changes now and then, so anything
+/// unreadable is logged and skipped; the worst case is an empty map.
+pub fn read_app_names(steam_path: &Path) -> HashMap<String, String> {
+    let path = steam_path.join("appcache").join("appinfo.vdf");
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::debug!("No Steam app info at {:?}: {}", path, e);
+            return HashMap::new();
+        }
+    };
+
+    let mut names = HashMap::new();
+    if let Err(e) = parse_app_names(&data, &mut names) {
+        tracing::warn!("Could not fully read {:?}, kept {} names: {}", path, names.len(), e);
+    }
+    names
+}
+
+/// Read every app's `appinfo/common/name` into `names`
+///
+/// Entries read before an error are kept.
+fn parse_app_names(data: &[u8], names: &mut HashMap<String, String>) -> Result<(), AppInfoError> {
+    let mut reader = Reader::new(data);
+    let magic = reader.u32()?;
+    let _universe = reader.u32()?;
+    let keys = match magic {
+        MAGIC_V29 => {
+            let offset = usize::try_from(reader.u64()?).map_err(|_| AppInfoError::Truncated)?;
+            let table = data.get(offset..).ok_or(AppInfoError::Truncated)?;
+            Some(read_string_table(table)?)
+        }
+        MAGIC_V27 | MAGIC_V28 => None,
+        other => return Err(AppInfoError::UnsupportedVersion(other)),
+    };
+    // Info state, last updated, access token, SHA-1 and change number, plus
+    // the binary data's SHA-1 since v28
+    let entry_header = if magic == MAGIC_V27 { 40 } else { 60 };
+
+    loop {
+        let app_id = reader.u32()?;
+        if app_id == 0 {
+            return Ok(());
+        }
+        let size = reader.u32()? as usize;
+        let entry = reader.take(size)?;
+
+        let mut kv = Reader::new(entry.get(entry_header..).ok_or(AppInfoError::Truncated)?);
+        match read_map(&mut kv, keys.as_deref()) {
+            Ok(root) => {
+                if let Some(name) = app_name(&root) {
+                    names.insert(app_id.to_string(), name.to_string());
+                }
+            }
+            Err(e) => tracing::debug!("Skipping app info for {}: {}", app_id, e),
+        }
+    }
+}
+
+/// A parsed binary KeyValues node; only strings, 32-bit integers and maps
+/// are kept
+#[derive(Debug)]
+pub(super) enum Kv {
+    Map(Vec<(String, Kv)>),
+    String(String),
+    Int(u32),
+    Other,
+}
+
+/// The name under `appinfo/common/name`
+fn app_name(root: &[(String, Kv)]) -> Option<&str> {
+    fn child<'a>(entries: &'a [(String, Kv)], key: &str) -> Option<&'a Kv> {
+        entries.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v)
+    }
+    let Some(Kv::Map(appinfo)) = child(root, "appinfo") else {
+        return None;
+    };
+    let Some(Kv::Map(common)) = child(appinfo, "common") else {
+        return None;
+    };
+    match child(common, "name") {
+        Some(Kv::String(name)) if !name.trim().is_empty() => Some(name.trim()),
+        _ => None,
+    }
+}
+
+/// Read nodes until the end of the current map
+///
+/// Keys are inline strings, or indexes into `keys` from v29 on.
+pub(super) fn read_map(
+    reader: &mut Reader,
+    keys: Option<&[String]>,
+) -> Result<Vec<(String, Kv)>, AppInfoError> {
+    let mut entries = Vec::new();
+    loop {
+        let kind = reader.u8()?;
+        if kind == KV_MAP_END || kind == KV_MAP_END_ALT {
+            return Ok(entries);
+        }
+        let key = match keys {
+            Some(keys) => {
+                let index = reader.u32()?;
+                keys.get(index as usize).cloned().ok_or(AppInfoError::UnknownKey(index))?
+            }
+            None => reader.c_str()?,
+        };
+        let value = match kind {
+            KV_MAP => Kv::Map(read_map(reader, keys)?),
+            KV_STRING => Kv::String(reader.c_str()?),
+            KV_INT32 => Kv::Int(reader.u32()?),
+            KV_FLOAT32 | KV_POINTER | KV_COLOR => {
+                reader.take(4)?;
+                Kv::Other
+            }
+            KV_UINT64 | KV_INT64 => {
+                reader.take(8)?;
+                Kv::Other
+            }
+            other => return Err(AppInfoError::UnknownType(other)),
+        };
+        entries.push((key, value));
+    }
+}
+
+/// The v29 key table: a count, then that many NUL-terminated strings
+fn read_string_table(data: &[u8]) -> Result<Vec<String>, AppInfoError> {
+    let mut reader = Reader::new(data);
+    let count = reader.u32()?;
+    (0..count).map(|_| reader.c_str()).collect()
+}
+
+/// Little-endian cursor over a byte slice
+pub(super) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AppInfoError> {
+        let end = self.pos.checked_add(len).ok_or(AppInfoError::Truncated)?;
+        let bytes = self.data.get(self.pos..end).ok_or(AppInfoError::Truncated)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, AppInfoError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, AppInfoError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("took 4 bytes")))
+    }
+
+    fn u64(&mut self) -> Result<u64, AppInfoError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("took 8 bytes")))
+    }
+
+    /// A NUL-terminated string, with invalid UTF-8 replaced
+    fn c_str(&mut self) -> Result<String, AppInfoError> {
+        let rest = &self.data[self.pos..];
+        let len = rest.iter().position(|&b| b == 0).ok_or(AppInfoError::Truncated)?;
+        let text = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(text)
+    }
+}
+
+/// Build a v29 `appinfo.vdf` naming each app, like Steam writes it
+#[cfg(test)]
+pub(super) fn appinfo_fixture(apps: &[(u32, &str)]) -> Vec<u8> {
+    let keys = ["appinfo", "appid", "common", "name", "type"];
+    let key = |name: &str| (keys.iter().position(|k| *k == name).unwrap() as u32).to_le_bytes();
+
+    let mut entries = Vec::new();
+    for (app_id, name) in apps {
+        let mut kv = vec![KV_MAP];
+        kv.extend(key("appinfo"));
+        kv.push(KV_INT32);
+        kv.extend(key("appid"));
+        kv.extend(app_id.to_le_bytes());
+        kv.push(KV_MAP);
+        kv.extend(key("common"));
+        kv.push(KV_STRING);
+        kv.extend(key("name"));
+        kv.extend(name.as_bytes());
+        kv.push(0);
+        kv.push(KV_STRING);
+        kv.extend(key("type"));
+        kv.extend(b"Game\0");
+        kv.extend([KV_MAP_END, KV_MAP_END, KV_MAP_END]);
+
+        let mut entry = vec![0u8; 60];
+        entry.extend(kv);
+        entries.extend(app_id.to_le_bytes());
+        entries.extend((entry.len() as u32).to_le_bytes());
+        entries.extend(entry);
+    }
+    entries.extend(0u32.to_le_bytes());
+
+    let mut data = Vec::new();
+    data.extend(MAGIC_V29.to_le_bytes());
+    data.extend(1u32.to_le_bytes());
+    data.extend(((16 + entries.len()) as u64).to_le_bytes());
+    data.extend(entries);
+    data.extend((keys.len() as u32).to_le_bytes());
+    for key in keys {
+        data.extend(key.as_bytes());
+        data.push(0);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_app_names_v29() {
+        let data = appinfo_fixture(&[(400, "Portal"), (620, "Portal 2")]);
+        let mut names = HashMap::new();
+        parse_app_names(&data, &mut names).unwrap();
+
+        assert_eq!(names.len(), 2);
+        assert_eq!(names["400"], "Portal");
+        assert_eq!(names["620"], "Portal 2");
+    }
+
+    #[test]
+    fn test_parse_app_names_v27_inline_keys() {
+        let mut kv = vec![KV_MAP];
+        kv.extend(b"appinfo\0");
+        kv.push(KV_MAP);
+        kv.extend(b"common\0");
+        kv.push(KV_STRING);
+        kv.extend(b"name\0Half-Life\0");
+        kv.extend([KV_MAP_END, KV_MAP_END, KV_MAP_END]);
+        let mut entry = vec![0u8; 40];
+        entry.extend(kv);
+
+        let mut data = Vec::new();
+        data.extend(MAGIC_V27.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(70u32.to_le_bytes());
+        data.extend((entry.len() as u32).to_le_bytes());
+        data.extend(entry);
+        data.extend(0u32.to_le_bytes());
+
+        let mut names = HashMap::new();
+        parse_app_names(&data, &mut names).unwrap();
+        assert_eq!(names["70"], "Half-Life");
+    }
+
+    #[test]
+    fn test_parse_app_names_is_tolerant() {
+        let mut names = HashMap::new();
+        let unknown = [0x2A, 0x44, 0x56, 0x07, 1, 0, 0, 0];
+        assert!(matches!(
+            parse_app_names(&unknown, &mut names),
+            Err(AppInfoError::UnsupportedVersion(_))
+        ));
+
+        // A broken entry keeps the ones read before it
+        let mut data = appinfo_fixture(&[(400, "Portal"), (620, "Portal 2")]);
+        let second_entry = data.windows(4).position(|w| w == 620u32.to_le_bytes()).unwrap();
+        data[second_entry + 4..second_entry + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_app_names(&data, &mut names).is_err());
+        assert_eq!(names.len(), 1);
+        assert_eq!(names["400"], "Portal");
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::text::read_manifest;
+use super::vdf::{find_block, find_child, parse_vdf, Vdf};
+
+/// Built-in collections that mirror launcher flags rather than categories
+const BUILT_IN_COLLECTIONS: &[&str] = &["favorite", "hidden"];
+
+/// A collection the user organized their Steam library into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteamCollection {
+    pub name: String,
+    /// App IDs of the games in it
+    pub app_ids: BTreeSet<String>,
+}
+
+/// Read the collections of every Steam account on this machine
+///
+/// Collection names and members live in Steam's cached cloud storage;
+/// `localconfig.vdf` only keeps their IDs. Older clients kept "categories" as
+/// tags in `sharedconfig.vdf`, which are read too. Collections with the same
+/// name are merged. Dynamic collections are rule-based rather than a list of
+/// games, so they are skipped.
+pub fn read_steam_collections(steam_path: &Path) -> Vec<SteamCollection> {
+    let Ok(users) = std::fs::read_dir(steam_path.join("userdata")) else {
+        return Vec::new();
+    };
+
+    let mut collections = Vec::new();
+    for user in users.flatten().map(|entry| entry.path()) {
+        let cloud = user
+            .join("config")
+            .join("cloudstorage")
+            .join("cloud-storage-namespace-1.json");
+        if let Ok(content) = read_manifest(&cloud) {
+            collections.extend(parse_cloud_collections(&content));
+        }
+
+        let shared = user.join("7").join("remote").join("sharedconfig.vdf");
+        if let Ok(content) = read_manifest(&shared) {
+            collections.extend(parse_shared_config_tags(&content));
+        }
+    }
+
+    merge_by_name(collections)
+}
+
+/// One entry of the cloud storage cache, a JSON-encoded collection
+#[derive(Deserialize)]
+struct CloudEntry {
+    #[serde(default)]
+    is_deleted: bool,
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CloudCollection {
+    id: String,
+    name: String,
+    #[serde(default)]
+    added: Vec<u64>,
+    /// Only present on dynamic collections
+    #[serde(rename = "filterSpec")]
+    filter_spec: Option<serde_json::Value>,
+}
+
+/// Collections from `cloud-storage-namespace-1.json`, a list of
+/// `[key, entry]` pairs
+fn parse_cloud_collections(content: &str) -> Vec<SteamCollection> {
+    let entries: Vec<(String, CloudEntry)> = match serde_json::from_str(content) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Could not parse Steam cloud storage: {}", e);
+            return Vec::new();
+        }
+    };
+
+    entries
+        .into_iter()
+        .filter(|(key, entry)| key.starts_with("user-collections.") && !entry.is_deleted)
+        .filter_map(|(_, entry)| serde_json::from_str::<CloudCollection>(&entry.value?).ok())
+        .filter(|collection| {
+            if collection.filter_spec.is_some() {
+                tracing::debug!("Skipping dynamic Steam collection {}", collection.name);
+            }
+            collection.filter_spec.is_none() && !BUILT_IN_COLLECTIONS.contains(&&*collection.id)
+        })
+        .map(|collection| SteamCollection {
+            name: collection.name,
+            app_ids: collection.added.iter().map(u64::to_string).collect(),
+        })
+        .collect()
+}
+
+/// Legacy categories, stored as tags under each app in `sharedconfig.vdf`
+fn parse_shared_config_tags(content: &str) -> Vec<SteamCollection> {
+    let root = parse_vdf(content);
+    let Some(apps) = find_block(&root, &["UserRoamingConfigStore", "Software", "Valve", "Steam"])
+        .and_then(|steam| find_child(steam, "apps"))
+    else {
+        return Vec::new();
+    };
+
+    let mut collections = Vec::new();
+    for (app_id, app) in apps {
+        let Vdf::Block(app) = app else {
+            continue;
+        };
+        let Some(tags) = find_child(app, "tags") else {
+            continue;
+        };
+        for (_, tag) in tags {
+            if let Vdf::Value(name) = tag {
+                if !BUILT_IN_COLLECTIONS.iter().any(|b| name.eq_ignore_ascii_case(b)) {
+                    collections.push(SteamCollection {
+                        name: name.clone(),
+                        app_ids: BTreeSet::from([app_id.clone()]),
+                    });
+                }
+            }
+        }
+    }
+    collections
+}
+
+/// Combine collections whose names differ only in case
+fn merge_by_name(collections: Vec<SteamCollection>) -> Vec<SteamCollection> {
+    let mut merged: Vec<SteamCollection> = Vec::new();
+    for collection in collections {
+        match merged.iter_mut().find(|c| c.name.eq_ignore_ascii_case(&collection.name)) {
+            Some(existing) => existing.app_ids.extend(collection.app_ids),
+            None => merged.push(collection),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> BTreeSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_cloud_collections() {
+        let content = r#"[
+            ["user-collections.uc-1", {"key": "user-collections.uc-1", "timestamp": 1,
+                "value": "{\"id\":\"uc-1\",\"name\":\"Shooters\",\"added\":[10,730],\"removed\":[]}"}],
+            ["user-collections.uc-2", {"key": "user-collections.uc-2", "timestamp": 1,
+                "value": "{\"id\":\"uc-2\",\"name\":\"Unplayed\",\"added\":[],\"removed\":[],\"filterSpec\":{\"nFormatVersion\":2}}"}],
+            ["user-collections.uc-3", {"key": "user-collections.uc-3", "timestamp": 1,
+                "is_deleted": true}],
+            ["user-collections.favorite", {"key": "user-collections.favorite", "timestamp": 1,
+                "value": "{\"id\":\"favorite\",\"name\":\"Favorites\",\"added\":[10],\"removed\":[]}"}],
+            ["showcases.1", {"key": "showcases.1", "timestamp": 1, "value": "{}"}]
+        ]"#;
+
+        assert_eq!(
+            parse_cloud_collections(content),
+            vec![SteamCollection {
+                name: "Shooters".to_string(),
+                app_ids: ids(&["10", "730"]),
+            }]
+        );
+        assert!(parse_cloud_collections("not json").is_empty());
+    }
+
+    #[test]
+    fn test_parse_shared_config_tags() {
+        let content = r#"
+"UserRoamingConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"10"
+					{
+						"tags"
+						{
+							"0"		"Classics"
+							"1"		"favorite"
+						}
+					}
+					"220"
+					{
+						"LastPlayed"		"1700000000"
+						"tags"
+						{
+							"0"		"classics"
+						}
+					}
+					"570"
+					{
+						"Hidden"		"1"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+
+        let collections = merge_by_name(parse_shared_config_tags(content));
+        assert_eq!(
+            collections,
+            vec![SteamCollection {
+                name: "Classics".to_string(),
+                app_ids: ids(&["10", "220"]),
+            }]
+        );
+    }
+}
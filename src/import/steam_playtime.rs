@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use super::text::read_manifest;
+use super::vdf::{find_block, parse_vdf, Vdf};
+
+/// Playtime and last-played data Steam keeps for an app
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SteamPlayStats {
+    pub playtime_minutes: Option<u64>,
+    pub last_played: Option<DateTime<Utc>>,
+}
+
+/// Read playtime keyed by app ID from every Steam account on this machine
+///
+/// Each account keeps its own `userdata/<id>/config/localconfig.vdf`. When
+/// more than one has played a game, the account that played it last wins,
+/// since that is whose library the user is most likely looking at. Missing or
+/// unreadable files are skipped.
+pub fn read_steam_play_stats(steam_path: &Path) -> HashMap<String, SteamPlayStats> {
+    let Ok(users) = std::fs::read_dir(steam_path.join("userdata")) else {
+        return HashMap::new();
+    };
+
+    let mut stats: HashMap<String, SteamPlayStats> = HashMap::new();
+    for user in users.flatten().map(|entry| entry.path()) {
+        let local_config = user.join("config").join("localconfig.vdf");
+        let Ok(content) = read_manifest(&local_config) else {
+            continue;
+        };
+        for (app_id, app) in parse_local_config(&content) {
+            let known = stats.entry(app_id).or_default();
+            let newer = (app.last_played, app.playtime_minutes)
+                > (known.last_played, known.playtime_minutes);
+            if newer {
+                *known = app;
+            }
+        }
+    }
+    stats
+}
+
+/// Per-app stats under `UserLocalConfigStore/Software/Valve/Steam/apps`
+fn parse_local_config(content: &str) -> Vec<(String, SteamPlayStats)> {
+    let root = parse_vdf(content);
+    let path = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"];
+    let Some(apps) = find_block(&root, &path) else {
+        return Vec::new();
+    };
+
+    apps.iter()
+        .filter_map(|(app_id, app)| {
+            let Vdf::Block(app) = app else {
+                return None;
+            };
+            let value = |key: &str| {
+                app.iter().find_map(|(k, node)| match node {
+                    Vdf::Value(value) if k.eq_ignore_ascii_case(key) => value.parse::<i64>().ok(),
+                    _ => None,
+                })
+            };
+            let stats = SteamPlayStats {
+                playtime_minutes: value("Playtime").and_then(|m| u64::try_from(m).ok()),
+                // Zero means never played
+                last_played: value("LastPlayed")
+                    .filter(|&t| t > 0)
+                    .and_then(|t| DateTime::from_timestamp(t, 0)),
+            };
+            (stats != SteamPlayStats::default()).then(|| (app_id.clone(), stats))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_config(apps: &str) -> String {
+        let path = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"];
+        path.iter()
+            .rev()
+            .fold(apps.to_string(), |inner, key| format!("\"{}\" {{ {} }}", key, inner))
+    }
+
+    #[test]
+    fn test_parse_local_config() {
+        let content = local_config(
+            r#""400" { "LastPlayed" "1700000000" "Playtime" "754" "Playtime2wks" "30" }
+               "220" { "LastPlayed" "0" "Playtime" "12" }
+               "10" { "cloud" { "quota" "1" } }"#,
+        );
+
+        let stats: HashMap<_, _> = parse_local_config(&content).into_iter().collect();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["400"].playtime_minutes, Some(754));
+        assert_eq!(stats["400"].last_played.map(|d| d.timestamp()), Some(1_700_000_000));
+        assert_eq!(stats["220"].last_played, None);
+        assert!(parse_local_config("not vdf").is_empty());
+    }
+
+    #[test]
+    fn test_latest_account_wins() {
+        let steam = std::env::temp_dir().join(format!("launcher-steam-{}", uuid::Uuid::new_v4()));
+        let accounts = [
+            ("1001", r#""400" { "LastPlayed" "1600000000" "Playtime" "900" }"#),
+            ("1002", r#""400" { "LastPlayed" "1700000000" "Playtime" "60" }"#),
+            // An account without a local config is skipped
+            ("1003", ""),
+        ];
+        for (account, apps) in accounts {
+            let config = steam.join("userdata").join(account).join("config");
+            std::fs::create_dir_all(&config).unwrap();
+            if !apps.is_empty() {
+                std::fs::write(config.join("localconfig.vdf"), local_config(apps)).unwrap();
+            }
+        }
+
+        let stats = read_steam_play_stats(&steam);
+        assert_eq!(stats["400"].playtime_minutes, Some(60));
+        assert!(read_steam_play_stats(&steam.join("missing")).is_empty());
+
+        std::fs::remove_dir_all(&steam).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+
+use super::steam_appinfo::{read_map, AppInfoError, Kv, Reader};
+use super::{DetectedGame, ScanReport, SkipReason};
+
+/// Games added to Steam with "Add a Non-Steam Game", from every account's
+/// binary `userdata/<id>/config/shortcuts.vdf`
+///
+/// Steam only keeps a pointer to these, so the executable is launched
+/// directly. Accounts without shortcuts are skipped.
+pub(super) fn scan_shortcuts(steam_path: &Path, report: &mut ScanReport) -> Vec<DetectedGame> {
+    let Ok(users) = std::fs::read_dir(steam_path.join("userdata")) else {
+        return Vec::new();
+    };
+
+    let mut games = Vec::new();
+    for user in users.flatten().map(|entry| entry.path()) {
+        let path = user.join("config").join("shortcuts.vdf");
+        let candidate = path.display().to_string();
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(_) => {
+                report.record(&candidate, Err(SkipReason::Unreadable));
+                continue;
+            }
+        };
+        let shortcuts = match parse_shortcuts(&data) {
+            Ok(shortcuts) => shortcuts,
+            Err(e) => {
+                tracing::debug!("Could not read {:?}: {}", path, e);
+                report.record(&candidate, Err(SkipReason::ParseError));
+                continue;
+            }
+        };
+        for (index, shortcut) in shortcuts {
+            let result = shortcut.and_then(detect_shortcut);
+            games.extend(report.record(&format!("{} #{}", candidate, index), result));
+        }
+    }
+    games
+}
+
+/// A shortcut's index in the file and the game it describes
+type ParsedShortcut = (String, Result<DetectedGame, SkipReason>);
+
+/// Each entry under the file's `shortcuts` map
+///
+/// Entries without a name or executable come back as a parse error.
+fn parse_shortcuts(data: &[u8]) -> Result<Vec<ParsedShortcut>, AppInfoError> {
+    let root = read_map(&mut Reader::new(data), None)?;
+    let Some(Kv::Map(entries)) = child(&root, "shortcuts") else {
+        return Ok(Vec::new());
+    };
+
+    Ok(entries
+        .iter()
+        .filter_map(|(index, entry)| match entry {
+            Kv::Map(entry) => Some((index.clone(), shortcut(entry))),
+            _ => None,
+        })
+        .collect())
+}
+
+fn shortcut(entry: &[(String, Kv)]) -> Result<DetectedGame, SkipReason> {
+    let string = |key: &str| match child(entry, key) {
+        // Paths are saved quoted, the way Steam's own dialog writes them
+        Some(Kv::String(value)) => Some(value.trim().trim_matches('"').to_string()),
+        _ => None,
+    }
+    .filter(|value| !value.is_empty());
+
+    let name = string("AppName").ok_or(SkipReason::ParseError)?;
+    let exe = PathBuf::from(string("Exe").ok_or(SkipReason::ParseError)?);
+    // Older files have no app ID; the executable identifies those instead
+    let source_id = match child(entry, "appid") {
+        Some(Kv::Int(app_id)) => app_id.to_string(),
+        _ => exe.display().to_string(),
+    };
+    let install_path = match string("StartDir") {
+        Some(dir) => PathBuf::from(dir),
+        None => exe.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+
+    Ok(DetectedGame {
+        name,
+        source_id,
+        executable_path: exe,
+        install_path,
+        icon_path: string("icon").map(PathBuf::from),
+        banner_path: None,
+        playtime_minutes: None,
+        last_played: None,
+        launch_args: string("LaunchOptions"),
+        emulator_command: None,
+    })
+}
+
+/// Keep a shortcut only if its executable is still there
+fn detect_shortcut(game: DetectedGame) -> Result<DetectedGame, SkipReason> {
+    if !game.executable_path.is_file() {
+        return Err(SkipReason::NoExecutable);
+    }
+    let icon_path = game.icon_path.clone().filter(|icon| icon.is_file());
+    Ok(DetectedGame { icon_path, ..game })
+}
+
+/// Steam has written these keys in more than one case over the years
+fn child<'a>(entries: &'a [(String, Kv)], key: &str) -> Option<&'a Kv> {
+    entries.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::steam_appinfo::{KV_INT32, KV_MAP, KV_MAP_END, KV_STRING};
+
+    fn string(kv: &mut Vec<u8>, key: &str, value: &str) {
+        kv.push(KV_STRING);
+        kv.extend(format!("{}\0{}\0", key, value).as_bytes());
+    }
+
+    /// A `shortcuts.vdf` with two entries, laid out the way Steam writes it
+    fn shortcuts_fixture(exe: &str) -> Vec<u8> {
+        let mut kv = vec![KV_MAP];
+        kv.extend(b"shortcuts\0");
+
+        kv.push(KV_MAP);
+        kv.extend(b"0\0");
+        kv.push(KV_INT32);
+        kv.extend(b"appid\0");
+        kv.extend(3_512_345_678u32.to_le_bytes());
+        string(&mut kv, "AppName", "Celeste");
+        string(&mut kv, "Exe", &format!("\"{}\"", exe));
+        string(&mut kv, "StartDir", "\"/games/celeste/\"");
+        string(&mut kv, "icon", "");
+        string(&mut kv, "LaunchOptions", "--windowed");
+        kv.push(KV_INT32);
+        kv.extend(b"IsHidden\0");
+        kv.extend(0u32.to_le_bytes());
+        kv.push(KV_MAP);
+        kv.extend(b"tags\0");
+        string(&mut kv, "0", "favorite");
+        kv.push(KV_MAP_END);
+        kv.push(KV_MAP_END);
+
+        // Older entries: lower-case keys, no app ID, no name
+        kv.push(KV_MAP);
+        kv.extend(b"1\0");
+        string(&mut kv, "appname", "");
+        string(&mut kv, "exe", "\"/games/broken\"");
+        kv.push(KV_MAP_END);
+
+        kv.extend([KV_MAP_END, KV_MAP_END]);
+        kv
+    }
+
+    #[test]
+    fn test_parse_shortcuts() {
+        let shortcuts = parse_shortcuts(&shortcuts_fixture("/games/celeste/Celeste")).unwrap();
+        assert_eq!(shortcuts.len(), 2);
+
+        let (index, game) = &shortcuts[0];
+        let game = game.as_ref().unwrap();
+        assert_eq!(index, "0");
+        assert_eq!(game.name, "Celeste");
+        assert_eq!(game.source_id, "3512345678");
+        assert_eq!(game.executable_path, PathBuf::from("/games/celeste/Celeste"));
+        assert_eq!(game.install_path, PathBuf::from("/games/celeste/"));
+        assert_eq!(game.icon_path, None);
+        assert_eq!(game.launch_args.as_deref(), Some("--windowed"));
+
+        assert_eq!(shortcuts[1].1.as_ref().unwrap_err(), &SkipReason::ParseError);
+        assert!(parse_shortcuts(b"\x00shortcuts\0").is_err());
+    }
+
+    #[test]
+    fn test_scan_shortcuts_keeps_existing_executables() {
+        let steam = std::env::temp_dir().join(format!("launcher-steam-{}", uuid::Uuid::new_v4()));
+        let exe = steam.join("Celeste");
+        let config = steam.join("userdata").join("1001").join("config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::write(&exe, b"").unwrap();
+        std::fs::write(config.join("shortcuts.vdf"), shortcuts_fixture(&exe.to_string_lossy()))
+            .unwrap();
+        // An account without shortcuts is skipped
+        std::fs::create_dir_all(steam.join("userdata").join("1002")).unwrap();
+
+        let mut report = ScanReport::default();
+        let games = scan_shortcuts(&steam, &mut report);
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].executable_path, exe);
+        assert_eq!(report.candidates, 2);
+        assert_eq!(report.skipped[0].1, SkipReason::ParseError);
+
+        std::fs::remove_file(&exe).unwrap();
+        let games = scan_shortcuts(&steam, &mut ScanReport::default());
+        assert!(games.is_empty());
+
+        std::fs::remove_dir_all(&steam).unwrap();
+    }
+}
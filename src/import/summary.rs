@@ -0,0 +1,255 @@
+use super::ScanReport;
+use crate::data::{Game, GameField, GameSource};
+
+/// What an import changed in the library, shown once it finishes
+#[derive(Debug, Clone)]
+pub struct ImportSummary {
+    pub source: GameSource,
+    /// Names of games that are new to the library
+    pub added: Vec<String>,
+    /// Existing games refreshed by the import
+    pub updated: Vec<UpdatedGame>,
+    /// Library games from this source the scan no longer found
+    pub missing: Vec<String>,
+    /// Games found again with nothing to refresh
+    pub unchanged: usize,
+    /// What the scan looked at and skipped
+    pub scan: ScanReport,
+}
+
+/// An existing game and the fields an import changed on it
+#[derive(Debug, Clone)]
+pub struct UpdatedGame {
+    pub name: String,
+    pub fields: Vec<&'static str>,
+}
+
+impl ImportSummary {
+    pub fn new(source: GameSource) -> Self {
+        Self {
+            source,
+            added: Vec::new(),
+            updated: Vec::new(),
+            missing: Vec::new(),
+            unchanged: 0,
+            scan: ScanReport::default(),
+        }
+    }
+
+    /// Record a refreshed game, or count it as unchanged if nothing moved
+    pub fn record_update(&mut self, name: &str, fields: Vec<&'static str>) {
+        if fields.is_empty() {
+            self.unchanged += 1;
+        } else {
+            self.updated.push(UpdatedGame {
+                name: name.to_string(),
+                fields,
+            });
+        }
+    }
+
+    /// Whether the import left the library as it was
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Refresh `existing` from an imported copy, returning which fields changed
+///
+/// Only source-owned fields are refreshed: name, executable, install folder,
+/// launch options and emulator, and only while the user hasn't edited them
+/// (see [`GameField`]). Everything else (categories, favorite, hidden,
+/// status, art, display preferences) belongs to the user and is never touched.
+///
+/// Store-reported stats only ever move forward, so a store that lost track of
+/// playtime can't wipe what the launcher recorded.
+pub fn refresh_from_import(existing: &mut Game, imported: Game) -> Vec<&'static str> {
+    if !existing.user_edits_tracked {
+        adopt_untracked_edits(existing, &imported);
+    }
+    let mut fields = Vec::new();
+
+    if !existing.is_user_modified(GameField::Name) && existing.name != imported.name {
+        existing.name = imported.name;
+        fields.push("name");
+    }
+    // A store listing the game as not installed has no executable to offer
+    if !existing.is_user_modified(GameField::ExecutablePath)
+        && !imported.executable_path.as_os_str().is_empty()
+        && existing.executable_path != imported.executable_path
+    {
+        existing.executable_path = imported.executable_path;
+        fields.push("executable");
+    }
+    if !existing.is_user_modified(GameField::InstallPath)
+        && existing.install_path != imported.install_path
+    {
+        existing.install_path = imported.install_path;
+        fields.push("install folder");
+    }
+    // A store that stopped passing arguments doesn't clear the ones we have
+    if !existing.is_user_modified(GameField::LaunchArgs)
+        && imported.launch_args.is_some()
+        && existing.launch_args != imported.launch_args
+    {
+        existing.launch_args = imported.launch_args;
+        fields.push("launch options");
+    }
+    if !existing.is_user_modified(GameField::EmulatorCommand)
+        && imported.emulator_command.is_some()
+        && existing.emulator_command != imported.emulator_command
+    {
+        existing.emulator_command = imported.emulator_command;
+        fields.push("emulator");
+    }
+    if imported.playtime_minutes > existing.playtime_minutes {
+        existing.playtime_minutes = imported.playtime_minutes;
+        fields.push("playtime");
+    }
+    if imported.last_played > existing.last_played {
+        existing.last_played = imported.last_played;
+        fields.push("last played");
+    }
+
+    fields
+}
+
+/// Keep what a game saved before edits were tracked already has
+///
+/// There's no telling a store's change from the user's on those, so the
+/// user's value wins: fields the user could edit that differ from the store
+/// are marked as edited. The install folder wasn't editable then.
+fn adopt_untracked_edits(existing: &mut Game, imported: &Game) {
+    let edited = [
+        (GameField::Name, existing.name != imported.name),
+        (
+            GameField::ExecutablePath,
+            !imported.executable_path.as_os_str().is_empty()
+                && existing.executable_path != imported.executable_path,
+        ),
+        (
+            GameField::LaunchArgs,
+            existing.launch_args.is_some() && existing.launch_args != imported.launch_args,
+        ),
+        (
+            GameField::EmulatorCommand,
+            existing.emulator_command.is_some()
+                && existing.emulator_command != imported.emulator_command,
+        ),
+    ];
+    for (field, differs) in edited {
+        if differs {
+            existing.mark_user_modified(field);
+        }
+    }
+    existing.user_edits_tracked = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Clock, MockClock};
+    use chrono::Duration;
+    use std::path::PathBuf;
+
+    fn game(exe: &str, playtime: u64) -> Game {
+        let mut game = Game::from_import(
+            "Portal".to_string(),
+            PathBuf::from(exe),
+            PathBuf::from("portal"),
+            GameSource::Steam,
+            "400".to_string(),
+            &MockClock::fixed(),
+        );
+        game.playtime_minutes = playtime;
+        game
+    }
+
+    #[test]
+    fn test_refresh_reports_changed_fields() {
+        let mut existing = game("portal/old.exe", 120);
+        let mut imported = game("portal/portal.exe", 60);
+        imported.last_played = Some(MockClock::fixed().now());
+
+        let fields = refresh_from_import(&mut existing, imported);
+        assert_eq!(fields, vec!["executable", "last played"]);
+        assert_eq!(existing.executable_path, PathBuf::from("portal/portal.exe"));
+        // Playtime never goes backwards
+        assert_eq!(existing.playtime_minutes, 120);
+    }
+
+    #[test]
+    fn test_refresh_keeps_user_edits() {
+        let mut existing = game("portal/portal.exe", 60);
+        existing.name = "Portal (GOTY)".to_string();
+        existing.mark_user_modified(GameField::Name);
+        existing.executable_path = PathBuf::from("portal/portal_vulkan.exe");
+        existing.mark_user_modified(GameField::ExecutablePath);
+        existing.favorite = true;
+
+        let mut imported = game("portal/portal.exe", 90);
+        imported.launch_args = Some("-novid".to_string());
+
+        let fields = refresh_from_import(&mut existing, imported);
+        assert_eq!(fields, vec!["launch options", "playtime"]);
+        assert_eq!(existing.name, "Portal (GOTY)");
+        assert_eq!(existing.executable_path, PathBuf::from("portal/portal_vulkan.exe"));
+        assert_eq!(existing.launch_args.as_deref(), Some("-novid"));
+        assert!(existing.favorite);
+    }
+
+    #[test]
+    fn test_refresh_follows_store_rename_when_untouched() {
+        let mut existing = game("portal/portal.exe", 60);
+        let mut imported = game("portal/portal.exe", 60);
+        imported.name = "Portal: Remastered".to_string();
+
+        let fields = refresh_from_import(&mut existing, imported);
+        assert_eq!(fields, vec!["name"]);
+        assert_eq!(existing.name, "Portal: Remastered");
+    }
+
+    #[test]
+    fn test_refresh_keeps_edits_from_before_tracking() {
+        // Saved before `user_modified` existed, renamed by the user back then
+        let mut existing: Game = {
+            let mut json = serde_json::to_value(game("portal/portal.exe", 60)).unwrap();
+            let fields = json.as_object_mut().unwrap();
+            fields.remove("user_modified");
+            fields.remove("user_edits_tracked");
+            fields["name"] = "Portal (GOTY)".into();
+            serde_json::from_value(json).unwrap()
+        };
+        assert!(!existing.user_edits_tracked);
+
+        let fields = refresh_from_import(&mut existing, game("portal/portal.exe", 60));
+        assert!(fields.is_empty());
+        assert_eq!(existing.name, "Portal (GOTY)");
+        assert!(existing.is_user_modified(GameField::Name));
+        assert!(!existing.is_user_modified(GameField::ExecutablePath));
+
+        // Later store changes to the other fields still come through
+        let mut imported = game("portal/portal_v2.exe", 60);
+        imported.name = "Portal: Remastered".to_string();
+        assert_eq!(refresh_from_import(&mut existing, imported), vec!["executable"]);
+    }
+
+    #[test]
+    fn test_refresh_unchanged_counts_separately() {
+        let mut existing = game("portal/portal.exe", 60);
+        existing.last_played = Some(MockClock::fixed().now());
+        let mut imported = game("portal/portal.exe", 60);
+        imported.last_played = existing.last_played.map(|t| t - Duration::days(1));
+
+        let fields = refresh_from_import(&mut existing, imported);
+        assert!(fields.is_empty());
+
+        let mut summary = ImportSummary::new(GameSource::Steam);
+        summary.record_update("Portal", fields);
+        summary.record_update("Portal 2", vec!["playtime"]);
+        assert_eq!(summary.unchanged, 1);
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(summary.updated[0].name, "Portal 2");
+        assert!(!summary.is_empty());
+    }
+}
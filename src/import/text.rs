@@ -0,0 +1,63 @@
+use std::path::Path;
+
+/// UTF-8 byte order mark some Windows tools write at the start of files
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Read a launcher manifest as text without giving up on bad bytes
+///
+/// A leading BOM is dropped, and files that aren't valid UTF-8 (e.g. a game
+/// name saved as Latin-1) are decoded lossily rather than failing, so one
+/// bad byte doesn't drop the whole game.
+pub fn read_manifest(path: &Path) -> std::io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(decode_manifest(&bytes, path))
+}
+
+fn decode_manifest(bytes: &[u8], path: &Path) -> String {
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(e) => {
+            tracing::warn!(
+                "{:?} is not valid UTF-8 (at byte {}), decoding lossily",
+                path,
+                e.valid_up_to()
+            );
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(bytes: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("launcher-manifest-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_bom_is_stripped() {
+        let path = fixture(b"\xEF\xBB\xBF\"AppState\"\n{\n\t\"name\"\t\t\"Portal\"\n}");
+        let content = read_manifest(&path).unwrap();
+        assert!(content.starts_with("\"AppState\""));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_latin1_bytes_decode_lossily() {
+        // "Pokémon" with the é written as a single Latin-1 byte
+        let path = fixture(b"\"name\"\t\t\"Pok\xE9mon\"\n\"appid\"\t\t\"42\"");
+        let content = read_manifest(&path).unwrap();
+        assert!(content.contains("\"Pok\u{FFFD}mon\""));
+        assert!(content.contains("\"appid\"\t\t\"42\""));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        assert!(read_manifest(Path::new("/nonexistent/launcher/manifest.acf")).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::PathBuf;
+
+use super::{DetectedGame, GameImporter, ImportError, ScanReport, SkipReason};
+use crate::data::{executable_candidates, ExcludePatterns, GameSource};
+
+/// Registry key listing Ubisoft Connect installs, one subkey per game id
+#[cfg(target_os = "windows")]
+const INSTALLS_KEY: &str = "SOFTWARE\\WOW6432Node\\Ubisoft\\Launcher\\Installs";
+
+/// A game registered with Ubisoft Connect
+#[derive(Debug, Clone)]
+struct UbisoftInstall {
+    id: String,
+    install_dir: PathBuf,
+    /// Name from the uninstall entry, if Ubisoft registered one
+    display_name: Option<String>,
+}
+
+/// Importer for Ubisoft Connect games
+pub struct UbisoftImporter {
+    launcher_path: Option<PathBuf>,
+    exclude: ExcludePatterns,
+    include_not_installed: bool,
+}
+
+impl UbisoftImporter {
+    pub fn new() -> Self {
+        Self {
+            launcher_path: Self::find_launcher_path(),
+            exclude: ExcludePatterns::default(),
+            include_not_installed: false,
+        }
+    }
+
+    /// Skip executables in folders matching the user's exclusion patterns
+    pub fn with_exclude_patterns(mut self, exclude: ExcludePatterns) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Also list registered games whose install folder is gone
+    pub fn with_not_installed(mut self, include: bool) -> Self {
+        self.include_not_installed = include;
+        self
+    }
+
+    /// Find the Ubisoft Connect install directory
+    #[cfg(target_os = "windows")]
+    fn find_launcher_path() -> Option<PathBuf> {
+        use crate::platform::get_registry_string;
+
+        get_registry_string("HKLM", "SOFTWARE\\WOW6432Node\\Ubisoft\\Launcher", "InstallDir")
+            .map(PathBuf::from)
+            .filter(|path| path.exists())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn find_launcher_path() -> Option<PathBuf> {
+        // Ubisoft Connect is Windows-only
+        None
+    }
+
+    /// Enumerate the installs Ubisoft Connect has registered
+    #[cfg(target_os = "windows")]
+    fn registered_installs(&self) -> Vec<UbisoftInstall> {
+        use crate::platform::{get_registry_string, get_registry_subkeys};
+
+        get_registry_subkeys("HKLM", INSTALLS_KEY)
+            .into_iter()
+            .filter_map(|id| {
+                let install_dir =
+                    get_registry_string("HKLM", &format!("{}\\{}", INSTALLS_KEY, id), "InstallDir")?;
+                let display_name = get_registry_string(
+                    "HKLM",
+                    &format!(
+                        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\Uplay Install {}",
+                        id
+                    ),
+                    "DisplayName",
+                );
+                Some(UbisoftInstall {
+                    id,
+                    install_dir: PathBuf::from(install_dir),
+                    display_name,
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn registered_installs(&self) -> Vec<UbisoftInstall> {
+        Vec::new()
+    }
+}
+
+/// Turn a registry install into a detected game
+///
+/// Games are launched through `uplay://`, so the executable only needs to be
+/// a good guess; installs without any executable are skipped. Installs whose
+/// folder is gone are skipped too, unless `include_not_installed` is set.
+fn detect_install(
+    install: UbisoftInstall,
+    exclude: &ExcludePatterns,
+    include_not_installed: bool,
+) -> Result<DetectedGame, SkipReason> {
+    let name = install
+        .display_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            install
+                .install_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+        })
+        .ok_or(SkipReason::ParseError)?;
+    if !install.install_dir.is_dir() {
+        return if include_not_installed {
+            Ok(DetectedGame::not_installed(name, install.id, install.install_dir))
+        } else {
+            Err(SkipReason::NotInstalled)
+        };
+    }
+    // Often a folder down, in something like `bin/`
+    let executable_path = executable_candidates(&install.install_dir, exclude, 1)
+        .into_iter()
+        .next()
+        .ok_or(SkipReason::NoExecutable)?;
+
+    Ok(DetectedGame {
+        name,
+        source_id: install.id,
+        executable_path,
+        install_path: install.install_dir,
+        icon_path: None,
+        banner_path: None,
+        playtime_minutes: None,
+        last_played: None,
+        launch_args: None,
+        emulator_command: None,
+    })
+}
+
+impl Default for UbisoftImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameImporter for UbisoftImporter {
+    fn source(&self) -> GameSource {
+        GameSource::Ubisoft
+    }
+
+    fn is_available(&self) -> bool {
+        self.launcher_path.is_some()
+    }
+
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        if !self.is_available() {
+            return Err(ImportError::NotInstalled);
+        }
+
+        let mut report = ScanReport::default();
+        let games: Vec<DetectedGame> = self
+            .registered_installs()
+            .into_iter()
+            .filter_map(|install| {
+                let candidate = format!("install {}", install.id);
+                let result = detect_install(install, &self.exclude, self.include_not_installed);
+                report.record(&candidate, result)
+            })
+            .collect();
+
+        tracing::info!(
+            "Found {} Ubisoft Connect games, skipped {}",
+            games.len(),
+            report.skipped.len()
+        );
+        Ok((games, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::MockClock;
+
+    /// Create an install folder containing the given files and their sizes
+    fn install_with(files: &[(&str, usize)]) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("launcher-ubisoft-{}", uuid::Uuid::new_v4()))
+            .join("Far Cry 5");
+        for (file, size) in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, vec![0u8; *size]).unwrap();
+        }
+        dir
+    }
+
+    fn cleanup(install: &std::path::Path) {
+        let _ = std::fs::remove_dir_all(install.parent().unwrap());
+    }
+
+    fn detect(
+        id: &str,
+        install_dir: PathBuf,
+        display_name: Option<&str>,
+    ) -> Result<DetectedGame, SkipReason> {
+        let install = UbisoftInstall {
+            id: id.to_string(),
+            install_dir,
+            display_name: display_name.map(str::to_string),
+        };
+        detect_install(install, &ExcludePatterns::default(), false)
+    }
+
+    #[test]
+    fn test_detect_install_names() {
+        let install = install_with(&[("Game.exe", 16)]);
+
+        let named = detect("635", install.clone(), Some(" Far Cry® 5 ")).unwrap();
+        assert_eq!(named.name, "Far Cry® 5");
+        assert_eq!(named.source_id, "635");
+        assert_eq!(named.executable_path, install.join("Game.exe"));
+
+        // Without an uninstall entry, fall back to the folder name
+        let unnamed = detect("635", install.clone(), None).unwrap();
+        assert_eq!(unnamed.name, "Far Cry 5");
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_detect_install_skips_missing_or_empty() {
+        let install = install_with(&[("unins000.exe", 16)]);
+
+        assert!(matches!(detect("1", install.clone(), None), Err(SkipReason::NoExecutable)));
+        assert!(matches!(
+            detect("2", install.join("missing"), None),
+            Err(SkipReason::NotInstalled)
+        ));
+
+        cleanup(&install);
+    }
+
+    #[test]
+    fn test_detect_install_lists_missing_when_asked() {
+        let install = install_with(&[("Game.exe", 16)]);
+        let missing = UbisoftInstall {
+            id: "635".to_string(),
+            install_dir: install.join("Far Cry 5"),
+            display_name: Some("Far Cry® 5".to_string()),
+        };
+
+        let game = detect_install(missing, &ExcludePatterns::default(), true).unwrap();
+        assert_eq!(game.name, "Far Cry® 5");
+        assert_eq!(game.install_path, install.join("Far Cry 5"));
+        assert!(!game.into_game(GameSource::Ubisoft, &MockClock::fixed()).is_installed());
+
+        cleanup(&install);
+    }
+}
@@ -0,0 +1,130 @@
+//! Reading Valve's KeyValues (VDF) text format
+
+/// A parsed VDF (Valve KeyValues) node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Vdf {
+    Value(String),
+    Block(Vec<(String, Vdf)>),
+}
+
+/// Parse VDF text into its top-level key/node pairs
+///
+/// Lenient: unbalanced braces end the current block, and comments and
+/// conditionals like `[$WIN32]` are ignored.
+pub(super) fn parse_vdf(content: &str) -> Vec<(String, Vdf)> {
+    let mut tokens = VdfTokens { chars: content.chars().peekable() };
+    parse_block(&mut tokens)
+}
+
+fn parse_block(tokens: &mut VdfTokens) -> Vec<(String, Vdf)> {
+    let mut entries = Vec::new();
+    while let Some(token) = tokens.next() {
+        let key = match token {
+            VdfToken::Text(key) => key,
+            VdfToken::Close => break,
+            VdfToken::Open => continue,
+        };
+        match tokens.next() {
+            Some(VdfToken::Text(value)) => entries.push((key, Vdf::Value(value))),
+            Some(VdfToken::Open) => entries.push((key, Vdf::Block(parse_block(tokens)))),
+            Some(VdfToken::Close) | None => break,
+        }
+    }
+    entries
+}
+
+enum VdfToken {
+    Text(String),
+    Open,
+    Close,
+}
+
+struct VdfTokens<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Iterator for VdfTokens<'_> {
+    type Item = VdfToken;
+
+    fn next(&mut self) -> Option<VdfToken> {
+        loop {
+            match self.chars.next()? {
+                '{' => return Some(VdfToken::Open),
+                '}' => return Some(VdfToken::Close),
+                '"' => {
+                    let mut text = String::new();
+                    while let Some(c) = self.chars.next() {
+                        match c {
+                            '"' => break,
+                            '\\' => text.extend(self.chars.next()),
+                            c => text.push(c),
+                        }
+                    }
+                    return Some(VdfToken::Text(text));
+                }
+                '/' if self.chars.peek() == Some(&'/') => {
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                '[' => {
+                    for c in self.chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                c if c.is_whitespace() => {}
+                c => {
+                    // Unquoted token
+                    let mut text = c.to_string();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                            break;
+                        }
+                        text.push(c);
+                        self.chars.next();
+                    }
+                    return Some(VdfToken::Text(text));
+                }
+            }
+        }
+    }
+}
+
+/// The block under `key`, matching keys case-insensitively like Steam does
+pub(super) fn find_child<'a>(
+    entries: &'a [(String, Vdf)],
+    key: &str,
+) -> Option<&'a [(String, Vdf)]> {
+    entries.iter().find_map(|(k, node)| match node {
+        Vdf::Block(children) if k.eq_ignore_ascii_case(key) => Some(children.as_slice()),
+        _ => None,
+    })
+}
+
+pub(super) fn find_block<'a>(
+    entries: &'a [(String, Vdf)],
+    path: &[&str],
+) -> Option<&'a [(String, Vdf)]> {
+    path.iter().try_fold(entries, |entries, key| find_child(entries, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vdf_escapes_and_comments() {
+        let parsed = parse_vdf("// comment\n\"a\" { \"b\" \"say \\\"hi\\\"\" }");
+        assert_eq!(
+            parsed,
+            vec![(
+                "a".to_string(),
+                Vdf::Block(vec![("b".to_string(), Vdf::Value("say \"hi\"".to_string()))])
+            )]
+        );
+    }
+}
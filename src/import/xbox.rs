@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+
+use super::text::read_manifest;
+use super::{DetectedGame, GameImporter, ImportError, ScanReport, SkipReason};
+use crate::data::GameSource;
+use crate::platform::{supports_feature, PlatformFeature};
+
+/// Gaming Services' install roots, one subkey per volume and, under it, one
+/// per installed package full name
+#[cfg(target_os = "windows")]
+const GAMING_ROOTS_KEY: &str = "SOFTWARE\\Microsoft\\GamingServices\\PackageRepository\\Root";
+
+/// Every installed app package, keyed by package full name
+#[cfg(target_os = "windows")]
+const APP_MODEL_PACKAGES_KEY: &str =
+    "Local Settings\\Software\\Microsoft\\Windows\\CurrentVersion\\AppModel\\Repository\\Packages";
+
+/// A package installed through the Xbox app or the Microsoft Store
+#[derive(Debug, Clone)]
+struct XboxPackage {
+    /// `<Name>_<Version>_<Arch>_<ResourceId>_<PublisherId>`
+    full_name: String,
+    root: PathBuf,
+    /// Name from the app model registry, often an unresolved resource reference
+    display_name: Option<String>,
+}
+
+/// Importer for Xbox app (Game Pass) and Microsoft Store games
+pub struct XboxImporter {
+    gaming_services: bool,
+}
+
+impl XboxImporter {
+    pub fn new() -> Self {
+        Self {
+            gaming_services: Self::has_gaming_services(),
+        }
+    }
+
+    /// Gaming Services is what installs and tracks Xbox app games
+    #[cfg(target_os = "windows")]
+    fn has_gaming_services() -> bool {
+        use crate::platform::get_registry_subkeys;
+
+        !get_registry_subkeys("HKLM", GAMING_ROOTS_KEY).is_empty()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn has_gaming_services() -> bool {
+        false
+    }
+
+    /// Enumerate the game packages Gaming Services has installed
+    ///
+    /// Plain Store apps live in the app model repository too, so only
+    /// packages under a Gaming Services install root are taken as games.
+    #[cfg(target_os = "windows")]
+    fn installed_packages(&self) -> Vec<XboxPackage> {
+        use crate::platform::{get_registry_string, get_registry_subkeys};
+
+        let mut packages = Vec::new();
+        for volume in get_registry_subkeys("HKLM", GAMING_ROOTS_KEY) {
+            let volume_key = format!("{}\\{}", GAMING_ROOTS_KEY, volume);
+            for full_name in get_registry_subkeys("HKLM", &volume_key) {
+                let package_key = format!("{}\\{}", volume_key, full_name);
+                let app_model_key = format!("{}\\{}", APP_MODEL_PACKAGES_KEY, full_name);
+                let root = get_registry_string("HKLM", &package_key, "Root").or_else(|| {
+                    get_registry_string("HKCR", &app_model_key, "PackageRootFolder")
+                });
+                let Some(root) = root else {
+                    continue;
+                };
+                packages.push(XboxPackage {
+                    display_name: get_registry_string("HKCR", &app_model_key, "DisplayName"),
+                    full_name,
+                    root: PathBuf::from(root),
+                });
+            }
+        }
+        packages
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn installed_packages(&self) -> Vec<XboxPackage> {
+        Vec::new()
+    }
+}
+
+/// Turn an installed package into a detected game
+///
+/// Packaged games can't be started from their executable, so the game
+/// launches through `shell:AppsFolder\<PFN>!<App>`, with that app user model
+/// ID kept as the source ID.
+fn detect_package(package: XboxPackage) -> Result<DetectedGame, SkipReason> {
+    if !package.root.is_dir() {
+        return Err(SkipReason::NotInstalled);
+    }
+    let family_name = package_family_name(&package.full_name).ok_or(SkipReason::ParseError)?;
+    let manifest =
+        read_manifest(&package.root.join("AppxManifest.xml")).map_err(|_| SkipReason::Unreadable)?;
+    let app_id = xml_attribute(&manifest, "Application", "Id").ok_or(SkipReason::NotApplication)?;
+    let app_user_model_id = format!("{}!{}", family_name, app_id);
+
+    let name = package_name(&package, &manifest)
+        .or_else(|| package.full_name.split('_').next().map(str::to_string))
+        .ok_or(SkipReason::ParseError)?;
+    let executable_path = GameSource::Xbox
+        .launch_uri(&app_user_model_id)
+        .map(PathBuf::from)
+        .ok_or(SkipReason::NoExecutable)?;
+
+    Ok(DetectedGame {
+        name,
+        source_id: app_user_model_id,
+        executable_path,
+        install_path: package.root,
+        icon_path: None,
+        banner_path: None,
+        playtime_minutes: None,
+        last_played: None,
+        launch_args: None,
+        emulator_command: None,
+    })
+}
+
+/// The first readable name: the registry's, the game config's, then the
+/// manifest's
+///
+/// Names starting with `@` or `ms-resource:` point into the package's
+/// resources, which can't be read without Windows resolving them.
+fn package_name(package: &XboxPackage, manifest: &str) -> Option<String> {
+    let game_config = read_manifest(&package.root.join("MicrosoftGame.config")).ok();
+    [
+        package.display_name.clone(),
+        game_config.and_then(|config| xml_attribute(&config, "ShellVisuals", "DefaultDisplayName")),
+        xml_text(manifest, "DisplayName"),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|name| name.trim().to_string())
+    .find(|name| !name.is_empty() && !name.starts_with('@') && !name.starts_with("ms-resource:"))
+}
+
+/// `<Name>_<PublisherId>`, from a package full name
+fn package_family_name(full_name: &str) -> Option<String> {
+    let parts: Vec<&str> = full_name.split('_').collect();
+    match parts.as_slice() {
+        [name, _version, _arch, _resource, publisher] if !name.is_empty() => {
+            Some(format!("{}_{}", name, publisher))
+        }
+        _ => None,
+    }
+}
+
+/// An attribute of the first `<element>`, enough for the flat manifests
+/// packages ship
+fn xml_attribute(xml: &str, element: &str, attribute: &str) -> Option<String> {
+    let tag = &xml[xml_tag(xml, element)?];
+    let (found, _) = tag
+        .match_indices(&format!("{}=\"", attribute))
+        .find(|(i, _)| tag[..*i].ends_with(char::is_whitespace))?;
+    let value = &tag[found + attribute.len() + 2..];
+    Some(unescape_xml(&value[..value.find('"')?]))
+}
+
+/// The text inside the first `<element>…</element>`
+fn xml_text(xml: &str, element: &str) -> Option<String> {
+    let rest = &xml[xml_tag(xml, element)?.end + 1..];
+    let len = rest.find(&format!("</{}>", element))?;
+    Some(unescape_xml(&rest[..len]))
+}
+
+/// Where the inside of the first `<element …>` tag is, from the element name
+/// up to the closing `>`
+fn xml_tag(xml: &str, element: &str) -> Option<std::ops::Range<usize>> {
+    let open = format!("<{}", element);
+    let mut search = 0;
+    while let Some(found) = xml[search..].find(&open) {
+        let start = search + found + 1;
+        let after = &xml[start + element.len()..];
+        // Skip longer names sharing the prefix, like `<ApplicationContentUriRules>`
+        if after.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            let len = xml[start..].find('>')?;
+            return Some(start..start + len);
+        }
+        search = start;
+    }
+    None
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+impl Default for XboxImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameImporter for XboxImporter {
+    fn source(&self) -> GameSource {
+        GameSource::Xbox
+    }
+
+    fn is_available(&self) -> bool {
+        supports_feature(PlatformFeature::XboxImport) && self.gaming_services
+    }
+
+    fn scan_games(&self) -> Result<(Vec<DetectedGame>, ScanReport), ImportError> {
+        if !self.is_available() {
+            return Err(ImportError::NotInstalled);
+        }
+
+        let mut report = ScanReport::default();
+        let games: Vec<DetectedGame> = self
+            .installed_packages()
+            .into_iter()
+            .filter_map(|package| {
+                let candidate = format!("package {}", package.full_name);
+                report.record(&candidate, detect_package(package))
+            })
+            .collect();
+
+        tracing::info!("Found {} Xbox games, skipped {}", games.len(), report.skipped.len());
+        Ok((games, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_NAME: &str = "Microsoft.254428597CFE2_1.2.3.0_x64__8wekyb3d8bbwe";
+
+    const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Package xmlns="http://schemas.microsoft.com/appx/manifest/foundation/windows10">
+  <Identity Name="Microsoft.254428597CFE2" Publisher="CN=Microsoft" Version="1.2.3.0" />
+  <Properties>
+    <DisplayName>ms-resource:DisplayName</DisplayName>
+  </Properties>
+  <Applications>
+    <ApplicationContentUriRules />
+    <Application Id="Game" Executable="GameLaunchHelper.exe"
+      EntryPoint="Windows.FullTrustApplication">
+    </Application>
+  </Applications>
+</Package>"#;
+
+    fn package_with(files: &[(&str, &str)], display_name: Option<&str>) -> XboxPackage {
+        let root = std::env::temp_dir()
+            .join(format!("launcher-xbox-{}", uuid::Uuid::new_v4()))
+            .join("Content");
+        std::fs::create_dir_all(&root).unwrap();
+        for (file, content) in files {
+            std::fs::write(root.join(file), content).unwrap();
+        }
+        XboxPackage {
+            full_name: FULL_NAME.to_string(),
+            root,
+            display_name: display_name.map(str::to_string),
+        }
+    }
+
+    fn cleanup(package: &XboxPackage) {
+        let _ = std::fs::remove_dir_all(package.root.parent().unwrap());
+    }
+
+    #[test]
+    fn test_package_family_name() {
+        assert_eq!(
+            package_family_name(FULL_NAME).as_deref(),
+            Some("Microsoft.254428597CFE2_8wekyb3d8bbwe")
+        );
+        assert_eq!(package_family_name("Microsoft.254428597CFE2"), None);
+    }
+
+    #[test]
+    fn test_detect_package_launches_through_apps_folder() {
+        let config = r#"<Game>
+  <ShellVisuals DefaultDisplayName="Forza Horizon 5 &amp; More" />
+</Game>"#;
+        let package =
+            package_with(&[("AppxManifest.xml", MANIFEST), ("MicrosoftGame.config", config)], None);
+
+        let game = detect_package(package.clone()).unwrap();
+        assert_eq!(game.name, "Forza Horizon 5 & More");
+        assert_eq!(game.source_id, "Microsoft.254428597CFE2_8wekyb3d8bbwe!Game");
+        assert_eq!(
+            game.executable_path,
+            PathBuf::from("shell:AppsFolder\\Microsoft.254428597CFE2_8wekyb3d8bbwe!Game")
+        );
+        assert_eq!(game.install_path, package.root);
+
+        cleanup(&package);
+    }
+
+    #[test]
+    fn test_detect_package_names_and_skips() {
+        // An unresolved resource name falls back to the package name
+        let package = package_with(&[("AppxManifest.xml", MANIFEST)], Some("@{Microsoft…}"));
+        assert_eq!(detect_package(package.clone()).unwrap().name, "Microsoft.254428597CFE2");
+        cleanup(&package);
+
+        let package = package_with(&[("AppxManifest.xml", MANIFEST)], Some("Forza Horizon 5"));
+        assert_eq!(detect_package(package.clone()).unwrap().name, "Forza Horizon 5");
+        cleanup(&package);
+
+        let package = package_with(&[], None);
+        assert_eq!(detect_package(package.clone()).unwrap_err(), SkipReason::Unreadable);
+        cleanup(&package);
+        assert_eq!(detect_package(package).unwrap_err(), SkipReason::NotInstalled);
+    }
+}
@@ -0,0 +1,118 @@
+//! Discord Rich Presence through the `discord-rich-presence` crate
+//!
+//! The crate talks to Discord with blocking socket calls, so each one runs on
+//! a blocking thread and is given up on if Discord doesn't answer in time.
+
+use std::io;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use discord_rich_presence::activity::{Activity, Timestamps};
+use discord_rich_presence::error::Error as DiscordError;
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+
+use crate::constants::DISCORD_IPC_TIMEOUT_SECS;
+
+/// A connection to the Discord client running on this machine
+pub struct DiscordPresence {
+    /// Taken while a call runs; left empty when one never came back
+    client: Option<DiscordIpcClient>,
+}
+
+impl DiscordPresence {
+    /// Connect to Discord and identify as the application `client_id`
+    ///
+    /// Fails when Discord isn't running, which callers treat as nothing to do.
+    pub async fn connect(client_id: &str) -> io::Result<Self> {
+        let mut presence = Self {
+            client: Some(DiscordIpcClient::new(client_id)),
+        };
+        presence.run(|client| client.connect()).await?;
+        Ok(presence)
+    }
+
+    /// Show `game` as being played since `started_at`
+    pub async fn set_activity(&mut self, game: &str, started_at: DateTime<Utc>) -> io::Result<()> {
+        let game = game.to_string();
+        self.run(move |client| {
+            let activity = Activity::new()
+                .details(game)
+                .timestamps(Timestamps::new().start(started_at.timestamp()));
+            client.set_activity(activity)?;
+            client.recv()
+        })
+        .await
+        .and_then(check_reply)
+    }
+
+    pub async fn clear_activity(&mut self) -> io::Result<()> {
+        self.run(|client| {
+            client.clear_activity()?;
+            client.recv()
+        })
+        .await
+        .and_then(check_reply)
+    }
+
+    /// Run one blocking call on the client, giving up once Discord has
+    /// taken too long
+    ///
+    /// A call that doesn't come back keeps the client, so every later call
+    /// fails and the caller drops the connection.
+    async fn run<T: Send + 'static>(
+        &mut self,
+        call: impl FnOnce(&mut DiscordIpcClient) -> Result<T, DiscordError> + Send + 'static,
+    ) -> io::Result<T> {
+        let mut client = self.client.take().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotConnected, "Discord connection was lost")
+        })?;
+        let blocking = tokio::task::spawn_blocking(move || {
+            let result = call(&mut client);
+            (client, result)
+        });
+        let timeout = Duration::from_secs(DISCORD_IPC_TIMEOUT_SECS);
+        let (client, result) = tokio::time::timeout(timeout, blocking)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "Discord did not answer"))?
+            .map_err(io::Error::other)?;
+        self.client = Some(client);
+        result.map_err(io::Error::other)
+    }
+}
+
+impl Drop for DiscordPresence {
+    fn drop(&mut self) {
+        if let Some(mut client) = self.client.take() {
+            let _ = client.close();
+        }
+    }
+}
+
+/// Turn an error Discord replied with into an error of the call
+fn check_reply((_, reply): (u32, serde_json::Value)) -> io::Result<()> {
+    if reply["evt"] == "ERROR" {
+        let message = format!("Discord refused the activity: {}", reply["data"]);
+        return Err(io::Error::other(message));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_replies_fail_the_call() {
+        assert!(check_reply((1, json!({ "cmd": "SET_ACTIVITY", "evt": null }))).is_ok());
+        assert!(check_reply((1, json!({ "evt": "ERROR", "data": {} }))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lost_connection_fails_every_call() {
+        let mut presence = DiscordPresence { client: None };
+
+        let error = presence.clear_activity().await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotConnected);
+    }
+}
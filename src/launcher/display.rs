@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+
+use crate::data::{DisplayPrefs, ExcludePatterns, WindowModePref};
+
+/// Game engines whose command line we know how to speak
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    Unity,
+    Unreal,
+    Unknown,
+}
+
+/// Guess the engine from the files around the executable
+pub fn detect_engine(executable_path: &Path) -> Engine {
+    let Some(dir) = executable_path.parent() else {
+        return Engine::Unknown;
+    };
+
+    // Unity ships UnityPlayer next to the exe and keeps assets in <exe>_Data
+    let data_dir = executable_path
+        .file_stem()
+        .map(|stem| dir.join(format!("{}_Data", stem.to_string_lossy())));
+    if dir.join("UnityPlayer.dll").exists()
+        || dir.join("UnityPlayer.so").exists()
+        || data_dir.is_some_and(|d| d.is_dir())
+    {
+        return Engine::Unity;
+    }
+
+    // Unreal executables live in <Project>/Binaries/<Platform>/
+    let in_binaries = dir
+        .parent()
+        .and_then(|p| p.file_name())
+        .is_some_and(|name| name.eq_ignore_ascii_case("Binaries"));
+    if in_binaries {
+        return Engine::Unreal;
+    }
+
+    Engine::Unknown
+}
+
+/// Extra arguments and environment variables to launch a game with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOverrides {
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// Run the game inside a terminal so its console output is visible
+    pub in_terminal: bool,
+    /// Start the game with administrator rights
+    pub run_as_admin: bool,
+    /// Write the game's stdout and stderr to this file instead of inheriting them
+    pub log_path: Option<PathBuf>,
+    /// Look for the game's binary when the executable path is a folder
+    pub resolve_folders: bool,
+    /// Folders and files that search skips, as imports do
+    pub exclude: ExcludePatterns,
+    /// Run the executable path through this emulator command template
+    pub emulator_command: Option<String>,
+}
+
+/// Translate display preferences into launch overrides for the game's engine
+///
+/// The SDL variable is harmless for games that don't use SDL, so it's always
+/// set. Command line flags are only added for engines we recognise.
+pub fn display_overrides(prefs: &DisplayPrefs, executable_path: &Path) -> LaunchOverrides {
+    let mut overrides = LaunchOverrides::default();
+    if prefs.is_default() {
+        return overrides;
+    }
+
+    if let Some(monitor) = prefs.monitor {
+        overrides
+            .env
+            .push(("SDL_VIDEO_FULLSCREEN_DISPLAY".to_string(), monitor.to_string()));
+    }
+
+    let args: Vec<String> = match detect_engine(executable_path) {
+        Engine::Unity => {
+            // Unity numbers monitors from 1
+            let monitor = prefs
+                .monitor
+                .map(|m| vec!["-monitor".to_string(), (m + 1).to_string()]);
+            let mode = match prefs.window_mode {
+                WindowModePref::Default => vec![],
+                WindowModePref::Fullscreen => vec!["-screen-fullscreen", "1"],
+                WindowModePref::Borderless => vec!["-popupwindow"],
+                WindowModePref::Windowed => vec!["-screen-fullscreen", "0"],
+            };
+            monitor
+                .unwrap_or_default()
+                .into_iter()
+                .chain(mode.into_iter().map(String::from))
+                .collect()
+        }
+        Engine::Unreal => match prefs.window_mode {
+            WindowModePref::Default => vec![],
+            WindowModePref::Fullscreen => vec!["-FULLSCREEN".to_string()],
+            // Unreal has no borderless switch; windowed is the closest it gets
+            WindowModePref::Borderless | WindowModePref::Windowed => {
+                vec!["-WINDOWED".to_string()]
+            }
+        },
+        Engine::Unknown => vec![],
+    };
+    overrides.args = args;
+
+    overrides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("launcher-display-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_unity_overrides() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(dir.join("Game_Data")).unwrap();
+        let exe = dir.join("Game.exe");
+
+        let prefs = DisplayPrefs {
+            monitor: Some(1),
+            window_mode: WindowModePref::Borderless,
+        };
+        let overrides = display_overrides(&prefs, &exe);
+        assert_eq!(overrides.args, vec!["-monitor", "2", "-popupwindow"]);
+        assert_eq!(
+            overrides.env,
+            vec![("SDL_VIDEO_FULLSCREEN_DISPLAY".to_string(), "1".to_string())]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_unknown_engine_only_sets_env() {
+        let dir = temp_dir();
+        let prefs = DisplayPrefs {
+            monitor: Some(0),
+            window_mode: WindowModePref::Fullscreen,
+        };
+        let overrides = display_overrides(&prefs, &dir.join("game"));
+        assert!(overrides.args.is_empty());
+        assert_eq!(overrides.env.len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_default_prefs_add_nothing() {
+        let overrides = display_overrides(&DisplayPrefs::default(), Path::new("game"));
+        assert_eq!(overrides, LaunchOverrides::default());
+    }
+}
@@ -0,0 +1,137 @@
+//! Launching games with administrator rights on Windows
+//!
+//! `Command` can't trigger a UAC prompt, so elevated games go through
+//! `ShellExecuteExW` with the `runas` verb instead.
+
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+
+use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_CANCELLED, HANDLE};
+use windows_sys::Win32::System::Threading::{
+    GetExitCodeProcess, GetProcessId, WaitForSingleObject, INFINITE,
+};
+use windows_sys::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+use super::LaunchError;
+
+/// A game started through UAC, owning its process handle
+#[derive(Debug)]
+pub struct ElevatedProcess {
+    handle: HANDLE,
+}
+
+// The handle is only used to query and wait on the process, which is safe
+// from any thread
+unsafe impl Send for ElevatedProcess {}
+
+impl ElevatedProcess {
+    pub fn id(&self) -> Option<u32> {
+        let pid = unsafe { GetProcessId(self.handle) };
+        (pid != 0).then_some(pid)
+    }
+
+    /// Block until the process exits, returning its exit code
+    pub fn wait_blocking(self) -> Option<u32> {
+        let mut code = 0;
+        unsafe {
+            WaitForSingleObject(self.handle, INFINITE);
+            (GetExitCodeProcess(self.handle, &mut code) != 0).then_some(code)
+        }
+    }
+}
+
+impl Drop for ElevatedProcess {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.handle);
+        }
+    }
+}
+
+/// Start an executable elevated, showing the UAC prompt
+///
+/// Declining the prompt is reported as [`LaunchError::ElevationDeclined`].
+pub fn spawn_elevated(
+    executable_path: &Path,
+    args: &[String],
+    working_dir: Option<&Path>,
+) -> Result<ElevatedProcess, LaunchError> {
+    let verb = wide(OsStr::new("runas"));
+    let file = wide(executable_path.as_os_str());
+    let parameters = wide(OsStr::new(
+        &args.iter().map(|a| quote_arg(a)).collect::<Vec<_>>().join(" "),
+    ));
+    let directory = working_dir.map(|dir| wide(dir.as_os_str()));
+
+    let mut info: SHELLEXECUTEINFOW = unsafe { std::mem::zeroed() };
+    info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+    info.fMask = SEE_MASK_NOCLOSEPROCESS;
+    info.lpVerb = verb.as_ptr();
+    info.lpFile = file.as_ptr();
+    info.lpParameters = parameters.as_ptr();
+    info.lpDirectory = directory.as_ref().map_or(std::ptr::null(), |d| d.as_ptr());
+    info.nShow = SW_SHOWNORMAL;
+
+    if unsafe { ShellExecuteExW(&mut info) } == 0 {
+        let error = unsafe { GetLastError() };
+        return Err(if error == ERROR_CANCELLED {
+            LaunchError::ElevationDeclined
+        } else {
+            LaunchError::SpawnFailed(std::io::Error::from_raw_os_error(error as i32).to_string())
+        });
+    }
+    if info.hProcess.is_null() {
+        return Err(LaunchError::SpawnFailed("No process handle returned".to_string()));
+    }
+
+    Ok(ElevatedProcess {
+        handle: info.hProcess,
+    })
+}
+
+fn wide(s: &OsStr) -> Vec<u16> {
+    s.encode_wide().chain(Some(0)).collect()
+}
+
+/// Quote an argument the way the MSVC runtime splits command lines
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are escapes, so double them
+                // and escape the quote itself
+                quoted.extend(std::iter::repeat_n('\\', backslashes + 1));
+                backslashes = 0;
+            }
+            _ => backslashes = 0,
+        }
+        quoted.push(c);
+    }
+    // Trailing backslashes would escape the closing quote
+    quoted.extend(std::iter::repeat_n('\\', backslashes));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(quote_arg("-windowed"), "-windowed");
+        assert_eq!(quote_arg(""), "\"\"");
+        assert_eq!(quote_arg("C:\\Program Files\\Game"), "\"C:\\Program Files\\Game\"");
+        assert_eq!(quote_arg("C:\\My Games\\"), "\"C:\\My Games\\\\\"");
+        assert_eq!(quote_arg("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+}
@@ -0,0 +1,105 @@
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// A log file that stops growing once it reaches its size limit
+pub(super) struct BoundedLog {
+    sink: Box<dyn Write + Send>,
+    written: u64,
+    limit: u64,
+    truncated: bool,
+}
+
+impl BoundedLog {
+    /// Create (or replace) the log file at `path`
+    pub(super) fn create(path: &Path, limit: u64) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::File::create(path)?;
+        Ok(Self::new(Box::new(file), limit))
+    }
+
+    fn new(sink: Box<dyn Write + Send>, limit: u64) -> Self {
+        Self {
+            sink,
+            written: 0,
+            limit,
+            truncated: false,
+        }
+    }
+
+    /// Append what fits, noting once in the log that the rest was dropped
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        let room = self.limit.saturating_sub(self.written) as usize;
+        let kept = &bytes[..bytes.len().min(room)];
+        self.sink.write_all(kept)?;
+        self.written += kept.len() as u64;
+
+        if kept.len() < bytes.len() && !self.truncated {
+            self.truncated = true;
+            self.sink.write_all(b"\n[log truncated, further output discarded]\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Copy a child's output stream into the shared log until it closes
+///
+/// The stream keeps being drained after the log is full, since a game
+/// blocked on a full pipe would hang.
+pub(super) async fn capture_stream(
+    mut stream: impl AsyncRead + Unpin,
+    log: Arc<Mutex<BoundedLog>>,
+) {
+    let mut buf = [0u8; 8192];
+    loop {
+        let read = match stream.read(&mut buf).await {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) => {
+                tracing::debug!("Stopped capturing game output: {}", e);
+                break;
+            }
+        };
+        let mut log = log.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = log.write(&buf[..read]) {
+            tracing::warn!("Failed to write game log: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sink the test can read back after the log is done with it
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capture_is_bounded_but_drains() {
+        let buf = SharedBuf::default();
+        let log = Arc::new(Mutex::new(BoundedLog::new(Box::new(buf.clone()), 10)));
+
+        let output: &[u8] = b"hello world, this is a lot of output";
+        capture_stream(output, log.clone()).await;
+        capture_stream(&b"more"[..], log).await;
+
+        let written = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(written, "hello worl\n[log truncated, further output discarded]\n");
+    }
+}
@@ -1,7 +1,18 @@
 //! Game launching functionality
 
+#[cfg(feature = "discord")]
+mod discord;
+mod display;
+#[cfg(target_os = "windows")]
+mod elevated;
+mod log;
 mod process;
+mod status;
 
-pub use process::launch_game;
-#[allow(unused_imports)]
-pub use process::LaunchError;
+#[cfg(feature = "discord")]
+pub use discord::DiscordPresence;
+pub use display::{display_overrides, LaunchOverrides};
+pub use process::{
+    build_command, launch_game, launch_store_uri, launch_uri, LaunchError, ResolvedCommand,
+};
+pub use status::{LaunchStatus, StatusWriter};
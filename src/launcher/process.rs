@@ -1,41 +1,532 @@
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
+use tokio::process::{Child, Command};
 
-/// Launch a game executable
-pub fn launch_game(executable_path: &Path, launch_args: Option<&str>) -> Result<(), LaunchError> {
-    if !executable_path.exists() {
-        return Err(LaunchError::ExecutableNotFound(
-            executable_path.to_string_lossy().to_string(),
-        ));
+#[cfg(target_os = "windows")]
+use super::elevated::{spawn_elevated, ElevatedProcess};
+
+use super::log::{capture_stream, BoundedLog};
+use super::LaunchOverrides;
+use crate::constants::MAX_GAME_LOG_BYTES;
+use crate::data::{find_executable_in_dir, find_path_ignoring_case};
+use crate::platform;
+
+/// Exactly what a launch would run, worked out without starting anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedCommand {
+    /// The game, or whatever wraps it (a terminal or `pkexec`)
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub working_dir: Option<PathBuf>,
+    /// Set on top of the launcher's own environment
+    pub env: Vec<(String, String)>,
+    /// Whether the game goes through an administrator prompt
+    pub elevated: bool,
+    /// Where the game's output is captured, if anywhere
+    pub log_path: Option<PathBuf>,
+    /// Whether the process started exits with the game; a terminal that
+    /// hands the game to another window doesn't
+    pub tracked: bool,
+}
+
+impl ResolvedCommand {
+    /// The program and its arguments as one line, quoted where needed
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.to_string_lossy().to_string())
+            .chain(self.args.iter().cloned())
+            .map(|arg| {
+                if arg.is_empty() || arg.contains(char::is_whitespace) {
+                    format!("\"{}\"", arg)
+                } else {
+                    arg
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
     }
+}
 
-    let mut command = Command::new(executable_path);
+/// Work out the command that launching a game would run
+///
+/// Relative paths are resolved against `base_dir` and symlinks are followed,
+/// so the working directory is the real game folder. A folder is searched
+/// for the game's binary when `resolve_folders` is set. With an
+/// `emulator_command`, the emulator runs with the path as its ROM.
+/// `overrides` are appended after the user's own arguments.
+///
+/// With `run_as_admin` set, Windows shows a UAC prompt and Linux goes through
+/// `pkexec` when it's installed; elsewhere the game starts normally.
+pub fn build_command(
+    executable_path: &Path,
+    launch_args: Option<&str>,
+    overrides: &LaunchOverrides,
+    base_dir: &Path,
+) -> Result<ResolvedCommand, LaunchError> {
+    let mut executable_path = resolve_executable(executable_path, base_dir)?;
+    // A ROM can be a folder too, which the emulator opens as it is
+    if executable_path.is_dir() && overrides.emulator_command.is_none() {
+        executable_path = executable_in_folder(&executable_path, overrides)?;
+    }
+    let working_dir = executable_path.parent().map(Path::to_path_buf);
+
+    // Split arguments by whitespace, respecting quotes
+    let mut args = launch_args.map(parse_args).unwrap_or_default();
+    args.extend(overrides.args.iter().cloned());
+
+    if let Some(template) = &overrides.emulator_command {
+        let (emulator, mut emulator_args) = emulator_invocation(template, &executable_path)?;
+        emulator_args.append(&mut args);
+        executable_path = emulator;
+        args = emulator_args;
+    }
+
+    // The UAC prompt starts the game itself, without our environment or pipes
+    #[cfg(target_os = "windows")]
+    if overrides.run_as_admin {
+        return Ok(ResolvedCommand {
+            program: executable_path,
+            args,
+            working_dir,
+            env: Vec::new(),
+            elevated: true,
+            log_path: None,
+            tracked: true,
+        });
+    }
+
+    let (program, args, elevated) = elevate_command(&executable_path, args, overrides);
+
+    let (program, args, tracked) = if overrides.in_terminal {
+        match platform::find_terminal() {
+            Some(terminal) => {
+                let command = terminal.command(&program, &args);
+                let args = command
+                    .get_args()
+                    .map(|arg| arg.to_string_lossy().to_string())
+                    .collect();
+                (PathBuf::from(command.get_program()), args, terminal.waits)
+            }
+            None => {
+                tracing::warn!("No terminal emulator found, launching directly");
+                (program, args, true)
+            }
+        }
+    } else {
+        (program, args, true)
+    };
+
+    Ok(ResolvedCommand {
+        program,
+        args,
+        working_dir,
+        env: overrides.env.clone(),
+        elevated,
+        // A terminal already shows the output, so only capture otherwise
+        log_path: overrides.log_path.clone().filter(|_| !overrides.in_terminal),
+        tracked,
+    })
+}
+
+/// Launch a game executable
+///
+/// The command is the one [`build_command`] describes. When it runs in a
+/// terminal the returned child is the terminal, which some emulators detach
+/// from immediately. Returns the spawned process so the caller can await the
+/// game's exit. Must be called from within the tokio runtime.
+///
+/// With `log_path` set the game's output goes to that file, up to
+/// [`MAX_GAME_LOG_BYTES`], unless it runs in a terminal or elevated on Windows.
+/// The UAC prompt blocks until it's answered, so it waits on the blocking pool.
+pub async fn launch_game(
+    executable_path: &Path,
+    launch_args: Option<&str>,
+    overrides: &LaunchOverrides,
+    base_dir: &Path,
+) -> Result<GameProcess, LaunchError> {
+    let resolved = build_command(executable_path, launch_args, overrides, base_dir)?;
 
-    // Set working directory to the executable's directory
-    if let Some(parent) = executable_path.parent() {
-        command.current_dir(parent);
+    #[cfg(target_os = "windows")]
+    if resolved.elevated {
+        if !overrides.env.is_empty() {
+            tracing::warn!("Environment overrides are not passed to elevated games");
+        }
+        let (program, args) = (resolved.program.clone(), resolved.args.clone());
+        let working_dir = resolved.working_dir.clone();
+        let process = tokio::task::spawn_blocking(move || {
+            spawn_elevated(&program, &args, working_dir.as_deref())
+        })
+        .await
+        .map_err(|e| LaunchError::SpawnFailed(e.to_string()))??;
+        tracing::info!(
+            "Launched game elevated: {:?} (PID: {:?})",
+            resolved.program,
+            process.id()
+        );
+        return Ok(GameProcess::Elevated(process));
     }
 
-    // Add launch arguments if provided
-    if let Some(args) = launch_args {
-        // Split arguments by whitespace, respecting quotes
-        let args = parse_args(args);
-        command.args(&args);
+    let mut command = direct_command(&resolved.program, &resolved.args);
+    if let Some(dir) = &resolved.working_dir {
+        command.current_dir(dir);
     }
+    command.envs(resolved.env.iter().map(|(k, v)| (k, v)));
+
+    let log = match &resolved.log_path {
+        Some(path) => match BoundedLog::create(path, MAX_GAME_LOG_BYTES) {
+            Ok(log) => {
+                command.stdout(Stdio::piped()).stderr(Stdio::piped());
+                Some(Arc::new(Mutex::new(log)))
+            }
+            Err(e) => {
+                tracing::warn!("Could not create game log {:?}: {}", path, e);
+                None
+            }
+        },
+        _ => None,
+    };
 
     // Spawn the process
-    let child = command.spawn().map_err(|e| LaunchError::SpawnFailed(e.to_string()))?;
+    let mut child = command.spawn().map_err(|e| LaunchError::SpawnFailed(e.to_string()))?;
+
+    if let Some(log) = log {
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(capture_stream(stdout, log.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(capture_stream(stderr, log));
+        }
+    }
 
     tracing::info!(
-        "Launched game: {:?} (PID: {})",
-        executable_path,
+        "Launched game: {} (PID: {:?})",
+        resolved.command_line(),
         child.id()
     );
 
+    Ok(GameProcess::Child {
+        child,
+        elevated: resolved.elevated,
+        tracked: resolved.tracked,
+    })
+}
+
+/// Wrap the game in `pkexec` when it should run as root
+///
+/// pkexec clears the environment and working directory, so both are handed
+/// over through `env`, along with what the game needs to open a window on
+/// the user's session. Returns the program to run, its arguments and whether
+/// it was elevated.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn elevate_command(
+    executable_path: &Path,
+    args: Vec<String>,
+    overrides: &LaunchOverrides,
+) -> (PathBuf, Vec<String>, bool) {
+    if !overrides.run_as_admin {
+        return (executable_path.to_path_buf(), args, false);
+    }
+    if !platform::command_exists("pkexec") {
+        tracing::warn!("pkexec not found, launching without administrator rights");
+        return (executable_path.to_path_buf(), args, false);
+    }
+    let dir = executable_path.parent().unwrap_or(Path::new("/"));
+    let env = pkexec_env(&overrides.env, |name| std::env::var(name).ok());
+    (PathBuf::from("pkexec"), pkexec_args(executable_path, &args, dir, &env), true)
+}
+
+#[cfg(target_os = "macos")]
+fn elevate_command(
+    executable_path: &Path,
+    args: Vec<String>,
+    overrides: &LaunchOverrides,
+) -> (PathBuf, Vec<String>, bool) {
+    if overrides.run_as_admin {
+        tracing::warn!("Running as administrator is not supported on macOS");
+    }
+    (executable_path.to_path_buf(), args, false)
+}
+
+#[cfg(target_os = "windows")]
+fn elevate_command(
+    executable_path: &Path,
+    args: Vec<String>,
+    _overrides: &LaunchOverrides,
+) -> (PathBuf, Vec<String>, bool) {
+    // Elevated launches go through `spawn_elevated` instead
+    (executable_path.to_path_buf(), args, false)
+}
+
+/// The session variables `lookup` finds, then the game's own, which win
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn pkexec_env(
+    env: &[(String, String)],
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<(String, String)> {
+    PKEXEC_SESSION_VARS
+        .iter()
+        .filter_map(|name| Some((name.to_string(), lookup(name)?)))
+        .chain(env.iter().cloned())
+        .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn pkexec_args(
+    executable_path: &Path,
+    args: &[String],
+    dir: &Path,
+    env: &[(String, String)],
+) -> Vec<String> {
+    let mut pkexec_args = vec!["env".to_string(), format!("--chdir={}", dir.display())];
+    pkexec_args.extend(env.iter().map(|(k, v)| format!("{}={}", k, v)));
+    pkexec_args.push(executable_path.to_string_lossy().to_string());
+    pkexec_args.extend(args.iter().cloned());
+    pkexec_args
+}
+
+/// Variables pkexec drops that a game needs to reach the user's display
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PKEXEC_SESSION_VARS: &[&str] =
+    &["DISPLAY", "WAYLAND_DISPLAY", "XAUTHORITY", "XDG_RUNTIME_DIR"];
+
+/// Exit code pkexec returns when the user dismisses the password prompt
+const PKEXEC_DISMISSED: i32 = 126;
+
+/// Exit code pkexec returns when authorization failed or couldn't be asked for
+const PKEXEC_NOT_AUTHORIZED: i32 = 127;
+
+/// A running game, started directly or through an elevation prompt
+#[derive(Debug)]
+pub enum GameProcess {
+    Child {
+        child: Child,
+        /// Started through pkexec, so its exit code may report a declined prompt
+        elevated: bool,
+        /// Whether the child exits with the game, see [`ResolvedCommand::tracked`]
+        tracked: bool,
+    },
+    #[cfg(target_os = "windows")]
+    Elevated(ElevatedProcess),
+}
+
+impl GameProcess {
+    pub fn id(&self) -> Option<u32> {
+        match self {
+            GameProcess::Child { child, .. } => child.id(),
+            #[cfg(target_os = "windows")]
+            GameProcess::Elevated(process) => process.id(),
+        }
+    }
+
+    /// Whether waiting on this process says when the game stopped
+    pub fn is_tracked(&self) -> bool {
+        match self {
+            GameProcess::Child { tracked, .. } => *tracked,
+            #[cfg(target_os = "windows")]
+            GameProcess::Elevated(_) => true,
+        }
+    }
+
+    /// Wait for the game to exit, returning how it exited when known
+    ///
+    /// A game killed by a signal has no exit code, but its status still
+    /// says it didn't succeed. A declined or failed pkexec prompt only shows
+    /// up here, as [`LaunchError::ElevationDeclined`] or
+    /// [`LaunchError::ElevationFailed`].
+    pub async fn wait(self) -> Result<Option<ExitStatus>, LaunchError> {
+        match self {
+            GameProcess::Child { mut child, elevated, .. } => {
+                let status = child.wait().await.map_err(|e| LaunchError::Wait(e.to_string()))?;
+                if elevated {
+                    match status.code() {
+                        Some(PKEXEC_DISMISSED) => return Err(LaunchError::ElevationDeclined),
+                        Some(PKEXEC_NOT_AUTHORIZED) => return Err(LaunchError::ElevationFailed),
+                        _ => {}
+                    }
+                }
+                Ok(Some(status))
+            }
+            #[cfg(target_os = "windows")]
+            GameProcess::Elevated(process) => {
+                use std::os::windows::process::ExitStatusExt;
+
+                let code = tokio::task::spawn_blocking(move || process.wait_blocking())
+                    .await
+                    .map_err(|e| LaunchError::Wait(e.to_string()))?;
+                Ok(code.map(ExitStatus::from_raw))
+            }
+        }
+    }
+}
+
+/// Resolve a stored executable path to the real file on disk
+///
+/// Relative paths (used by portable setups) are taken relative to `base_dir`
+/// rather than the process working directory, then symlinks are resolved.
+/// On Linux a path whose case doesn't match the files on disk still resolves.
+pub fn resolve_executable(
+    executable_path: &Path,
+    base_dir: &Path,
+) -> Result<PathBuf, LaunchError> {
+    let path = if executable_path.is_relative() {
+        base_dir.join(executable_path)
+    } else {
+        executable_path.to_path_buf()
+    };
+    let path = find_path_ignoring_case(&path).unwrap_or(path);
+
+    match std::fs::canonicalize(&path) {
+        Ok(resolved) => Ok(strip_verbatim_prefix(resolved)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(
+            LaunchError::ExecutableNotFound(path.to_string_lossy().to_string()),
+        ),
+        Err(e) => Err(LaunchError::InvalidPath(
+            path.to_string_lossy().to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+/// Stands for the ROM's path in an emulator command template
+const ROM_PLACEHOLDER: &str = "{rom}";
+
+/// The emulator and its arguments for running `rom`
+///
+/// The template is split like launch arguments, then `{rom}` is replaced in
+/// each argument, so a path with spaces stays one argument. A template
+/// without `{rom}` gets the ROM as its last argument.
+fn emulator_invocation(
+    template: &str,
+    rom: &Path,
+) -> Result<(PathBuf, Vec<String>), LaunchError> {
+    let mut parts = parse_args(template);
+    if parts.is_empty() {
+        return Err(LaunchError::InvalidEmulatorCommand(template.to_string()));
+    }
+    let rom = rom.to_string_lossy();
+    let program = PathBuf::from(parts.remove(0));
+    let mut substituted = false;
+    for arg in &mut parts {
+        if arg.contains(ROM_PLACEHOLDER) {
+            *arg = arg.replace(ROM_PLACEHOLDER, &rom);
+            substituted = true;
+        }
+    }
+    if !substituted {
+        parts.push(rom.into_owned());
+    }
+    Ok((program, parts))
+}
+
+/// The program to run for an executable path that turned out to be a folder
+///
+/// A macOS `.app` bundle runs its bundled binary. Any other folder gets the
+/// same guess imports make for a game's main executable, skipping the same
+/// excluded folders and helpers like uninstallers. A folder holding nothing
+/// else stays an error rather than running the wrong program.
+fn executable_in_folder(dir: &Path, overrides: &LaunchOverrides) -> Result<PathBuf, LaunchError> {
+    let folder_error = || LaunchError::ExecutableIsFolder(dir.to_string_lossy().to_string());
+    if !overrides.resolve_folders {
+        return Err(folder_error());
+    }
+
+    #[cfg(target_os = "macos")]
+    if dir.extension().is_some_and(|ext| ext == "app") {
+        return platform::get_app_executable(dir).ok_or_else(folder_error);
+    }
+
+    let found = find_executable_in_dir(dir, &overrides.exclude).ok_or_else(folder_error)?;
+    tracing::info!("Executable path {:?} is a folder, launching {:?}", dir, found);
+    Ok(found)
+}
+
+/// Windows canonical paths are `\\?\`-prefixed, which many games can't handle
+/// as a working directory
+fn strip_verbatim_prefix(path: PathBuf) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let s = path.to_string_lossy();
+        if let Some(stripped) = s.strip_prefix(r"\\?\") {
+            if !stripped.starts_with("UNC") {
+                return PathBuf::from(stripped);
+            }
+        }
+    }
+    path
+}
+
+fn direct_command(executable_path: &Path, args: &[String]) -> Command {
+    let mut command = Command::new(executable_path);
+    command.args(args);
+    command
+}
+
+/// Switches Steam to Big Picture, its controller-friendly interface
+const STEAM_BIG_PICTURE_URI: &str = "steam://open/bigpicture";
+
+/// Link prefixes that start a Steam game
+const STEAM_RUN_PREFIXES: &[&str] = &["steam://rungameid/", "steam://run/"];
+
+/// The URIs to open, in order, to launch `uri`
+///
+/// With `big_picture`, a Steam game link is preceded by a switch to Big
+/// Picture so the game starts in the controller interface. Other links are
+/// opened as they are.
+pub fn uri_launch_sequence(uri: &str, big_picture: bool) -> Vec<String> {
+    let uri = uri.trim();
+    let is_steam_game = STEAM_RUN_PREFIXES
+        .iter()
+        .any(|prefix| uri.get(..prefix.len()).is_some_and(|p| p.eq_ignore_ascii_case(prefix)));
+
+    if big_picture && is_steam_game {
+        vec![STEAM_BIG_PICTURE_URI.to_string(), uri.to_string()]
+    } else {
+        vec![uri.to_string()]
+    }
+}
+
+/// Open a launch link, switching Steam to Big Picture first when asked
+///
+/// Big Picture failing to open doesn't stop the game, it starts normally.
+pub fn launch_store_uri(uri: &str, big_picture: bool) -> Result<(), LaunchError> {
+    let sequence = uri_launch_sequence(uri, big_picture);
+    let (game_uri, before) = sequence.split_last().expect("the sequence ends with the link");
+    for step in before {
+        if let Err(e) = launch_uri(step) {
+            tracing::warn!("Could not open Big Picture, launching normally: {}", e);
+        }
+    }
+    launch_uri(game_uri)
+}
+
+/// Open a URI (e.g. a store protocol link) with the system handler
+pub fn launch_uri(uri: &str) -> Result<(), LaunchError> {
+    uri_command(uri)
+        .spawn()
+        .map_err(|e| LaunchError::SpawnFailed(e.to_string()))?;
+
+    tracing::info!("Opened URI: {}", uri);
     Ok(())
 }
 
+/// Build the command that hands a URI to the system handler
+///
+/// On Windows that is Explorer rather than `cmd /C start`, which would read
+/// the `&` in a link like Epic's `?action=launch&silent=true` as the start
+/// of another command.
+fn uri_command(uri: &str) -> std::process::Command {
+    #[cfg(target_os = "windows")]
+    let mut command = std::process::Command::new("explorer");
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command.arg(uri.trim());
+    command
+}
+
 /// Parse command line arguments, handling quoted strings
 fn parse_args(args_str: &str) -> Vec<String> {
     let mut args = Vec::new();
@@ -72,21 +563,43 @@ fn parse_args(args_str: &str) -> Vec<String> {
 }
 
 /// Errors that can occur when launching a game
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum LaunchError {
     #[error("Executable not found: {0}")]
     ExecutableNotFound(String),
 
+    #[error("{0} is a folder, and no program to launch was found in it")]
+    ExecutableIsFolder(String),
+
+    #[error("Emulator command is empty: {0:?}")]
+    InvalidEmulatorCommand(String),
+
+    #[error("Could not resolve {0}: {1}")]
+    InvalidPath(String, String),
+
     #[error("Failed to spawn process: {0}")]
     SpawnFailed(String),
 
     #[error("Permission denied")]
     PermissionDenied,
+
+    /// The user said no to the administrator prompt
+    #[error("Administrator permission was declined")]
+    ElevationDeclined,
+
+    /// The system wouldn't grant administrator rights, e.g. a wrong password
+    /// or no polkit agent to ask with
+    #[error("Administrator permission could not be obtained")]
+    ElevationFailed,
+
+    #[error("Failed to wait for game process: {0}")]
+    Wait(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::{ExcludePatterns, GameSource};
 
     #[test]
     fn test_parse_args_simple() {
@@ -100,6 +613,295 @@ mod tests {
         assert_eq!(args, vec!["-path", "C:\\Program Files\\Game"]);
     }
 
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("launcher-launch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_resolve_relative_against_base() {
+        let base = temp_dir();
+        std::fs::create_dir_all(base.join("games")).unwrap();
+        std::fs::write(base.join("games").join("game"), b"").unwrap();
+
+        let resolved = resolve_executable(Path::new("games/game"), &base).unwrap();
+        assert_eq!(resolved, base.join("games").join("game"));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn test_resolve_missing_executable() {
+        let base = temp_dir();
+        assert!(matches!(
+            resolve_executable(Path::new("missing"), &base),
+            Err(LaunchError::ExecutableNotFound(_))
+        ));
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_follows_symlinked_dir() {
+        let base = temp_dir();
+        std::fs::create_dir_all(base.join("real")).unwrap();
+        std::fs::write(base.join("real").join("game"), b"").unwrap();
+        std::os::unix::fs::symlink(base.join("real"), base.join("link")).unwrap();
+
+        let resolved = resolve_executable(&base.join("link").join("game"), &base).unwrap();
+        assert_eq!(resolved, base.join("real").join("game"));
+        assert_eq!(resolved.parent().unwrap(), base.join("real"));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn test_pkexec_args_carry_dir_and_env() {
+        let args = pkexec_args(
+            Path::new("/games/doom/doom"),
+            &["-fullscreen".to_string()],
+            Path::new("/games/doom"),
+            &[("SDL_VIDEO_FULLSCREEN_DISPLAY".to_string(), "1".to_string())],
+        );
+        assert_eq!(
+            args,
+            vec![
+                "env",
+                "--chdir=/games/doom",
+                "SDL_VIDEO_FULLSCREEN_DISPLAY=1",
+                "/games/doom/doom",
+                "-fullscreen",
+            ]
+        );
+
+        // The game keeps its window on the user's display
+        let session = |name: &str| match name {
+            "DISPLAY" => Some(":0".to_string()),
+            "XDG_RUNTIME_DIR" => Some("/run/user/1000".to_string()),
+            _ => None,
+        };
+        let env = pkexec_env(&[("DISPLAY".to_string(), ":1".to_string())], session);
+        let env: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        assert_eq!(env, ["DISPLAY=:0", "XDG_RUNTIME_DIR=/run/user/1000", "DISPLAY=:1"]);
+    }
+
+    #[test]
+    fn test_uri_command_uses_system_handler() {
+        let command = uri_command(" https://play.example.com/game ");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args.last().unwrap(), &"https://play.example.com/game");
+
+        let program = command.get_program();
+        if cfg!(target_os = "windows") {
+            assert_eq!(program, "explorer");
+        } else if cfg!(target_os = "macos") {
+            assert_eq!(program, "open");
+        } else {
+            assert_eq!(program, "xdg-open");
+        }
+
+        // Linked store copies launch through links like Epic's, whose `&`
+        // has to reach the handler as part of the one argument
+        let epic = GameSource::Epic.launch_uri("Fortnite").unwrap();
+        let command = uri_command(&epic);
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, [epic.as_str()]);
+    }
+
+    #[test]
+    fn test_uri_launch_sequence_big_picture() {
+        let uri = GameSource::Steam.launch_uri("400").unwrap();
+        assert_eq!(uri_launch_sequence(&uri, false), ["steam://rungameid/400"]);
+        assert_eq!(
+            uri_launch_sequence(&uri, true),
+            ["steam://open/bigpicture", "steam://rungameid/400"]
+        );
+        assert_eq!(
+            uri_launch_sequence(" STEAM://run/400 ", true),
+            ["steam://open/bigpicture", "STEAM://run/400"]
+        );
+
+        // Only Steam games have a controller mode to switch to
+        let epic = GameSource::Epic.launch_uri("Fortnite").unwrap();
+        assert_eq!(uri_launch_sequence(&epic, true), [epic]);
+        assert_eq!(
+            uri_launch_sequence("steam://store/400", true),
+            ["steam://store/400"]
+        );
+    }
+
+    #[test]
+    fn test_build_command_direct() {
+        let base = temp_dir();
+        std::fs::create_dir_all(base.join("game")).unwrap();
+        std::fs::write(base.join("game").join("run"), b"").unwrap();
+
+        let overrides = LaunchOverrides {
+            args: vec!["-monitor".to_string(), "2".to_string()],
+            env: vec![("SDL_VIDEO_FULLSCREEN_DISPLAY".to_string(), "1".to_string())],
+            log_path: Some(base.join("run.log")),
+            ..Default::default()
+        };
+        let resolved = build_command(
+            Path::new("game/run"),
+            Some("-name \"Big Boss\""),
+            &overrides,
+            &base,
+        )
+        .unwrap();
+
+        assert_eq!(resolved.program, base.join("game").join("run"));
+        assert_eq!(resolved.args, vec!["-name", "Big Boss", "-monitor", "2"]);
+        assert_eq!(resolved.working_dir, Some(base.join("game")));
+        assert_eq!(resolved.env, overrides.env);
+        assert_eq!(resolved.log_path, Some(base.join("run.log")));
+        assert!(!resolved.elevated);
+        assert!(resolved.command_line().ends_with("run -name \"Big Boss\" -monitor 2"));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn test_build_command_in_terminal_skips_log() {
+        let base = temp_dir();
+        std::fs::write(base.join("run"), b"").unwrap();
+
+        let overrides = LaunchOverrides {
+            in_terminal: true,
+            log_path: Some(base.join("run.log")),
+            ..Default::default()
+        };
+        let resolved = build_command(Path::new("run"), None, &overrides, &base).unwrap();
+
+        // Whichever terminal is installed, the game is still what it runs
+        assert!(resolved.command_line().contains(&*base.join("run").to_string_lossy()));
+        assert_eq!(resolved.log_path, None);
+        // Only followed when the terminal stays around for as long as the game
+        let waits = platform::find_terminal().is_none_or(|terminal| terminal.waits);
+        assert_eq!(resolved.tracked, waits);
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn test_build_command_missing_executable() {
+        let base = temp_dir();
+        assert!(matches!(
+            build_command(Path::new("missing"), None, &LaunchOverrides::default(), &base),
+            Err(LaunchError::ExecutableNotFound(_))
+        ));
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_command_resolves_folder() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = temp_dir();
+        let game_dir = base.join("game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        let overrides = LaunchOverrides {
+            resolve_folders: true,
+            ..Default::default()
+        };
+
+        // An uninstaller is no game to run
+        let uninstaller = game_dir.join("unins000");
+        std::fs::write(&uninstaller, b"unins").unwrap();
+        std::fs::set_permissions(&uninstaller, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(matches!(
+            build_command(&game_dir, None, &overrides, &base),
+            Err(LaunchError::ExecutableIsFolder(_))
+        ));
+
+        let binary = game_dir.join("game.x86_64");
+        std::fs::write(&binary, b"").unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let command = build_command(&game_dir, None, &overrides, &base).unwrap();
+        assert_eq!(command.program, binary);
+        assert_eq!(command.working_dir, Some(game_dir.clone()));
+
+        // Excluded binaries are passed over, as imports pass them over
+        let excluding = LaunchOverrides {
+            exclude: ExcludePatterns::new(&["*.x86_64".to_string()]),
+            ..overrides.clone()
+        };
+        assert!(matches!(
+            build_command(&game_dir, None, &excluding, &base),
+            Err(LaunchError::ExecutableIsFolder(_))
+        ));
+
+        // Turned off, a folder is an error even with a binary in it
+        assert!(matches!(
+            build_command(&game_dir, None, &LaunchOverrides::default(), &base),
+            Err(LaunchError::ExecutableIsFolder(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn test_build_command_through_emulator() {
+        let base = temp_dir();
+        let roms = base.join("SNES Games");
+        std::fs::create_dir_all(&roms).unwrap();
+        let rom = roms.join("Super Metroid.sfc");
+        std::fs::write(&rom, b"").unwrap();
+        let rom_arg = rom.to_string_lossy().to_string();
+
+        let overrides = LaunchOverrides {
+            emulator_command: Some("retroarch -L \"cores/snes9x.so\" \"{rom}\"".to_string()),
+            ..Default::default()
+        };
+        let command = build_command(&rom, Some("--fullscreen"), &overrides, &base).unwrap();
+        assert_eq!(command.program, PathBuf::from("retroarch"));
+        assert_eq!(command.args, ["-L", "cores/snes9x.so", rom_arg.as_str(), "--fullscreen"]);
+        assert_eq!(command.working_dir, Some(roms.clone()));
+
+        // Without a placeholder the ROM goes last
+        let (_, args) = emulator_invocation("mednafen -force_module snes", &rom).unwrap();
+        assert_eq!(args, ["-force_module", "snes", rom_arg.as_str()]);
+        assert!(matches!(
+            emulator_invocation("  ", &rom),
+            Err(LaunchError::InvalidEmulatorCommand(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_build_command_resolves_app_bundle() {
+        let base = temp_dir();
+        let bundle = base.join("Game.app");
+        let macos = bundle.join("Contents").join("MacOS");
+        std::fs::create_dir_all(&macos).unwrap();
+        std::fs::write(macos.join("Game"), b"").unwrap();
+        let overrides = LaunchOverrides {
+            resolve_folders: true,
+            ..Default::default()
+        };
+
+        let command = build_command(&bundle, None, &overrides, &base).unwrap();
+        assert_eq!(command.program, macos.join("Game"));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_wait_reports_killed_game_as_failed() {
+        let child = Command::new("sh").args(["-c", "kill -9 $$"]).spawn().unwrap();
+        let process = GameProcess::Child { child, elevated: false, tracked: true };
+
+        let status = process.wait().await.unwrap().unwrap();
+        assert_eq!(status.code(), None);
+        assert!(!status.success());
+    }
+
     #[test]
     fn test_parse_args_empty() {
         let args = parse_args("");
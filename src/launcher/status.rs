@@ -0,0 +1,126 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::data::{write_file, GameSource};
+
+/// The game being played, written to a file that external tools (stream
+/// overlays, rich presence helpers) can poll
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchStatus {
+    pub game: String,
+    pub source: GameSource,
+    pub started_at: DateTime<Utc>,
+    /// False once the game has exited, until the next launch
+    pub running: bool,
+    /// Whether `running` follows the game; games handed to a store or URL
+    /// are never seen exiting, so for those it only says they were started
+    pub exit_tracked: bool,
+}
+
+impl LaunchStatus {
+    /// Replace the status file at `path`
+    ///
+    /// The JSON is written next to it and renamed into place, so a reader
+    /// never sees a half-written file.
+    pub async fn write(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        write_file(path, &json).await
+    }
+}
+
+/// Writes the status file in the order statuses were produced
+///
+/// Each write runs as a task of its own and those can finish in any order,
+/// so writes are numbered when they're made and one older than the last
+/// written is dropped. A game's exit can't be overwritten by its launch.
+#[derive(Debug, Clone, Default)]
+pub struct StatusWriter {
+    issued: Arc<AtomicU64>,
+    written: Arc<tokio::sync::Mutex<u64>>,
+}
+
+impl StatusWriter {
+    /// Take the next place in line for writing `status` to `path`
+    pub fn write(
+        &self,
+        status: LaunchStatus,
+        path: PathBuf,
+    ) -> impl std::future::Future<Output = io::Result<()>> + Send + 'static {
+        let number = self.issued.fetch_add(1, Ordering::SeqCst) + 1;
+        let written = self.written.clone();
+        async move {
+            let mut last = written.lock().await;
+            if *last > number {
+                return Ok(());
+            }
+            status.write(&path).await?;
+            *last = number;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Clock, MockClock};
+
+    #[tokio::test]
+    async fn test_launch_status_is_replaced_in_place() {
+        let dir = std::env::temp_dir().join(format!("launcher-status-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("launch_status.json");
+        let mut status = LaunchStatus {
+            game: "Portal".to_string(),
+            source: GameSource::Steam,
+            started_at: MockClock::fixed().now(),
+            running: true,
+            exit_tracked: true,
+        };
+        status.write(&path).await.unwrap();
+
+        status.running = false;
+        status.write(&path).await.unwrap();
+
+        let written: LaunchStatus =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, status);
+        // Only the status file is left behind
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_writer_keeps_the_newest_status() {
+        let dir = std::env::temp_dir().join(format!("launcher-status-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("launch_status.json");
+        let launched = LaunchStatus {
+            game: "Portal".to_string(),
+            source: GameSource::Steam,
+            started_at: MockClock::fixed().now(),
+            running: true,
+            exit_tracked: true,
+        };
+        let exited = LaunchStatus {
+            running: false,
+            ..launched.clone()
+        };
+
+        // The exit is written before the launch that came first gets its turn
+        let writer = StatusWriter::default();
+        let launch = writer.write(launched, path.clone());
+        writer.write(exited.clone(), path.clone()).await.unwrap();
+        launch.await.unwrap();
+
+        let written: LaunchStatus =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, exited);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -22,8 +22,11 @@ mod launcher;
 // Platform-specific code
 mod platform;
 
+// Artwork cache
+mod cache;
+
 use app::App;
-use constants::{APP_NAME, DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH};
+use constants::APP_NAME;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 fn main() -> iced::Result {
@@ -38,10 +41,12 @@ fn main() -> iced::Result {
 
     tracing::info!("Starting {}", APP_NAME);
 
-    iced::application(App::new, App::update, App::view)
-        .title(APP_NAME)
+    // A daemon rather than an application so the now playing window can sit
+    // beside the main one; App::new opens the main window
+    iced::daemon(App::new, App::dispatch, App::view)
+        .title(App::title)
         .subscription(App::subscription)
         .theme(App::theme)
-        .window_size((DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT))
+        .scale_factor(App::scale_factor)
         .run()
 }
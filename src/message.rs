@@ -1,6 +1,17 @@
-use crate::data::{CategoryId, Config, Game, GameId, GameSource, Library};
+use crate::cache::ArtPaths;
+#[cfg(feature = "steamgriddb")]
+use crate::cache::steamgriddb::{ArtCandidate, ArtEntry, ArtKind, SteamGridDbError};
+use crate::import::{DetectedGame, ScanCache, ScanReport, SteamCollection};
+use crate::launcher::LaunchError;
+use crate::data::{
+    ActivityEntry, ActivityKind, BundleContents, BundleExportReport, CardStyle, CategoryId, Config,
+    CsvContents, DataDir, Density, DisplayPrefs, Game, GameId, GameSource, LaunchWindowBehavior,
+    Library, LibraryError, PlayStatus, RuleCondition, SaveBackup,
+};
+use iced::window;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::process::ExitStatus;
 
 /// All possible messages/events in the application
 #[derive(Debug, Clone)]
@@ -11,39 +22,230 @@ pub enum Message {
     // Library
     GameSelected(GameId),
     LaunchGame(GameId),
-    GameLaunched(Result<(), String>),
+    LaunchGameVia(GameId, GameSource),
+    /// Open the store's install page for a game that isn't installed
+    InstallGame(GameId),
+    /// Show the command launching a game would run, without running it
+    PreviewLaunchCommand(GameId),
+    CloseLaunchPreview,
+    /// Look for executables in a game's install folder to pick a better one
+    RedetectExecutable(GameId),
+    ExecutableCandidatesFound(GameId, Vec<PathBuf>),
+    ExecutableChosen(GameId, PathBuf),
+    CloseExecutableCandidates,
+    EmulatorCommandInputChanged(String),
+    SetEmulatorCommand(GameId),
+    /// Edit where a game is installed
+    InstallPathInputChanged(String),
+    SetInstallPath(GameId),
+    /// Edit a game's save folder, then back it up or restore it
+    SavePathInputChanged(String),
+    SetSavePath(GameId),
+    FindSaveFolders(GameId),
+    SaveFoldersFound(GameId, Vec<PathBuf>),
+    BackupSaves(GameId),
+    SavesBackedUp(GameId, Result<PathBuf, String>),
+    ShowSaveBackups(GameId),
+    SaveBackupsListed(GameId, Vec<SaveBackup>),
+    /// Ask before replacing the save folder with a backup
+    AskRestoreSaves(GameId, PathBuf),
+    RestoreSaves(GameId, PathBuf),
+    /// Restored, with the backup taken of the saves it replaced
+    SavesRestored(GameId, Result<Option<PathBuf>, String>),
+    CloseSaveBackups,
+    ConfirmPinChanged(String),
+    ConfirmAccepted,
+    ConfirmCancelled,
+    GameLaunched(GameId, Result<Option<u32>, LaunchError>),
+    /// A game stopped, with minutes played and how it exited when known
+    GameExited(GameId, u64, Option<ExitStatus>),
+    /// Kill a running game's processes, from the now playing window
+    StopGame(GameId),
+    SampleResources,
+    WindowFocused(window::Id),
+    WindowClosed(window::Id),
+    WindowResized(window::Id, iced::Size),
 
     // Search & Filter
     SearchChanged(String),
-    CategorySelected(Option<CategoryId>),
+    FilterSelected(Option<Filter>),
     SortChanged(SortOrder),
     ViewModeChanged(ViewMode),
+    ToggleViewMode,
+    GroupByChanged(GroupBy),
+
+    // Sidebar
+    ToggleSidebar,
+    /// Open or close the header menu that stands in for the sidebar in
+    /// narrow windows
+    ToggleSidebarMenu,
+    SidebarDragStarted,
+    /// The splitter moved to this x position in the window
+    SidebarDragged(f32),
+    SidebarDragEnded,
 
     // Import
     StartImport(GameSource),
+    /// Scan a store again even though its last scan is cached
+    RescanImport(GameSource),
+    ScanCacheLoaded(ScanCache),
     ImportProgress(ImportProgress),
     ImportComplete(Result<(Vec<Game>, GameSource), String>),
+    ImportPreview(GameSource, Vec<DetectedGame>, ScanReport),
+    /// Time for the background scan to check which stores are due
+    AutoScanDue,
+    AutoScanComplete(Vec<(GameSource, ScanResult)>),
+    SteamCollectionsLoaded(Vec<SteamCollection>),
+    ImportPreviewToggled(usize, bool),
+    ImportPreviewSelectAll(bool),
+    ImportPreviewTabChanged(ImportPreviewTab),
+    CommitImport(GameSource, Vec<DetectedGame>),
+    /// Go ahead with an import the large-import warning stopped
+    ConfirmLargeImport,
+    /// Leave the large-import warning for the preview list
+    ReviewLargeImport,
+    CancelImport,
+    CsvPathInputChanged(String),
+    ImportCsv,
+    CsvRead(Result<CsvContents, String>),
+    BundlePathInputChanged(String),
+    ImportBundle,
+    BundleRead(Result<BundleContents, String>),
+    RomFolderInputChanged(String),
+    /// Scan the ROM folder, matching files to emulators by extension
+    StartRomImport,
+    DismissImportSummary,
     ClearLibrary,
 
+    // Stats
+    ExportYearInReview,
+    YearInReviewExported(Result<PathBuf, String>),
+    ActivityLoaded(Vec<ActivityEntry>),
+    /// Show only one kind of activity, or everything
+    ActivityFilterChanged(Option<ActivityKind>),
+
     // Settings
     ThemeChanged(String),
+    SystemThemeChanged(iced::theme::Mode),
     SettingChanged(SettingKey, SettingValue),
+    LaunchBehaviorChanged(LaunchWindowBehavior),
+    DensityChanged(Density),
+    CardStyleChanged(CardStyle),
+    /// Show or hide the badge of one source
+    SourceBadgeToggled(GameSource, bool),
+    /// How many days new games are badged, `None` for never
+    NewBadgeDaysChanged(Option<u32>),
+    FontScaleChanged(f32),
+    /// Window width the sidebar folds into a menu below, `None` for never
+    NarrowLayoutWidthChanged(Option<u32>),
+    /// Drop the "New" badge from every game added so far
+    ClearNewBadges,
+    AutoScanIntervalChanged(Option<u32>),
+    /// Whether a store's imports include games that aren't installed
+    IncludeNotInstalledToggled(GameSource, bool),
+    ImportWarnThresholdChanged(usize),
+    SettingsSectionSelected(SettingsSection),
+    /// Move to the next (true) or previous settings section
+    SettingsSectionStep(bool),
+    /// Move keyboard focus to the next (true) or previous field
+    FocusStep(bool),
+    SteamLibraryPathInputChanged(String),
+    AddSteamLibraryPath,
+    RemoveSteamLibraryPath(PathBuf),
+    ExcludePatternInputChanged(String),
+    AddExcludePattern,
+    RemoveExcludePattern(String),
+    EmulatorExtensionInputChanged(String),
+    EmulatorTemplateInputChanged(String),
+    AddEmulator,
+    /// Forget the emulator for this ROM extension
+    RemoveEmulator(String),
+    RevealPath(PathBuf),
+    CopyToClipboard(String),
+    LibraryPassphraseInputChanged(String),
+    SetLibraryPassphrase,
+    LaunchPinInputChanged(String),
+    SetLaunchPin,
+    ClearLaunchPin,
+
+    // Artwork
+    DownloadMissingArt,
+    ArtForceRedownloadToggled(bool),
+    ArtDownloadProgress(ImportProgress),
+    ArtDownloadComplete(Vec<(GameId, ArtPaths)>),
+    #[cfg(feature = "steamgriddb")]
+    FindArtwork(GameId),
+    #[cfg(feature = "steamgriddb")]
+    ArtworkCandidatesFound(GameId, Result<Vec<ArtEntry>, SteamGridDbError>),
+    /// Fetch thumbnails for the next page of candidates
+    #[cfg(feature = "steamgriddb")]
+    MoreArtwork(GameId),
+    #[cfg(feature = "steamgriddb")]
+    ArtworkThumbnailsLoaded(GameId, Vec<ArtCandidate>),
+    #[cfg(feature = "steamgriddb")]
+    ArtworkCandidateChosen(GameId, usize),
+    #[cfg(feature = "steamgriddb")]
+    ArtworkDownloaded(GameId, Result<(ArtKind, PathBuf), SteamGridDbError>),
+    #[cfg(feature = "steamgriddb")]
+    CloseArtworkPicker,
+    /// The SteamGridDB key being typed, saved once submitted
+    #[cfg(feature = "steamgriddb")]
+    SteamGridDbKeyEdited(String),
 
     // Game Management
     AddGamePressed,
     AddGame(Game),
     RemoveGame(GameId),
+    DuplicateGame(GameId),
     EditGame(GameId),
     UpdateGame(GameId, GameUpdate),
     ToggleFavorite(GameId),
+    ToggleHidden(GameId),
+    TogglePinned(GameId),
+    ShowHiddenToggled(bool),
+
+    // Multi-select and bulk actions
+    ToggleGameSelected(GameId),
+    ClearSelection,
+    ExportSelected,
+    ExportFolderPicked(Option<PathBuf>),
+    SelectionExported(Result<(PathBuf, BundleExportReport), String>),
+    /// Ask before folding the selected manual entry into the selected
+    /// imported one
+    MergeSelected,
+    /// Fold a manual entry into the imported one, once confirmed
+    MergeIntoImported(GameId, GameId),
+
+    // Card hover, for cards that only show their buttons under the mouse
+    CardHovered(GameId),
+    CardUnhovered(GameId),
+
+    // Card context menu
+    OpenContextMenu(GameId),
+    CloseContextMenu,
+    /// A menu entry was picked: close the menu, then handle the wrapped message
+    ContextMenuAction(Box<Message>),
 
     // Add Game Form
     NewGameNameChanged(String),
     NewGamePathChanged(String),
 
     // Category Management
+    CategoryNameInputChanged(String),
     AddCategory(String),
     RemoveCategory(CategoryId),
+    /// Set or clear a category's icon, by its name in the icon set
+    SetCategoryIcon(CategoryId, Option<String>),
+    /// Category new manual games go in, `None` for none
+    DefaultManualCategoryChanged(Option<CategoryId>),
+    /// Sort a category's games its own way; `None` follows the library sort
+    SetCategorySort(CategoryId, Option<SortOrder>),
+    /// Open the rule editor for a category, or close it
+    EditCategoryRule(Option<CategoryId>),
+    AddRuleCondition(CategoryId, RuleCondition),
+    RemoveRuleCondition(CategoryId, usize),
+    /// Turn a smart category back into a hand-picked one
+    ClearCategoryRule(CategoryId),
     AssignCategory(GameId, CategoryId),
     UnassignCategory(GameId, CategoryId),
 
@@ -55,13 +257,29 @@ pub enum Message {
     SaveLibrary,
     LibrarySaved(Result<(), String>),
     LoadLibrary,
-    LibraryLoaded(Library, Config),
+    /// The library and the config, and where a corrupt library file was moved
+    LibraryLoaded(Box<(Result<Library, LibraryError>, Config)>, Option<PathBuf>),
+    DismissQuarantineNotice,
+    UnlockPassphraseChanged(String),
+    UnlockLibrary,
+    LibraryUnlocked(Result<Library, LibraryError>),
+    SwitchLibraryInputChanged(String),
+    /// Save everything, then reload the launcher from another data dir
+    SwitchLibrary(PathBuf),
+    /// Both files were saved, or the settings failed to
+    LibrarySwitched(DataDir, Result<(), String>),
 
     // Misc
     Tick,
     None,
+    /// Result of a task started while the library with this generation was
+    /// open, see `App::dispatch`
+    ForLibrary(u64, Box<Message>),
 }
 
+/// Games a store scan found, or why it couldn't scan
+pub type ScanResult = Result<(Vec<DetectedGame>, ScanReport), String>;
+
 /// Application views/screens
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum View {
@@ -71,6 +289,8 @@ pub enum View {
     Settings,
     Import,
     AddGame,
+    YearInReview,
+    Activity,
 }
 
 /// Sort order for game library
@@ -106,6 +326,64 @@ impl SortOrder {
     }
 }
 
+/// What the library view is narrowed down to from the sidebar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Favorites,
+    Category(CategoryId),
+    Status(PlayStatus),
+    /// Maintenance worklist of games without cover art or an icon
+    MissingArt,
+    /// Maintenance worklist of games without categories or an install folder
+    MissingMetadata,
+}
+
+/// Sections the library view is split into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GroupBy {
+    /// One flat list
+    #[default]
+    None,
+    Source,
+    Category,
+    FirstLetter,
+    Installed,
+}
+
+impl GroupBy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::None => "No grouping",
+            GroupBy::Source => "Source",
+            GroupBy::Category => "Category",
+            GroupBy::FirstLetter => "First letter",
+            GroupBy::Installed => "Installed",
+        }
+    }
+
+    pub fn all() -> &'static [GroupBy] {
+        &[
+            GroupBy::None,
+            GroupBy::Source,
+            GroupBy::Category,
+            GroupBy::FirstLetter,
+            GroupBy::Installed,
+        ]
+    }
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl std::fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
 /// View mode for library display
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ViewMode {
@@ -114,6 +392,84 @@ pub enum ViewMode {
     List,
 }
 
+impl ViewMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViewMode::Grid => "Grid",
+            ViewMode::List => "List",
+        }
+    }
+
+    pub fn all() -> &'static [ViewMode] {
+        &[ViewMode::Grid, ViewMode::List]
+    }
+
+    /// The other mode, for the toggle shortcut
+    pub fn toggled(self) -> Self {
+        match self {
+            ViewMode::Grid => ViewMode::List,
+            ViewMode::List => ViewMode::Grid,
+        }
+    }
+}
+
+/// Pages of the settings screen, in nav order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettingsSection {
+    #[default]
+    Appearance,
+    Library,
+    Import,
+    Launching,
+    Advanced,
+}
+
+impl SettingsSection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingsSection::Appearance => "Appearance",
+            SettingsSection::Library => "Library",
+            SettingsSection::Import => "Import",
+            SettingsSection::Launching => "Launching",
+            SettingsSection::Advanced => "Advanced",
+        }
+    }
+
+    pub fn all() -> &'static [SettingsSection] {
+        &[
+            SettingsSection::Appearance,
+            SettingsSection::Library,
+            SettingsSection::Import,
+            SettingsSection::Launching,
+            SettingsSection::Advanced,
+        ]
+    }
+
+    /// The section below this one, staying put at the end
+    pub fn next(self) -> Self {
+        let all = Self::all();
+        let index = all.iter().position(|s| *s == self).unwrap_or(0);
+        all[(index + 1).min(all.len() - 1)]
+    }
+
+    /// The section above this one, staying put at the start
+    pub fn previous(self) -> Self {
+        let all = Self::all();
+        let index = all.iter().position(|s| *s == self).unwrap_or(0);
+        all[index.saturating_sub(1)]
+    }
+}
+
+/// Tabs of the import preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportPreviewTab {
+    /// Games not yet in the library
+    #[default]
+    New,
+    /// Games that would refresh an existing library entry
+    InLibrary,
+}
+
 /// Import progress information
 #[derive(Debug, Clone)]
 pub struct ImportProgress {
@@ -130,6 +486,24 @@ pub enum SettingKey {
     StartMinimized,
     CloseToTray,
     DefaultView,
+    RestoreWindowOnGameExit,
+    ShowNowPlayingWindow,
+    PreferBigPicture,
+    ConfirmInstallOnLaunch,
+    ResolveExecutableFolders,
+    SteamGridDbApiKey,
+    ShowResourceUsage,
+    MergeCrossStore,
+    ImportSteamCollections,
+    ShowSources,
+    EncryptLibrary,
+    LaunchInTerminal,
+    CaptureGameOutput,
+    WriteStatusFile,
+    DiscordRpcEnabled,
+    DiscordClientId,
+    HideEmptyCategories,
+    CropCardArt,
 }
 
 /// Setting values
@@ -144,6 +518,15 @@ pub enum SettingValue {
 pub struct GameUpdate {
     pub name: Option<String>,
     pub executable_path: Option<PathBuf>,
+    pub install_path: Option<PathBuf>,
     pub launch_args: Option<String>,
     pub icon_path: Option<PathBuf>,
+    pub display_prefs: Option<DisplayPrefs>,
+    pub launch_in_terminal: Option<bool>,
+    pub confirm_before_launch: Option<bool>,
+    pub run_as_admin: Option<bool>,
+    pub track_playtime: Option<bool>,
+    pub status: Option<PlayStatus>,
+    pub save_path: Option<PathBuf>,
+    pub emulator_command: Option<String>,
 }
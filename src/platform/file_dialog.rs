@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+const DIALOG_TITLE: &str = "Choose the game's executable";
+const EXPORT_DIALOG_TITLE: &str = "Choose where to export the games";
+
+/// Ask the user for a game executable with the system's file picker
+///
+/// Returns `None` when the dialog is cancelled or no picker is available.
+/// On Linux the picker comes through the desktop portal, so it matches the
+/// desktop without a GUI toolkit being linked for it. Only Windows narrows
+/// the list to programs; elsewhere the Add Game form checks the chosen file.
+pub async fn pick_executable() -> Option<PathBuf> {
+    let mut dialog = rfd::AsyncFileDialog::new().set_title(DIALOG_TITLE);
+    if cfg!(target_os = "windows") {
+        dialog = dialog.add_filter("Programs", &["exe"]).add_filter("All files", &["*"]);
+    }
+    if let Some(home) = directories::UserDirs::new() {
+        dialog = dialog.set_directory(home.home_dir());
+    }
+    dialog.pick_file().await.map(|file| file.path().to_path_buf())
+}
+
+/// Ask the user for a folder to export games into
+///
+/// Returns `None` when the dialog is cancelled or no picker is available.
+pub async fn pick_export_folder() -> Option<PathBuf> {
+    let mut dialog = rfd::AsyncFileDialog::new().set_title(EXPORT_DIALOG_TITLE);
+    if let Some(home) = directories::UserDirs::new() {
+        dialog = dialog.set_directory(home.home_dir());
+    }
+    dialog.pick_folder().await.map(|folder| folder.path().to_path_buf())
+}
@@ -1,6 +1,6 @@
 // macOS-specific functionality
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Get the Application Support directory
 pub fn application_support_dir() -> Option<PathBuf> {
@@ -21,7 +21,7 @@ pub fn app_bundle_exists(name: &str) -> bool {
 }
 
 /// Get the executable path from an app bundle
-pub fn get_app_executable(app_path: &PathBuf) -> Option<PathBuf> {
+pub fn get_app_executable(app_path: &Path) -> Option<PathBuf> {
     // macOS app bundles have executables in Contents/MacOS/
     let contents = app_path.join("Contents/MacOS");
     if contents.exists() {
@@ -9,7 +9,24 @@ mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
 
-use std::path::PathBuf;
+mod file_dialog;
+mod resources;
+mod terminal;
+
+pub use file_dialog::{pick_executable, pick_export_folder};
+pub use resources::{ResourceSampler, ResourceUsage};
+pub use terminal::find_terminal;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub use terminal::command_exists;
+#[cfg(target_os = "linux")]
+pub use linux::xdg_config_dir;
+#[cfg(target_os = "windows")]
+pub use windows::{get_registry_string, get_registry_subkeys};
+#[cfg(target_os = "macos")]
+pub use macos::get_app_executable;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Get the platform name
 pub fn platform_name() -> &'static str {
@@ -72,12 +89,92 @@ pub fn default_game_directories() -> Vec<PathBuf> {
     }
 }
 
+/// Folders games commonly keep saves in, for guessing a game's save folder
+///
+/// Saves usually sit in a folder named after the game, directly in one of
+/// these or one level down under the publisher's name. Only folders that
+/// exist are returned.
+pub fn save_folder_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(user) = directories::UserDirs::new() {
+        if let Some(documents) = user.document_dir() {
+            roots.push(documents.join("My Games"));
+            roots.push(documents.to_path_buf());
+        }
+    }
+    if let Some(base) = directories::BaseDirs::new() {
+        #[cfg(target_os = "windows")]
+        {
+            roots.push(base.home_dir().join("Saved Games"));
+            // AppData\LocalLow, which has no known-folder API of its own
+            if let Some(app_data) = base.data_local_dir().parent() {
+                roots.push(app_data.join("LocalLow"));
+            }
+        }
+        roots.push(base.data_dir().to_path_buf());
+        roots.push(base.data_local_dir().to_path_buf());
+        roots.push(base.config_dir().to_path_buf());
+    }
+    roots.dedup();
+    roots.retain(|root| root.is_dir());
+    roots
+}
+
+/// Show a file or folder in the system file manager
+///
+/// Files are selected in their parent folder where the platform supports it.
+/// A path that doesn't exist yet falls back to its closest existing parent.
+pub fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    let Some(target) = path.ancestors().find(|p| p.exists()) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} does not exist", path.display()),
+        ));
+    };
+    let is_file = target.is_file();
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("explorer");
+        if is_file {
+            command.arg(format!("/select,{}", target.display()));
+        } else {
+            command.arg(target);
+        }
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        if is_file {
+            command.arg("-R");
+        }
+        command.arg(target);
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        // xdg-open can't select a file, so open the folder containing it
+        let folder = if is_file {
+            target.parent().unwrap_or(target)
+        } else {
+            target
+        };
+        let mut command = Command::new("xdg-open");
+        command.arg(folder);
+        command
+    };
+
+    command.spawn().map(|_| ())
+}
+
 /// Check if the platform supports a specific feature
 pub fn supports_feature(feature: PlatformFeature) -> bool {
     match feature {
         PlatformFeature::SteamImport => true, // All platforms
         PlatformFeature::EpicImport => cfg!(target_os = "windows"),
         PlatformFeature::GOGImport => cfg!(target_os = "windows"),
+        PlatformFeature::XboxImport => cfg!(target_os = "windows"),
         PlatformFeature::SystemTray => true, // All platforms via iced
     }
 }
@@ -88,5 +185,6 @@ pub enum PlatformFeature {
     SteamImport,
     EpicImport,
     GOGImport,
+    XboxImport,
     SystemTray,
 }
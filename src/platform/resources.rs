@@ -0,0 +1,135 @@
+// Process resource sampling for running games
+
+use std::collections::HashMap;
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// CPU and memory usage of a process tree
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// CPU usage as a percentage of total machine capacity
+    pub cpu_percent: f32,
+
+    /// Resident memory in bytes
+    pub memory_bytes: u64,
+}
+
+impl ResourceUsage {
+    /// Short human-readable readout, e.g. "CPU 12% · RAM 1.4 GB"
+    pub fn display(&self) -> String {
+        let mb = self.memory_bytes as f64 / (1024.0 * 1024.0);
+        let memory = if mb >= 1024.0 {
+            format!("{:.1} GB", mb / 1024.0)
+        } else {
+            format!("{:.0} MB", mb)
+        };
+        format!("CPU {:.0}% · RAM {}", self.cpu_percent, memory)
+    }
+}
+
+/// Samples resource usage for game processes
+///
+/// CPU usage is computed from the difference between two refreshes, so the
+/// sampler must be kept alive between polls and the first sample reads 0%.
+pub struct ResourceSampler {
+    system: System,
+    cpu_count: f32,
+}
+
+impl ResourceSampler {
+    pub fn new() -> Self {
+        let cpu_count = std::thread::available_parallelism()
+            .map(|n| n.get() as f32)
+            .unwrap_or(1.0);
+
+        Self {
+            system: System::new(),
+            cpu_count,
+        }
+    }
+
+    /// Sample usage for each root PID, summing the whole process tree
+    ///
+    /// PIDs whose process no longer exists are left out of the result.
+    pub fn sample(&mut self, root_pids: &[u32]) -> HashMap<u32, ResourceUsage> {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        let processes = self.system.processes();
+        let children = child_map(&self.system);
+
+        let mut usage = HashMap::new();
+        for &root in root_pids {
+            let root_pid = Pid::from_u32(root);
+            if !processes.contains_key(&root_pid) {
+                continue;
+            }
+
+            let mut total = ResourceUsage::default();
+            for pid in process_tree(root_pid, &children) {
+                if let Some(process) = processes.get(&pid) {
+                    total.cpu_percent += process.cpu_usage() / self.cpu_count;
+                    total.memory_bytes += process.memory();
+                }
+            }
+            usage.insert(root, total);
+        }
+
+        usage
+    }
+
+    /// Kill a game's whole process tree, returning whether anything was killed
+    ///
+    /// Children go first so a stub can't respawn the real binary.
+    pub fn stop(&mut self, root_pid: u32) -> bool {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+
+        let children = child_map(&self.system);
+        let mut killed = false;
+        for pid in process_tree(Pid::from_u32(root_pid), &children).into_iter().rev() {
+            if let Some(process) = self.system.process(pid) {
+                killed |= process.kill();
+            }
+        }
+        killed
+    }
+}
+
+/// Each process's direct children
+///
+/// Games often run through a stub that forks the real binary.
+fn child_map(system: &System) -> HashMap<Pid, Vec<Pid>> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in system.processes() {
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+    children
+}
+
+/// A process and all its descendants, parents before their children
+fn process_tree(root: Pid, children: &HashMap<Pid, Vec<Pid>>) -> Vec<Pid> {
+    let mut tree = vec![root];
+    let mut next = 0;
+    while let Some(&pid) = tree.get(next) {
+        if let Some(kids) = children.get(&pid) {
+            tree.extend(kids.iter().copied());
+        }
+        next += 1;
+    }
+    tree
+}
+
+impl Default for ResourceSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+use std::process::Command;
+
+/// A terminal emulator that can run a program in a new window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Terminal {
+    /// Terminal executable
+    pub program: &'static str,
+    /// Arguments placed before the wrapped program
+    exec_args: &'static [&'static str],
+    /// Whether the terminal process lasts as long as the program in it, so
+    /// its exit says when the program stopped
+    pub waits: bool,
+}
+
+/// Terminals to try, in order of preference
+///
+/// Those that wait come first, so games in a terminal can still be followed.
+#[cfg(target_os = "windows")]
+const CANDIDATES: &[Terminal] = &[
+    // Always available; a console program started from a GUI gets its own window
+    Terminal {
+        program: "cmd.exe",
+        exec_args: &["/K"],
+        // Until the window is closed, which /K leaves to the user
+        waits: true,
+    },
+    Terminal {
+        program: "wt.exe",
+        exec_args: &["new-tab", "--"],
+        // Hands the tab to a running Windows Terminal and returns
+        waits: false,
+    },
+];
+
+#[cfg(target_os = "macos")]
+const CANDIDATES: &[Terminal] = &[Terminal {
+    program: "osascript",
+    exec_args: &["-e"],
+    // Terminal.app runs the script; osascript is done once it's handed over
+    waits: false,
+}];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const CANDIDATES: &[Terminal] = &[
+    Terminal {
+        program: "gnome-terminal",
+        exec_args: &["--wait", "--"],
+        waits: true,
+    },
+    Terminal {
+        program: "konsole",
+        exec_args: &["--nofork", "-e"],
+        waits: true,
+    },
+    Terminal {
+        program: "xterm",
+        exec_args: &["-e"],
+        waits: true,
+    },
+    // Only when it isn't one of the above, see `default_terminal`
+    Terminal {
+        program: "x-terminal-emulator",
+        exec_args: &["-e"],
+        // Could be any terminal, some of which return straight away
+        waits: false,
+    },
+];
+
+/// Find a terminal emulator to run games in
+///
+/// The system's default terminal is preferred when it's one we know how to
+/// wait on.
+pub fn find_terminal() -> Option<Terminal> {
+    default_terminal().or_else(|| {
+        CANDIDATES
+            .iter()
+            .find(|terminal| command_exists(terminal.program))
+            .cloned()
+    })
+}
+
+/// The terminal Debian's `x-terminal-emulator` alternative points at
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn default_terminal() -> Option<Terminal> {
+    let link = std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join("x-terminal-emulator"))
+        .find(|path| path.is_file())?;
+    known_terminal(&std::fs::canonicalize(link).ok()?)
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn default_terminal() -> Option<Terminal> {
+    None
+}
+
+/// The waiting candidate a resolved terminal binary is, if any
+///
+/// Debian installs some terminals as `<name>.wrapper` scripts translating
+/// `-e`; the terminal itself is run instead, with its own flags.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn known_terminal(target: &Path) -> Option<Terminal> {
+    let name = target.file_name()?.to_str()?;
+    let name = name.strip_suffix(".wrapper").unwrap_or(name);
+    CANDIDATES
+        .iter()
+        .find(|terminal| terminal.waits && terminal.program == name)
+        .cloned()
+}
+
+impl Terminal {
+    /// Build a command that runs `program` with `args` inside this terminal
+    pub fn command(&self, program: &Path, args: &[String]) -> Command {
+        let mut command = Command::new(self.program);
+        command.args(self.exec_args);
+
+        if cfg!(target_os = "macos") {
+            // Terminal.app only takes a script line, so quote it for the shell
+            let dir = program.parent().unwrap_or(Path::new("."));
+            let line = std::iter::once(program.to_string_lossy().to_string())
+                .chain(args.iter().cloned())
+                .map(|arg| shell_quote(&arg))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let line = format!("cd {} && {}", shell_quote(&dir.to_string_lossy()), line);
+            command.arg(format!(
+                "tell application \"Terminal\" to do script \"{}\"",
+                line.replace('\\', "\\\\").replace('"', "\\\"")
+            ));
+        } else {
+            command.arg(program).args(args);
+        }
+
+        command
+    }
+}
+
+/// Quote an argument for a POSIX shell
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Check whether a program can be found on PATH
+pub fn command_exists(program: &str) -> bool {
+    let finder = if cfg!(target_os = "windows") { "where" } else { "which" };
+    Command::new(finder)
+        .arg(program)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waiting_terminals_come_first() {
+        assert!(CANDIDATES.windows(2).all(|pair| pair[0].waits >= pair[1].waits));
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn test_known_terminal() {
+        let konsole = known_terminal(Path::new("/usr/bin/konsole")).unwrap();
+        assert_eq!(konsole.exec_args, ["--nofork", "-e"]);
+        let gnome = known_terminal(Path::new("/usr/bin/gnome-terminal.wrapper")).unwrap();
+        assert_eq!(gnome.program, "gnome-terminal");
+        assert!(known_terminal(Path::new("/usr/bin/tilix")).is_none());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
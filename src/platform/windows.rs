@@ -8,17 +8,31 @@ use winreg::RegKey;
 /// Get a string value from the Windows registry
 #[cfg(target_os = "windows")]
 pub fn get_registry_string(hive: &str, path: &str, key: &str) -> Option<String> {
-    let hkey = match hive {
-        "HKEY_LOCAL_MACHINE" | "HKLM" => RegKey::predef(HKEY_LOCAL_MACHINE),
-        "HKEY_CURRENT_USER" | "HKCU" => RegKey::predef(HKEY_CURRENT_USER),
-        _ => return None,
-    };
-
-    hkey.open_subkey(path)
+    predef_key(hive)?
+        .open_subkey(path)
         .ok()
         .and_then(|k| k.get_value::<String, _>(key).ok())
 }
 
+/// List the names of the subkeys under a registry key
+#[cfg(target_os = "windows")]
+pub fn get_registry_subkeys(hive: &str, path: &str) -> Vec<String> {
+    predef_key(hive)
+        .and_then(|hkey| hkey.open_subkey(path).ok())
+        .map(|key| key.enum_keys().flatten().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn predef_key(hive: &str) -> Option<RegKey> {
+    match hive {
+        "HKEY_LOCAL_MACHINE" | "HKLM" => Some(RegKey::predef(HKEY_LOCAL_MACHINE)),
+        "HKEY_CURRENT_USER" | "HKCU" => Some(RegKey::predef(HKEY_CURRENT_USER)),
+        "HKEY_CLASSES_ROOT" | "HKCR" => Some(RegKey::predef(HKEY_CLASSES_ROOT)),
+        _ => None,
+    }
+}
+
 /// Check if a program is installed by looking for its registry entry
 #[cfg(target_os = "windows")]
 pub fn is_program_installed(name: &str) -> bool {
@@ -0,0 +1,68 @@
+/// A built-in category icon
+///
+/// Categories store the `name`, never the glyph, so the glyphs can change
+/// without touching saved libraries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryIcon {
+    pub name: &'static str,
+    pub glyph: &'static str,
+}
+
+impl CategoryIcon {
+    /// Look up an icon by its stored name
+    pub fn by_name(name: &str) -> Option<&'static CategoryIcon> {
+        CATEGORY_ICONS.iter().find(|icon| icon.name == name)
+    }
+
+    pub fn all() -> &'static [CategoryIcon] {
+        CATEGORY_ICONS
+    }
+}
+
+impl std::fmt::Display for CategoryIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.glyph, self.name)
+    }
+}
+
+const CATEGORY_ICONS: &[CategoryIcon] = &[
+    CategoryIcon { name: "sword", glyph: "⚔" },
+    CategoryIcon { name: "dragon", glyph: "🐉" },
+    CategoryIcon { name: "chess", glyph: "♟" },
+    CategoryIcon { name: "puzzle", glyph: "🧩" },
+    CategoryIcon { name: "gear", glyph: "⚙" },
+    CategoryIcon { name: "ball", glyph: "⚽" },
+    CategoryIcon { name: "heart", glyph: "♥" },
+    CategoryIcon { name: "star", glyph: "★" },
+    CategoryIcon { name: "controller", glyph: "🎮" },
+    CategoryIcon { name: "rocket", glyph: "🚀" },
+    CategoryIcon { name: "car", glyph: "🚗" },
+    CategoryIcon { name: "ghost", glyph: "👻" },
+    CategoryIcon { name: "music", glyph: "♪" },
+    CategoryIcon { name: "flag", glyph: "⚑" },
+    CategoryIcon { name: "crown", glyph: "♛" },
+    CategoryIcon { name: "globe", glyph: "🌍" },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Library;
+
+    #[test]
+    fn test_default_category_icons_resolve() {
+        for category in Library::new().all_categories() {
+            let icon = category.icon.as_deref().expect("default categories have icons");
+            assert!(CategoryIcon::by_name(icon).is_some(), "unknown icon {}", icon);
+        }
+        assert!(CategoryIcon::by_name("missing").is_none());
+    }
+
+    #[test]
+    fn test_icon_names_are_unique() {
+        let mut names: Vec<_> = CategoryIcon::all().iter().map(|icon| icon.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), CategoryIcon::all().len());
+    }
+}
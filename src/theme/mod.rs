@@ -1,12 +1,17 @@
+mod icons;
 mod palette;
 
-pub use palette::Palette;
+pub use icons::CategoryIcon;
+pub use palette::{HexColor, Palette};
 
 use iced::theme;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tokio::fs;
 
+/// Display name of the built-in high-contrast theme
+const HIGH_CONTRAST_NAME: &str = "High Contrast";
+
 /// Custom theme for the application
 #[derive(Debug, Clone)]
 pub struct CustomTheme {
@@ -36,6 +41,20 @@ impl CustomTheme {
         }
     }
 
+    /// Built-in high-contrast theme, for low vision
+    pub fn high_contrast() -> Self {
+        Self {
+            name: HIGH_CONTRAST_NAME.to_string(),
+            palette: Palette::high_contrast(),
+        }
+    }
+
+    /// Whether this uses the high-contrast palette, which also thickens
+    /// focus outlines
+    pub fn is_high_contrast(&self) -> bool {
+        self.palette == Palette::high_contrast()
+    }
+
     /// Load a theme from a JSON file
     pub async fn load_from_file(path: &Path) -> Result<Self, ThemeError> {
         let content = fs::read_to_string(path)
@@ -65,10 +84,29 @@ impl CustomTheme {
         iced::Theme::custom(self.name.clone(), iced_palette)
     }
 
+    /// Built-in theme matching the OS light/dark preference
+    ///
+    /// Falls back to dark when the OS doesn't report a preference.
+    pub fn for_mode(mode: theme::Mode) -> Self {
+        match mode {
+            theme::Mode::Light => Self::light(),
+            theme::Mode::Dark | theme::Mode::None => Self::dark(),
+        }
+    }
+
+    /// Whether a configured theme name means "follow the OS"
+    pub fn follows_system(name: &str) -> bool {
+        name.eq_ignore_ascii_case("system")
+    }
+
     /// Get theme by name
+    ///
+    /// `"system"` resolves to dark until the OS preference is known.
     pub fn by_name(name: &str) -> Self {
         match name.to_lowercase().as_str() {
             "light" => Self::light(),
+            "high-contrast" => Self::high_contrast(),
+            "system" => Self::for_mode(theme::Mode::None),
             _ => Self::dark(), // Default to dark
         }
     }
@@ -94,24 +132,3 @@ pub enum ThemeError {
     #[error("Parse error: {0}")]
     Parse(String),
 }
-
-/// Get list of available themes (built-in + user themes)
-pub async fn available_themes(user_themes_dir: &Path) -> Vec<String> {
-    let mut themes = vec!["Dark".to_string(), "Light".to_string()];
-
-    // Try to read user themes directory
-    if let Ok(mut entries) = fs::read_dir(user_themes_dir).await {
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            if let Some(name) = entry.file_name().to_str() {
-                if name.ends_with(".json") {
-                    // Try to load and get the theme name
-                    if let Ok(theme) = CustomTheme::load_from_file(&entry.path()).await {
-                        themes.push(theme.name);
-                    }
-                }
-            }
-        }
-    }
-
-    themes
-}
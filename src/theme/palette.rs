@@ -2,7 +2,7 @@ use iced::Color;
 use serde::{Deserialize, Serialize};
 
 /// Color palette for a theme
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Palette {
     /// Main background color
     pub background: HexColor,
@@ -67,10 +67,26 @@ impl Palette {
             error: HexColor::from_hex("#ef4444"),
         }
     }
+
+    /// High-contrast palette: pure black and white with saturated accents
+    pub fn high_contrast() -> Self {
+        Self {
+            background: HexColor::from_hex("#000000"),
+            surface: HexColor::from_hex("#000000"),
+            primary: HexColor::from_hex("#ffd400"),
+            secondary: HexColor::from_hex("#00e5ff"),
+            accent: HexColor::from_hex("#ff66ff"),
+            text: HexColor::from_hex("#ffffff"),
+            text_secondary: HexColor::from_hex("#ffffff"),
+            success: HexColor::from_hex("#00ff66"),
+            warning: HexColor::from_hex("#ffd400"),
+            error: HexColor::from_hex("#ff6b6b"),
+        }
+    }
 }
 
 /// A color stored as a hex string but convertible to iced Color
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct HexColor(pub String);
 